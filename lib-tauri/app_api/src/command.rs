@@ -1,35 +1,78 @@
 use std::{
     collections::{HashMap, HashSet},
-    error::Error,
+    path::Path,
 };
 
-use app_data_processing::{IdGeneratorStanza, SimpleDataFrame, TransposedDataFrame};
+use app_data_processing::{
+    BackupArchive, BackupFascicoli, COLONNE_FASCICOLO, COLONNE_INFISSI, EdificioHeatLoss,
+    EdificioMetrics, EdificioScore, EnergyScore, ExportData, ExportDatiStanzaToExcel,
+    ExportScenariToExcel, FascicoloScore, Geometry, ImportFascicolo, ImportInfissi,
+    ImportInfissiPreview, ImportOptions, ImportPreview, LocalBackup, LocalBackups, MappingProfile,
+    MappingProfileStore, QualityReport, QualityReportStore, RawSheet, RestoreMode, RestoreReport,
+    Scenario, ScenarioComparison, ScenarioStore, Scenarios, SimpleDataFrame, Transmittance,
+};
 use app_services::{
     dto::{
         AnnotazioneDTO, AnnotazioneEdificioDTO, AnnotazioneInfissoDTO, AnnotazioneStanzaDTO,
-        EdificioDTO, FotovoltaicoDTO, InfissoDTO, StanzaDTO, StanzaDTOBuilder, TableWithPrimaryKey,
-        TipoDTO, UtenzaDTO,
+        EdificioDTO, FotovoltaicoDTO, InfissoDTO, StanzaDTO, StimaProduzioneDTO,
+        TableWithPrimaryKey, TipoDTO, TrasmittanzaDTO, UtenzaDTO,
     },
+    query::{FotovoltaicoQuery, InfissoQuery, StanzaQuery, UtenzaQuery},
     service::{
         AnnotazioneService, CreateService, EdificioService, FotovoltaicoService, InfissoService,
-        StanzaService, TypeService, TypeServiceImpl, UpdateService, UtenzeService,
+        StanzaService, TypeDTO, TypeService, TypeServiceImpl, UpdateService, UtenzeService,
     },
 };
-use app_state::{database::DatabaseManager, selected_edificio::EdificioSelected};
-use app_utils::app_interface::service_interface::{
-    CreateBatchService, RetrieveByEdificioSelected, RetrieveManyService, SelectedEdificioState,
-    SelectedEdificioTrait,
+use app_state::{
+    database::{ConnectionProfile, DatabaseManager, MigrationReport},
+    selected_edificio::EdificioSelected,
+};
+use app_task_background::{TaskRun, TaskState, get_background_manager};
+use app_utils::{
+    app_error::{ApplicationError, DomainError, ErrorKind, TauriError},
+    app_interface::service_interface::{
+        DeleteService, RetrieveBy, RetrieveByEdificioSelected, RetrieveManyService,
+        SelectedEdificioState, SelectedEdificioTrait,
+    },
 };
-use log::info;
+use log::{error, info};
+use serde::Serialize;
 use serde_json::Value;
 use tauri::{AppHandle, Emitter, Runtime, State};
 
 use crate::{
-    events_payload::{EdificioChangePayload, EventWrapper, NewEdificioPayload, TypeEvent},
+    emit_database_status,
+    events_payload::{
+        DeletedPayload, EdificioChangePayload, EventWrapper, NewEdificioPayload, TypeEvent,
+    },
     get_chiave_selected_edificio, is_selected_edificio,
+    settings::{
+        AppSettings, ConnectionProfileStore, DatabaseProfiles, DatabaseStatus, SettingsStore,
+    },
 };
 
-pub(crate) type ResultCommand<T> = Result<T, String>;
+pub(crate) type ResultCommand<T> = Result<T, ApplicationError>;
+
+/// Righe restituite dai comandi di ricerca che non indicano un `limit`
+const QUERY_LIMIT_DEFAULT: u32 = 200;
+
+fn map_tauri_error(e: tauri::Error) -> ApplicationError {
+    ApplicationError::Tauri(TauriError::Plugin(e.into()))
+}
+
+fn emit_deleted<R: Runtime, K: Serialize + Clone>(
+    app_handle: &AppHandle<R>,
+    event: &str,
+    type_event: TypeEvent,
+    id: K,
+) -> ResultCommand<()> {
+    app_handle
+        .emit(
+            event,
+            EventWrapper::new(type_event, DeletedPayload::new(id)),
+        )
+        .map_err(map_tauri_error)
+}
 
 /***************************************************************************
  * ********************** */
@@ -81,7 +124,7 @@ pub async fn set_edificio(
                 ),
             ),
         )
-        .map_err(|e| e.to_string())?;
+        .map_err(map_tauri_error)?;
 
     info!("Edificio switched");
     Ok(())
@@ -103,73 +146,64 @@ pub async fn clear_edificio(
 /***************************************************************************
  * ********************** */
 
-fn get_field(row: &HashMap<&str, &str>, name: &str) -> Result<String, Box<dyn Error>> {
-    match row.get(name) {
-        Some(chiave) => Ok(chiave.to_string()),
-        None => Err(Box::from(format!("Campo {name} non trovato"))),
-    }
+/// Legge il file nel formato riconosciuto dal contenuto (xlsx, xls, ods o
+/// csv) e ne ricava le colonne `fields` con il profilo indicato
+fn read_import_file<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    path: &str,
+    profile: Option<&str>,
+    fields: &[&str],
+) -> ResultCommand<SimpleDataFrame> {
+    let profile = match profile {
+        Some(name) => MappingProfileStore::get(app_handle, name)?,
+        None => MappingProfile::default(),
+    };
+    let required = profile.required_columns(fields);
+    let options = ImportOptions::default()
+        .detect_header(&required.iter().map(String::as_str).collect::<Vec<_>>());
+
+    let df = SimpleDataFrame::from_file(path, &options)?;
+    profile.apply(&df, fields).map_err(ApplicationError::from)
 }
 
-pub async fn save_to_database(
+/// Analizza il file senza scrivere nel database
+#[tauri::command]
+pub async fn preview_fascicolo_from_file<R: Runtime>(
+    app_handle: AppHandle<R>,
     db: State<'_, DatabaseManager>,
-    df: TransposedDataFrame,
-) -> Result<Vec<EdificioDTO>, Box<dyn Error>> {
-    let df_clone = {
-        let mut df = df.clone().traspose();
-        df.select(&["chiave", "fascicolo", "nome_via"]).ok();
-        df.unique();
-        df.traspose()
-    };
-    let mut result = Vec::new();
-    for row in df_clone.iter_rows() {
-        let new_edificio = EdificioDTO {
-            chiave: get_field(&row, "chiave")?,
-            fascicolo: get_field(&row, "fascicolo")?.parse()?,
-            indirizzo: get_field(&row, "nome_via")?,
-            anno_costruzione: None,
-            anno_riqualificazione: None,
-            note_riqualificazione: None,
-            isolamento_tetto: false,
-            cappotto: false,
-        };
-        result.push(EdificioService::create(db.clone(), new_edificio).await?);
-    }
-
-    let mut generator_id_stanza = IdGeneratorStanza::new();
-    let mut new_stanze: Vec<StanzaDTO> = Vec::new();
-    for row in df.iter_rows() {
-        let new_stanza: StanzaDTO = generator_id_stanza
-            .generate_id(
-                StanzaDTOBuilder::default()
-                    .edificio_id(get_field(&row, "chiave")?)
-                    .piano(get_field(&row, "piano")?)
-                    .id_spazio(get_field(&row, "id_spazio")?)
-                    .cod_stanza(get_field(&row, "cod_stanza")?)
-                    .destinazione_uso(get_field(&row, "destinazione_uso")?)
-                    .build()
-                    .into(),
-            )?
-            .into();
-        new_stanze.push(new_stanza);
-    }
-    StanzaService::create_batch(db.clone(), new_stanze).await?;
-    Ok(result)
+    path: String,
+    profile: Option<String>,
+) -> ResultCommand<ImportPreview> {
+    let df = read_import_file(
+        &app_handle,
+        path.as_str(),
+        profile.as_deref(),
+        COLONNE_FASCICOLO,
+    )?;
+    ImportFascicolo::preview(db, &df).await
 }
 
 #[tauri::command]
-pub async fn add_new_fascicolo_from_xlsx<R: Runtime>(
+pub async fn add_new_fascicolo_from_file<R: Runtime>(
     app_handle: AppHandle<R>,
     db: State<'_, DatabaseManager>,
     selected_edificio: State<'_, SelectedEdificioState<EdificioSelected>>,
     path: String,
+    profile: Option<String>,
 ) -> ResultCommand<()> {
-    let df = SimpleDataFrame::from_xlsx(path.as_str()).map_err(|e| e.to_string())?;
-    let chiavi = df.column("chiave").map_err(|e| e.to_string())?;
-    let first_chiave = chiavi.first().ok_or(Box::from("Chiave non trovato"))?;
+    let df = read_import_file(
+        &app_handle,
+        path.as_str(),
+        profile.as_deref(),
+        COLONNE_FASCICOLO,
+    )?;
+    let chiavi = df.column("chiave")?;
+    let first_chiave = chiavi.first().ok_or(DomainError::InvalidInput(
+        ErrorKind::EmptyField,
+        "chiave".to_string(),
+    ))?;
 
-    let new_edifici = save_to_database(db, df.traspose())
-        .await
-        .map_err(|e| e.to_string())?;
+    let report = ImportFascicolo::import(db, &df).await?;
 
     selected_edificio
         .write()
@@ -183,18 +217,56 @@ pub async fn add_new_fascicolo_from_xlsx<R: Runtime>(
             EventWrapper::new(
                 TypeEvent::NewEdificio,
                 NewEdificioPayload::new(
-                    new_edifici,
+                    report.edifici,
                     get_chiave_selected_edificio(selected_edificio)
                         .await
                         .unwrap(),
                 ),
             ),
         )
-        .map_err(|e| e.to_string())?;
+        .map_err(map_tauri_error)?;
 
     Ok(())
 }
 
+#[tauri::command]
+pub async fn get_mapping_profiles<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> ResultCommand<Vec<MappingProfile>> {
+    MappingProfileStore::load(&app_handle)
+}
+
+#[tauri::command]
+pub async fn save_mapping_profile<R: Runtime>(
+    app_handle: AppHandle<R>,
+    profile: MappingProfile,
+) -> ResultCommand<()> {
+    MappingProfileStore::save(&app_handle, profile)
+}
+
+#[tauri::command]
+pub async fn delete_mapping_profile<R: Runtime>(
+    app_handle: AppHandle<R>,
+    name: String,
+) -> ResultCommand<bool> {
+    MappingProfileStore::delete(&app_handle, name.as_str())
+}
+
+/// Nome del profilo più adatto alle intestazioni del file, `None` se le
+/// colonne hanno già i nomi attesi o nessun profilo è compatibile
+#[tauri::command]
+pub async fn suggest_mapping_profile<R: Runtime>(
+    app_handle: AppHandle<R>,
+    path: String,
+) -> ResultCommand<Option<String>> {
+    let profiles = MappingProfileStore::load(&app_handle)?;
+    let sheet = RawSheet::read(path.as_str(), &ImportOptions::default())?;
+    Ok(
+        MappingProfile::suggest(&profiles, sheet.rows(), COLONNE_FASCICOLO)
+            .map(|profile| profile.name.clone()),
+    )
+}
+
 /***************************************************************************
  * ********************** */
 /************************************** COMMAND PER INFISSI
@@ -207,9 +279,17 @@ pub async fn get_infissi(
     db: State<'_, DatabaseManager>,
     edificio_selected: State<'_, SelectedEdificioState<EdificioSelected>>,
 ) -> ResultCommand<Vec<InfissoDTO>> {
-    InfissoService::retrieve_by_edificio_selected(db, edificio_selected)
-        .await
-        .map_err(|e| e.to_string())
+    InfissoService::retrieve_by_edificio_selected(db, edificio_selected).await
+}
+
+/// Infissi che soddisfano i filtri, ordinati e paginati. Senza `limit`
+/// restituisce al più `QUERY_LIMIT_DEFAULT` infissi
+#[tauri::command]
+pub async fn query_infissi(
+    db: State<'_, DatabaseManager>,
+    query: InfissoQuery,
+) -> ResultCommand<Vec<InfissoDTO>> {
+    InfissoService::retrieve_by(db, query.limit_or(QUERY_LIMIT_DEFAULT)).await
 }
 
 #[tauri::command]
@@ -217,9 +297,7 @@ pub async fn insert_infisso(
     db: State<'_, DatabaseManager>,
     infisso: InfissoDTO,
 ) -> ResultCommand<InfissoDTO> {
-    InfissoService::create(db, infisso)
-        .await
-        .map_err(|e| e.to_string())
+    InfissoService::create(db, infisso).await
 }
 
 #[tauri::command]
@@ -227,9 +305,66 @@ pub async fn update_infisso(
     db: State<'_, DatabaseManager>,
     infisso: InfissoDTO,
 ) -> ResultCommand<InfissoDTO> {
-    InfissoService::update(db, infisso)
+    InfissoService::update(db, infisso).await
+}
+
+/// Analizza il file degli infissi dell'edificio selezionato senza scrivere
+/// nel database
+#[tauri::command]
+pub async fn preview_infissi_from_file<R: Runtime>(
+    app_handle: AppHandle<R>,
+    db: State<'_, DatabaseManager>,
+    selected_edificio: State<'_, SelectedEdificioState<EdificioSelected>>,
+    path: String,
+    profile: Option<String>,
+) -> ResultCommand<ImportInfissiPreview> {
+    let chiave = get_chiave_selected_edificio(selected_edificio)
         .await
-        .map_err(|e| e.to_string())
+        .ok_or(ApplicationError::EdificioNotSelected)?;
+    let df = read_import_file(
+        &app_handle,
+        path.as_str(),
+        profile.as_deref(),
+        COLONNE_INFISSI,
+    )?;
+    ImportInfissi::preview(db, chiave.as_str(), &df).await
+}
+
+#[tauri::command]
+pub async fn import_infissi_from_file<R: Runtime>(
+    app_handle: AppHandle<R>,
+    db: State<'_, DatabaseManager>,
+    selected_edificio: State<'_, SelectedEdificioState<EdificioSelected>>,
+    path: String,
+    profile: Option<String>,
+) -> ResultCommand<ImportInfissiPreview> {
+    let chiave = get_chiave_selected_edificio(selected_edificio)
+        .await
+        .ok_or(ApplicationError::EdificioNotSelected)?;
+    let df = read_import_file(
+        &app_handle,
+        path.as_str(),
+        profile.as_deref(),
+        COLONNE_INFISSI,
+    )?;
+    ImportInfissi::import(db, chiave.as_str(), &df).await
+}
+
+#[tauri::command]
+pub async fn delete_infisso<R: Runtime>(
+    app_handle: AppHandle<R>,
+    db: State<'_, DatabaseManager>,
+    id: String,
+    id_edificio: String,
+) -> ResultCommand<bool> {
+    let deleted = InfissoService::delete(db, (id.clone(), id_edificio.clone())).await?;
+    emit_deleted(
+        &app_handle,
+        "infisso",
+        TypeEvent::DeletedInfisso,
+        (id, id_edificio),
+    )?;
+    Ok(deleted)
 }
 
 /***************************************************************************
@@ -244,9 +379,17 @@ pub async fn get_stanze(
     db: State<'_, DatabaseManager>,
     edificio_selected: State<'_, SelectedEdificioState<EdificioSelected>>,
 ) -> ResultCommand<Vec<StanzaDTO>> {
-    StanzaService::retrieve_by_edificio_selected(db, edificio_selected)
-        .await
-        .map_err(|e| e.to_string())
+    StanzaService::retrieve_by_edificio_selected(db, edificio_selected).await
+}
+
+/// Stanze che soddisfano i filtri, ordinate e paginate. Senza `limit`
+/// restituisce al più `QUERY_LIMIT_DEFAULT` stanze
+#[tauri::command]
+pub async fn query_stanze(
+    db: State<'_, DatabaseManager>,
+    query: StanzaQuery,
+) -> ResultCommand<Vec<StanzaDTO>> {
+    StanzaService::retrieve_by(db, query.limit_or(QUERY_LIMIT_DEFAULT)).await
 }
 
 #[tauri::command]
@@ -254,9 +397,7 @@ pub async fn insert_stanza(
     db: State<'_, DatabaseManager>,
     stanza: StanzaDTO,
 ) -> ResultCommand<StanzaDTO> {
-    StanzaService::create(db, stanza)
-        .await
-        .map_err(|e| e.to_string())
+    StanzaService::create(db, stanza).await
 }
 
 #[tauri::command]
@@ -264,9 +405,18 @@ pub async fn update_stanza(
     db: State<'_, DatabaseManager>,
     stanza: StanzaDTO,
 ) -> ResultCommand<StanzaDTO> {
-    StanzaService::update(db, stanza)
-        .await
-        .map_err(|e| e.to_string())
+    StanzaService::update(db, stanza).await
+}
+
+#[tauri::command]
+pub async fn delete_stanza<R: Runtime>(
+    app_handle: AppHandle<R>,
+    db: State<'_, DatabaseManager>,
+    id: u64,
+) -> ResultCommand<bool> {
+    let deleted = StanzaService::delete(db, id).await?;
+    emit_deleted(&app_handle, "stanza", TypeEvent::DeletedStanza, id)?;
+    Ok(deleted)
 }
 
 /***************************************************************************
@@ -280,16 +430,33 @@ pub async fn update_stanza(
 pub async fn get_all_tipi(
     db: State<'_, DatabaseManager>,
 ) -> ResultCommand<HashMap<String, Vec<Value>>> {
-    TypeServiceImpl::retrieve_all(db)
-        .await
-        .map_err(|e| e.to_string())
+    TypeServiceImpl::retrieve_all(db).await
 }
 
 #[tauri::command]
 pub async fn insert_tipo(db: State<'_, DatabaseManager>, tipo: TipoDTO) -> ResultCommand<TipoDTO> {
-    TypeServiceImpl::insert_type(db, tipo)
-        .await
-        .map_err(|e| e.to_string())
+    TypeServiceImpl::insert_type(db, tipo).await
+}
+
+#[tauri::command]
+pub async fn delete_tipo<R: Runtime>(
+    app_handle: AppHandle<R>,
+    db: State<'_, DatabaseManager>,
+    tipo: TypeDTO,
+    name: String,
+) -> ResultCommand<bool> {
+    let deleted = TypeServiceImpl::delete_type(db, tipo.clone(), name.clone()).await?;
+    emit_deleted(&app_handle, "tipo", TypeEvent::DeletedTipo, (tipo, name))?;
+    Ok(deleted)
+}
+
+/// Aggiorna la trasmittanza di un materiale o di un vetro del catalogo
+#[tauri::command]
+pub async fn update_trasmittanza(
+    db: State<'_, DatabaseManager>,
+    trasmittanza: TrasmittanzaDTO,
+) -> ResultCommand<TrasmittanzaDTO> {
+    TypeServiceImpl::update_trasmittanza(db, trasmittanza).await
 }
 
 /***************************************************************************
@@ -298,15 +465,402 @@ pub async fn insert_tipo(db: State<'_, DatabaseManager>, tipo: TipoDTO) -> Resul
  * ***************************************** */
 /***************************************************************************
  * ********************** */
-/*
+
+#[tauri::command]
+pub async fn export_data_to_excel<R: Runtime>(
+    app_handle: AppHandle<R>,
+    db: State<'_, DatabaseManager>,
+    fascicolo: i32,
+    path: String,
+) -> ResultCommand<()> {
+    let pesi = SettingsStore::load(&app_handle)?.pesi_punteggio;
+    ExportDatiStanzaToExcel::export(db, fascicolo, &pesi, Path::new(&path)).await
+}
+
+/***************************************************************************
+ * ********************** */
+/************************************** COMMAND PER BACKUP
+ * ***************************************** */
+/***************************************************************************
+ * ********************** */
+
+/// Salva i dati dei fascicoli in un file json, o zip se il percorso ha
+/// estensione `.zip`
+#[tauri::command]
+pub async fn export_fascicoli_backup(
+    db: State<'_, DatabaseManager>,
+    fascicoli: Vec<i32>,
+    path: String,
+) -> ResultCommand<()> {
+    let archive = BackupFascicoli::snapshot(db, &fascicoli).await?;
+    archive
+        .write(Path::new(&path))
+        .map_err(ApplicationError::from)
+}
+
+#[tauri::command]
+pub async fn restore_fascicoli_backup<R: Runtime>(
+    app_handle: AppHandle<R>,
+    db: State<'_, DatabaseManager>,
+    selected_edificio: State<'_, SelectedEdificioState<EdificioSelected>>,
+    path: String,
+) -> ResultCommand<RestoreReport> {
+    let archive = BackupArchive::read(Path::new(&path))?;
+    restore_backup(
+        app_handle,
+        db,
+        selected_edificio,
+        &archive,
+        RestoreMode::Insert,
+    )
+    .await
+}
+
+/// Backup automatici salvati nella cartella locale, dal più recente
+#[tauri::command]
+pub async fn get_local_backups(
+    backups: State<'_, LocalBackups>,
+) -> ResultCommand<Vec<LocalBackup>> {
+    backups.list().map_err(ApplicationError::from)
+}
+
+#[tauri::command]
+pub async fn restore_local_backup<R: Runtime>(
+    app_handle: AppHandle<R>,
+    db: State<'_, DatabaseManager>,
+    selected_edificio: State<'_, SelectedEdificioState<EdificioSelected>>,
+    backups: State<'_, LocalBackups>,
+    file_name: String,
+) -> ResultCommand<RestoreReport> {
+    let path = backups.path(&file_name)?;
+    let archive = BackupArchive::read(&path)?;
+    // i backup automatici servono a recuperare un database danneggiato, in cui
+    // gli edifici sono ancora presenti
+    restore_backup(
+        app_handle,
+        db,
+        selected_edificio,
+        &archive,
+        RestoreMode::Replace,
+    )
+    .await
+}
+
+/// Ripristina il backup e seleziona il primo edificio ripristinato
+async fn restore_backup<R: Runtime>(
+    app_handle: AppHandle<R>,
+    db: State<'_, DatabaseManager>,
+    selected_edificio: State<'_, SelectedEdificioState<EdificioSelected>>,
+    archive: &BackupArchive,
+    mode: RestoreMode,
+) -> ResultCommand<RestoreReport> {
+    let report = BackupFascicoli::restore(db, archive, mode).await?;
+
+    if let Some(first) = report.edifici.first() {
+        selected_edificio
+            .write()
+            .await
+            .set_chiave(first.chiave.clone());
+        app_handle
+            .emit(
+                "edificio",
+                EventWrapper::new(
+                    TypeEvent::NewEdificio,
+                    NewEdificioPayload::new(report.edifici.clone(), first.chiave.clone()),
+                ),
+            )
+            .map_err(map_tauri_error)?;
+    }
+
+    Ok(report)
+}
+
+/***************************************************************************
+ * ********************** */
+/************************************** COMMAND PER QUALITÀ DEI DATI
+ * ***************************************** */
+/***************************************************************************
+ * ********************** */
+
+/// Ultimi report di qualità dei dati, solo quello dell'edificio indicato se
+/// presente
+#[tauri::command]
+pub async fn get_data_quality<R: Runtime>(
+    app_handle: AppHandle<R>,
+    edificio: Option<String>,
+) -> ResultCommand<Vec<QualityReport>> {
+    QualityReportStore::load(&app_handle, edificio.as_deref())
+}
+
+/// Superfici, volumi e rapporto aero-illuminante delle stanze dell'edificio,
+/// con i totali per piano e per edificio
+#[tauri::command]
+pub async fn get_metriche_edificio(
+    db: State<'_, DatabaseManager>,
+    edificio: String,
+) -> ResultCommand<EdificioMetrics> {
+    Geometry::edificio(db, edificio.as_str()).await
+}
+
+/// Trasmittanza degli infissi e dispersione per trasmissione delle stanze
+/// dell'edificio
+#[tauri::command]
+pub async fn get_dispersioni_edificio(
+    db: State<'_, DatabaseManager>,
+    edificio: String,
+) -> ResultCommand<EdificioHeatLoss> {
+    Transmittance::edificio(db, edificio.as_str()).await
+}
+
+/// Punteggio energetico dell'edificio, calcolato con i pesi delle impostazioni
+#[tauri::command]
+pub async fn get_punteggio_edificio<R: Runtime>(
+    app_handle: AppHandle<R>,
+    db: State<'_, DatabaseManager>,
+    edificio: String,
+) -> ResultCommand<EdificioScore> {
+    let pesi = SettingsStore::load(&app_handle)?.pesi_punteggio;
+    EnergyScore::edificio(db, edificio.as_str(), &pesi).await
+}
+
+/// Punteggio energetico del fascicolo e dei suoi edifici
+#[tauri::command]
+pub async fn get_punteggio_fascicolo<R: Runtime>(
+    app_handle: AppHandle<R>,
+    db: State<'_, DatabaseManager>,
+    fascicolo: i32,
+) -> ResultCommand<FascicoloScore> {
+    let pesi = SettingsStore::load(&app_handle)?.pesi_punteggio;
+    EnergyScore::fascicolo(db, fascicolo, &pesi).await
+}
+
+/***************************************************************************
+ * ********************** */
+/********************************* COMMAND PER GLI SCENARI
+ * ******************************* */
+/***************************************************************************
+ * ********************** */
+
+#[tauri::command]
+pub async fn get_scenari<R: Runtime>(
+    app_handle: AppHandle<R>,
+    edificio: String,
+) -> ResultCommand<Vec<Scenario>> {
+    ScenarioStore::load(&app_handle, edificio.as_str())
+}
+
+/// Confronto degli indicatori prima e dopo lo scenario, senza salvarlo
+#[tauri::command]
+pub async fn simulate_scenario<R: Runtime>(
+    app_handle: AppHandle<R>,
+    db: State<'_, DatabaseManager>,
+    scenario: Scenario,
+) -> ResultCommand<ScenarioComparison> {
+    let pesi = SettingsStore::load(&app_handle)?.pesi_punteggio;
+    Scenarios::simulate(db, &scenario, &pesi).await
+}
+
+/// Salva lo scenario dopo averlo simulato, così da rifiutare le sostituzioni
+/// con tipi non presenti nei cataloghi
+#[tauri::command]
+pub async fn save_scenario<R: Runtime>(
+    app_handle: AppHandle<R>,
+    db: State<'_, DatabaseManager>,
+    scenario: Scenario,
+) -> ResultCommand<ScenarioComparison> {
+    let pesi = SettingsStore::load(&app_handle)?.pesi_punteggio;
+    let confronto = Scenarios::simulate(db, &scenario, &pesi).await?;
+    ScenarioStore::save(&app_handle, scenario)?;
+    Ok(confronto)
+}
+
+#[tauri::command]
+pub async fn delete_scenario<R: Runtime>(
+    app_handle: AppHandle<R>,
+    edificio: String,
+    nome: String,
+) -> ResultCommand<bool> {
+    ScenarioStore::delete(&app_handle, edificio.as_str(), nome.as_str())
+}
+
+/// Esporta in Excel il confronto degli scenari salvati per l'edificio
+#[tauri::command]
+pub async fn export_scenari<R: Runtime>(
+    app_handle: AppHandle<R>,
+    db: State<'_, DatabaseManager>,
+    edificio: String,
+    path: String,
+) -> ResultCommand<()> {
+    let pesi = SettingsStore::load(&app_handle)?.pesi_punteggio;
+    let scenari = ScenarioStore::load(&app_handle, edificio.as_str())?;
+    let confronti = Scenarios::simulate_all(db, edificio.as_str(), &scenari, &pesi).await?;
+    ExportScenariToExcel::export(&confronti, Path::new(&path))
+}
+
+/***************************************************************************
+ * ********************** */
+/********************************* COMMAND PER LE IMPOSTAZIONI
+ * ******************************* */
+/***************************************************************************
+ * ********************** */
+
+#[tauri::command]
+pub async fn get_settings<R: Runtime>(app_handle: AppHandle<R>) -> ResultCommand<AppSettings> {
+    SettingsStore::load(&app_handle)
+}
+
+#[tauri::command]
+pub async fn save_settings<R: Runtime>(
+    app_handle: AppHandle<R>,
+    settings: AppSettings,
+) -> ResultCommand<()> {
+    SettingsStore::save(&app_handle, &settings)
+}
+
+/***************************************************************************
+ * ********************** */
+/************************** COMMAND PER LA CONNESSIONE AL DATABASE
+ * **************************** */
+/***************************************************************************
+ * ********************** */
+
+#[tauri::command]
+pub async fn get_database_status<R: Runtime>(
+    app_handle: AppHandle<R>,
+    db: State<'_, DatabaseManager>,
+) -> ResultCommand<DatabaseStatus> {
+    DatabaseStatus::read(&app_handle, &db).await
+}
+
+#[tauri::command]
+pub async fn get_connection_profiles<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> ResultCommand<DatabaseProfiles> {
+    Ok(DatabaseProfiles::from(&ConnectionProfileStore::load(
+        &app_handle,
+    )?))
+}
+
+#[tauri::command]
+pub async fn save_connection_profile<R: Runtime>(
+    app_handle: AppHandle<R>,
+    profile: ConnectionProfile,
+) -> ResultCommand<()> {
+    ConnectionProfileStore::save(&app_handle, profile)
+}
+
 #[tauri::command]
-pub async fn export_data_to_excel(
-db: State<'_, DatabaseManager>,
-name_file: Option<String>,
+pub async fn delete_connection_profile<R: Runtime>(
+    app_handle: AppHandle<R>,
+    name: String,
+) -> ResultCommand<bool> {
+    ConnectionProfileStore::delete(&app_handle, name.as_str())
+}
+
+/// Prova a connettersi con il profilo senza salvarlo né usarlo. Senza
+/// password usa quella salvata nel profilo con lo stesso nome.
+#[tauri::command]
+pub async fn test_connection<R: Runtime>(
+    app_handle: AppHandle<R>,
+    profile: ConnectionProfile,
 ) -> ResultCommand<()> {
-ExportDatiStanzaToExcel::export(db, name_file).map_err(|e| e.to_string())
+    let profile = ConnectionProfileStore::with_saved_password(&app_handle, profile)?;
+    tauri::async_runtime::spawn_blocking(move || profile.test())
+        .await
+        .map_err(map_tauri_error)??;
+    Ok(())
+}
+
+/// Connette l'applicazione al database del profilo salvato, dopo averne
+/// aggiornato lo schema, e lo rende il profilo usato ai successivi avvii.
+/// Avvia i task in background se l'applicazione è partita senza database.
+#[tauri::command]
+pub async fn connect_database<R: Runtime>(
+    app_handle: AppHandle<R>,
+    db: State<'_, DatabaseManager>,
+    name: String,
+) -> ResultCommand<MigrationReport> {
+    let profile = ConnectionProfileStore::load(&app_handle)?
+        .profile(name.as_str())?
+        .clone();
+    let report = db.connect(profile).await?;
+    ConnectionProfileStore::set_active(&app_handle, name.as_str())?;
+    info!("Connected to the database with profile {}", name);
+    if let Err(e) = get_background_manager()
+        .lock()
+        .await
+        .start(&app_handle)
+        .await
+    {
+        error!("Error starting the Background Manager: {}", e);
+    }
+    emit_database_status(&app_handle, &db).await;
+    Ok(report)
+}
+
+/***************************************************************************
+ * ********************** */
+/************************************** COMMAND PER TASK IN BACKGROUND
+ * ***************************************** */
+/***************************************************************************
+ * ********************** */
+
+#[tauri::command]
+pub async fn get_background_tasks() -> ResultCommand<Vec<TaskState>> {
+    Ok(get_background_manager().lock().await.tasks())
+}
+
+#[tauri::command]
+pub async fn pause_background_task(name: String) -> ResultCommand<()> {
+    get_background_manager()
+        .lock()
+        .await
+        .pause_task(&name)
+        .await
+}
+
+#[tauri::command]
+pub async fn resume_background_task(name: String) -> ResultCommand<()> {
+    get_background_manager()
+        .lock()
+        .await
+        .resume_task(&name)
+        .await
+}
+
+#[tauri::command]
+pub async fn stop_background_task(name: String) -> ResultCommand<()> {
+    get_background_manager().lock().await.stop_task(&name).await
+}
+
+/// Esecuzioni salvate dalla più recente, di tutti i task se `name` non è
+/// indicato
+#[tauri::command]
+pub async fn get_background_task_history(name: Option<String>) -> ResultCommand<Vec<TaskRun>> {
+    get_background_manager()
+        .lock()
+        .await
+        .history(name.as_deref())
+}
+
+/// Segnala che l'utente sta usando l'applicazione, rimandando i task eseguiti
+/// solo durante l'inattività
+#[tauri::command]
+pub async fn notify_user_activity() -> ResultCommand<()> {
+    get_background_manager().lock().await.record_activity();
+    Ok(())
 }
-*/
+
+/// Esegue subito il task, senza attendere il prossimo intervallo
+#[tauri::command]
+pub async fn trigger_background_task(name: String) -> ResultCommand<()> {
+    get_background_manager()
+        .lock()
+        .await
+        .trigger_task(&name)
+        .await
+}
+
 /***************************************************************************
  * ********************** */
 /************************************ COMMAND PER EDIFICIO
@@ -315,9 +869,7 @@ ExportDatiStanzaToExcel::export(db, name_file).map_err(|e| e.to_string())
  * ********************** */
 #[tauri::command]
 pub async fn get_edifici(db: State<'_, DatabaseManager>) -> ResultCommand<Vec<EdificioDTO>> {
-    EdificioService::retrieve_many(db)
-        .await
-        .map_err(|e| e.to_string())
+    EdificioService::retrieve_many(db).await
 }
 
 #[tauri::command]
@@ -325,9 +877,24 @@ pub async fn update_edificio(
     db: State<'_, DatabaseManager>,
     edificio: EdificioDTO,
 ) -> ResultCommand<EdificioDTO> {
-    EdificioService::update(db, edificio)
-        .await
-        .map_err(|e| e.to_string())
+    EdificioService::update(db, edificio).await
+}
+
+#[tauri::command]
+pub async fn delete_edificio<R: Runtime>(
+    app_handle: AppHandle<R>,
+    db: State<'_, DatabaseManager>,
+    edificio_selected: State<'_, SelectedEdificioState<EdificioSelected>>,
+    chiave: String,
+) -> ResultCommand<bool> {
+    let deleted = EdificioService::delete(db, chiave.clone()).await?;
+
+    if get_chiave_selected_edificio(edificio_selected.clone()).await == Some(chiave.clone()) {
+        EdificioService::clear_edificio(edificio_selected).await;
+    }
+
+    emit_deleted(&app_handle, "edificio", TypeEvent::DeletedEdificio, chiave)?;
+    Ok(deleted)
 }
 
 /***************************************************************************
@@ -346,9 +913,17 @@ pub async fn get_utenze(
         return Ok(Vec::new());
     }
 
-    UtenzeService::retrieve_by_edificio_selected(db, selected_edificio)
-        .await
-        .map_err(|e| e.to_string())
+    UtenzeService::retrieve_by_edificio_selected(db, selected_edificio).await
+}
+
+/// Utenze che soddisfano i filtri, ordinate e paginate. Senza `limit`
+/// restituisce al più `QUERY_LIMIT_DEFAULT` utenze
+#[tauri::command]
+pub async fn query_utenze(
+    db: State<'_, DatabaseManager>,
+    query: UtenzaQuery,
+) -> ResultCommand<Vec<UtenzaDTO>> {
+    UtenzeService::retrieve_by(db, query.limit_or(QUERY_LIMIT_DEFAULT)).await
 }
 
 #[tauri::command]
@@ -358,7 +933,7 @@ pub async fn insert_utenza(
     utenza: UtenzaDTO,
 ) -> ResultCommand<UtenzaDTO> {
     if !is_selected_edificio(selected_edificio.clone()).await {
-        return Err("Non selezionato un edificio".to_string());
+        return Err(ApplicationError::EdificioNotSelected);
     }
 
     if get_chiave_selected_edificio(selected_edificio)
@@ -366,12 +941,31 @@ pub async fn insert_utenza(
         .unwrap()
         != utenza.edificio_id
     {
-        return Err("Chiave non corrispondente".to_string());
+        return Err(
+            DomainError::InvalidInput(ErrorKind::InvalidField, "edificio_id".to_string()).into(),
+        );
     }
 
-    UtenzeService::create(db, utenza)
-        .await
-        .map_err(|e| e.to_string())
+    UtenzeService::create(db, utenza).await
+}
+
+#[tauri::command]
+pub async fn update_utenza(
+    db: State<'_, DatabaseManager>,
+    utenza: UtenzaDTO,
+) -> ResultCommand<UtenzaDTO> {
+    UtenzeService::update(db, utenza).await
+}
+
+#[tauri::command]
+pub async fn delete_utenza<R: Runtime>(
+    app_handle: AppHandle<R>,
+    db: State<'_, DatabaseManager>,
+    id: u64,
+) -> ResultCommand<bool> {
+    let deleted = UtenzeService::delete(db, id).await?;
+    emit_deleted(&app_handle, "utenza", TypeEvent::DeletedUtenza, id)?;
+    Ok(deleted)
 }
 
 /***************************************************************************
@@ -389,9 +983,17 @@ pub async fn get_fotovoltaico(
         return Ok(Vec::new());
     }
 
-    FotovoltaicoService::retrieve_by_edificio_selected(db, selected_edificio)
-        .await
-        .map_err(|e| e.to_string())
+    FotovoltaicoService::retrieve_by_edificio_selected(db, selected_edificio).await
+}
+
+/// Impianti che soddisfano i filtri, ordinati e paginati. Senza `limit`
+/// restituisce al più `QUERY_LIMIT_DEFAULT` impianti
+#[tauri::command]
+pub async fn query_fotovoltaico(
+    db: State<'_, DatabaseManager>,
+    query: FotovoltaicoQuery,
+) -> ResultCommand<Vec<FotovoltaicoDTO>> {
+    FotovoltaicoService::retrieve_by(db, query.limit_or(QUERY_LIMIT_DEFAULT)).await
 }
 
 #[tauri::command]
@@ -401,7 +1003,7 @@ pub async fn insert_fotovoltaico(
     fotovoltaico: FotovoltaicoDTO,
 ) -> ResultCommand<FotovoltaicoDTO> {
     if !is_selected_edificio(selected_edificio.clone()).await {
-        return Err("Non selezionato un edificio".to_string());
+        return Err(ApplicationError::EdificioNotSelected);
     }
 
     if get_chiave_selected_edificio(selected_edificio)
@@ -409,12 +1011,46 @@ pub async fn insert_fotovoltaico(
         .unwrap()
         != fotovoltaico.id_edificio
     {
-        return Err("Chiave non corrispondente".to_string());
+        return Err(
+            DomainError::InvalidInput(ErrorKind::InvalidField, "id_edificio".to_string()).into(),
+        );
     }
 
-    FotovoltaicoService::create(db, fotovoltaico)
-        .await
-        .map_err(|e| e.to_string())
+    FotovoltaicoService::create(db, fotovoltaico).await
+}
+
+#[tauri::command]
+pub async fn update_fotovoltaico(
+    db: State<'_, DatabaseManager>,
+    fotovoltaico: FotovoltaicoDTO,
+) -> ResultCommand<FotovoltaicoDTO> {
+    FotovoltaicoService::update(db, fotovoltaico).await
+}
+
+#[tauri::command]
+pub async fn delete_fotovoltaico<R: Runtime>(
+    app_handle: AppHandle<R>,
+    db: State<'_, DatabaseManager>,
+    id: u64,
+) -> ResultCommand<bool> {
+    let deleted = FotovoltaicoService::delete(db, id).await?;
+    emit_deleted(
+        &app_handle,
+        "fotovoltaico",
+        TypeEvent::DeletedFotovoltaico,
+        id,
+    )?;
+    Ok(deleted)
+}
+
+/// Produzione annua stimata degli impianti dell'edificio, confrontata con il
+/// consumo elettrico delle utenze
+#[tauri::command]
+pub async fn get_stima_fotovoltaico(
+    db: State<'_, DatabaseManager>,
+    edificio: String,
+) -> ResultCommand<StimaProduzioneDTO> {
+    FotovoltaicoService::stima_produzione(db, &edificio).await
 }
 
 /***************************************************************************
@@ -432,24 +1068,21 @@ pub async fn get_annotazioni(
         TableWithPrimaryKey::Edificio(..) => Ok(<AnnotazioneService as RetrieveManyService<
             AnnotazioneEdificioDTO,
         >>::retrieve_many(db)
-        .await
-        .map_err(|e| e.to_string())?
+        .await?
         .into_iter()
         .map(AnnotazioneDTO::from)
         .collect::<Vec<AnnotazioneDTO>>()),
         TableWithPrimaryKey::Stanza(..) => Ok(<AnnotazioneService as RetrieveManyService<
             AnnotazioneStanzaDTO,
         >>::retrieve_many(db)
-        .await
-        .map_err(|e| e.to_string())?
+        .await?
         .into_iter()
         .map(AnnotazioneDTO::from)
         .collect::<Vec<AnnotazioneDTO>>()),
         TableWithPrimaryKey::Infisso(..) => Ok(<AnnotazioneService as RetrieveManyService<
             AnnotazioneInfissoDTO,
         >>::retrieve_many(db)
-        .await
-        .map_err(|e| e.to_string())?
+        .await?
         .into_iter()
         .map(AnnotazioneDTO::from)
         .collect::<Vec<AnnotazioneDTO>>()),
@@ -465,24 +1098,48 @@ pub async fn insert_annotazione(
         TableWithPrimaryKey::Edificio(..) => Ok(<AnnotazioneService as CreateService<
             AnnotazioneEdificioDTO,
         >>::create(db, annotazione.into())
-        .await
-        .map_err(|e| e.to_string())?
+        .await?
         .into()),
         TableWithPrimaryKey::Stanza(..) => Ok(<AnnotazioneService as CreateService<
             AnnotazioneStanzaDTO,
         >>::create(db, annotazione.into())
-        .await
-        .map_err(|e| e.to_string())?
+        .await?
         .into()),
         TableWithPrimaryKey::Infisso(..) => Ok(<AnnotazioneService as CreateService<
             AnnotazioneInfissoDTO,
         >>::create(db, annotazione.into())
-        .await
-        .map_err(|e| e.to_string())?
+        .await?
         .into()),
     }
 }
 
+#[tauri::command]
+pub async fn delete_annotazione<R: Runtime>(
+    app_handle: AppHandle<R>,
+    db: State<'_, DatabaseManager>,
+    table: TableWithPrimaryKey,
+    id: u64,
+) -> ResultCommand<bool> {
+    let deleted = match table {
+        TableWithPrimaryKey::Edificio(..) => {
+            <AnnotazioneService as DeleteService<AnnotazioneEdificioDTO, u64>>::delete(db, id).await
+        }
+        TableWithPrimaryKey::Stanza(..) => {
+            <AnnotazioneService as DeleteService<AnnotazioneStanzaDTO, u64>>::delete(db, id).await
+        }
+        TableWithPrimaryKey::Infisso(..) => {
+            <AnnotazioneService as DeleteService<AnnotazioneInfissoDTO, u64>>::delete(db, id).await
+        }
+    }?;
+    emit_deleted(
+        &app_handle,
+        "annotazione",
+        TypeEvent::DeletedAnnotazione,
+        (table.to_string(), id),
+    )?;
+    Ok(deleted)
+}
+
 #[cfg(test)]
 mod tests {
     use app_utils::{
@@ -497,7 +1154,7 @@ mod tests {
     use super::*;
 
     #[tokio::test]
-    async fn test_add_new_fascicolo_from_xlsx() {
+    async fn test_add_new_fascicolo_from_file() {
         let app = tauri::test::mock_app();
         let app_handle = app.handle();
 
@@ -518,11 +1175,12 @@ mod tests {
             assert!(event.payload().contains("edificio_change"))
         });
 
-        match add_new_fascicolo_from_xlsx(
+        match add_new_fascicolo_from_file(
             app_handle.clone(),
             db_state.clone(),
             selected_edificio.clone(),
             path.to_string(),
+            None,
         )
         .await
         {
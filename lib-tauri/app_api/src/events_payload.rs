@@ -1,11 +1,25 @@
+use app_data_processing::QualityReport;
 use app_services::dto::EdificioDTO;
+use app_task_background::TaskState;
 use serde::Serialize;
 
+use crate::settings::DatabaseStatus;
+
 #[derive(Serialize, Clone)]
 #[non_exhaustive]
 pub enum TypeEvent {
     ChangedEdificio,
     NewEdificio,
+    DeletedEdificio,
+    DeletedStanza,
+    DeletedInfisso,
+    DeletedUtenza,
+    DeletedFotovoltaico,
+    DeletedAnnotazione,
+    DeletedTipo,
+    TaskStatusChanged,
+    DataQualityUpdated,
+    DatabaseStatusChanged,
 }
 
 #[derive(Serialize, Clone)]
@@ -55,3 +69,45 @@ impl NewEdificioPayload {
         }
     }
 }
+
+#[derive(Serialize, Clone)]
+pub struct DeletedPayload<K>
+where
+    K: Serialize + Clone,
+{
+    id: K,
+}
+
+impl<K> DeletedPayload<K>
+where
+    K: Serialize + Clone,
+{
+    pub fn new(id: K) -> Self { Self { id } }
+}
+
+#[derive(Serialize, Clone)]
+pub struct TaskStatusPayload {
+    task: TaskState,
+}
+
+impl TaskStatusPayload {
+    pub fn new(task: TaskState) -> Self { Self { task } }
+}
+
+#[derive(Serialize, Clone)]
+pub struct DataQualityPayload {
+    reports: Vec<QualityReport>,
+}
+
+impl DataQualityPayload {
+    pub fn new(reports: Vec<QualityReport>) -> Self { Self { reports } }
+}
+
+#[derive(Serialize, Clone)]
+pub struct DatabaseStatusPayload {
+    status: DatabaseStatus,
+}
+
+impl DatabaseStatusPayload {
+    pub fn new(status: DatabaseStatus) -> Self { Self { status } }
+}
@@ -1,11 +1,21 @@
-use std::ops::Deref;
+use std::{ops::Deref, sync::Arc};
 
-use app_state::selected_edificio::EdificioSelected;
+use app_state::{database::DatabaseManager, selected_edificio::EdificioSelected};
+use app_task_background::{QualityListener, StatusListener};
 use app_utils::app_interface::service_interface::{SelectedEdificioState, SelectedEdificioTrait};
-use tauri::State;
+use log::error;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+use crate::{
+    events_payload::{
+        DataQualityPayload, DatabaseStatusPayload, EventWrapper, TaskStatusPayload, TypeEvent,
+    },
+    settings::DatabaseStatus,
+};
 
 pub mod command;
 mod events_payload;
+pub mod settings;
 
 async fn is_selected_edificio(
     selected_edificio: State<'_, SelectedEdificioState<EdificioSelected>>,
@@ -20,3 +30,56 @@ async fn get_chiave_selected_edificio(
 ) -> Option<String> {
     selected_edificio.deref().read().await.deref().get_chiave()
 }
+
+/// Listener che invia al frontend l'evento `background_task` a ogni cambio di
+/// stato dei task in background
+pub fn background_task_listener<R: Runtime>(app_handle: AppHandle<R>) -> StatusListener {
+    Arc::new(move |task| {
+        let event = EventWrapper::new(TypeEvent::TaskStatusChanged, TaskStatusPayload::new(task));
+        if let Err(e) = app_handle.emit("background_task", event) {
+            error!("Error emitting the task status: {}", e);
+        }
+    })
+}
+
+/// Listener che invia al frontend l'evento `data_quality` con i report
+/// calcolati dall'analisi della qualità dei dati
+pub fn data_quality_listener<R: Runtime>(app_handle: AppHandle<R>) -> QualityListener {
+    Arc::new(move |reports| {
+        let event = EventWrapper::new(
+            TypeEvent::DataQualityUpdated,
+            DataQualityPayload::new(reports),
+        );
+        if let Err(e) = app_handle.emit("data_quality", event) {
+            error!("Error emitting the data quality reports: {}", e);
+        }
+    })
+}
+
+/// Allinea lo schema del database connesso all'avvio e invia al frontend
+/// l'evento `database_status` con l'esito
+pub async fn check_database_schema<R: Runtime>(app_handle: &AppHandle<R>) {
+    let db = app_handle.state::<DatabaseManager>();
+    if db.is_connected().await {
+        // l'errore resta nello stato dello schema del DatabaseManager
+        let _ = db.migrate().await;
+    }
+    emit_database_status(app_handle, &db).await;
+}
+
+async fn emit_database_status<R: Runtime>(app_handle: &AppHandle<R>, db: &DatabaseManager) {
+    let status = match DatabaseStatus::read(app_handle, db).await {
+        Ok(status) => status,
+        Err(e) => {
+            error!("Error reading the database status: {}", e);
+            return;
+        }
+    };
+    let event = EventWrapper::new(
+        TypeEvent::DatabaseStatusChanged,
+        DatabaseStatusPayload::new(status),
+    );
+    if let Err(e) = app_handle.emit("database_status", event) {
+        error!("Error emitting the database status: {}", e);
+    }
+}
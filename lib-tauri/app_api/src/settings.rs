@@ -0,0 +1,221 @@
+use app_data_processing::ScoreWeights;
+use app_state::database::{ConnectionProfile, DatabaseManager, SchemaState};
+use app_utils::{
+    app_error::{AppResult, InfrastructureError, Locale},
+    store::{JsonStore, map_store_error},
+};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+
+/// Impostazioni dell'applicazione scelte dall'utente
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppSettings {
+    /// Lingua dei messaggi di errore
+    pub locale: Locale,
+    /// Pesi dei componenti del punteggio energetico
+    pub pesi_punteggio: ScoreWeights,
+}
+
+pub struct SettingsStore;
+
+impl SettingsStore {
+    const STORE: JsonStore = JsonStore::new("settings.json", "settings");
+
+    /// Impostazioni salvate, quelle predefinite se non ancora salvate
+    pub fn load<R: Runtime>(app_handle: &AppHandle<R>) -> AppResult<AppSettings> {
+        Ok(Self::STORE
+            .load(app_handle, map_store_error)?
+            .unwrap_or_default())
+    }
+
+    /// Salva le impostazioni e le applica
+    pub fn save<R: Runtime>(app_handle: &AppHandle<R>, settings: &AppSettings) -> AppResult<()> {
+        settings.pesi_punteggio.validate()?;
+        Self::STORE.save(app_handle, settings, map_store_error)?;
+        Self::apply(settings);
+        Ok(())
+    }
+
+    /// Applica all'avvio le impostazioni salvate, restando su quelle
+    /// predefinite se non possono essere lette
+    pub fn apply_saved<R: Runtime>(app_handle: &AppHandle<R>) {
+        match Self::load(app_handle) {
+            Ok(settings) => Self::apply(&settings),
+            Err(e) => warn!("Settings not loaded, using the defaults: {}", e),
+        }
+    }
+
+    fn apply(settings: &AppSettings) { Locale::set_current(settings.locale); }
+}
+
+/// Profili di connessione al database e nome di quello in uso
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DatabaseSettings {
+    pub profiles: Vec<ConnectionProfile>,
+    pub active: Option<String>,
+}
+
+impl DatabaseSettings {
+    pub fn profile(&self, name: &str) -> AppResult<&ConnectionProfile> {
+        self.profiles
+            .iter()
+            .find(|profile| profile.name == name)
+            .ok_or_else(|| InfrastructureError::ConnectionProfileNotFound(name.to_string()).into())
+    }
+}
+
+/// Profilo di connessione inviato al frontend, senza la password salvata
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConnectionProfileSummary {
+    pub name: String,
+    pub host: String,
+    pub port: u16,
+    pub database: String,
+    pub user: String,
+    pub pool_size: u32,
+    pub has_password: bool,
+}
+
+impl From<&ConnectionProfile> for ConnectionProfileSummary {
+    fn from(profile: &ConnectionProfile) -> Self {
+        Self {
+            name: profile.name.clone(),
+            host: profile.host.clone(),
+            port: profile.port,
+            database: profile.database.clone(),
+            user: profile.user.clone(),
+            pool_size: profile.pool_size,
+            has_password: !profile.password.is_empty(),
+        }
+    }
+}
+
+/// Profili di connessione inviati al frontend
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DatabaseProfiles {
+    pub profiles: Vec<ConnectionProfileSummary>,
+    pub active: Option<String>,
+}
+
+impl From<&DatabaseSettings> for DatabaseProfiles {
+    fn from(settings: &DatabaseSettings) -> Self {
+        Self {
+            profiles: settings
+                .profiles
+                .iter()
+                .map(ConnectionProfileSummary::from)
+                .collect(),
+            active: settings.active.clone(),
+        }
+    }
+}
+
+/// Stato della connessione e dello schema mostrato all'avvio
+#[derive(Debug, Clone, Serialize)]
+pub struct DatabaseStatus {
+    pub connected: bool,
+    pub active: Option<String>,
+    pub schema: SchemaState,
+}
+
+impl DatabaseStatus {
+    pub async fn read<R: Runtime>(
+        app_handle: &AppHandle<R>,
+        db: &DatabaseManager,
+    ) -> AppResult<Self> {
+        Ok(Self {
+            connected: db.is_connected().await,
+            active: ConnectionProfileStore::load(app_handle)?.active,
+            schema: db.schema().await,
+        })
+    }
+}
+
+pub struct ConnectionProfileStore;
+
+impl ConnectionProfileStore {
+    const STORE: JsonStore = JsonStore::new("database.json", "connection");
+
+    pub fn load<R: Runtime>(app_handle: &AppHandle<R>) -> AppResult<DatabaseSettings> {
+        Ok(Self::STORE
+            .load(app_handle, map_store_error)?
+            .unwrap_or_default())
+    }
+
+    fn store<R: Runtime>(app_handle: &AppHandle<R>, settings: &DatabaseSettings) -> AppResult<()> {
+        Self::STORE.save(app_handle, settings, map_store_error)
+    }
+
+    /// Profilo in uso, `None` se l'utente non ne ha ancora scelto uno
+    pub fn active<R: Runtime>(app_handle: &AppHandle<R>) -> AppResult<Option<ConnectionProfile>> {
+        let settings = Self::load(app_handle)?;
+        match settings.active.as_deref() {
+            Some(name) => settings.profile(name).cloned().map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Completa un profilo ricevuto dal frontend: senza password viene usata
+    /// quella salvata nel profilo con lo stesso nome, solo se il profilo si
+    /// collega ancora allo stesso server con lo stesso utente
+    pub fn with_saved_password<R: Runtime>(
+        app_handle: &AppHandle<R>,
+        mut profile: ConnectionProfile,
+    ) -> AppResult<ConnectionProfile> {
+        if !profile.password.is_empty() {
+            return Ok(profile);
+        }
+        let saved = Self::load(app_handle)?
+            .profiles
+            .into_iter()
+            .find(|saved| saved.name == profile.name && saved.same_server(&profile));
+        if let Some(saved) = saved {
+            profile.password = saved.password;
+        }
+        Ok(profile)
+    }
+
+    /// Aggiunge il profilo o sostituisce quello con lo stesso nome,
+    /// mantenendo la password salvata se non ne viene indicata una nuova
+    pub fn save<R: Runtime>(
+        app_handle: &AppHandle<R>,
+        profile: ConnectionProfile,
+    ) -> AppResult<()> {
+        let profile = Self::with_saved_password(app_handle, profile)?;
+        profile.validate()?;
+        let mut settings = Self::load(app_handle)?;
+        match settings
+            .profiles
+            .iter_mut()
+            .find(|p| p.name == profile.name)
+        {
+            Some(existing) => *existing = profile,
+            None => settings.profiles.push(profile),
+        }
+        Self::store(app_handle, &settings)
+    }
+
+    pub fn delete<R: Runtime>(app_handle: &AppHandle<R>, name: &str) -> AppResult<bool> {
+        let mut settings = Self::load(app_handle)?;
+        let len = settings.profiles.len();
+        settings.profiles.retain(|profile| profile.name != name);
+        if settings.profiles.len() == len {
+            return Ok(false);
+        }
+        if settings.active.as_deref() == Some(name) {
+            settings.active = None;
+        }
+        Self::store(app_handle, &settings)?;
+        Ok(true)
+    }
+
+    pub fn set_active<R: Runtime>(app_handle: &AppHandle<R>, name: &str) -> AppResult<()> {
+        let mut settings = Self::load(app_handle)?;
+        settings.profile(name)?;
+        settings.active = Some(name.to_string());
+        Self::store(app_handle, &settings)
+    }
+}
@@ -7,12 +7,20 @@ use std::{
     time::Duration,
 };
 
-use app_api::command::*;
+use app_api::{
+    background_task_listener, check_database_schema,
+    command::*,
+    data_quality_listener,
+    settings::{ConnectionProfileStore, SettingsStore},
+};
+use app_data_processing::LocalBackups;
 use app_state::{
     database::DatabaseManager,
     selected_edificio::{EdificioSelected, SelectedEdificioTrait},
 };
-use app_task_background::get_background_manager;
+use app_task_background::{
+    BackgroundTask, DataQualityScanner, IdStanzeProcessor, LocalBackupTask, get_background_manager,
+};
 use dirs_next::document_dir;
 use log::{error, info, warn};
 use tauri::{App, Builder, Manager, Wry, async_runtime::RwLock};
@@ -43,13 +51,33 @@ pub fn initialize_tauri() -> Builder<Wry> {
             setup_logger(app)?;
             info!("Starting application ...");
 
+            SettingsStore::apply_saved(app.handle());
+
             // Manage Database
-            tauri::async_runtime::block_on(async {
-                let database = DatabaseManager::new().await;
-                info!("Database Manager is created successfully");
+            // Senza un profilo valido l'applicazione parte comunque e mostra la
+            // configurazione della connessione
+            let profile = ConnectionProfileStore::active(app.handle()).unwrap_or_else(|e| {
+                warn!("Connection profile not loaded: {}", e);
+                None
+            });
+            let connected = tauri::async_runtime::block_on(async {
+                let database = DatabaseManager::new(profile).await;
+                let connected = database.is_connected().await;
+                if connected {
+                    info!("Database Manager is created successfully");
+                } else {
+                    warn!("Database not connected, waiting for a connection profile");
+                }
                 app.manage(database);
+                check_database_schema(app.handle()).await;
+                connected
             });
 
+            // Manage local backups
+            let mut backup_directory = document_dir().unwrap();
+            backup_directory.push(format!("{NAME_DIR_DATABASE}/backup"));
+            app.manage(LocalBackups::new(backup_directory));
+
             // Manage Edificio Selected
             let stato_edificio = Arc::new(RwLock::new(EdificioSelected::new()));
             app.manage(stato_edificio);
@@ -60,10 +88,37 @@ pub fn initialize_tauri() -> Builder<Wry> {
 
             tauri::async_runtime::spawn(async move {
                 let mut manager = bg_manager.lock().await;
+                manager.set_listener(background_task_listener(app_handle.clone()));
                 let app_handle_arc = Arc::new(app_handle);
 
-                if let Err(e) = manager.start(app_handle_arc).await {
-                    eprintln!("Errore during starting the Background Manager: {}", e);
+                let id_stanze = IdStanzeProcessor::new(app_handle_arc.clone());
+                let local_backup = LocalBackupTask::new(app_handle_arc.clone());
+                let data_quality = DataQualityScanner::new(
+                    app_handle_arc.clone(),
+                    data_quality_listener(app_handle_arc.as_ref().clone()),
+                );
+                let registrations = [
+                    (id_stanze.info().name(), manager.register(id_stanze).await),
+                    (
+                        local_backup.info().name(),
+                        manager.register(local_backup).await,
+                    ),
+                    (
+                        data_quality.info().name(),
+                        manager.register(data_quality).await,
+                    ),
+                ];
+                for (name, result) in registrations {
+                    if let Err(e) = result {
+                        error!("Error registering background task {}: {}", name, e);
+                    }
+                }
+                // senza database i task vengono avviati da `connect_database`
+                if !connected {
+                    return;
+                }
+                if let Err(e) = manager.start(&app_handle_arc).await {
+                    error!("Error starting the Background Manager: {}", e);
                 }
             });
 
@@ -72,8 +127,45 @@ pub fn initialize_tauri() -> Builder<Wry> {
         })
         .invoke_handler(tauri::generate_handler![
             // miscellaneous
-            //export_data_to_excel,
-            add_new_fascicolo_from_xlsx,
+            export_data_to_excel,
+            export_fascicoli_backup,
+            restore_fascicoli_backup,
+            get_local_backups,
+            restore_local_backup,
+            get_data_quality,
+            get_metriche_edificio,
+            get_dispersioni_edificio,
+            get_punteggio_edificio,
+            get_punteggio_fascicolo,
+            // scenari
+            get_scenari,
+            simulate_scenario,
+            save_scenario,
+            delete_scenario,
+            export_scenari,
+            get_settings,
+            save_settings,
+            // connessione al database
+            get_database_status,
+            get_connection_profiles,
+            save_connection_profile,
+            delete_connection_profile,
+            test_connection,
+            connect_database,
+            // task in background
+            get_background_tasks,
+            get_background_task_history,
+            pause_background_task,
+            resume_background_task,
+            stop_background_task,
+            trigger_background_task,
+            notify_user_activity,
+            preview_fascicolo_from_file,
+            add_new_fascicolo_from_file,
+            get_mapping_profiles,
+            save_mapping_profile,
+            delete_mapping_profile,
+            suggest_mapping_profile,
             // database
             set_edificio,
             clear_edificio,
@@ -81,26 +173,43 @@ pub fn initialize_tauri() -> Builder<Wry> {
             // tipi
             get_all_tipi,
             insert_tipo,
+            delete_tipo,
+            update_trasmittanza,
             // stanza
             get_stanze,
+            query_stanze,
             insert_stanza,
             update_stanza,
+            delete_stanza,
             // infisso
             get_infissi,
+            query_infissi,
             insert_infisso,
             update_infisso,
+            delete_infisso,
+            preview_infissi_from_file,
+            import_infissi_from_file,
             // edificio
             get_edifici,
             update_edificio,
+            delete_edificio,
             // utenze
             get_utenze,
+            query_utenze,
             insert_utenza,
+            update_utenza,
+            delete_utenza,
             // fotovoltaico
             get_fotovoltaico,
+            query_fotovoltaico,
             insert_fotovoltaico,
+            update_fotovoltaico,
+            delete_fotovoltaico,
+            get_stima_fotovoltaico,
             // annotazioni
             get_annotazioni,
-            insert_annotazione
+            insert_annotazione,
+            delete_annotazione
         ])
         .on_window_event(handle_window_events)
 }
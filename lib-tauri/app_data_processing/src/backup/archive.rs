@@ -0,0 +1,328 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs::File,
+    io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+use app_services::dto::{EdificioDTO, FotovoltaicoDTO, InfissoDTO, StanzaDTO, UtenzaDTO};
+use app_utils::app_error::ErrorBackup;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use zip::{ZipArchive, ZipWriter, write::SimpleFileOptions};
+
+/// Versione del formato scritta nei nuovi backup. I backup con una versione
+/// successiva vengono rifiutati.
+pub const BACKUP_VERSION: u32 = 2;
+/// Nome del file json all'interno dell'archivio zip
+const ZIP_ENTRY: &str = "backup.json";
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+
+fn io_error(e: impl ToString) -> ErrorBackup { ErrorBackup::Io(e.to_string()) }
+
+fn archive_error(e: impl ToString) -> ErrorBackup { ErrorBackup::InvalidArchive(e.to_string()) }
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VoceCatalogo {
+    pub nome: String,
+    pub eff_energetica: i16,
+    /// Trasmittanza del telaio, solo per i materiali degli infissi
+    #[serde(default)]
+    pub uf: Option<f32>,
+    /// Trasmittanza del vetro, solo per i vetri degli infissi
+    #[serde(default)]
+    pub ug: Option<f32>,
+}
+
+/// Voci dei cataloghi usate dai dati del backup
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Cataloghi {
+    pub tipi_infisso: Vec<String>,
+    pub materiali_infisso: Vec<VoceCatalogo>,
+    pub vetri_infisso: Vec<VoceCatalogo>,
+    pub climatizzazione: Vec<VoceCatalogo>,
+    pub illuminazione: Vec<VoceCatalogo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StanzaConInfissiBackup {
+    pub infisso_id: String,
+    pub edificio_id: String,
+    pub stanza_id: i32,
+    pub num_infisso: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AnnotazioneEdificioBackup {
+    pub id: i32,
+    pub edificio_id: String,
+    pub content: String,
+    pub data: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AnnotazioneStanzaBackup {
+    pub id: i32,
+    pub stanza_id: i32,
+    pub content: String,
+    pub data: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AnnotazioneInfissoBackup {
+    pub id: i32,
+    pub infisso_id: String,
+    pub edificio_id: String,
+    pub content: String,
+    pub data: NaiveDateTime,
+}
+
+/// Contenuto di un backup di uno o più fascicoli. Gli id delle stanze e delle
+/// annotazioni sono quelli del database di origine e vengono riassegnati al
+/// ripristino.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BackupArchive {
+    pub version: u32,
+    pub created_at: NaiveDateTime,
+    pub fascicoli: Vec<i32>,
+    #[serde(default)]
+    pub cataloghi: Cataloghi,
+    pub edifici: Vec<EdificioDTO>,
+    #[serde(default)]
+    pub stanze: Vec<StanzaDTO>,
+    #[serde(default)]
+    pub infissi: Vec<InfissoDTO>,
+    #[serde(default)]
+    pub stanze_con_infissi: Vec<StanzaConInfissiBackup>,
+    #[serde(default)]
+    pub fotovoltaico: Vec<FotovoltaicoDTO>,
+    #[serde(default)]
+    pub utenze: Vec<UtenzaDTO>,
+    #[serde(default)]
+    pub annotazioni_edificio: Vec<AnnotazioneEdificioBackup>,
+    #[serde(default)]
+    pub annotazioni_stanza: Vec<AnnotazioneStanzaBackup>,
+    #[serde(default)]
+    pub annotazioni_infisso: Vec<AnnotazioneInfissoBackup>,
+}
+
+impl BackupArchive {
+    /// Scrive il backup come json, oppure come archivio zip contenente il json
+    /// se il file ha estensione `.zip`
+    pub fn write(&self, path: &Path) -> Result<(), ErrorBackup> {
+        let mut writer = BufWriter::new(File::create(path).map_err(io_error)?);
+        let is_zip = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"));
+
+        if is_zip {
+            let mut zip = ZipWriter::new(writer);
+            zip.start_file(ZIP_ENTRY, SimpleFileOptions::default())
+                .map_err(io_error)?;
+            serde_json::to_writer_pretty(&mut zip, self).map_err(io_error)?;
+            zip.finish().map_err(io_error)?.flush().map_err(io_error)
+        } else {
+            serde_json::to_writer_pretty(&mut writer, self).map_err(io_error)?;
+            writer.flush().map_err(io_error)
+        }
+    }
+
+    /// Legge un backup json o zip, riconoscendo il formato dal contenuto
+    pub fn read(path: &Path) -> Result<Self, ErrorBackup> {
+        let mut reader = BufReader::new(File::open(path).map_err(io_error)?);
+        let mut head = Vec::with_capacity(ZIP_MAGIC.len());
+        (&mut reader)
+            .take(ZIP_MAGIC.len() as u64)
+            .read_to_end(&mut head)
+            .map_err(io_error)?;
+        reader.seek(SeekFrom::Start(0)).map_err(io_error)?;
+
+        let value: serde_json::Value = if head == ZIP_MAGIC {
+            let mut zip = ZipArchive::new(reader).map_err(archive_error)?;
+            let entry = zip.by_name(ZIP_ENTRY).map_err(archive_error)?;
+            serde_json::from_reader(entry).map_err(archive_error)?
+        } else {
+            serde_json::from_reader(reader).map_err(archive_error)?
+        };
+        Self::from_value(value)
+    }
+
+    fn from_value(value: serde_json::Value) -> Result<Self, ErrorBackup> {
+        let version = value
+            .get("version")
+            .and_then(serde_json::Value::as_u64)
+            .ok_or_else(|| ErrorBackup::InvalidArchive("missing version".to_string()))?;
+        if version > BACKUP_VERSION as u64 {
+            return Err(ErrorBackup::UnsupportedVersion {
+                found: version as u32,
+                supported: BACKUP_VERSION,
+            });
+        }
+        serde_json::from_value(value).map_err(archive_error)
+    }
+
+    /// Controlla che ogni riferimento punti a un dato presente nel backup.
+    /// Restituisce la descrizione dei riferimenti non validi.
+    pub fn check_integrity(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        let nomi = |voci: &[VoceCatalogo]| -> HashSet<String> {
+            voci.iter().map(|voce| voce.nome.clone()).collect()
+        };
+        let tipi: HashSet<&String> = self.cataloghi.tipi_infisso.iter().collect();
+        let materiali = nomi(&self.cataloghi.materiali_infisso);
+        let vetri = nomi(&self.cataloghi.vetri_infisso);
+        let climatizzazione = nomi(&self.cataloghi.climatizzazione);
+        let illuminazione = nomi(&self.cataloghi.illuminazione);
+
+        let mut edifici = HashSet::new();
+        for edificio in &self.edifici {
+            if !edifici.insert(edificio.chiave.as_str()) {
+                errors.push(format!("Edificio {} duplicato", edificio.chiave));
+            }
+        }
+
+        let mut stanze = HashMap::new();
+        for stanza in &self.stanze {
+            if !edifici.contains(stanza.edificio_id.as_str()) {
+                errors.push(format!(
+                    "Stanza {}: edificio {} non presente",
+                    stanza.id, stanza.edificio_id
+                ));
+            }
+            if stanze
+                .insert(stanza.id as i32, stanza.edificio_id.as_str())
+                .is_some()
+            {
+                errors.push(format!("Stanza {} duplicata", stanza.id));
+            }
+            for value in [&stanza.riscaldamento, &stanza.raffrescamento]
+                .into_iter()
+                .flatten()
+            {
+                if !climatizzazione.contains(value) {
+                    errors.push(format!(
+                        "Stanza {}: climatizzazione {value} non presente",
+                        stanza.id
+                    ));
+                }
+            }
+            if let Some(value) = stanza
+                .illuminazione
+                .as_ref()
+                .filter(|value| !illuminazione.contains(*value))
+            {
+                errors.push(format!(
+                    "Stanza {}: illuminazione {value} non presente",
+                    stanza.id
+                ));
+            }
+        }
+
+        let mut infissi = HashSet::new();
+        for infisso in &self.infissi {
+            let label = format!(
+                "Infisso {} dell'edificio {}",
+                infisso.id, infisso.id_edificio
+            );
+            if !edifici.contains(infisso.id_edificio.as_str()) {
+                errors.push(format!("{label}: edificio non presente"));
+            }
+            if !infissi.insert((infisso.id.as_str(), infisso.id_edificio.as_str())) {
+                errors.push(format!("{label} duplicato"));
+            }
+            if !tipi.contains(&infisso.tipo) {
+                errors.push(format!("{label}: tipo {} non presente", infisso.tipo));
+            }
+            if !materiali.contains(&infisso.materiale) {
+                errors.push(format!(
+                    "{label}: materiale {} non presente",
+                    infisso.materiale
+                ));
+            }
+            if !vetri.contains(&infisso.vetro) {
+                errors.push(format!("{label}: vetro {} non presente", infisso.vetro));
+            }
+        }
+
+        for sci in &self.stanze_con_infissi {
+            if stanze.get(&sci.stanza_id) != Some(&sci.edificio_id.as_str()) {
+                errors.push(format!(
+                    "Infissi della stanza {}: stanza non presente nell'edificio {}",
+                    sci.stanza_id, sci.edificio_id
+                ));
+            }
+            if !infissi.contains(&(sci.infisso_id.as_str(), sci.edificio_id.as_str())) {
+                errors.push(format!(
+                    "Infissi della stanza {}: infisso {} non presente",
+                    sci.stanza_id, sci.infisso_id
+                ));
+            }
+        }
+
+        for edificio_id in self
+            .fotovoltaico
+            .iter()
+            .map(|f| &f.id_edificio)
+            .chain(self.utenze.iter().map(|u| &u.edificio_id))
+            .chain(self.annotazioni_edificio.iter().map(|a| &a.edificio_id))
+        {
+            if !edifici.contains(edificio_id.as_str()) {
+                errors.push(format!("Edificio {edificio_id} non presente"));
+            }
+        }
+        for annotazione in &self.annotazioni_stanza {
+            if !stanze.contains_key(&annotazione.stanza_id) {
+                errors.push(format!(
+                    "Annotazione {}: stanza {} non presente",
+                    annotazione.id, annotazione.stanza_id
+                ));
+            }
+        }
+        for annotazione in &self.annotazioni_infisso {
+            let chiave = (
+                annotazione.infisso_id.as_str(),
+                annotazione.edificio_id.as_str(),
+            );
+            if !infissi.contains(&chiave) {
+                errors.push(format!(
+                    "Annotazione {}: infisso {} non presente",
+                    annotazione.id, annotazione.infisso_id
+                ));
+            }
+        }
+
+        errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use app_utils::test::ResultTest;
+
+    use super::*;
+
+    #[test]
+    fn test_check_integrity() -> ResultTest {
+        let json = serde_json::json!({
+            "version": BACKUP_VERSION,
+            "created_at": "2025-01-01T00:00:00",
+            "fascicoli": [500],
+            "edifici": [],
+            "stanze_con_infissi": [
+                { "infisso_id": "A", "edificio_id": "500-1", "stanza_id": 1, "num_infisso": 1 }
+            ]
+        });
+        let archive: BackupArchive = serde_json::from_value(json)?;
+        assert_eq!(archive.check_integrity().len(), 2);
+
+        let future = serde_json::json!({ "version": BACKUP_VERSION + 1 });
+        assert_eq!(
+            BackupArchive::from_value(future).err(),
+            Some(ErrorBackup::UnsupportedVersion {
+                found: BACKUP_VERSION + 1,
+                supported: BACKUP_VERSION
+            })
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,730 @@
+use std::collections::{HashMap, HashSet};
+
+use app_models::models::{
+    Climatizzazione, Illuminazione, NewAnnotazioneEdificio, NewAnnotazioneInfisso,
+    NewAnnotazioneStanza, NewFotovoltaico, NewInfisso, NewStanza, NewUtenza, StanzaConInfissi,
+    UpdateStanza,
+};
+use app_services::{
+    dao::{
+        AnnotazioneEdificioDAO, AnnotazioneInfissoDAO, AnnotazioneStanzaDAO, ClimatizzazioneDAO,
+        EdificioDAO, FotovoltaicoDAO, IlluminazioneDAO, InfissoDAO, MaterialeInfissoDAO,
+        PareteEspostaDAO, StanzaConInfissiDao, StanzaDAO, TipoInfissoDAO, UtenzeDAO,
+        VetroInfissoDAO,
+    },
+    dto::{EdificioDTO, FotovoltaicoDTO, InfissoDTO, PareteEspostaDTO, StanzaDTO, UtenzaDTO},
+};
+use app_utils::{
+    app_error::{AppResult, ApplicationError, ErrorBackup},
+    app_interface::{
+        dao_interface::crud_operations::{Delete, Get, GetAll, Insert, Update},
+        database_interface::{DatabaseManagerTrait, PostgresPooled},
+    },
+};
+use diesel::{Connection, RunQueryDsl};
+use log::info;
+use serde::Serialize;
+use tauri::State;
+
+use crate::backup::archive::{
+    AnnotazioneEdificioBackup, AnnotazioneInfissoBackup, AnnotazioneStanzaBackup, BACKUP_VERSION,
+    BackupArchive, Cataloghi, StanzaConInfissiBackup, VoceCatalogo,
+};
+
+/// Come il ripristino tratta gli edifici già presenti nel database
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestoreMode {
+    /// Il backup viene rifiutato se contiene edifici già presenti
+    Insert,
+    /// Gli edifici dei fascicoli del backup vengono eliminati e sostituiti
+    /// da quelli del backup, per recuperare un database danneggiato
+    Replace,
+}
+
+/// Dati inseriti dal ripristino di un backup
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoreReport {
+    pub fascicoli: Vec<i32>,
+    pub edifici: Vec<EdificioDTO>,
+    /// Edifici eliminati prima del ripristino in modalità `Replace`
+    pub sostituiti: Vec<String>,
+    pub stanze: usize,
+    pub infissi: usize,
+    pub annotazioni: usize,
+    /// Voci di climatizzazione e illuminazione aggiunte ai cataloghi
+    pub voci_catalogo: usize,
+    /// Trasmittanze del backup diverse da quelle del database, che restano
+    /// invariate perché condivise con gli altri edifici
+    pub trasmittanze_diverse: Vec<TrasmittanzaDiversa>,
+}
+
+/// Trasmittanza di un materiale o di un vetro diversa tra backup e database
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TrasmittanzaDiversa {
+    pub catalogo: String,
+    pub nome: String,
+    pub backup: f32,
+    pub database: Option<f32>,
+}
+
+pub struct BackupFascicoli;
+
+impl BackupFascicoli {
+    /// Raccoglie tutti i dati dei fascicoli indicati
+    pub async fn snapshot(
+        db: State<'_, impl DatabaseManagerTrait + Send + Sync>,
+        fascicoli: &[i32],
+    ) -> AppResult<BackupArchive> {
+        let mut conn = db.get_connection().await?;
+        Self::read_consistent(&mut conn, |tx| Self::collect(tx, fascicoli))
+    }
+
+    /// Raccoglie i dati di tutti i fascicoli presenti nel database
+    pub async fn snapshot_all(
+        db: State<'_, impl DatabaseManagerTrait + Send + Sync>,
+    ) -> AppResult<BackupArchive> {
+        let mut conn = db.get_connection().await?;
+        Self::read_consistent(&mut conn, |tx| {
+            let mut fascicoli: Vec<i32> = EdificioDAO::get_all(tx)?
+                .iter()
+                .map(|e| e.fascicolo)
+                .collect();
+            fascicoli.sort();
+            fascicoli.dedup();
+            Self::collect(tx, &fascicoli)
+        })
+    }
+
+    /// Ripristina il backup in un'unica transazione. Il backup viene rifiutato
+    /// se contiene riferimenti non validi o, in modalità `Insert`, edifici già
+    /// presenti nel database.
+    pub async fn restore(
+        db: State<'_, impl DatabaseManagerTrait + Send + Sync>,
+        archive: &BackupArchive,
+        mode: RestoreMode,
+    ) -> AppResult<RestoreReport> {
+        let mut conn = db.get_connection().await?;
+        conn.transaction::<_, ApplicationError, _>(|tx| Self::apply(tx, archive, mode))
+    }
+
+    /// Esegue le letture in un'unica transazione in sola lettura, così che il
+    /// backup sia coerente anche se i dati vengono modificati nel frattempo
+    fn read_consistent<T>(
+        conn: &mut PostgresPooled,
+        read: impl FnOnce(&mut PostgresPooled) -> AppResult<T>,
+    ) -> AppResult<T> {
+        conn.transaction::<_, ApplicationError, _>(|tx| {
+            diesel::sql_query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+                .execute(tx)?;
+            read(tx)
+        })
+    }
+
+    fn collect(conn: &mut PostgresPooled, fascicoli: &[i32]) -> AppResult<BackupArchive> {
+        let edifici: Vec<EdificioDTO> = EdificioDAO::get_all(conn)?
+            .iter()
+            .filter(|e| fascicoli.contains(&e.fascicolo))
+            .map(EdificioDTO::from)
+            .collect();
+        if let Some(fascicolo) = fascicoli
+            .iter()
+            .find(|f| !edifici.iter().any(|e| e.fascicolo == **f))
+        {
+            return Err(ErrorBackup::FascicoloNotFound(*fascicolo).into());
+        }
+
+        let mut archive = BackupArchive {
+            version: BACKUP_VERSION,
+            created_at: chrono::Local::now().naive_local(),
+            fascicoli: fascicoli.to_vec(),
+            cataloghi: Cataloghi::default(),
+            edifici: Vec::new(),
+            stanze: Vec::new(),
+            infissi: Vec::new(),
+            stanze_con_infissi: Vec::new(),
+            fotovoltaico: Vec::new(),
+            utenze: Vec::new(),
+            annotazioni_edificio: Vec::new(),
+            annotazioni_stanza: Vec::new(),
+            annotazioni_infisso: Vec::new(),
+        };
+        for edificio in &edifici {
+            let chiave = edificio.chiave.as_str();
+            let stanze = StanzaDAO::get(conn, chiave)?;
+            let id_stanze: Vec<i32> = stanze.iter().map(|stanza| stanza.id).collect();
+            let pareti = PareteEspostaDAO::get(conn, id_stanze.as_slice())?;
+            for stanza in &stanze {
+                let mut stanza_dto = StanzaDTO::from(stanza);
+                let pareti_esposte = pareti
+                    .iter()
+                    .filter(|parete| parete.stanza_id == stanza.id)
+                    .map(PareteEspostaDTO::try_from)
+                    .collect::<Result<Vec<_>, _>>()?;
+                if !pareti_esposte.is_empty() {
+                    stanza_dto.pareti_esposte = Some(pareti_esposte);
+                }
+                archive.stanze.push(stanza_dto);
+            }
+            archive.infissi.extend(
+                InfissoDAO::get(conn, chiave.to_string())?
+                    .iter()
+                    .map(InfissoDTO::from),
+            );
+            archive.stanze_con_infissi.extend(
+                StanzaConInfissiDao::get(conn, chiave)?
+                    .into_iter()
+                    .map(|sci| StanzaConInfissiBackup {
+                        infisso_id: sci.infisso_id.trim().to_string(),
+                        edificio_id: sci.edificio_id,
+                        stanza_id: sci.stanza_id,
+                        num_infisso: sci.num_infisso,
+                    }),
+            );
+            archive.fotovoltaico.extend(
+                FotovoltaicoDAO::get(conn, chiave.to_string())?
+                    .iter()
+                    .map(FotovoltaicoDTO::from),
+            );
+            archive.utenze.extend(
+                UtenzeDAO::get(conn, chiave.to_string())?
+                    .iter()
+                    .map(UtenzaDTO::from),
+            );
+        }
+        archive.stanze.sort_by_key(|s| s.id);
+
+        let chiavi: HashSet<&str> = edifici.iter().map(|e| e.chiave.as_str()).collect();
+        let stanze: HashSet<i32> = archive.stanze.iter().map(|s| s.id as i32).collect();
+        archive.annotazioni_edificio = AnnotazioneEdificioDAO::get_all(conn)?
+            .into_iter()
+            .filter(|a| chiavi.contains(a.edificio_id.as_str()))
+            .map(|a| AnnotazioneEdificioBackup {
+                id: a.id,
+                edificio_id: a.edificio_id,
+                content: a.content,
+                data: a.data,
+            })
+            .collect();
+        archive.annotazioni_stanza = AnnotazioneStanzaDAO::get_all(conn)?
+            .into_iter()
+            .filter(|a| stanze.contains(&a.stanza_id))
+            .map(|a| AnnotazioneStanzaBackup {
+                id: a.id,
+                stanza_id: a.stanza_id,
+                content: a.content,
+                data: a.data,
+            })
+            .collect();
+        archive.annotazioni_infisso = AnnotazioneInfissoDAO::get_all(conn)?
+            .into_iter()
+            .filter(|a| chiavi.contains(a.edificio_id.as_str()))
+            .map(|a| AnnotazioneInfissoBackup {
+                id: a.id,
+                infisso_id: a.infisso_id.trim().to_string(),
+                edificio_id: a.edificio_id,
+                content: a.content,
+                data: a.data,
+            })
+            .collect();
+
+        archive.cataloghi = Self::collect_cataloghi(conn, &archive)?;
+        archive.edifici = edifici;
+        Ok(archive)
+    }
+
+    /// Voci dei cataloghi usate da infissi e stanze del backup
+    fn collect_cataloghi(
+        conn: &mut PostgresPooled,
+        archive: &BackupArchive,
+    ) -> AppResult<Cataloghi> {
+        let usati =
+            |values: Vec<&String>| -> HashSet<String> { values.into_iter().cloned().collect() };
+        let tipi = usati(archive.infissi.iter().map(|i| &i.tipo).collect());
+        let materiali = usati(archive.infissi.iter().map(|i| &i.materiale).collect());
+        let vetri = usati(archive.infissi.iter().map(|i| &i.vetro).collect());
+        let climatizzazione = usati(
+            archive
+                .stanze
+                .iter()
+                .flat_map(|s| [&s.riscaldamento, &s.raffrescamento])
+                .flatten()
+                .collect(),
+        );
+        let illuminazione = usati(
+            archive
+                .stanze
+                .iter()
+                .filter_map(|s| s.illuminazione.as_ref())
+                .collect(),
+        );
+
+        Ok(Cataloghi {
+            tipi_infisso: TipoInfissoDAO::get_all(conn)?
+                .into_iter()
+                .map(|t| t.nome)
+                .filter(|nome| tipi.contains(nome))
+                .collect(),
+            materiali_infisso: MaterialeInfissoDAO::get_all(conn)?
+                .into_iter()
+                .filter(|m| materiali.contains(&m.materiale))
+                .map(|m| VoceCatalogo {
+                    nome: m.materiale,
+                    eff_energetica: m.eff_energetica,
+                    uf: m.uf,
+                    ug: None,
+                })
+                .collect(),
+            vetri_infisso: VetroInfissoDAO::get_all(conn)?
+                .into_iter()
+                .filter(|v| vetri.contains(&v.vetro))
+                .map(|v| VoceCatalogo {
+                    nome: v.vetro,
+                    eff_energetica: v.eff_energetica,
+                    uf: None,
+                    ug: v.ug,
+                })
+                .collect(),
+            climatizzazione: ClimatizzazioneDAO::get_all(conn)?
+                .into_iter()
+                .filter(|c| climatizzazione.contains(&c.nome))
+                .map(|c| VoceCatalogo {
+                    nome: c.nome,
+                    eff_energetica: c.eff_energetica,
+                    uf: None,
+                    ug: None,
+                })
+                .collect(),
+            illuminazione: IlluminazioneDAO::get_all(conn)?
+                .into_iter()
+                .filter(|i| illuminazione.contains(&i.lampadina))
+                .map(|i| VoceCatalogo {
+                    nome: i.lampadina,
+                    eff_energetica: i.eff_energetica,
+                    uf: None,
+                    ug: None,
+                })
+                .collect(),
+        })
+    }
+
+    fn apply(
+        tx: &mut PostgresPooled,
+        archive: &BackupArchive,
+        mode: RestoreMode,
+    ) -> AppResult<RestoreReport> {
+        let mut errors = archive.check_integrity();
+
+        // i cataloghi degli infissi sono fissi, quelli della climatizzazione e
+        // dell'illuminazione vengono completati con le voci mancanti
+        let tipi: HashSet<String> = TipoInfissoDAO::get_all(tx)?
+            .into_iter()
+            .map(|t| t.nome)
+            .collect();
+        let uf: HashMap<String, Option<f32>> = MaterialeInfissoDAO::get_all(tx)?
+            .into_iter()
+            .map(|m| (m.materiale, m.uf))
+            .collect();
+        let ug: HashMap<String, Option<f32>> = VetroInfissoDAO::get_all(tx)?
+            .into_iter()
+            .map(|v| (v.vetro, v.ug))
+            .collect();
+        let materiali: HashSet<String> = uf.keys().cloned().collect();
+        let vetri: HashSet<String> = ug.keys().cloned().collect();
+        let cataloghi = &archive.cataloghi;
+        for (nome, catalogo, label) in cataloghi
+            .tipi_infisso
+            .iter()
+            .map(|nome| (nome, &tipi, "Tipo infisso"))
+            .chain(
+                cataloghi
+                    .materiali_infisso
+                    .iter()
+                    .map(|v| (&v.nome, &materiali, "Materiale infisso")),
+            )
+            .chain(
+                cataloghi
+                    .vetri_infisso
+                    .iter()
+                    .map(|v| (&v.nome, &vetri, "Vetro infisso")),
+            )
+        {
+            if !catalogo.contains(nome) {
+                errors.push(format!("{label} {nome} non presente nel database"));
+            }
+        }
+        if !errors.is_empty() {
+            return Err(ErrorBackup::Integrity(errors).into());
+        }
+
+        let sostituiti = match mode {
+            RestoreMode::Insert => {
+                let esistenti: Vec<String> = EdificioDAO::get_all(tx)?
+                    .into_iter()
+                    .filter(|e| archive.edifici.iter().any(|b| b.chiave == e.chiave))
+                    .map(|e| e.chiave)
+                    .collect();
+                if !esistenti.is_empty() {
+                    return Err(ErrorBackup::EdificiAlreadyExist(esistenti).into());
+                }
+                Vec::new()
+            }
+            // stanze, infissi, impianti, utenze e annotazioni vengono eliminati
+            // a cascata con l'edificio
+            RestoreMode::Replace => {
+                let mut sostituiti = Vec::new();
+                for edificio in EdificioDAO::get_all(tx)? {
+                    if archive.fascicoli.contains(&edificio.fascicolo)
+                        || archive.edifici.iter().any(|b| b.chiave == edificio.chiave)
+                    {
+                        EdificioDAO::delete(tx, edificio.chiave.clone())?;
+                        sostituiti.push(edificio.chiave);
+                    }
+                }
+                sostituiti.sort();
+                sostituiti
+            }
+        };
+
+        let trasmittanze_diverse: Vec<TrasmittanzaDiversa> = cataloghi
+            .materiali_infisso
+            .iter()
+            .filter_map(|voce| Some(("Materiale infisso", voce, voce.uf?, uf[&voce.nome])))
+            .chain(
+                cataloghi
+                    .vetri_infisso
+                    .iter()
+                    .filter_map(|voce| Some(("Vetro infisso", voce, voce.ug?, ug[&voce.nome]))),
+            )
+            .filter(|(_, _, backup, database)| *database != Some(*backup))
+            .map(|(catalogo, voce, backup, database)| TrasmittanzaDiversa {
+                catalogo: catalogo.to_string(),
+                nome: voce.nome.clone(),
+                backup,
+                database,
+            })
+            .collect();
+
+        let mut voci_catalogo = 0;
+        let climatizzazione: HashSet<String> = ClimatizzazioneDAO::get_all(tx)?
+            .into_iter()
+            .map(|c| c.nome)
+            .collect();
+        for voce in &cataloghi.climatizzazione {
+            if !climatizzazione.contains(&voce.nome) {
+                ClimatizzazioneDAO::insert(
+                    tx,
+                    Climatizzazione {
+                        nome: voce.nome.clone(),
+                        eff_energetica: voce.eff_energetica,
+                    },
+                )?;
+                voci_catalogo += 1;
+            }
+        }
+        let illuminazione: HashSet<String> = IlluminazioneDAO::get_all(tx)?
+            .into_iter()
+            .map(|i| i.lampadina)
+            .collect();
+        for voce in &cataloghi.illuminazione {
+            if !illuminazione.contains(&voce.nome) {
+                IlluminazioneDAO::insert(
+                    tx,
+                    Illuminazione {
+                        lampadina: voce.nome.clone(),
+                        eff_energetica: voce.eff_energetica,
+                    },
+                )?;
+                voci_catalogo += 1;
+            }
+        }
+
+        let mut edifici = Vec::with_capacity(archive.edifici.len());
+        for edificio in &archive.edifici {
+            EdificioDAO::insert(tx, edificio.clone().into())?;
+            let updated =
+                EdificioDAO::update(tx, edificio.chiave.clone(), edificio.clone().into())?;
+            edifici.push(EdificioDTO::from(&updated));
+        }
+
+        // id della stanza nel backup -> id assegnato dal database
+        let mut id_stanze: HashMap<i32, i32> = HashMap::with_capacity(archive.stanze.len());
+        for stanza in &archive.stanze {
+            let inserted = StanzaDAO::insert(tx, NewStanza::from(stanza.clone()))?;
+            let update = UpdateStanza::from(stanza.clone());
+            if update.altezza.is_some()
+                || update.spessore_muro.is_some()
+                || update.riscaldamento.is_some()
+                || update.raffrescamento.is_some()
+                || update.illuminazione.is_some()
+                || update.superficie.is_some()
+                || update.perimetro.is_some()
+            {
+                StanzaDAO::update(tx, inserted.id, update)?;
+            }
+            if let Some(pareti) = stanza.pareti_esposte.clone() {
+                PareteEspostaDAO::insert(
+                    tx,
+                    pareti
+                        .into_iter()
+                        .map(|parete| parete.into_model(inserted.id))
+                        .collect(),
+                )?;
+            }
+            id_stanze.insert(stanza.id as i32, inserted.id);
+        }
+
+        for infisso in &archive.infissi {
+            InfissoDAO::insert(tx, NewInfisso::from(infisso.clone()))?;
+        }
+        for sci in &archive.stanze_con_infissi {
+            StanzaConInfissiDao::insert(
+                tx,
+                StanzaConInfissi {
+                    infisso_id: sci.infisso_id.clone(),
+                    edificio_id: sci.edificio_id.clone(),
+                    stanza_id: id_stanze[&sci.stanza_id],
+                    num_infisso: sci.num_infisso,
+                },
+            )?;
+        }
+        for fotovoltaico in &archive.fotovoltaico {
+            FotovoltaicoDAO::insert(tx, NewFotovoltaico::from(fotovoltaico.clone()))?;
+        }
+        for utenza in &archive.utenze {
+            UtenzeDAO::insert(tx, NewUtenza::from(utenza.clone()))?;
+        }
+
+        for annotazione in &archive.annotazioni_edificio {
+            AnnotazioneEdificioDAO::insert(
+                tx,
+                NewAnnotazioneEdificio {
+                    edificio_id: annotazione.edificio_id.as_str().into(),
+                    content: annotazione.content.as_str().into(),
+                    data: Some(annotazione.data),
+                },
+            )?;
+        }
+        for annotazione in &archive.annotazioni_stanza {
+            AnnotazioneStanzaDAO::insert(
+                tx,
+                NewAnnotazioneStanza {
+                    stanza_id: id_stanze[&annotazione.stanza_id],
+                    content: annotazione.content.as_str().into(),
+                    data: Some(annotazione.data),
+                },
+            )?;
+        }
+        for annotazione in &archive.annotazioni_infisso {
+            AnnotazioneInfissoDAO::insert(
+                tx,
+                NewAnnotazioneInfisso {
+                    infisso_id: annotazione.infisso_id.as_str().into(),
+                    edificio_id: annotazione.edificio_id.as_str().into(),
+                    content: annotazione.content.as_str().into(),
+                    data: Some(annotazione.data),
+                },
+            )?;
+        }
+
+        let annotazioni = archive.annotazioni_edificio.len()
+            + archive.annotazioni_stanza.len()
+            + archive.annotazioni_infisso.len();
+        info!(
+            "Ripristino backup dei fascicoli {:?}: {} edifici, {} stanze, {} infissi, {} annotazioni",
+            archive.fascicoli,
+            edifici.len(),
+            id_stanze.len(),
+            archive.infissi.len(),
+            annotazioni
+        );
+
+        Ok(RestoreReport {
+            fascicoli: archive.fascicoli.clone(),
+            edifici,
+            sostituiti,
+            stanze: id_stanze.len(),
+            infissi: archive.infissi.len(),
+            annotazioni,
+            voci_catalogo,
+            trasmittanze_diverse,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use app_models::models::UpdateMaterialeInfisso;
+    use app_state::database::DatabaseManager;
+    use app_utils::{
+        app_interface::dao_interface::crud_operations::Update,
+        test::{ResultTest, TestServiceEnvironment},
+    };
+
+    use super::*;
+
+    fn setup_fascicolo(conn: &mut PostgresPooled) -> ResultTest {
+        let edificio = EdificioDTO {
+            chiave: "500-1".to_string(),
+            fascicolo: 500,
+            indirizzo: "Via Roma".to_string(),
+            anno_costruzione: Some(1970),
+            anno_riqualificazione: None,
+            note_riqualificazione: None,
+            isolamento_tetto: false,
+            cappotto: true,
+        };
+        EdificioDAO::insert(conn, edificio.clone().into())?;
+        EdificioDAO::update(conn, edificio.chiave.clone(), edificio.into())?;
+        let stanza = StanzaDAO::insert(
+            conn,
+            NewStanza {
+                edificio_id: "500-1".into(),
+                piano: "T".into(),
+                id_spazio: "1".into(),
+                cod_stanza: "PT_UFF_01".into(),
+                destinazione_uso: "Ufficio".into(),
+            },
+        )?;
+        InfissoDAO::insert(
+            conn,
+            NewInfisso {
+                id: "A".into(),
+                edificio_id: "500-1".into(),
+                tipo: "Finestra".into(),
+                altezza: 120,
+                larghezza: 80,
+                materiale: "Legno".into(),
+                vetro: "Doppio".into(),
+            },
+        )?;
+        StanzaConInfissiDao::insert(
+            conn,
+            StanzaConInfissi {
+                infisso_id: "A".to_string(),
+                edificio_id: "500-1".to_string(),
+                stanza_id: stanza.id,
+                num_infisso: 3,
+            },
+        )?;
+        AnnotazioneStanzaDAO::insert(
+            conn,
+            NewAnnotazioneStanza {
+                stanza_id: stanza.id,
+                content: "Controsoffitto".into(),
+                data: None,
+            },
+        )?;
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn test_snapshot_and_restore() -> ResultTest {
+        let env = TestServiceEnvironment::new::<_, _>(|db: DatabaseManager| async move {
+            let mut conn = db.get_connection().await?;
+            setup_fascicolo(&mut conn)
+        })
+        .await?;
+
+        let archive = BackupFascicoli::snapshot(env.database(), &[500]).await?;
+        assert!(archive.check_integrity().is_empty());
+        assert_eq!(archive.stanze.len(), 1);
+        assert_eq!(archive.cataloghi.tipi_infisso, vec!["Finestra"]);
+        let all = BackupFascicoli::snapshot_all(env.database()).await?;
+        assert!(all.fascicoli.contains(&500));
+
+        let path = std::env::temp_dir().join("test_backup_fascicolo.zip");
+        archive.write(&path)?;
+        let read = BackupArchive::read(&path)?;
+        std::fs::remove_file(&path)?;
+        assert_eq!(read.annotazioni_stanza, archive.annotazioni_stanza);
+
+        // il fascicolo è ancora presente nel database
+        match BackupFascicoli::restore(env.database(), &read, RestoreMode::Insert).await {
+            Err(ApplicationError::Backup(ErrorBackup::EdificiAlreadyExist(chiavi))) => {
+                assert_eq!(chiavi, vec!["500-1"])
+            }
+            other => panic!("Expected EdificiAlreadyExist, got {other:?}"),
+        }
+
+        let materiale = read.cataloghi.materiali_infisso[0].clone();
+        assert!(materiale.uf.is_some());
+        {
+            let mut conn = env.database().get_connection().await?;
+            EdificioDAO::delete(&mut conn, "500-1".to_string())?;
+            MaterialeInfissoDAO::update(
+                &mut conn,
+                &materiale.nome,
+                UpdateMaterialeInfisso { uf: Some(9.9) },
+            )?;
+        }
+        let report = BackupFascicoli::restore(env.database(), &read, RestoreMode::Insert).await?;
+        assert!(report.sostituiti.is_empty());
+        assert_eq!(report.stanze, 1);
+        assert!(report.edifici[0].cappotto);
+        // il catalogo condiviso non viene modificato dal ripristino
+        assert_eq!(
+            report.trasmittanze_diverse,
+            vec![TrasmittanzaDiversa {
+                catalogo: "Materiale infisso".to_string(),
+                nome: materiale.nome.clone(),
+                backup: materiale.uf.unwrap(),
+                database: Some(9.9),
+            }]
+        );
+
+        let restored = BackupFascicoli::snapshot(env.database(), &[500]).await?;
+        assert_eq!(restored.stanze_con_infissi[0].num_infisso, 3);
+        assert_eq!(restored.cataloghi.materiali_infisso[0].uf, Some(9.9));
+        assert_eq!(
+            restored.annotazioni_stanza[0].data,
+            read.annotazioni_stanza[0].data
+        );
+        assert_eq!(
+            restored.annotazioni_stanza[0].stanza_id,
+            restored.stanze[0].id as i32
+        );
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn test_restore_replace() -> ResultTest {
+        let env = TestServiceEnvironment::new::<_, _>(|db: DatabaseManager| async move {
+            let mut conn = db.get_connection().await?;
+            setup_fascicolo(&mut conn)
+        })
+        .await?;
+        let archive = BackupFascicoli::snapshot(env.database(), &[500]).await?;
+
+        // dati modificati dopo il backup
+        {
+            let mut conn = env.database().get_connection().await?;
+            StanzaDAO::insert(
+                &mut conn,
+                NewStanza {
+                    edificio_id: "500-1".into(),
+                    piano: "1".into(),
+                    id_spazio: "2".into(),
+                    cod_stanza: "P01_UFF_01".into(),
+                    destinazione_uso: "Ufficio".into(),
+                },
+            )?;
+            InfissoDAO::delete(&mut conn, ("A".to_string(), "500-1".to_string()))?;
+        }
+
+        let report =
+            BackupFascicoli::restore(env.database(), &archive, RestoreMode::Replace).await?;
+        assert_eq!(report.sostituiti, vec!["500-1"]);
+        assert_eq!(report.stanze, 1);
+
+        let restored = BackupFascicoli::snapshot(env.database(), &[500]).await?;
+        assert!(restored.edifici[0].cappotto);
+        assert_eq!(restored.stanze.len(), 1);
+        assert_eq!(restored.infissi.len(), 1);
+        assert_eq!(restored.stanze_con_infissi[0].num_infisso, 3);
+        assert_eq!(
+            restored.annotazioni_stanza[0].content,
+            archive.annotazioni_stanza[0].content
+        );
+        Ok(())
+    }
+}
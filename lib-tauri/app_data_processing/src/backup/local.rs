@@ -0,0 +1,204 @@
+use std::{
+    cmp::Reverse,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use app_utils::app_error::ErrorBackup;
+use chrono::{NaiveDateTime, TimeDelta};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::backup::archive::BackupArchive;
+
+const FILE_PREFIX: &str = "backup_";
+const FILE_EXTENSION: &str = ".zip";
+const FILE_DATE_FORMAT: &str = "%Y%m%d_%H%M%S";
+
+fn io_error(e: impl ToString) -> ErrorBackup { ErrorBackup::Io(e.to_string()) }
+
+/// Quanti backup automatici conservare
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RotationPolicy {
+    /// Numero massimo di backup, il più recente viene sempre conservato
+    pub max_count: usize,
+    /// Età massima in giorni
+    pub max_age_days: u32,
+}
+
+impl Default for RotationPolicy {
+    fn default() -> Self {
+        Self {
+            max_count: 10,
+            max_age_days: 30,
+        }
+    }
+}
+
+/// Backup automatico salvato nella cartella locale
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LocalBackup {
+    pub file_name: String,
+    pub created_at: NaiveDateTime,
+    pub size: u64,
+}
+
+/// Cartella dei backup automatici. I file sono nominati con la data del
+/// backup, usata per ordinarli e ruotarli.
+#[derive(Debug, Clone)]
+pub struct LocalBackups {
+    dir: PathBuf,
+    rotation: RotationPolicy,
+}
+
+impl LocalBackups {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            rotation: RotationPolicy::default(),
+        }
+    }
+
+    pub fn with_rotation(mut self, rotation: RotationPolicy) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn dir(&self) -> &Path { &self.dir }
+
+    /// Salva il backup nella cartella, creandola se necessario
+    pub fn write(&self, archive: &BackupArchive) -> Result<LocalBackup, ErrorBackup> {
+        fs::create_dir_all(&self.dir).map_err(io_error)?;
+        let file_name = format!(
+            "{FILE_PREFIX}{}{FILE_EXTENSION}",
+            archive.created_at.format(FILE_DATE_FORMAT)
+        );
+        let path = self.dir.join(&file_name);
+        archive.write(&path)?;
+
+        Ok(LocalBackup {
+            file_name,
+            created_at: archive.created_at,
+            size: fs::metadata(&path).map_err(io_error)?.len(),
+        })
+    }
+
+    /// Backup presenti nella cartella, dal più recente
+    pub fn list(&self) -> Result<Vec<LocalBackup>, ErrorBackup> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut backups = Vec::new();
+        for entry in fs::read_dir(&self.dir).map_err(io_error)? {
+            let entry = entry.map_err(io_error)?;
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let Some(created_at) = Self::parse_file_name(&file_name) else {
+                continue;
+            };
+            backups.push(LocalBackup {
+                file_name,
+                created_at,
+                size: entry.metadata().map_err(io_error)?.len(),
+            });
+        }
+        backups.sort_by_key(|backup| Reverse(backup.created_at));
+        Ok(backups)
+    }
+
+    /// Percorso di un backup della cartella. Sono accettati solo i nomi
+    /// restituiti da `list`.
+    pub fn path(&self, file_name: &str) -> Result<PathBuf, ErrorBackup> {
+        let path = self.dir.join(file_name);
+        if Self::parse_file_name(file_name).is_none() || !path.is_file() {
+            return Err(ErrorBackup::InvalidArchive(format!(
+                "{file_name} is not a local backup"
+            )));
+        }
+        Ok(path)
+    }
+
+    /// Elimina i backup in eccesso o più vecchi dell'età massima.
+    /// Restituisce i nomi dei file eliminati.
+    pub fn rotate(&self, now: NaiveDateTime) -> Result<Vec<String>, ErrorBackup> {
+        let min_date = now - TimeDelta::days(self.rotation.max_age_days.into());
+        let mut removed = Vec::new();
+        for (index, backup) in self.list()?.into_iter().enumerate() {
+            let keep =
+                index == 0 || (index < self.rotation.max_count && backup.created_at >= min_date);
+            if keep {
+                continue;
+            }
+            match fs::remove_file(self.dir.join(&backup.file_name)) {
+                Ok(()) => removed.push(backup.file_name),
+                Err(e) => warn!("Could not remove backup {}: {e}", backup.file_name),
+            }
+        }
+        if !removed.is_empty() {
+            info!("Removed old backups: {}", removed.join(", "));
+        }
+        Ok(removed)
+    }
+
+    fn parse_file_name(file_name: &str) -> Option<NaiveDateTime> {
+        let date = file_name
+            .strip_prefix(FILE_PREFIX)?
+            .strip_suffix(FILE_EXTENSION)?;
+        NaiveDateTime::parse_from_str(date, FILE_DATE_FORMAT).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use app_utils::test::ResultTest;
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn at(day: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 3, day)
+            .unwrap()
+            .and_hms_opt(2, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_rotate() -> ResultTest {
+        // la cartella viene eliminata anche se il test fallisce
+        let temp = tempfile::tempdir()?;
+        let dir = temp.path().join("backups");
+        let backups = LocalBackups::new(dir.clone()).with_rotation(RotationPolicy {
+            max_count: 3,
+            max_age_days: 10,
+        });
+        assert!(backups.list()?.is_empty());
+
+        fs::create_dir_all(&dir)?;
+        for day in [1, 15, 20, 24, 25] {
+            let file_name = format!(
+                "{FILE_PREFIX}{}{FILE_EXTENSION}",
+                at(day).format(FILE_DATE_FORMAT)
+            );
+            fs::write(dir.join(file_name), b"{}")?;
+        }
+        fs::write(dir.join("note.txt"), b"")?;
+
+        let removed = backups.rotate(at(26))?;
+        assert_eq!(
+            removed,
+            vec!["backup_20250315_020000.zip", "backup_20250301_020000.zip"]
+        );
+        let names: Vec<String> = backups.list()?.into_iter().map(|b| b.file_name).collect();
+        assert_eq!(names[0], "backup_20250325_020000.zip");
+        assert_eq!(names.len(), 3);
+        assert!(backups.path(&names[0]).is_ok());
+        assert!(backups.path("note.txt").is_err());
+        assert!(backups.path("../backup_20250325_020000.zip").is_err());
+
+        // il backup più recente resta anche se scaduto
+        assert_eq!(backups.rotate(at(31) + TimeDelta::days(30))?.len(), 2);
+        assert_eq!(backups.list()?.len(), 1);
+
+        temp.close()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,7 @@
+mod archive;
+mod fascicoli;
+mod local;
+
+pub use archive::*;
+pub use fascicoli::{BackupFascicoli, RestoreMode, RestoreReport, TrasmittanzaDiversa};
+pub use local::{LocalBackup, LocalBackups, RotationPolicy};
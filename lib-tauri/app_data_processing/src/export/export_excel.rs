@@ -1,253 +1,544 @@
-use crate::dao::crud_operations::GetAll;
-use crate::dao::entity::{DatiStanza, Edificio, Fotovoltaico};
-use crate::dao::{DatiStanzeViewDAO, EdificioDAO, FotovoltaicoDAO};
-use crate::database::Database;
-use crate::utils::{AppError, ToList};
-use dirs_next::document_dir;
+use std::{fs, path::Path};
+
+use app_models::models::DatiStanza;
+use app_services::{
+    dao::{DatiStanzeViewDAO, EdificioDAO, FotovoltaicoDAO},
+    dto::{EdificioDTO, FotovoltaicoDTO},
+};
+use app_utils::{
+    app_error::{AppResult, ErrorExport},
+    app_interface::{
+        dao_interface::crud_operations::Get, database_interface::DatabaseManagerTrait,
+    },
+};
+use async_trait::async_trait;
 use log::info;
-use rust_xlsxwriter::{ColNum, Format, RowNum, Workbook, Worksheet};
-use std::any::{Any, TypeId};
-use std::fs;
+use rust_xlsxwriter::{ColNum, Format, RowNum, Workbook, Worksheet, XlsxError};
 use tauri::State;
 
+use crate::{
+    metrics::{ComponentScore, EnergyScore, ScoreComponent, ScoreWeights},
+    scenario::{ScenarioComparison, ScenarioIndicators},
+};
+
+pub const SHEET_STANZE: &str = "Stanze";
+pub const SHEET_EDIFICI: &str = "Edifici";
+pub const SHEET_PUNTEGGIO: &str = "Punteggio";
+pub const SHEET_SCENARI: &str = "Scenari";
+
+#[async_trait]
 pub trait ExportData {
-    fn export(db: State<'_, Database>, name_file: Option<String>) -> Result<(), String>;
+    async fn export(
+        db: State<'_, impl DatabaseManagerTrait + Send + Sync>,
+        fascicolo: i32,
+        pesi: &ScoreWeights,
+        path: &Path,
+    ) -> AppResult<()>;
 }
 
 pub struct ExportDatiStanzaToExcel;
 
+#[async_trait]
 impl ExportData for ExportDatiStanzaToExcel {
-    fn export(db: State<'_, Database>, name_file: Option<String>) -> Result<(), String> {
-        let conn = db.get_conn();
-        if let Some(conn) = conn.as_ref() {
-            let dati_stanze = DatiStanzeViewDAO::get_all(conn)?;
-
-            let mut workbook = Workbook::new();
-            // Primo worksheet
-            let worksheet = workbook.add_worksheet();
-            worksheet.set_name("Stanze").map_err(|e| e.to_string())?;
-
-            // Scrittura dell'headers
-            Self::write_headers(
-                worksheet,
-                DatiStanza::get_fields()
-                    .iter()
-                    .map(|x| x.to_string())
-                    .collect(),
-            )?;
-
-            // Scrittura dei dati
-            for (i, dato) in dati_stanze.iter().enumerate() {
-                let row = (i + 1) as u32;
-                Self::write_row(worksheet, row, &dato.to_list())?
+    async fn export(
+        db: State<'_, impl DatabaseManagerTrait + Send + Sync>,
+        fascicolo: i32,
+        pesi: &ScoreWeights,
+        path: &Path,
+    ) -> AppResult<()> {
+        let (dati_stanze, edifici) = {
+            let mut conn = db.get_connection().await?;
+            let dati_stanze = DatiStanzeViewDAO::get(&mut conn, fascicolo)?;
+            if dati_stanze.is_empty() {
+                return Err(ErrorExport::EmptyFascicolo(fascicolo).into());
             }
+            let edifici = EdificioDAO::get(&mut conn, fascicolo)?
+                .iter()
+                .map(|edificio| {
+                    let fotovoltaici: Vec<FotovoltaicoDTO> =
+                        FotovoltaicoDAO::get(&mut conn, edificio.chiave.clone())?
+                            .iter()
+                            .map(FotovoltaicoDTO::from)
+                            .collect();
+                    Ok((EdificioDTO::from(edificio), fotovoltaici))
+                })
+                .collect::<AppResult<Vec<_>>>()?;
+            (dati_stanze, edifici)
+        };
+        let punteggio = EnergyScore::fascicolo(db, fascicolo, pesi).await?;
 
-            // Adatta le colonne
-            worksheet.autofit();
+        let mut workbook = Workbook::new();
 
-            // Secondo worksheet
-            let worksheet = workbook.add_worksheet();
-            worksheet.set_name("Edifici").map_err(|e| e.to_string())?;
-
-            // headers
-            let f_headers = Fotovoltaico::get_fields();
-            let mut headers: Vec<String> = Edificio::get_fields();
-            headers.extend(vec![f_headers[2].clone(), f_headers[3].clone()]);
-            Self::write_headers(worksheet, headers)?;
+        // Primo worksheet
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name(SHEET_STANZE).map_err(map_xlsx_error)?;
+        Self::write_headers(worksheet, DatiStanza::HEADERS)?;
+        for (i, dato) in dati_stanze.iter().enumerate() {
+            Self::write_row(worksheet, (i + 1) as RowNum, &dato.to_row())?;
+        }
+        worksheet.autofit();
 
-            // Dati
-            let edifici = EdificioDAO::get_all(conn)?;
-            let fotovoltaico = FotovoltaicoDAO::get_all(conn)?;
-            for (index, edificio) in edifici.iter().enumerate() {
-                let f = fotovoltaico
-                    .iter()
-                    .find(|&f| f.id_edificio == edificio.chiave);
-                let edificio = edificio.to_list();
-                Self::write_row(worksheet, (index + 1) as RowNum, &edificio)?;
-                if let Some(f) = f {
-                    let f_list = f.to_list();
-                    Self::write_cell(
-                        worksheet,
-                        0,
-                        (edificio.len() + 1) as ColNum,
-                        f_list.get(2).unwrap(),
-                    )?;
-                    Self::write_cell(
-                        worksheet,
-                        0,
-                        (edificio.len() + 2) as ColNum,
-                        f_list.get(3).unwrap(),
-                    )?;
-                }
-            }
+        // Secondo worksheet
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name(SHEET_EDIFICI).map_err(map_xlsx_error)?;
+        Self::write_headers(worksheet, EdificioRow::HEADERS)?;
+        for (i, (edificio, fotovoltaici)) in edifici.iter().enumerate() {
+            let row = EdificioRow::new(edificio, fotovoltaici);
+            Self::write_row(worksheet, (i + 1) as RowNum, &row.to_row())?;
+        }
+        worksheet.autofit();
 
-            worksheet.autofit();
+        // Terzo worksheet
+        let worksheet = workbook.add_worksheet();
+        worksheet
+            .set_name(SHEET_PUNTEGGIO)
+            .map_err(map_xlsx_error)?;
+        Self::write_headers(worksheet, PunteggioRow::HEADERS)?;
+        for (i, edificio) in punteggio.edifici.iter().enumerate() {
+            let row = PunteggioRow {
+                edificio: edificio.edificio.clone(),
+                punteggio: edificio.punteggio,
+                componenti: &edificio.componenti,
+            };
+            Self::write_row(worksheet, (i + 1) as RowNum, &row.to_row())?;
+        }
+        let totale = PunteggioRow {
+            edificio: "Totale".to_string(),
+            punteggio: punteggio.punteggio,
+            componenti: &punteggio.componenti,
+        };
+        Self::write_row(
+            worksheet,
+            (punteggio.edifici.len() + 1) as RowNum,
+            &totale.to_row(),
+        )?;
+        worksheet.autofit();
 
-            // Determina il percorso di salvataggio
-            let doc_dir = document_dir().ok_or("Impossibile trovare la directory home")?;
-            let mut export_path = doc_dir.join("Dati_Sopralluogo/export");
-            if !export_path.exists() {
-                match fs::create_dir_all(&export_path) {
-                    Ok(()) => println!("Cartella {{export}} creata correttamente"),
-                    Err(e) => return Err(format!("Errore nella creazione della cartella: {}", e)),
-                }
-            }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(ErrorExport::from)?;
+        }
+        workbook.save(path).map_err(map_xlsx_error)?;
 
-            export_path.push(format!(
-                "{}.xlsx",
-                match name_file {
-                    Some(name) => name,
-                    None => dati_stanze.first().unwrap().fascicolo.clone(),
-                }
-            ));
+        info!("File Excel esportato in {}", path.display());
+        Ok(())
+    }
+}
 
-            // Salva il file
-            workbook
-                .save(export_path.to_str().unwrap())
-                .map_err(|e| e.to_string())?;
+/// Esporta il confronto degli scenari di riqualificazione di un edificio
+pub struct ExportScenariToExcel;
 
-            info!("File Excel esportato con successo!");
+impl ExportScenariToExcel {
+    pub fn export(confronti: &[ScenarioComparison], path: &Path) -> AppResult<()> {
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name(SHEET_SCENARI).map_err(map_xlsx_error)?;
+        ExportDatiStanzaToExcel::write_headers(worksheet, ScenarioRow::HEADERS)?;
+        let rows = confronti.iter().flat_map(ScenarioRow::from_comparison);
+        for (i, row) in rows.enumerate() {
+            ExportDatiStanzaToExcel::write_row(worksheet, (i + 1) as RowNum, &row.to_row())?;
+        }
+        worksheet.autofit();
 
-            Ok(())
-        } else {
-            Err(AppError::DatabaseNotInitialized.to_string())
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(ErrorExport::from)?;
         }
+        workbook.save(path).map_err(map_xlsx_error)?;
+
+        info!("Scenari esportati in {}", path.display());
+        Ok(())
     }
 }
 
 impl ExportDatiStanzaToExcel {
-    fn write_headers(worksheet: &mut Worksheet, fields: Vec<String>) -> Result<(), String> {
+    fn write_headers(worksheet: &mut Worksheet, headers: &[&str]) -> AppResult<()> {
         let format = Format::new().set_bold();
-
-        for (i, dato) in fields.iter().enumerate() {
+        for (i, header) in headers.iter().enumerate() {
             worksheet
-                .write_string_with_format(0, i as u16, dato, &format)
-                .map_err(|e| e.to_string())?;
+                .write_string_with_format(0, i as ColNum, *header, &format)
+                .map_err(map_xlsx_error)?;
         }
         Ok(())
     }
 
-    fn write_row(
-        worksheet: &mut Worksheet,
-        row: RowNum,
-        row_value: &Vec<Box<dyn Any>>,
-    ) -> Result<(), String> {
-        for (index, value) in row_value.iter().enumerate() {
-            Self::write_cell(worksheet, row, index as ColNum, value)?;
+    fn write_row(worksheet: &mut Worksheet, row: RowNum, values: &[CellValue]) -> AppResult<()> {
+        let decimal = Format::new().set_num_format("0.00");
+        for (i, value) in values.iter().enumerate() {
+            let col = i as ColNum;
+            match value {
+                CellValue::Text(value) => worksheet.write_string(row, col, value),
+                CellValue::Integer(value) => worksheet.write_number(row, col, *value as f64),
+                CellValue::Decimal(value) => {
+                    worksheet.write_number_with_format(row, col, *value, &decimal)
+                }
+                CellValue::Bool(value) => {
+                    worksheet.write_string(row, col, if *value { "SI" } else { "NO" })
+                }
+                CellValue::Empty => continue,
+            }
+            .map_err(map_xlsx_error)?;
         }
         Ok(())
     }
+}
 
-    fn write_cell(
-        worksheet: &mut Worksheet,
-        row: RowNum,
-        col: ColNum,
-        value: &Box<dyn Any>,
-    ) -> Result<(), String> {
-        let format = Format::new().set_num_format("0.00");
-        let type_id = (**value).type_id();
+fn map_xlsx_error(e: XlsxError) -> ErrorExport { ErrorExport::Excel(e.to_string()) }
 
-        match type_id {
-            id if id == TypeId::of::<String>() => {
-                let value = value.downcast_ref::<String>().unwrap();
-                Self::write_cell_string(worksheet, row, col, value)?;
-            }
-            id if id == TypeId::of::<u64>() => {
-                let value = value.downcast_ref::<u64>().unwrap();
-                Self::write_cell_number(worksheet, row, col, *value as f64)?;
-            }
-            id if id == TypeId::of::<Option<u16>>() || id == TypeId::of::<Option<u8>>() => {
-                let value_u16 = if id == TypeId::of::<Option<u8>>() {
-                    Self::try_convert_option_number::<u8, u16>(value).unwrap()
-                } else {
-                    *value.downcast_ref::<Option<u16>>().unwrap()
-                };
+/// Valore di una cella del foglio Excel
+enum CellValue {
+    Text(String),
+    Integer(i64),
+    Decimal(f64),
+    Bool(bool),
+    Empty,
+}
 
-                match value_u16 {
-                    Some(value) => Self::write_cell_number(worksheet, row, col, value as f64)?,
-                    None => Self::write_cell_number(worksheet, row, col, 0.0)?,
-                };
+impl From<Option<String>> for CellValue {
+    fn from(value: Option<String>) -> Self { value.map_or(CellValue::Empty, CellValue::Text) }
+}
+
+impl From<Option<i16>> for CellValue {
+    fn from(value: Option<i16>) -> Self {
+        value.map_or(CellValue::Empty, |v| CellValue::Integer(v as i64))
+    }
+}
+
+impl From<Option<i32>> for CellValue {
+    fn from(value: Option<i32>) -> Self {
+        value.map_or(CellValue::Empty, |v| CellValue::Integer(v as i64))
+    }
+}
+
+impl From<Option<f64>> for CellValue {
+    fn from(value: Option<f64>) -> Self { value.map_or(CellValue::Empty, CellValue::Decimal) }
+}
+
+trait ToRow {
+    const HEADERS: &'static [&'static str];
+
+    fn to_row(&self) -> Vec<CellValue>;
+}
+
+impl ToRow for DatiStanza {
+    const HEADERS: &'static [&'static str] = &[
+        "id",
+        "fascicolo",
+        "chiave",
+        "piano",
+        "id_spazio",
+        "cod_stanza",
+        "destinazione_uso",
+        "altezza",
+        "spessore_muro",
+        "riscaldamento",
+        "raffrescamento",
+        "illuminazione",
+        "mq_infissi",
+        "materiale",
+        "vetro",
+        "superficie",
+        "volume",
+    ];
+
+    fn to_row(&self) -> Vec<CellValue> {
+        vec![
+            CellValue::Integer(self.id as i64),
+            CellValue::Integer(self.fascicolo as i64),
+            CellValue::Text(self.chiave.clone()),
+            CellValue::Text(self.piano.clone()),
+            CellValue::Text(self.id_spazio.clone()),
+            CellValue::Text(self.cod_stanza.clone()),
+            CellValue::Text(self.destinazione_uso.clone()),
+            self.altezza.into(),
+            self.spessore_muro.into(),
+            self.riscaldamento.clone().into(),
+            self.raffrescamento.clone().into(),
+            self.illuminazione.clone().into(),
+            CellValue::Decimal(self.mq_infissi),
+            self.materiale.clone().into(),
+            self.vetro.clone().into(),
+            self.superficie.into(),
+            self.volume.into(),
+        ]
+    }
+}
+
+/// Riga del foglio degli edifici con i dati aggregati degli impianti
+/// fotovoltaici
+struct EdificioRow<'a> {
+    edificio: &'a EdificioDTO,
+    potenza: Option<f32>,
+    proprietari: Option<String>,
+}
+
+impl<'a> EdificioRow<'a> {
+    /// `impianti` sono gli impianti fotovoltaici dell'edificio
+    fn new(edificio: &'a EdificioDTO, impianti: &[FotovoltaicoDTO]) -> Self {
+        if impianti.is_empty() {
+            return Self {
+                edificio,
+                potenza: None,
+                proprietari: None,
+            };
+        }
+
+        let mut proprietari: Vec<&str> = impianti.iter().map(|f| f.proprietario.as_str()).collect();
+        proprietari.sort_unstable();
+        proprietari.dedup();
+
+        Self {
+            edificio,
+            potenza: Some(impianti.iter().map(|f| f.potenza).sum()),
+            proprietari: Some(proprietari.join(", ")),
+        }
+    }
+}
+
+impl ToRow for EdificioRow<'_> {
+    const HEADERS: &'static [&'static str] = &[
+        "chiave",
+        "fascicolo",
+        "indirizzo",
+        "anno_costruzione",
+        "anno_riqualificazione",
+        "note_riqualificazione",
+        "isolamento_tetto",
+        "cappotto",
+        "potenza_fotovoltaico",
+        "proprietario_fotovoltaico",
+    ];
+
+    fn to_row(&self) -> Vec<CellValue> {
+        vec![
+            CellValue::Text(self.edificio.chiave.clone()),
+            CellValue::Integer(self.edificio.fascicolo as i64),
+            CellValue::Text(self.edificio.indirizzo.clone()),
+            self.edificio.anno_costruzione.into(),
+            self.edificio.anno_riqualificazione.into(),
+            self.edificio.note_riqualificazione.clone().into(),
+            CellValue::Bool(self.edificio.isolamento_tetto),
+            CellValue::Bool(self.edificio.cappotto),
+            self.potenza
+                .map_or(CellValue::Empty, |p| CellValue::Decimal(p as f64)),
+            self.proprietari.clone().into(),
+        ]
+    }
+}
+
+/// Riga del foglio del punteggio energetico, per edificio o per l'intero
+/// fascicolo
+struct PunteggioRow<'a> {
+    edificio: String,
+    punteggio: Option<f64>,
+    componenti: &'a [ComponentScore],
+}
+
+impl ToRow for PunteggioRow<'_> {
+    const HEADERS: &'static [&'static str] = &[
+        "chiave",
+        "punteggio",
+        "illuminazione",
+        "climatizzazione",
+        "infissi",
+        "isolamento_tetto",
+        "cappotto",
+        "fotovoltaico",
+    ];
+
+    fn to_row(&self) -> Vec<CellValue> {
+        let mut row = vec![
+            CellValue::Text(self.edificio.clone()),
+            self.punteggio.into(),
+        ];
+        row.extend(ScoreComponent::ALL.iter().map(|componente| {
+            self.componenti
+                .iter()
+                .find(|c| c.componente == *componente)
+                .and_then(|c| c.punteggio)
+                .into()
+        }));
+        row
+    }
+}
+
+/// Riga del foglio degli scenari, un indicatore per riga
+struct ScenarioRow<'a> {
+    scenario: &'a str,
+    indicatore: String,
+    prima: Option<f64>,
+    dopo: Option<f64>,
+}
+
+impl<'a> ScenarioRow<'a> {
+    fn from_comparison(confronto: &'a ScenarioComparison) -> Vec<Self> {
+        let row = |indicatore: String, valore: fn(&ScenarioIndicators) -> Option<f64>| Self {
+            scenario: confronto.scenario.nome.as_str(),
+            indicatore,
+            prima: valore(&confronto.prima),
+            dopo: valore(&confronto.dopo),
+        };
+        let mut rows = vec![
+            row("punteggio".to_string(), |i| i.punteggio),
+            row("h_infissi".to_string(), |i| Some(i.h_infissi)),
+            row("uw_medio".to_string(), |i| i.uw_medio),
+        ];
+        rows.extend(ScoreComponent::ALL.iter().map(|componente| {
+            let punteggio = |indicatori: &ScenarioIndicators| {
+                indicatori
+                    .componenti
+                    .iter()
+                    .find(|c| c.componente == *componente)
+                    .and_then(|c| c.punteggio)
+            };
+            Self {
+                scenario: confronto.scenario.nome.as_str(),
+                indicatore: componente.to_string(),
+                prima: punteggio(&confronto.prima),
+                dopo: punteggio(&confronto.dopo),
             }
-            id if id == TypeId::of::<Option<String>>() => {
-                let value = value.downcast_ref::<Option<String>>().unwrap();
-                if let Some(value) = value.clone() {
-                    Self::write_cell_string(worksheet, row, col, value.as_str())?;
+        }));
+        rows
+    }
+}
+
+impl ToRow for ScenarioRow<'_> {
+    const HEADERS: &'static [&'static str] =
+        &["scenario", "indicatore", "prima", "dopo", "differenza"];
+
+    fn to_row(&self) -> Vec<CellValue> {
+        vec![
+            CellValue::Text(self.scenario.to_string()),
+            CellValue::Text(self.indicatore.clone()),
+            self.prima.into(),
+            self.dopo.into(),
+            self.prima
+                .zip(self.dopo)
+                .map(|(prima, dopo)| dopo - prima)
+                .into(),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use app_models::models::NewStanza;
+    use app_services::{dao::StanzaDAO, dto::StanzaDTO};
+    use app_state::database::DatabaseManager;
+    use app_utils::{
+        app_error::ApplicationError,
+        app_interface::dao_interface::crud_operations::Insert,
+        path_data_fake,
+        test::{ResultTest, TestServiceEnvironment, utils::read_json_file},
+    };
+    use calamine::{Reader, Xlsx, open_workbook};
+
+    use super::*;
+
+    const FASCICOLO: i32 = 3858;
+    const EDIFICIO: &str = "8361-122";
+
+    async fn setup_env_export() -> ResultTest<TestServiceEnvironment<DatabaseManager>> {
+        TestServiceEnvironment::new::<_, _>(|db_manager: DatabaseManager| async move {
+            let edifici_dto =
+                read_json_file::<EdificioDTO>(path_data_fake!("edificiFake").as_str())?;
+            let stanze_dto = read_json_file::<StanzaDTO>(path_data_fake!("stanzeFake").as_str())?;
+            {
+                let mut conn = db_manager.get_connection().await?;
+                for edificio_dto in edifici_dto {
+                    let _ = EdificioDAO::insert(&mut conn, edificio_dto.into());
                 }
-            }
-            id if id == TypeId::of::<Option<f32>>() => {
-                match value.downcast_ref::<Option<f32>>().unwrap() {
-                    Some(value) => Self::write_cell_number_with_format(
-                        worksheet,
-                        row,
-                        col,
-                        *value as f64,
-                        &format,
-                    )?,
-                    None => Self::write_cell_number_with_format(worksheet, row, col, 0.0, &format)?,
-                };
-            }
-            id if id == TypeId::of::<Option<bool>>() => {
-                match value.downcast_ref::<Option<bool>>().unwrap() {
-                    Some(value) => Self::write_cell_string(
-                        worksheet,
-                        row,
-                        col,
-                        if *value { "SI" } else { "NO" },
-                    )?,
-                    None => Self::write_cell_string(worksheet, row, col, "NO")?,
+                for stanza_dto in stanze_dto {
+                    let new_stanza: NewStanza = stanza_dto.into();
+                    let _ = StanzaDAO::insert(&mut conn, new_stanza);
+                }
+                let fotovoltaico = FotovoltaicoDTO {
+                    id: None,
+                    id_edificio: EDIFICIO.to_string(),
+                    potenza: 3.5,
+                    proprietario: "Comune".to_string(),
+                    orientamento: None,
+                    inclinazione: None,
+                    anno_installazione: None,
+                    provincia: None,
                 };
+                FotovoltaicoDAO::insert(&mut conn, fotovoltaico.into())?;
             }
-            _ => {} // Gestione per tipi non supportati
-        }
-        Ok(())
+            Ok(())
+        })
+        .await
     }
 
-    fn try_convert_option_number<From: Any + Copy, To: TryFrom<From>>(
-        value: &Box<dyn Any>,
-    ) -> Option<Option<To>>
-    where
-        <To as TryFrom<From>>::Error: std::fmt::Debug,
-    {
-        if let Some(opt_from) = value.downcast_ref::<Option<From>>() {
-            return Some(opt_from.map(|val| To::try_from(val).unwrap()));
-        }
-        None
-    }
+    #[test]
+    fn test_edificio_row_proprietari() {
+        let edificio = read_json_file::<EdificioDTO>(path_data_fake!("edificiFake").as_str())
+            .unwrap()
+            .remove(0);
+        let impianto = |proprietario: &str| FotovoltaicoDTO {
+            id: None,
+            id_edificio: edificio.chiave.clone(),
+            potenza: 2.0,
+            proprietario: proprietario.to_string(),
+            orientamento: None,
+            inclinazione: None,
+            anno_installazione: None,
+            provincia: None,
+        };
+        let impianti = [impianto("Comune"), impianto("Privato"), impianto("Comune")];
 
-    fn write_cell_string(
-        worksheet: &mut Worksheet,
-        row: RowNum,
-        col: ColNum,
-        value: &str,
-    ) -> Result<(), String> {
-        worksheet
-            .write_string(row, col, value)
-            .map_err(|e| e.to_string())?;
-        Ok(())
+        let row = EdificioRow::new(&edificio, &impianti);
+        assert_eq!(row.potenza, Some(6.0));
+        assert_eq!(row.proprietari.as_deref(), Some("Comune, Privato"));
     }
 
-    fn write_cell_number(
-        worksheet: &mut Worksheet,
-        row: RowNum,
-        col: ColNum,
-        value: f64,
-    ) -> Result<(), String> {
-        worksheet
-            .write_number(row, col, value)
-            .map_err(|e| e.to_string())?;
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn test_export_fascicolo() -> ResultTest {
+        let env = setup_env_export().await?;
+        let path = std::env::temp_dir()
+            .join("export_excel_test")
+            .join(format!("{FASCICOLO}.xlsx"));
+
+        ExportDatiStanzaToExcel::export(env.database(), FASCICOLO, &ScoreWeights::default(), &path)
+            .await?;
+
+        let mut workbook: Xlsx<_> = open_workbook(&path)?;
+        assert_eq!(
+            workbook.sheet_names(),
+            vec![SHEET_STANZE, SHEET_EDIFICI, SHEET_PUNTEGGIO]
+        );
+
+        let stanze = workbook.worksheet_range(SHEET_STANZE)?;
+        assert_eq!(stanze.height(), 6);
+        assert_eq!(stanze.width(), DatiStanza::HEADERS.len());
+
+        let edifici = workbook.worksheet_range(SHEET_EDIFICI)?;
+        assert_eq!(edifici.height(), 2);
+        assert_eq!(
+            edifici.get((1, 9)).map(|c| c.to_string()),
+            Some("Comune".to_string())
+        );
+
+        // un edificio e la riga del totale
+        let punteggio = workbook.worksheet_range(SHEET_PUNTEGGIO)?;
+        assert_eq!(punteggio.height(), 3);
+        assert_eq!(punteggio.width(), PunteggioRow::HEADERS.len());
+
+        fs::remove_file(&path)?;
         Ok(())
     }
 
-    fn write_cell_number_with_format(
-        worksheet: &mut Worksheet,
-        row: RowNum,
-        col: ColNum,
-        value: f64,
-        format: &Format,
-    ) -> Result<(), String> {
-        worksheet
-            .write_number_with_format(row, col, value, format)
-            .map_err(|e| e.to_string())?;
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn test_export_empty_fascicolo() -> ResultTest {
+        let env = setup_env_export().await?;
+        let path = std::env::temp_dir()
+            .join("export_excel_test")
+            .join("empty.xlsx");
+
+        match ExportDatiStanzaToExcel::export(env.database(), -1, &ScoreWeights::default(), &path)
+            .await
+        {
+            Err(ApplicationError::Export(ErrorExport::EmptyFascicolo(-1))) => {}
+            other => panic!("Expected EmptyFascicolo, got {:?}", other.err()),
+        }
+        assert!(!path.exists());
         Ok(())
     }
 }
@@ -1 +1,6 @@
-pub mod export_excel;
+mod export_excel;
+
+pub use export_excel::{
+    ExportData, ExportDatiStanzaToExcel, ExportScenariToExcel, SHEET_EDIFICI, SHEET_PUNTEGGIO,
+    SHEET_SCENARI, SHEET_STANZE,
+};
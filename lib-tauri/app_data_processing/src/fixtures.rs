@@ -0,0 +1,49 @@
+//! Dati di prova condivisi dai test del crate
+
+use std::collections::HashMap;
+
+use app_services::dto::{EdificioDTO, InfissoDTO, InfissoDTOBuilder, StanzaDTO, StanzaDTOBuilder};
+
+use crate::CatalogoEfficienza;
+
+/// Edificio `500-1` del fascicolo 500, senza dati opzionali
+pub(crate) fn edificio() -> EdificioDTO {
+    EdificioDTO {
+        chiave: "500-1".to_string(),
+        fascicolo: 500,
+        indirizzo: "Via Roma 1".to_string(),
+        anno_costruzione: None,
+        anno_riqualificazione: None,
+        note_riqualificazione: None,
+        isolamento_tetto: false,
+        cappotto: false,
+    }
+}
+
+/// Stanza a piano terra dell'edificio `500-1`, senza dati opzionali
+pub(crate) fn stanza(id: u64) -> StanzaDTOBuilder {
+    StanzaDTO::builder()
+        .id(id)
+        .edificio_id("500-1".to_string())
+        .piano("T".to_string())
+        .id_spazio(id.to_string())
+        .cod_stanza(format!("ST{id:03}"))
+        .destinazione_uso("Ufficio".to_string())
+}
+
+/// Finestra 150x100 in legno con vetro doppio dell'edificio `500-1`
+pub(crate) fn infisso(id: &str) -> InfissoDTOBuilder {
+    InfissoDTO::builder()
+        .id(id.to_string())
+        .id_edificio("500-1".to_string())
+        .tipo("Finestra".to_string())
+        .altezza(150)
+        .larghezza(100)
+        .materiale("Legno".to_string())
+        .vetro("Doppio".to_string())
+}
+
+/// Tipi di un catalogo di efficienza con la loro efficienza energetica
+pub(crate) fn tipi(tipi: &[(&str, i16)]) -> HashMap<String, Option<f64>> {
+    CatalogoEfficienza::normalize(tipi.iter().map(|(nome, eff)| (nome.to_string(), *eff)))
+}
@@ -0,0 +1,125 @@
+use app_utils::app_error::ErrorImport;
+use encoding_rs::{Encoding, UTF_8, WINDOWS_1252};
+
+/// Separatori presi in considerazione dal rilevamento automatico
+const DELIMITERS: &[u8] = b";,\t|";
+/// Righe analizzate per rilevare il separatore
+const SAMPLE_LINES: usize = 20;
+
+/// Decodifica il contenuto del file. Senza `label` la codifica viene
+/// rilevata dal BOM o, in mancanza, scegliendo UTF-8 se valido e altrimenti
+/// Windows-1252, la codifica usata da Excel per i CSV in italiano.
+pub(crate) fn decode(bytes: &[u8], label: Option<&str>) -> Result<String, ErrorImport> {
+    let encoding = match label {
+        Some(label) => Encoding::for_label(label.trim().as_bytes())
+            .ok_or_else(|| ErrorImport::UnknownEncoding(label.to_string()))?,
+        None => detect_encoding(bytes),
+    };
+    // `decode` rimuove anche l'eventuale BOM
+    let (text, ..) = encoding.decode(bytes);
+    Ok(text.into_owned())
+}
+
+fn detect_encoding(bytes: &[u8]) -> &'static Encoding {
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return encoding;
+    }
+    if std::str::from_utf8(bytes).is_ok() {
+        UTF_8
+    } else {
+        WINDOWS_1252
+    }
+}
+
+/// Sceglie il separatore che compare più spesso con lo stesso numero di
+/// occorrenze in ogni riga
+pub(crate) fn detect_delimiter(text: &str) -> u8 {
+    let lines: Vec<&str> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .take(SAMPLE_LINES)
+        .collect();
+
+    DELIMITERS
+        .iter()
+        .map(|&delimiter| {
+            let counts: Vec<usize> = lines
+                .iter()
+                .map(|line| count_outside_quotes(line, delimiter))
+                .collect();
+            let max = counts.iter().copied().max().unwrap_or(0);
+            let consistent = counts.iter().filter(|&&c| c == max).count();
+            (delimiter, max, consistent)
+        })
+        .filter(|&(_, max, _)| max > 0)
+        .max_by_key(|&(_, max, consistent)| (consistent, max))
+        .map_or(b',', |(delimiter, ..)| delimiter)
+}
+
+fn count_outside_quotes(line: &str, delimiter: u8) -> usize {
+    let mut in_quotes = false;
+    line.bytes()
+        .filter(|&b| {
+            if b == b'"' {
+                in_quotes = !in_quotes;
+            }
+            b == delimiter && !in_quotes
+        })
+        .count()
+}
+
+pub(crate) fn read_rows(text: &str, delimiter: u8) -> Result<Vec<Vec<String>>, ErrorImport> {
+    csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(delimiter)
+        .from_reader(text.as_bytes())
+        .records()
+        .map(|record| {
+            record
+                .map(|record| record.iter().map(String::from).collect())
+                .map_err(|e| ErrorImport::Csv(e.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() -> Result<(), ErrorImport> {
+        // "Città" in Windows-1252
+        let latin = b"Citt\xe0";
+        assert_eq!(decode(latin, None)?, "Città");
+        assert_eq!(decode("\u{feff}Città".as_bytes(), None)?, "Città");
+        assert_eq!(decode(latin, Some("latin1"))?, "Città");
+        assert_eq!(
+            decode(latin, Some("klingon")),
+            Err(ErrorImport::UnknownEncoding("klingon".to_string()))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect_delimiter() {
+        assert_eq!(detect_delimiter("a;b;c\n1;2,5;3\n"), b';');
+        assert_eq!(detect_delimiter("a,b,c\n1,\"2;5\",3\n"), b',');
+        assert_eq!(detect_delimiter("a\tb\n1\t2\n"), b'\t');
+        assert_eq!(detect_delimiter("solo una colonna\n"), b',');
+    }
+
+    #[test]
+    fn test_read_rows() -> Result<(), ErrorImport> {
+        let rows = read_rows("chiave;nome via\n1-1;\"Via Roma; 3\"\n1-2\n", b';')?;
+        assert_eq!(
+            rows,
+            vec![
+                vec!["chiave", "nome via"],
+                vec!["1-1", "Via Roma; 3"],
+                vec!["1-2"],
+            ]
+        );
+        Ok(())
+    }
+}
@@ -1,76 +1,241 @@
 use std::{
     collections::{HashMap, HashSet},
     fmt::{Display, Formatter},
+    fs::{self, File},
+    io::BufReader,
+    path::Path,
 };
 
-use calamine::{Reader, Xlsx, open_workbook};
+use app_utils::app_error::ErrorImport;
+use calamine::{Ods, Reader, Xls, Xlsx, open_workbook};
+
+use crate::import::{
+    csv_reader,
+    format::FileFormat,
+    options::{HeaderRow, ImportOptions, SheetSelector},
+};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct SimpleDataFrame {
     headers: Vec<String>,
     columns: Vec<Vec<String>>,
+    /// Indice (partendo da 0) nel foglio della prima riga di dati
+    first_row: usize,
 }
 
-type Error = Box<dyn std::error::Error + Send + Sync>;
+type Error = ErrorImport;
 
-impl SimpleDataFrame {
-    pub fn from_xlsx(path: &str) -> Result<Self, Error> {
-        let mut workbook: Xlsx<_> = open_workbook(path)?;
-        let name_first_sheet = workbook.sheet_names().first().cloned().unwrap();
-        let sheet = workbook.worksheet_range(name_first_sheet.as_str())?;
+pub(crate) fn normalize_header(cell: &str) -> String {
+    cell.trim().to_ascii_lowercase().replace(" ", "_")
+}
 
-        let headers: Vec<String> = sheet
-            .rows()
-            .nth(5)
-            .unwrap()
-            .iter()
-            .map(|cell| cell.to_string().to_ascii_lowercase().replace(" ", "_"))
-            .collect();
+/// Righe di un foglio così come sono nel file, prima di individuare
+/// intestazione e piè di pagina
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawSheet {
+    rows: Vec<Vec<String>>,
+    /// Indice (partendo da 0) nel foglio della prima riga letta
+    offset: usize,
+}
 
-        let row_to_process = sheet.rows().skip(6).take(sheet.height() - 6);
-        let rows_vec: Vec<_> = row_to_process.collect();
-        let is_process_last_row = if !rows_vec.is_empty() {
-            let last_row = rows_vec.last().unwrap();
-            last_row
+impl RawSheet {
+    /// Legge il foglio riconoscendo il formato del file dal contenuto e
+    /// dall'estensione
+    pub fn read(path: &str, options: &ImportOptions) -> Result<Self, Error> {
+        match FileFormat::detect(Path::new(path))? {
+            FileFormat::Xlsx => Self::read_workbook::<Xlsx<_>>(path, options),
+            FileFormat::Xls => Self::read_workbook::<Xls<_>>(path, options),
+            FileFormat::Ods => Self::read_workbook::<Ods<_>>(path, options),
+            FileFormat::Csv => Self::read_csv(path, options),
+        }
+    }
+
+    fn read_workbook<R>(path: &str, options: &ImportOptions) -> Result<Self, Error>
+    where
+        R: Reader<BufReader<File>>,
+        R::Error: Display,
+    {
+        let mut workbook: R =
+            open_workbook(path).map_err(|e: R::Error| Error::Workbook(e.to_string()))?;
+
+        let sheet_names = workbook.sheet_names();
+        let sheet_name = match &options.sheet {
+            SheetSelector::Index(index) => sheet_names
+                .get(*index)
+                .cloned()
+                .ok_or(Error::SheetIndexOutOfRange(*index))?,
+            SheetSelector::Name(name) => sheet_names
                 .iter()
-                .any(|cell| cell.to_string().eq_ignore_ascii_case("Totale complessivo"))
-        } else {
-            false
+                .find(|sheet| sheet.eq_ignore_ascii_case(name))
+                .cloned()
+                .ok_or_else(|| Error::SheetNotFound(name.clone()))?,
         };
+        let sheet = workbook
+            .worksheet_range(sheet_name.as_str())
+            .map_err(|e| Error::Workbook(e.to_string()))?;
 
-        let rows_count = if is_process_last_row || rows_vec.is_empty() {
-            sheet.height() - (1 + 6)
-        } else {
-            rows_vec.len()
-        };
+        let rows: Vec<Vec<String>> = sheet
+            .rows()
+            .map(|row| row.iter().map(|cell| cell.to_string()).collect())
+            .collect();
+        if rows.is_empty() {
+            return Err(Error::EmptySheet(sheet_name));
+        }
+
+        Ok(Self {
+            rows,
+            // il range del foglio parte dalla prima cella non vuota
+            offset: sheet.start().map_or(0, |(row, _)| row as usize),
+        })
+    }
+
+    fn read_csv(path: &str, options: &ImportOptions) -> Result<Self, Error> {
+        let bytes = fs::read(path).map_err(|e| Error::Workbook(e.to_string()))?;
+        let text = csv_reader::decode(&bytes, options.csv.encoding.as_deref())?;
+        let delimiter = options
+            .csv
+            .delimiter
+            .unwrap_or_else(|| csv_reader::detect_delimiter(&text));
+
+        let rows = csv_reader::read_rows(&text, delimiter)?;
+        if rows.is_empty() {
+            return Err(Error::EmptySheet(path.to_string()));
+        }
+        Ok(Self { rows, offset: 0 })
+    }
 
-        // estrapolo tutti i dati e li salvo per colonne
+    pub fn rows(&self) -> &[Vec<String>] { &self.rows }
+
+    pub fn to_dataframe(&self, options: &ImportOptions) -> Result<SimpleDataFrame, Error> {
+        let mut df = SimpleDataFrame::build(&self.rows, options)?;
+        df.first_row += self.offset;
+        Ok(df)
+    }
+}
+
+impl SimpleDataFrame {
+    pub fn from_xlsx(path: &str) -> Result<Self, Error> {
+        Self::from_xlsx_with_options(path, &ImportOptions::default())
+    }
+
+    pub fn from_xlsx_with_options(path: &str, options: &ImportOptions) -> Result<Self, Error> {
+        RawSheet::read_workbook::<Xlsx<_>>(path, options)?.to_dataframe(options)
+    }
+
+    pub fn from_xls(path: &str, options: &ImportOptions) -> Result<Self, Error> {
+        RawSheet::read_workbook::<Xls<_>>(path, options)?.to_dataframe(options)
+    }
+
+    pub fn from_ods(path: &str, options: &ImportOptions) -> Result<Self, Error> {
+        RawSheet::read_workbook::<Ods<_>>(path, options)?.to_dataframe(options)
+    }
+
+    pub fn from_csv(path: &str, options: &ImportOptions) -> Result<Self, Error> {
+        RawSheet::read_csv(path, options)?.to_dataframe(options)
+    }
+
+    /// Costruisce il dataframe riconoscendo il formato del file dal contenuto
+    /// e dall'estensione
+    pub fn from_file(path: &str, options: &ImportOptions) -> Result<Self, Error> {
+        RawSheet::read(path, options)?.to_dataframe(options)
+    }
+
+    /// Costruisce il dataframe dalle righe grezze di un foglio, applicando le
+    /// regole di intestazione e piè di pagina di `options`
+    pub fn from_rows(rows: Vec<Vec<String>>, options: &ImportOptions) -> Result<Self, Error> {
+        Self::build(&rows, options)
+    }
+
+    fn build(rows: &[Vec<String>], options: &ImportOptions) -> Result<Self, Error> {
+        let header_index = Self::find_header_row(rows, &options.header)?;
+
+        let headers: Vec<String> = rows[header_index]
+            .iter()
+            .map(|cell| normalize_header(cell))
+            .collect();
+
+        // estrapolo tutti i dati fino al piè di pagina e li salvo per colonne
         let mut column_data: Vec<Vec<String>> = vec![Vec::new(); headers.len()];
-        for row_index in 0..rows_count {
-            let row = rows_vec.get(row_index).unwrap();
-            for (i, cell) in row.iter().enumerate() {
-                if i < column_data.len() {
-                    column_data[i].push(cell.to_string());
-                }
+        for row in rows
+            .iter()
+            .skip(header_index + 1)
+            .take_while(|row| !options.footer.iter().any(|rule| rule.matches(row)))
+        {
+            for (i, column) in column_data.iter_mut().enumerate() {
+                column.push(row.get(i).cloned().unwrap_or_default());
             }
         }
 
         Ok(SimpleDataFrame {
             headers,
             columns: column_data,
+            first_row: header_index + 1,
         })
     }
 
+    /// Nuovo dataframe con le stesse righe del foglio ma colonne diverse
+    pub(crate) fn with_columns(&self, headers: Vec<String>, columns: Vec<Vec<String>>) -> Self {
+        SimpleDataFrame {
+            headers,
+            columns,
+            first_row: self.first_row,
+        }
+    }
+
+    fn find_header_row(rows: &[Vec<String>], header: &HeaderRow) -> Result<usize, Error> {
+        match header {
+            HeaderRow::Index(index) => {
+                if *index < rows.len() {
+                    Ok(*index)
+                } else {
+                    Err(Error::HeaderRowOutOfRange {
+                        row: *index,
+                        height: rows.len(),
+                    })
+                }
+            }
+            HeaderRow::Detect { required, max_rows } => {
+                let required: Vec<String> = required.iter().map(|c| normalize_header(c)).collect();
+                // colonne mancanti nella riga che si avvicina di più all'intestazione
+                let mut best_missing: Option<Vec<String>> = None;
+
+                for (index, row) in rows.iter().take(*max_rows).enumerate() {
+                    let cells: HashSet<String> = row.iter().map(|c| normalize_header(c)).collect();
+                    let missing: Vec<String> = required
+                        .iter()
+                        .filter(|column| !cells.contains(*column))
+                        .cloned()
+                        .collect();
+                    if missing.is_empty() {
+                        return Ok(index);
+                    }
+                    if best_missing
+                        .as_ref()
+                        .is_none_or(|best| missing.len() < best.len())
+                    {
+                        best_missing = Some(missing);
+                    }
+                }
+
+                Err(Error::HeaderNotFound(best_missing.unwrap_or(required)))
+            }
+        }
+    }
+
+    pub fn headers(&self) -> &[String] { &self.headers }
+
+    pub fn height(&self) -> usize { self.columns.first().map_or(0, |c| c.len()) }
+
+    /// Numero della riga nel foglio (partendo da 1) della riga di dati `index`
+    pub fn row_number(&self, index: usize) -> usize { self.first_row + index + 1 }
+
     pub fn column(&self, col_name: &str) -> Result<Vec<String>, Error> {
-        let index = self
-            .headers
+        self.headers
             .iter()
             .position(|header| header == col_name)
-            .unwrap();
-        self.columns
-            .get(index)
+            .and_then(|index| self.columns.get(index))
             .cloned()
-            .ok_or(format!("Column {} not found", col_name).into())
+            .ok_or_else(|| Error::ColumnNotFound(col_name.to_string()))
     }
 
     pub fn set_column(&mut self, col_name: &str, value: Vec<String>) -> Result<(), Error> {
@@ -78,7 +243,14 @@ impl SimpleDataFrame {
             .headers
             .iter()
             .position(|header| header == col_name)
-            .ok_or_else(|| format!("Column {} not found", col_name))?;
+            .ok_or_else(|| Error::ColumnNotFound(col_name.to_string()))?;
+        if value.len() != self.height() {
+            return Err(Error::ColumnLength {
+                column: col_name.to_string(),
+                expected: self.height(),
+                found: value.len(),
+            });
+        }
         self.columns[index] = value;
         Ok(())
     }
@@ -90,7 +262,7 @@ impl SimpleDataFrame {
                 self.headers
                     .iter()
                     .position(|header| header.eq_ignore_ascii_case(name))
-                    .ok_or_else(|| format!("Column {} not found", name))
+                    .ok_or_else(|| Error::ColumnNotFound(name.to_string()))
             })
             .collect::<Result<Vec<_>, _>>()?;
         indices.sort();
@@ -114,7 +286,7 @@ impl SimpleDataFrame {
         let mut seen = HashSet::new();
         let mut unique_rows: Vec<Vec<String>> = vec![vec![]; self.columns.len()];
 
-        for i in 0..self.columns[0].len() {
+        for i in 0..self.height() {
             let mut row = Vec::new();
             for j in 0..self.columns.len() {
                 row.push(self.columns[j][i].clone());
@@ -142,7 +314,7 @@ impl Display for SimpleDataFrame {
             write!(f, "{}\t", header)?;
         }
         writeln!(f)?;
-        for i in 0..self.columns[0].len() {
+        for i in 0..self.height() {
             for j in 0..self.columns.len() {
                 write!(f, "{}\t", self.columns[j][i])?;
             }
@@ -156,11 +328,12 @@ impl Display for SimpleDataFrame {
 pub struct TransposedDataFrame {
     headers: Vec<String>,
     rows: Vec<Vec<String>>,
+    first_row: usize,
 }
 
 impl TransposedDataFrame {
     pub fn from_dataframe(df: &SimpleDataFrame) -> Self {
-        let mut rows: Vec<Vec<String>> = vec![vec![]; df.columns[0].len()];
+        let mut rows: Vec<Vec<String>> = vec![vec![]; df.height()];
         rows.iter_mut().enumerate().for_each(|(i, row)| {
             for j in 0..df.headers.len() {
                 row.push(df.columns[j][i].clone());
@@ -169,6 +342,7 @@ impl TransposedDataFrame {
         TransposedDataFrame {
             headers: df.headers.clone(),
             rows,
+            first_row: df.first_row,
         }
     }
 
@@ -183,6 +357,7 @@ impl TransposedDataFrame {
         SimpleDataFrame {
             headers: self.headers.clone(),
             columns,
+            first_row: self.first_row,
         }
     }
 
@@ -240,6 +415,7 @@ mod tests {
     use app_utils::test::ResultTest;
 
     use super::*;
+    use crate::import::options::FooterRule;
 
     #[test]
     fn test_import() {
@@ -321,6 +497,152 @@ mod tests {
         }
     }
 
+    fn write_test_workbook(name: &str) -> ResultTest<String> {
+        let path = std::env::temp_dir().join(format!("dataframe_test_{name}.xlsx"));
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+
+        let sheet = workbook.add_worksheet();
+        sheet.set_name("Riepilogo")?;
+        sheet.write_string(0, 0, "Nessun dato")?;
+
+        let sheet = workbook.add_worksheet();
+        sheet.set_name("Anagrafica")?;
+        sheet.write_string(0, 0, "Anagrafica edifici")?;
+        for (col, header) in ["Chiave", "Fascicolo", "Nome via"].iter().enumerate() {
+            sheet.write_string(2, col as u16, *header)?;
+        }
+        for (row, chiave) in ["1-1", "1-2", "1-3"].iter().enumerate() {
+            sheet.write_string(3 + row as u32, 0, *chiave)?;
+            sheet.write_number(3 + row as u32, 1, 100.0)?;
+            sheet.write_string(3 + row as u32, 2, "Via Roma")?;
+        }
+        sheet.write_string(6, 0, "Totale complessivo")?;
+
+        workbook.save(&path)?;
+        Ok(path.to_string_lossy().to_string())
+    }
+
+    #[test]
+    fn test_import_with_options() -> ResultTest {
+        let path = write_test_workbook("options")?;
+        let options = ImportOptions::default()
+            .sheet(SheetSelector::Name("anagrafica".to_string()))
+            .detect_header(&["chiave", "nome_via"]);
+
+        let df = SimpleDataFrame::from_xlsx_with_options(path.as_str(), &options)?;
+        assert_eq!(df.headers(), &["chiave", "fascicolo", "nome_via"]);
+        assert_eq!(df.height(), 3);
+        assert_eq!(df.row_number(0), 4);
+        assert_eq!(df.column("chiave")?, vec!["1-1", "1-2", "1-3"]);
+
+        std::fs::remove_file(path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_errors() -> ResultTest {
+        let path = write_test_workbook("errors")?;
+
+        let options = ImportOptions::default().sheet(SheetSelector::Name("Stanze".to_string()));
+        assert_eq!(
+            SimpleDataFrame::from_xlsx_with_options(path.as_str(), &options),
+            Err(ErrorImport::SheetNotFound("Stanze".to_string()))
+        );
+
+        let options = ImportOptions::default().sheet(SheetSelector::Index(5));
+        assert_eq!(
+            SimpleDataFrame::from_xlsx_with_options(path.as_str(), &options),
+            Err(ErrorImport::SheetIndexOutOfRange(5))
+        );
+
+        // il primo foglio ha una sola riga
+        assert_eq!(
+            SimpleDataFrame::from_xlsx(path.as_str()),
+            Err(ErrorImport::HeaderRowOutOfRange { row: 5, height: 1 })
+        );
+
+        let options = ImportOptions::default()
+            .sheet(SheetSelector::Index(1))
+            .detect_header(&["chiave", "piano"]);
+        assert_eq!(
+            SimpleDataFrame::from_xlsx_with_options(path.as_str(), &options),
+            Err(ErrorImport::HeaderNotFound(vec!["piano".to_string()]))
+        );
+
+        assert!(matches!(
+            SimpleDataFrame::from_xlsx("/path/not/exists.xlsx"),
+            Err(ErrorImport::Workbook(_))
+        ));
+
+        std::fs::remove_file(path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_csv() -> ResultTest {
+        let path = std::env::temp_dir().join("dataframe_test_csv.csv");
+        // export in Windows-1252 con separatore ";" e riga di titolo
+        let mut content = b"Anagrafica edifici;;\r\n".to_vec();
+        content.extend_from_slice(b"Chiave;Fascicolo;Nome via\r\n");
+        content.extend_from_slice(b"1-1;100;Via Citt\xe0 di Castello\r\n");
+        content.extend_from_slice(b"1-2;100;\"Via Roma; 3\"\r\n");
+        content.extend_from_slice(b"Totale complessivo;;\r\n");
+        std::fs::write(&path, content)?;
+        let path = path.to_string_lossy().to_string();
+
+        let options = ImportOptions::default().detect_header(&["chiave", "nome_via"]);
+        let df = SimpleDataFrame::from_file(path.as_str(), &options)?;
+        assert_eq!(df.height(), 2);
+        assert_eq!(df.row_number(0), 3);
+        assert_eq!(
+            df.column("nome_via")?,
+            vec!["Via Città di Castello", "Via Roma; 3"]
+        );
+
+        std::fs::remove_file(path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_file_detects_xlsx() -> ResultTest {
+        let path = write_test_workbook("from_file")?;
+        let options = ImportOptions::default()
+            .sheet(SheetSelector::Index(1))
+            .detect_header(&["chiave"]);
+
+        let df = SimpleDataFrame::from_file(path.as_str(), &options)?;
+        assert_eq!(
+            df,
+            SimpleDataFrame::from_xlsx_with_options(path.as_str(), &options)?
+        );
+
+        std::fs::remove_file(path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_rows_footer_empty_row() -> ResultTest {
+        let rows: Vec<Vec<String>> = vec![
+            vec!["Chiave".into(), "Piano".into()],
+            vec!["1-1".into(), "T".into()],
+            vec!["1-1".into()],
+            vec!["".into(), "".into()],
+            vec!["Note".into(), "".into()],
+        ];
+        let options = ImportOptions::default()
+            .header(HeaderRow::Index(0))
+            .footer(vec![FooterRule::EmptyRow]);
+
+        let df = SimpleDataFrame::from_rows(rows, &options)?;
+        assert_eq!(df.height(), 2);
+        assert_eq!(df.column("piano")?, vec!["T", ""]);
+        assert_eq!(
+            df.column("cod_stanza"),
+            Err(ErrorImport::ColumnNotFound("cod_stanza".to_string()))
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_traspose_traspose() {
         let mut df = SimpleDataFrame::from_xlsx(
@@ -0,0 +1,494 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use app_models::models::{Edificio, NewStanza, Stanza, UpdateStanza};
+use app_services::{
+    dao::{EdificioDAO, StanzaDAO},
+    dto::{EdificioDTO, StanzaDTO, StanzaDTOBuilder},
+};
+use app_utils::{
+    app_error::{AppResult, ApplicationError, DomainError, ErrorImport},
+    app_interface::{
+        dao_interface::crud_operations::{Get, GetAll, Insert, Update},
+        database_interface::{DatabaseManagerTrait, PostgresPooled},
+    },
+};
+use diesel::Connection;
+use log::info;
+use serde::Serialize;
+use tauri::State;
+
+use crate::import::{
+    dataframe::SimpleDataFrame,
+    id_generator::{Error as ErrorStanza, IdGeneratorStanza},
+};
+
+/// Colonne necessarie per creare edifici e stanze di un nuovo fascicolo
+pub const COLONNE_FASCICOLO: &[&str] = &[
+    "chiave",
+    "fascicolo",
+    "nome_via",
+    "piano",
+    "id_spazio",
+    "cod_stanza",
+    "destinazione_uso",
+];
+
+/// Errore di validazione di una riga del foglio
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct RowError {
+    /// Numero della riga nel foglio (partendo da 1)
+    pub row: usize,
+    pub column: Option<String>,
+    pub message: String,
+}
+
+impl RowError {
+    pub(crate) fn new(row: usize, column: &str, message: impl Into<String>) -> Self {
+        Self {
+            row,
+            column: Some(column.to_string()),
+            message: message.into(),
+        }
+    }
+}
+
+/// Risultato dell'analisi del foglio rispetto al contenuto del database.
+/// Le stanze sono riconosciute per edificio, piano e id spazio, così che
+/// reimportare lo stesso foglio non produca duplicati.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportPreview {
+    /// Edifici non ancora presenti nel database
+    pub edifici: Vec<EdificioDTO>,
+    /// Stanze nuove, con il `cod_stanza` generato
+    pub stanze: Vec<StanzaDTO>,
+    /// Stanze già presenti di cui cambia la destinazione d'uso, con il
+    /// `cod_stanza` rigenerato se cambia la sigla della destinazione
+    pub stanze_aggiornate: Vec<StanzaDTO>,
+    /// Stanze presenti nel database ma non più nel foglio, che non vengono
+    /// eliminate perché potrebbero contenere dati del sopralluogo
+    pub stanze_mancanti: Vec<StanzaDTO>,
+    pub errors: Vec<RowError>,
+}
+
+impl ImportPreview {
+    pub fn is_valid(&self) -> bool { self.errors.is_empty() }
+}
+
+type ChiaveStanza = (String, String, String);
+
+fn chiave_stanza(stanza: &Stanza) -> ChiaveStanza {
+    (
+        stanza.edificio_id.clone(),
+        stanza.piano.clone(),
+        stanza.id_spazio.clone(),
+    )
+}
+
+/// Edifici e stanze già presenti nel database per le chiavi del foglio
+struct Esistenti {
+    edifici: HashMap<String, Edificio>,
+    stanze: Vec<Stanza>,
+}
+
+pub struct ImportFascicolo;
+
+impl ImportFascicolo {
+    /// Analizza il dataframe senza scrivere nel database
+    pub async fn preview(
+        db: State<'_, impl DatabaseManagerTrait + Send + Sync>,
+        df: &SimpleDataFrame,
+    ) -> AppResult<ImportPreview> {
+        let mut conn = db.get_connection().await?;
+        let esistenti = Self::load(&mut conn, df)?;
+        Self::plan(df, esistenti)
+    }
+
+    /// Applica l'import in un'unica transazione: inserisce gli edifici e le
+    /// stanze nuove e aggiorna la destinazione d'uso di quelle esistenti. Se
+    /// anche una sola riga non è valida non viene scritto nulla.
+    pub async fn import(
+        db: State<'_, impl DatabaseManagerTrait + Send + Sync>,
+        df: &SimpleDataFrame,
+    ) -> AppResult<ImportPreview> {
+        let mut conn = db.get_connection().await?;
+        conn.transaction::<_, ApplicationError, _>(|tx| {
+            let esistenti = Self::load(tx, df)?;
+            let plan = Self::plan(df, esistenti)?;
+            if !plan.is_valid() {
+                return Err(ErrorImport::InvalidRows(plan.errors.len()).into());
+            }
+            Ok(Self::apply(tx, plan)?)
+        })
+    }
+
+    fn load(conn: &mut PostgresPooled, df: &SimpleDataFrame) -> AppResult<Esistenti> {
+        let chiavi: HashSet<String> = df
+            .column("chiave")?
+            .into_iter()
+            .map(|chiave| chiave.trim().to_string())
+            .collect();
+
+        let edifici: HashMap<String, Edificio> = EdificioDAO::get_all(conn)?
+            .into_iter()
+            .filter(|e| chiavi.contains(&e.chiave))
+            .map(|e| (e.chiave.clone(), e))
+            .collect();
+        let mut stanze = Vec::new();
+        for chiave in edifici.keys() {
+            stanze.extend(StanzaDAO::get(conn, chiave.as_str())?);
+        }
+        stanze.sort_by_key(|s| s.id);
+
+        Ok(Esistenti { edifici, stanze })
+    }
+
+    fn plan(df: &SimpleDataFrame, esistenti: Esistenti) -> AppResult<ImportPreview> {
+        for name in COLONNE_FASCICOLO {
+            df.column(name)?;
+        }
+
+        let mut generator_id_stanza = IdGeneratorStanza::new();
+        // stanze esistenti non ancora associate a una riga del foglio
+        let mut da_associare: HashMap<ChiaveStanza, VecDeque<Stanza>> = HashMap::new();
+        for stanza in esistenti.stanze {
+            generator_id_stanza.register(&stanza);
+            da_associare
+                .entry(chiave_stanza(&stanza))
+                .or_default()
+                .push_back(stanza);
+        }
+
+        let mut errors = Vec::new();
+        let mut edifici: Vec<EdificioDTO> = Vec::new();
+        // chiave -> (fascicolo, indirizzo, riga della prima occorrenza)
+        let mut visti: HashMap<String, (i32, String, usize)> = HashMap::new();
+        let mut stanze = Vec::new();
+        let mut stanze_aggiornate = Vec::new();
+
+        for (i, values) in df.traspose().iter_rows().enumerate() {
+            let row = df.row_number(i);
+            let field = |name: &str| values.get(name).map_or("", |v| v.trim()).to_string();
+
+            let chiave = field("chiave");
+            if chiave.is_empty() {
+                errors.push(RowError::new(row, "chiave", "Chiave vuota"));
+                continue;
+            }
+            let fascicolo = match field("fascicolo").parse::<i32>() {
+                Ok(fascicolo) => fascicolo,
+                Err(_) => {
+                    errors.push(RowError::new(
+                        row,
+                        "fascicolo",
+                        format!("Fascicolo '{}' non valido", field("fascicolo")),
+                    ));
+                    continue;
+                }
+            };
+            let indirizzo = field("nome_via");
+
+            if let Some(edificio) = esistenti.edifici.get(&chiave) {
+                if edificio.fascicolo != fascicolo {
+                    errors.push(RowError::new(
+                        row,
+                        "fascicolo",
+                        format!(
+                            "L'edificio {chiave} è già presente nel fascicolo {}",
+                            edificio.fascicolo
+                        ),
+                    ));
+                    continue;
+                }
+            } else {
+                match visti.get(&chiave) {
+                    Some((first_fascicolo, first_indirizzo, first_row)) => {
+                        if *first_fascicolo != fascicolo || *first_indirizzo != indirizzo {
+                            errors.push(RowError::new(
+                                row,
+                                "chiave",
+                                format!(
+                                    "Chiave {chiave} duplicata con fascicolo o indirizzo \
+                                     diversi dalla riga {first_row}"
+                                ),
+                            ));
+                            continue;
+                        }
+                    }
+                    None => {
+                        visti.insert(chiave.clone(), (fascicolo, indirizzo.clone(), row));
+                        edifici.push(EdificioDTO {
+                            chiave: chiave.clone(),
+                            fascicolo,
+                            indirizzo,
+                            anno_costruzione: None,
+                            anno_riqualificazione: None,
+                            note_riqualificazione: None,
+                            isolamento_tetto: false,
+                            cappotto: false,
+                        });
+                    }
+                }
+            }
+
+            let stanza: StanzaDTO = StanzaDTOBuilder::default()
+                .edificio_id(chiave)
+                .piano(field("piano"))
+                .id_spazio(field("id_spazio"))
+                .cod_stanza(field("cod_stanza"))
+                .destinazione_uso(field("destinazione_uso"))
+                .build();
+            let stanza: Stanza = stanza.into();
+            if let Err(e) = generator_id_stanza.validate(&stanza) {
+                let column = match e {
+                    ErrorStanza::InvalidPiano => "piano",
+                    ErrorStanza::InvalidUso => "destinazione_uso",
+                };
+                errors.push(RowError::new(row, column, e.to_string()));
+                continue;
+            }
+
+            let esistente = da_associare
+                .get_mut(&chiave_stanza(&stanza))
+                .and_then(|stanze| stanze.pop_front());
+            match esistente {
+                Some(esistente) => {
+                    if esistente.destinazione_uso != stanza.destinazione_uso {
+                        let aggiornata =
+                            generator_id_stanza.change_uso(esistente, stanza.destinazione_uso)?;
+                        stanze_aggiornate.push(StanzaDTO::from(&aggiornata));
+                    }
+                }
+                None => stanze.push(generator_id_stanza.generate_id(stanza)?.into()),
+            }
+        }
+
+        let mut stanze_mancanti: Vec<StanzaDTO> = da_associare
+            .into_values()
+            .flatten()
+            .map(StanzaDTO::from)
+            .collect();
+        stanze_mancanti.sort_by_key(|s| s.id);
+
+        Ok(ImportPreview {
+            edifici,
+            stanze,
+            stanze_aggiornate,
+            stanze_mancanti,
+            errors,
+        })
+    }
+
+    fn apply(tx: &mut PostgresPooled, plan: ImportPreview) -> Result<ImportPreview, DomainError> {
+        let mut edifici = Vec::with_capacity(plan.edifici.len());
+        for edificio in plan.edifici {
+            let inserted = EdificioDAO::insert(tx, edificio.into())?;
+            edifici.push(EdificioDTO::from(&inserted));
+        }
+
+        let stanze = if plan.stanze.is_empty() {
+            Vec::new()
+        } else {
+            let new_stanze: Vec<NewStanza> = plan.stanze.into_iter().map(NewStanza::from).collect();
+            StanzaDAO::insert(tx, new_stanze)?
+                .iter()
+                .map(StanzaDTO::from)
+                .collect()
+        };
+
+        let mut stanze_aggiornate = Vec::with_capacity(plan.stanze_aggiornate.len());
+        for stanza in plan.stanze_aggiornate {
+            let update = UpdateStanza {
+                cod_stanza: Some(stanza.cod_stanza.into()),
+                destinazione_uso: Some(stanza.destinazione_uso.into()),
+                altezza: None,
+                spessore_muro: None,
+                riscaldamento: None,
+                raffrescamento: None,
+                illuminazione: None,
+                superficie: None,
+                perimetro: None,
+            };
+            let updated = StanzaDAO::update(tx, stanza.id as i32, update)?;
+            stanze_aggiornate.push(StanzaDTO::from(&updated));
+        }
+
+        info!(
+            "Import fascicolo: {} edifici e {} stanze inseriti, {} stanze aggiornate, {} stanze \
+             non più presenti nel foglio",
+            edifici.len(),
+            stanze.len(),
+            stanze_aggiornate.len(),
+            plan.stanze_mancanti.len()
+        );
+
+        Ok(ImportPreview {
+            edifici,
+            stanze,
+            stanze_aggiornate,
+            stanze_mancanti: plan.stanze_mancanti,
+            errors: plan.errors,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use app_state::database::DatabaseManager;
+    use app_utils::test::{ResultTest, TestServiceEnvironment};
+
+    use super::*;
+    use crate::import::options::{HeaderRow, ImportOptions};
+
+    fn dataframe(rows: &[[&str; 7]]) -> ResultTest<SimpleDataFrame> {
+        let rows = std::iter::once(COLONNE_FASCICOLO.to_vec())
+            .chain(rows.iter().map(|row| row.to_vec()))
+            .map(|row| row.into_iter().map(String::from).collect())
+            .collect();
+        let options = ImportOptions::default().header(HeaderRow::Index(0));
+        Ok(SimpleDataFrame::from_rows(rows, &options)?)
+    }
+
+    fn stanza(id: i32, piano: &str, id_spazio: &str, cod_stanza: &str, uso: &str) -> Stanza {
+        Stanza {
+            id,
+            edificio_id: "100-1".to_string(),
+            piano: piano.to_string(),
+            id_spazio: id_spazio.to_string(),
+            cod_stanza: cod_stanza.to_string(),
+            destinazione_uso: uso.to_string(),
+            altezza: None,
+            spessore_muro: None,
+            riscaldamento: None,
+            raffrescamento: None,
+            illuminazione: None,
+            superficie: None,
+            perimetro: None,
+        }
+    }
+
+    fn cod_stanze(stanze: &[StanzaDTO]) -> Vec<&str> {
+        stanze.iter().map(|s| s.cod_stanza.as_str()).collect()
+    }
+
+    #[test]
+    fn test_plan_errors() -> ResultTest {
+        let df = dataframe(&[
+            ["100-1", "100", "Via Roma", "T", "1", "", "Ufficio"],
+            ["100-1", "100", "Via Roma", "T", "2", "", "Ufficio"],
+            ["100-1", "101", "Via Roma", "1", "3", "", "Ufficio"],
+            ["100-2", "abc", "Via Po", "1", "1", "", "Bagno"],
+            ["100-2", "100", "Via Po", "X", "2", "", "Bagno"],
+            ["", "100", "Via Po", "1", "1", "", "Bagno"],
+        ])?;
+        let esistenti = Esistenti {
+            edifici: HashMap::new(),
+            stanze: Vec::new(),
+        };
+
+        let preview = ImportFascicolo::plan(&df, esistenti)?;
+        assert_eq!(preview.edifici.len(), 2);
+        assert_eq!(cod_stanze(&preview.stanze), vec!["PT_UFF_01", "PT_UFF_02"]);
+        assert_eq!(
+            preview
+                .errors
+                .iter()
+                .map(|e| (e.row, e.column.as_deref()))
+                .collect::<Vec<_>>(),
+            vec![
+                (4, Some("chiave")),
+                (5, Some("fascicolo")),
+                (6, Some("piano")),
+                (7, Some("chiave")),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_plan_merge() -> ResultTest {
+        let df = dataframe(&[
+            ["100-1", "100", "Via Roma", "T", "1", "", "Ufficio"],
+            ["100-1", "100", "Via Roma", "T", "2", "", "Bagno"],
+            ["100-1", "100", "Via Roma", "T", "4", "", "Ufficio"],
+        ])?;
+        let edificio = Edificio {
+            chiave: "100-1".to_string(),
+            fascicolo: 100,
+            indirizzo: "Via Roma".to_string(),
+            anno_costruzione: None,
+            anno_riqualificazione: None,
+            note_riqualificazione: None,
+            isolamento_tetto: false,
+            cappotto: false,
+        };
+        let esistenti = Esistenti {
+            edifici: HashMap::from([(edificio.chiave.clone(), edificio)]),
+            stanze: vec![
+                stanza(1, "T", "1", "PT_UFF_01", "Ufficio"),
+                stanza(2, "T", "2", "PT_UFF_02", "Ufficio"),
+                stanza(3, "1", "3", "P01_BAG_01", "Bagno"),
+            ],
+        };
+
+        let preview = ImportFascicolo::plan(&df, esistenti)?;
+        assert!(preview.is_valid());
+        assert!(preview.edifici.is_empty());
+        // la numerazione prosegue da quella delle stanze esistenti
+        assert_eq!(cod_stanze(&preview.stanze), vec!["PT_UFF_03"]);
+        assert_eq!(preview.stanze_aggiornate.len(), 1);
+        assert_eq!(preview.stanze_aggiornate[0].id, 2);
+        assert_eq!(preview.stanze_aggiornate[0].destinazione_uso, "Bagno");
+        assert_eq!(cod_stanze(&preview.stanze_aggiornate), vec!["PT_BAG_01"]);
+        assert_eq!(cod_stanze(&preview.stanze_mancanti), vec!["P01_BAG_01"]);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn test_import_is_atomic() -> ResultTest {
+        let env =
+            TestServiceEnvironment::new::<_, _>(|_: DatabaseManager| async { Ok(()) }).await?;
+
+        let invalid = dataframe(&[
+            ["200-1", "200", "Via Roma", "T", "1", "", "Ufficio"],
+            ["200-2", "200", "Via Roma", "Z", "1", "", "Ufficio"],
+        ])?;
+        match ImportFascicolo::import(env.database(), &invalid).await {
+            Err(ApplicationError::Import(ErrorImport::InvalidRows(1))) => {}
+            other => panic!("Expected InvalidRows, got {other:?}"),
+        }
+        let preview = ImportFascicolo::preview(env.database(), &invalid).await?;
+        assert_eq!(preview.edifici.len(), 2);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn test_reimport_is_idempotent() -> ResultTest {
+        let env =
+            TestServiceEnvironment::new::<_, _>(|_: DatabaseManager| async { Ok(()) }).await?;
+
+        let df = dataframe(&[
+            ["200-1", "200", "Via Roma", "T", "1", "", "Ufficio"],
+            ["200-1", "200", "Via Roma", "T", "2", "", "Ufficio"],
+        ])?;
+        let report = ImportFascicolo::import(env.database(), &df).await?;
+        assert_eq!(report.edifici.len(), 1);
+        assert_eq!(report.stanze.len(), 2);
+
+        let report = ImportFascicolo::import(env.database(), &df).await?;
+        assert!(report.edifici.is_empty());
+        assert!(report.stanze.is_empty());
+        assert!(report.stanze_aggiornate.is_empty());
+        assert!(report.stanze_mancanti.is_empty());
+
+        let updated = dataframe(&[
+            ["200-1", "200", "Via Roma", "T", "2", "", "Bagno"],
+            ["200-1", "200", "Via Roma", "1", "3", "", "Ufficio"],
+        ])?;
+        let report = ImportFascicolo::import(env.database(), &updated).await?;
+        assert_eq!(cod_stanze(&report.stanze), vec!["P01_UFF_01"]);
+        assert_eq!(report.stanze_aggiornate[0].destinazione_uso, "Bagno");
+        assert_eq!(cod_stanze(&report.stanze_aggiornate), vec!["PT_BAG_01"]);
+        assert_eq!(cod_stanze(&report.stanze_mancanti), vec!["PT_UFF_01"]);
+        Ok(())
+    }
+}
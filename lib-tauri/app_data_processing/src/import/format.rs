@@ -0,0 +1,103 @@
+use std::{fs::File, io::Read, path::Path};
+
+use app_utils::app_error::ErrorImport;
+
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+const OLE_MAGIC: &[u8] = &[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+/// Nei file ODS il primo elemento dell'archivio è `mimetype`, non compresso
+const ODS_MIMETYPE: &[u8] = b"application/vnd.oasis.opendocument.spreadsheet";
+
+/// Formati da cui è possibile costruire un `SimpleDataFrame`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    Xlsx,
+    Xls,
+    Ods,
+    Csv,
+}
+
+impl FileFormat {
+    pub fn from_extension(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_string_lossy().to_ascii_lowercase();
+        match extension.as_str() {
+            "xlsx" | "xlsm" => Some(FileFormat::Xlsx),
+            "xls" => Some(FileFormat::Xls),
+            "ods" => Some(FileFormat::Ods),
+            "csv" | "tsv" | "txt" => Some(FileFormat::Csv),
+            _ => None,
+        }
+    }
+
+    /// Riconosce il formato dai primi byte del file; l'estensione viene usata
+    /// solo quando il contenuto non basta a distinguerlo
+    pub fn detect(path: &Path) -> Result<Self, ErrorImport> {
+        let mut head = Vec::with_capacity(128);
+        File::open(path)
+            .and_then(|file| file.take(128).read_to_end(&mut head))
+            .map_err(|e| ErrorImport::Workbook(e.to_string()))?;
+
+        Self::from_content(&head)
+            .or_else(|| Self::from_extension(path))
+            .ok_or_else(|| ErrorImport::UnsupportedFormat(path.display().to_string()))
+    }
+
+    fn from_content(head: &[u8]) -> Option<Self> {
+        if head.starts_with(ZIP_MAGIC) {
+            let is_ods = head
+                .windows(ODS_MIMETYPE.len())
+                .any(|window| window == ODS_MIMETYPE);
+            return Some(
+                if is_ods {
+                    FileFormat::Ods
+                } else {
+                    FileFormat::Xlsx
+                },
+            );
+        }
+        if head.starts_with(OLE_MAGIC) {
+            return Some(FileFormat::Xls);
+        }
+        // testo con BOM UTF-16 oppure senza byte nulli
+        if head.starts_with(&[0xFF, 0xFE]) || head.starts_with(&[0xFE, 0xFF]) {
+            return Some(FileFormat::Csv);
+        }
+        if !head.is_empty() && !head.contains(&0) {
+            return Some(FileFormat::Csv);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_content() {
+        let mut ods = b"PK\x03\x04".to_vec();
+        ods.extend_from_slice(&[0; 26]);
+        ods.extend_from_slice(b"mimetype");
+        ods.extend_from_slice(ODS_MIMETYPE);
+
+        assert_eq!(FileFormat::from_content(&ods), Some(FileFormat::Ods));
+        assert_eq!(
+            FileFormat::from_content(b"PK\x03\x04\x14\x00[Content_Types].xml"),
+            Some(FileFormat::Xlsx)
+        );
+        assert_eq!(FileFormat::from_content(OLE_MAGIC), Some(FileFormat::Xls));
+        assert_eq!(
+            FileFormat::from_content(b"chiave;fascicolo\n1-1;100"),
+            Some(FileFormat::Csv)
+        );
+        assert_eq!(FileFormat::from_content(&[0x00, 0x01, 0x02]), None);
+    }
+
+    #[test]
+    fn test_from_extension() {
+        assert_eq!(
+            FileFormat::from_extension(Path::new("anagrafica.ODS")),
+            Some(FileFormat::Ods)
+        );
+        assert_eq!(FileFormat::from_extension(Path::new("anagrafica")), None);
+    }
+}
@@ -4,7 +4,7 @@ use std::{
 };
 
 use app_models::models::Stanza;
-use app_utils::app_error::{AppResult, DomainError, ErrorKind};
+use app_utils::app_error::{AppResult, DomainError, ErrorKind, Locale, Localized, Message};
 
 #[derive(Debug)]
 pub enum Error {
@@ -12,15 +12,21 @@ pub enum Error {
     InvalidUso,
 }
 
-impl Display for Error {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+impl Localized for Error {
+    fn message(&self) -> Message {
         match self {
-            Error::InvalidPiano => f.write_str("Piano non valido o vuoto"),
-            Error::InvalidUso => f.write_str("Destinazione uso non valido o vuoto"),
+            Error::InvalidPiano => Message::new("import.invalid_piano"),
+            Error::InvalidUso => Message::new("import.invalid_uso"),
         }
     }
 }
 
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.localized(Locale::current()))
+    }
+}
+
 impl std::error::Error for Error {}
 
 type IdEdificio = String;
@@ -47,11 +53,9 @@ impl IdGeneratorStanza {
         let piano = if let Some(piano) = self.format_piano(stanza.piano.as_str()) {
             piano
         } else {
-            return Err(DomainError::InvalidInput(
-                ErrorKind::InvalidFormat,
-                "Piano non valido o vuoto".to_string(),
-            )
-            .into());
+            return Err(
+                DomainError::InvalidInput(ErrorKind::InvalidFormat, "piano".to_string()).into(),
+            );
         };
 
         let des_uso = if let Some(des_uso) = self.format_uso(stanza.destinazione_uso.as_str()) {
@@ -59,7 +63,7 @@ impl IdGeneratorStanza {
         } else {
             return Err(DomainError::InvalidInput(
                 ErrorKind::InvalidFormat,
-                "Destinazione d'uso non valido o vuoto".to_string(),
+                "destinazione_uso".to_string(),
             )
             .into());
         };
@@ -82,6 +86,55 @@ impl IdGeneratorStanza {
         Ok(stanza)
     }
 
+    /// Registra il codice di una stanza già presente nel database, così che i
+    /// nuovi codici dello stesso piano e destinazione d'uso ne proseguano la
+    /// numerazione. I codici che non seguono il formato generato sono ignorati.
+    pub fn register(&mut self, stanza: &Stanza) {
+        let Some((prefix, counter)) = stanza.cod_stanza.rsplit_once('_') else {
+            return;
+        };
+        let (Some((piano, des_uso)), Ok(counter)) =
+            (prefix.split_once('_'), counter.parse::<u32>())
+        else {
+            return;
+        };
+
+        let current = self
+            .counters
+            .entry((stanza.edificio_id.clone(), piano.to_string()))
+            .or_default()
+            .entry(des_uso.to_string())
+            .or_insert(0);
+        *current = (*current).max(counter);
+    }
+
+    /// Cambia la destinazione d'uso della stanza, generando un nuovo codice se
+    /// cambia la sigla della destinazione contenuta nel codice
+    pub fn change_uso(
+        &mut self,
+        mut stanza: Stanza,
+        destinazione_uso: String,
+    ) -> AppResult<Stanza> {
+        let stessa_sigla =
+            self.format_uso(&stanza.destinazione_uso) == self.format_uso(&destinazione_uso);
+        stanza.destinazione_uso = destinazione_uso;
+        if stessa_sigla {
+            Ok(stanza)
+        } else {
+            self.generate_id(stanza)
+        }
+    }
+
+    /// Verifica che piano e destinazione d'uso della stanza siano validi, senza
+    /// incrementare i contatori
+    pub fn validate(&self, stanza: &Stanza) -> Result<(), Error> {
+        self.format_piano(stanza.piano.as_str())
+            .ok_or(Error::InvalidPiano)?;
+        self.format_uso(stanza.destinazione_uso.as_str())
+            .ok_or(Error::InvalidUso)?;
+        Ok(())
+    }
+
     fn format_piano(&self, piano: &str) -> Option<String> {
         match piano.trim() {
             s if s.parse::<i32>().is_ok_and(|i| i < 0) => {
@@ -150,9 +203,40 @@ mod tests {
             riscaldamento: None,
             raffrescamento: None,
             illuminazione: None,
+            superficie: None,
+            perimetro: None,
         }
     }
 
+    #[test]
+    fn test_register_existing_codes() -> AppResult<()> {
+        let mut id_gen = IdGeneratorStanza::new();
+        id_gen.register(&init_stanza(
+            "T".into(),
+            "PT_UFF_04".into(),
+            "Ufficio".into(),
+        ));
+        id_gen.register(&init_stanza("T".into(), "ST001".into(), "Ufficio".into()));
+
+        let stanza = id_gen.generate_id(init_stanza("T".into(), "".into(), "Ufficio".into()))?;
+        assert_eq!(stanza.cod_stanza, "PT_UFF_05");
+        Ok(())
+    }
+
+    #[test]
+    fn test_change_uso() -> AppResult<()> {
+        let mut id_gen = IdGeneratorStanza::new();
+        let stanza = init_stanza("T".into(), "PT_UFF_01".into(), "Ufficio".into());
+        id_gen.register(&stanza);
+
+        let stanza = id_gen.change_uso(stanza, "Uffici".into())?;
+        assert_eq!(stanza.cod_stanza, "PT_UFF_01");
+        let stanza = id_gen.change_uso(stanza, "Bagno".into())?;
+        assert_eq!(stanza.cod_stanza, "PT_BAG_01");
+        assert_eq!(stanza.destinazione_uso, "Bagno");
+        Ok(())
+    }
+
     #[test]
     fn test_piano_interrato() {
         let id_gen = IdGeneratorStanza::new();
@@ -0,0 +1,579 @@
+use std::collections::{BTreeMap, HashMap};
+
+use app_models::models::{Infisso, NewInfisso, Stanza, StanzaConInfissi, UpdateStanzaConInfissi};
+use app_services::{
+    dao::{
+        InfissoDAO, MaterialeInfissoDAO, StanzaConInfissiDao, StanzaDAO, TipoInfissoDAO,
+        VetroInfissoDAO,
+    },
+    dto::InfissoDTO,
+};
+use app_utils::{
+    app_error::{AppResult, ApplicationError, DomainError, ErrorImport},
+    app_interface::{
+        dao_interface::crud_operations::{Get, GetAll, Insert, Update},
+        database_interface::{DatabaseManagerTrait, PostgresPooled},
+    },
+};
+use diesel::Connection;
+use log::info;
+use serde::Serialize;
+use tauri::State;
+
+use crate::import::{dataframe::SimpleDataFrame, fascicolo::RowError};
+
+/// Colonne necessarie per importare gli infissi di un edificio. La colonna
+/// `stanza` contiene il `cod_stanza` oppure l'id spazio della stanza.
+pub const COLONNE_INFISSI: &[&str] = &[
+    "tipo",
+    "altezza",
+    "larghezza",
+    "materiale",
+    "vetro",
+    "stanza",
+    "quantita",
+];
+
+/// Numero di infissi di un tipo presenti in una stanza
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct AssegnazioneInfisso {
+    pub stanza_id: i32,
+    pub cod_stanza: String,
+    pub infisso_id: String,
+    pub num_infisso: i32,
+    /// Numero registrato prima dell'import, 0 se l'infisso non era presente
+    pub num_precedente: i32,
+}
+
+/// Risultato dell'analisi del foglio degli infissi di un edificio. Gli
+/// infissi uguali a quelli già presenti vengono riutilizzati e il numero per
+/// stanza sostituisce quello registrato, così che reimportare lo stesso
+/// foglio non modifichi nulla.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportInfissiPreview {
+    /// Infissi non ancora presenti, con l'id generato
+    pub infissi: Vec<InfissoDTO>,
+    /// Assegnazioni nuove o con un numero di infissi diverso
+    pub assegnazioni: Vec<AssegnazioneInfisso>,
+    pub errors: Vec<RowError>,
+}
+
+impl ImportInfissiPreview {
+    pub fn is_valid(&self) -> bool { self.errors.is_empty() }
+}
+
+/// Valori ammessi per tipo, materiale e vetro
+struct Catalogo {
+    tipi: Vec<String>,
+    materiali: Vec<String>,
+    vetri: Vec<String>,
+}
+
+/// Dati dell'edificio già presenti nel database
+struct Esistenti {
+    catalogo: Catalogo,
+    stanze: Vec<Stanza>,
+    infissi: Vec<Infisso>,
+    assegnazioni: Vec<StanzaConInfissi>,
+}
+
+type ChiaveInfisso = (String, i16, i16, String, String);
+
+fn chiave_infisso(infisso: &Infisso) -> ChiaveInfisso {
+    (
+        infisso.tipo.clone(),
+        infisso.altezza,
+        infisso.larghezza,
+        infisso.materiale.clone(),
+        infisso.vetro.clone(),
+    )
+}
+
+/// Id successivo nella sequenza A, B, ..., Z, AA, AB, ... usata dal form
+/// di inserimento degli infissi
+fn next_id(prev: Option<&str>) -> String {
+    let Some(prev) = prev.filter(|prev| !prev.is_empty()) else {
+        return "A".to_string();
+    };
+    let mut chars: Vec<char> = prev.chars().collect();
+    for c in chars.iter_mut().rev() {
+        if *c == 'Z' {
+            *c = 'A';
+        } else {
+            *c = (*c as u8 + 1) as char;
+            return chars.into_iter().collect();
+        }
+    }
+    std::iter::once('A').chain(chars).collect()
+}
+
+/// Restituisce il valore del catalogo uguale a `value` senza considerare
+/// maiuscole e minuscole
+fn find_in<'a>(catalogo: &'a [String], value: &str) -> Option<&'a String> {
+    catalogo
+        .iter()
+        .find(|item| item.eq_ignore_ascii_case(value))
+}
+
+pub struct ImportInfissi;
+
+impl ImportInfissi {
+    /// Analizza il dataframe senza scrivere nel database
+    pub async fn preview(
+        db: State<'_, impl DatabaseManagerTrait + Send + Sync>,
+        edificio: &str,
+        df: &SimpleDataFrame,
+    ) -> AppResult<ImportInfissiPreview> {
+        let mut conn = db.get_connection().await?;
+        let esistenti = Self::load(&mut conn, edificio)?;
+        Self::plan(edificio, df, esistenti)
+    }
+
+    /// Inserisce gli infissi nuovi e aggiorna il numero di infissi delle
+    /// stanze in un'unica transazione. Se anche una sola riga non è valida
+    /// non viene scritto nulla.
+    pub async fn import(
+        db: State<'_, impl DatabaseManagerTrait + Send + Sync>,
+        edificio: &str,
+        df: &SimpleDataFrame,
+    ) -> AppResult<ImportInfissiPreview> {
+        let mut conn = db.get_connection().await?;
+        conn.transaction::<_, ApplicationError, _>(|tx| {
+            let esistenti = Self::load(tx, edificio)?;
+            let plan = Self::plan(edificio, df, esistenti)?;
+            if !plan.is_valid() {
+                return Err(ErrorImport::InvalidRows(plan.errors.len()).into());
+            }
+            Ok(Self::apply(tx, edificio, plan)?)
+        })
+    }
+
+    fn load(conn: &mut PostgresPooled, edificio: &str) -> AppResult<Esistenti> {
+        let catalogo = Catalogo {
+            tipi: TipoInfissoDAO::get_all(conn)?
+                .into_iter()
+                .map(|t| t.nome)
+                .collect(),
+            materiali: MaterialeInfissoDAO::get_all(conn)?
+                .into_iter()
+                .map(|m| m.materiale)
+                .collect(),
+            vetri: VetroInfissoDAO::get_all(conn)?
+                .into_iter()
+                .map(|v| v.vetro)
+                .collect(),
+        };
+        let stanze = StanzaDAO::get(conn, edificio)?;
+        let infissi = InfissoDAO::get(conn, edificio.to_string())?;
+        let assegnazioni = StanzaConInfissiDao::get(conn, edificio)?;
+
+        Ok(Esistenti {
+            catalogo,
+            stanze,
+            infissi,
+            assegnazioni,
+        })
+    }
+
+    fn plan(
+        edificio: &str,
+        df: &SimpleDataFrame,
+        esistenti: Esistenti,
+    ) -> AppResult<ImportInfissiPreview> {
+        for name in COLONNE_INFISSI {
+            df.column(name)?;
+        }
+
+        let catalogo = &esistenti.catalogo;
+        let mut ids: HashMap<ChiaveInfisso, String> = esistenti
+            .infissi
+            .iter()
+            .map(|infisso| (chiave_infisso(infisso), infisso.id.trim().to_string()))
+            .collect();
+        let mut last_id = ids
+            .values()
+            .max_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))
+            .cloned();
+
+        let per_codice: HashMap<&str, &Stanza> = esistenti
+            .stanze
+            .iter()
+            .map(|stanza| (stanza.cod_stanza.as_str(), stanza))
+            .collect();
+        let mut per_spazio: HashMap<&str, Vec<&Stanza>> = HashMap::new();
+        for stanza in &esistenti.stanze {
+            per_spazio
+                .entry(stanza.id_spazio.as_str())
+                .or_default()
+                .push(stanza);
+        }
+
+        let mut errors = Vec::new();
+        let mut infissi = Vec::new();
+        // (stanza, infisso) -> (cod_stanza, numero di infissi nel foglio)
+        let mut conteggi: BTreeMap<(i32, String), (String, i32)> = BTreeMap::new();
+
+        for (i, values) in df.traspose().iter_rows().enumerate() {
+            let row = df.row_number(i);
+            let field = |name: &str| values.get(name).map_or("", |v| v.trim()).to_string();
+
+            let Some(tipo) = find_in(&catalogo.tipi, &field("tipo")) else {
+                errors.push(RowError::new(
+                    row,
+                    "tipo",
+                    format!("Tipo di infisso '{}' non valido", field("tipo")),
+                ));
+                continue;
+            };
+            let mut dimensioni = [0i16; 2];
+            let mut valide = true;
+            for (dimensione, name) in dimensioni.iter_mut().zip(["altezza", "larghezza"]) {
+                match field(name).parse::<i16>() {
+                    Ok(value) if value > 0 => *dimensione = value,
+                    _ => {
+                        errors.push(RowError::new(
+                            row,
+                            name,
+                            format!("Valore '{}' non valido per {name}", field(name)),
+                        ));
+                        valide = false;
+                        break;
+                    }
+                }
+            }
+            if !valide {
+                continue;
+            }
+            let [altezza, larghezza] = dimensioni;
+            let Some(materiale) = find_in(&catalogo.materiali, &field("materiale")) else {
+                errors.push(RowError::new(
+                    row,
+                    "materiale",
+                    format!("Materiale '{}' non valido", field("materiale")),
+                ));
+                continue;
+            };
+            let Some(vetro) = find_in(&catalogo.vetri, &field("vetro")) else {
+                errors.push(RowError::new(
+                    row,
+                    "vetro",
+                    format!("Vetro '{}' non valido", field("vetro")),
+                ));
+                continue;
+            };
+            let quantita = match field("quantita") {
+                value if value.is_empty() => 1,
+                value => match value.parse::<i32>() {
+                    Ok(quantita) if quantita > 0 => quantita,
+                    _ => {
+                        errors.push(RowError::new(
+                            row,
+                            "quantita",
+                            format!("Quantità '{value}' non valida"),
+                        ));
+                        continue;
+                    }
+                },
+            };
+
+            let riferimento = field("stanza");
+            let stanza = match per_codice.get(riferimento.as_str()) {
+                Some(stanza) => *stanza,
+                None => match per_spazio.get(riferimento.as_str()).map(Vec::as_slice) {
+                    Some([stanza]) => *stanza,
+                    Some(_) => {
+                        errors.push(RowError::new(
+                            row,
+                            "stanza",
+                            format!(
+                                "L'id spazio {riferimento} corrisponde a più stanze, usare il \
+                                 codice stanza"
+                            ),
+                        ));
+                        continue;
+                    }
+                    None => {
+                        errors.push(RowError::new(
+                            row,
+                            "stanza",
+                            format!("Stanza '{riferimento}' non trovata nell'edificio {edificio}"),
+                        ));
+                        continue;
+                    }
+                },
+            };
+
+            let chiave = (
+                tipo.clone(),
+                altezza,
+                larghezza,
+                materiale.clone(),
+                vetro.clone(),
+            );
+            let infisso_id = match ids.get(&chiave) {
+                Some(id) => id.clone(),
+                None => {
+                    let id = next_id(last_id.as_deref());
+                    infissi.push(InfissoDTO {
+                        id: id.clone(),
+                        id_edificio: edificio.to_string(),
+                        tipo: tipo.clone(),
+                        altezza: altezza as u16,
+                        larghezza: larghezza as u16,
+                        materiale: materiale.clone(),
+                        vetro: vetro.clone(),
+                    });
+                    ids.insert(chiave, id.clone());
+                    last_id = Some(id.clone());
+                    id
+                }
+            };
+
+            conteggi
+                .entry((stanza.id, infisso_id))
+                .or_insert_with(|| (stanza.cod_stanza.clone(), 0))
+                .1 += quantita;
+        }
+
+        let precedenti: HashMap<(i32, String), i32> = esistenti
+            .assegnazioni
+            .iter()
+            .map(|a| {
+                (
+                    (a.stanza_id, a.infisso_id.trim().to_string()),
+                    a.num_infisso,
+                )
+            })
+            .collect();
+        let assegnazioni = conteggi
+            .into_iter()
+            .filter_map(|((stanza_id, infisso_id), (cod_stanza, num_infisso))| {
+                let num_precedente = precedenti
+                    .get(&(stanza_id, infisso_id.clone()))
+                    .copied()
+                    .unwrap_or_default();
+                (num_infisso != num_precedente).then_some(AssegnazioneInfisso {
+                    stanza_id,
+                    cod_stanza,
+                    infisso_id,
+                    num_infisso,
+                    num_precedente,
+                })
+            })
+            .collect();
+
+        Ok(ImportInfissiPreview {
+            infissi,
+            assegnazioni,
+            errors,
+        })
+    }
+
+    fn apply(
+        tx: &mut PostgresPooled,
+        edificio: &str,
+        plan: ImportInfissiPreview,
+    ) -> Result<ImportInfissiPreview, DomainError> {
+        let mut infissi = Vec::with_capacity(plan.infissi.len());
+        for infisso in plan.infissi {
+            let inserted = InfissoDAO::insert(tx, NewInfisso::from(infisso))?;
+            infissi.push(InfissoDTO::from(&inserted));
+        }
+
+        for assegnazione in &plan.assegnazioni {
+            // l'update somma al numero registrato, o inserisce l'assegnazione
+            // se non esiste ancora
+            StanzaConInfissiDao::update(
+                tx,
+                (
+                    edificio.to_string(),
+                    assegnazione.stanza_id,
+                    assegnazione.infisso_id.clone(),
+                ),
+                UpdateStanzaConInfissi {
+                    num_infisso: assegnazione.num_infisso - assegnazione.num_precedente,
+                },
+            )?;
+        }
+
+        info!(
+            "Import infissi dell'edificio {edificio}: {} infissi inseriti, {} assegnazioni a \
+             stanze aggiornate",
+            infissi.len(),
+            plan.assegnazioni.len()
+        );
+
+        Ok(ImportInfissiPreview {
+            infissi,
+            assegnazioni: plan.assegnazioni,
+            errors: plan.errors,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use app_state::database::DatabaseManager;
+    use app_utils::test::{ResultTest, TestServiceEnvironment};
+
+    use super::*;
+    use crate::import::{
+        fascicolo::{COLONNE_FASCICOLO, ImportFascicolo},
+        options::{HeaderRow, ImportOptions},
+    };
+
+    fn dataframe(headers: &[&str], rows: &[&[&str]]) -> ResultTest<SimpleDataFrame> {
+        let rows = std::iter::once(headers)
+            .chain(rows.iter().copied())
+            .map(|row| row.iter().map(|cell| cell.to_string()).collect())
+            .collect();
+        let options = ImportOptions::default().header(HeaderRow::Index(0));
+        Ok(SimpleDataFrame::from_rows(rows, &options)?)
+    }
+
+    fn stanza(id: i32, id_spazio: &str, cod_stanza: &str) -> Stanza {
+        Stanza {
+            id,
+            edificio_id: "100-1".to_string(),
+            piano: "T".to_string(),
+            id_spazio: id_spazio.to_string(),
+            cod_stanza: cod_stanza.to_string(),
+            destinazione_uso: "Ufficio".to_string(),
+            altezza: None,
+            spessore_muro: None,
+            riscaldamento: None,
+            raffrescamento: None,
+            illuminazione: None,
+            superficie: None,
+            perimetro: None,
+        }
+    }
+
+    fn esistenti() -> Esistenti {
+        let catalogo = |values: &[&str]| values.iter().map(|v| v.to_string()).collect();
+        Esistenti {
+            catalogo: Catalogo {
+                tipi: catalogo(&["Finestra", "Porta"]),
+                materiali: catalogo(&["Legno", "PVC"]),
+                vetri: catalogo(&["Singolo", "Doppio"]),
+            },
+            stanze: vec![
+                stanza(1, "1", "PT_UFF_01"),
+                stanza(2, "2", "PT_UFF_02"),
+                stanza(3, "2", "PT_UFF_03"),
+            ],
+            infissi: vec![Infisso {
+                id: "A   ".to_string(),
+                edificio_id: "100-1".to_string(),
+                tipo: "Finestra".to_string(),
+                altezza: 120,
+                larghezza: 80,
+                materiale: "Legno".to_string(),
+                vetro: "Doppio".to_string(),
+                mq: 0.96,
+            }],
+            assegnazioni: vec![StanzaConInfissi {
+                infisso_id: "A   ".to_string(),
+                edificio_id: "100-1".to_string(),
+                stanza_id: 1,
+                num_infisso: 2,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_next_id() {
+        assert_eq!(next_id(None), "A");
+        assert_eq!(next_id(Some("B")), "C");
+        assert_eq!(next_id(Some("Z")), "AA");
+        assert_eq!(next_id(Some("AZ")), "BA");
+    }
+
+    #[test]
+    fn test_plan() -> ResultTest {
+        let df = dataframe(
+            COLONNE_INFISSI,
+            &[
+                &["finestra", "120", "80", "legno", "doppio", "PT_UFF_01", "2"],
+                &["Porta", "210", "90", "PVC", "Singolo", "1", ""],
+                &["Porta", "210", "90", "PVC", "Singolo", "PT_UFF_03", "1"],
+                &["Porta", "210", "90", "PVC", "Singolo", "PT_UFF_03", "2"],
+                &["Lucernario", "50", "50", "PVC", "Singolo", "1", "1"],
+                &["Porta", "0", "90", "PVC", "Singolo", "1", "1"],
+                &["Porta", "210", "90", "PVC", "Singolo", "2", "1"],
+                &["Porta", "210", "90", "PVC", "Singolo", "9", "1"],
+                &["Porta", "210", "90", "PVC", "Singolo", "1", "-1"],
+            ],
+        )?;
+
+        let preview = ImportInfissi::plan("100-1", &df, esistenti())?;
+        assert_eq!(preview.infissi.len(), 1);
+        assert_eq!(preview.infissi[0].id, "B");
+        assert_eq!(preview.infissi[0].tipo, "Porta");
+        // la finestra è già assegnata con lo stesso numero alla stanza 1
+        assert_eq!(
+            preview
+                .assegnazioni
+                .iter()
+                .map(|a| (a.stanza_id, a.infisso_id.as_str(), a.num_infisso))
+                .collect::<Vec<_>>(),
+            vec![(1, "B", 1), (3, "B", 3)]
+        );
+        assert_eq!(
+            preview
+                .errors
+                .iter()
+                .map(|e| (e.row, e.column.as_deref()))
+                .collect::<Vec<_>>(),
+            vec![
+                (6, Some("tipo")),
+                (7, Some("altezza")),
+                (8, Some("stanza")),
+                (9, Some("stanza")),
+                (10, Some("quantita")),
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn test_import_infissi() -> ResultTest {
+        let env =
+            TestServiceEnvironment::new::<_, _>(|_: DatabaseManager| async { Ok(()) }).await?;
+
+        let fascicolo = dataframe(
+            COLONNE_FASCICOLO,
+            &[
+                &["300-1", "300", "Via Roma", "T", "1", "", "Ufficio"],
+                &["300-1", "300", "Via Roma", "T", "2", "", "Ufficio"],
+            ],
+        )?;
+        ImportFascicolo::import(env.database(), &fascicolo).await?;
+
+        let df = dataframe(
+            COLONNE_INFISSI,
+            &[
+                &["Finestra", "120", "80", "Legno", "Doppio", "PT_UFF_01", "2"],
+                &["Finestra", "120", "80", "Legno", "Doppio", "2", "1"],
+                &["Porta", "210", "90", "PVC", "Singolo", "2", "1"],
+            ],
+        )?;
+        let report = ImportInfissi::import(env.database(), "300-1", &df).await?;
+        assert_eq!(report.infissi.len(), 2);
+        assert_eq!(report.assegnazioni.len(), 3);
+
+        let report = ImportInfissi::import(env.database(), "300-1", &df).await?;
+        assert!(report.infissi.is_empty());
+        assert!(report.assegnazioni.is_empty());
+
+        let invalid = dataframe(
+            COLONNE_INFISSI,
+            &[&["Finestra", "120", "80", "Legno", "Doppio", "PT_UFF_09", "1"]],
+        )?;
+        match ImportInfissi::import(env.database(), "300-1", &invalid).await {
+            Err(ApplicationError::Import(ErrorImport::InvalidRows(1))) => {}
+            other => panic!("Expected InvalidRows, got {other:?}"),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,322 @@
+use std::collections::{BTreeMap, HashSet};
+
+use app_utils::{
+    app_error::{AppResult, ErrorImport},
+    store::JsonStore,
+};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+
+use crate::import::dataframe::{SimpleDataFrame, normalize_header};
+
+/// Righe in cui viene cercata l'intestazione per suggerire un profilo
+const HEADER_SEARCH_ROWS: usize = 20;
+
+/// Trasformazione applicata ai valori della colonna sorgente, nell'ordine in
+/// cui compaiono nel profilo
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Transform {
+    Trim,
+    Uppercase,
+    Lowercase,
+    Replace { from: String, to: String },
+    StripPrefix { prefix: String },
+}
+
+impl Transform {
+    fn apply(&self, value: String) -> String {
+        match self {
+            Transform::Trim => value.trim().to_string(),
+            Transform::Uppercase => value.to_uppercase(),
+            Transform::Lowercase => value.to_lowercase(),
+            Transform::Replace { from, to } => value.replace(from.as_str(), to),
+            Transform::StripPrefix { prefix } => value
+                .strip_prefix(prefix.as_str())
+                .map(str::to_string)
+                .unwrap_or(value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct FieldMapping {
+    /// Colonna del foglio da cui leggere il campo, se manca viene usata la
+    /// colonna con lo stesso nome del campo
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Valore usato quando la colonna non esiste o la cella è vuota
+    #[serde(default)]
+    pub default: Option<String>,
+    #[serde(default)]
+    pub transforms: Vec<Transform>,
+}
+
+/// Associazione tra le colonne di un foglio e i campi attesi dall'import.
+/// I campi senza mapping vengono letti dalla colonna con lo stesso nome.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct MappingProfile {
+    pub name: String,
+    #[serde(default)]
+    pub fields: BTreeMap<String, FieldMapping>,
+}
+
+impl MappingProfile {
+    fn source(&self, field: &str) -> String {
+        self.fields
+            .get(field)
+            .and_then(|mapping| mapping.source.as_deref())
+            .map_or_else(|| normalize_header(field), normalize_header)
+    }
+
+    fn default_value(&self, field: &str) -> Option<&str> {
+        self.fields
+            .get(field)
+            .and_then(|mapping| mapping.default.as_deref())
+    }
+
+    /// Colonne del foglio necessarie per i campi senza valore di default, da
+    /// usare per individuare la riga di intestazione
+    pub fn required_columns(&self, fields: &[&str]) -> Vec<String> {
+        fields
+            .iter()
+            .filter(|field| self.default_value(field).is_none())
+            .map(|field| self.source(field))
+            .collect()
+    }
+
+    /// Numero di campi che il profilo riesce a valorizzare con le intestazioni
+    /// indicate, e quanti di questi provengono da una colonna del foglio
+    fn score(&self, headers: &HashSet<String>, fields: &[&str]) -> (usize, usize) {
+        fields.iter().fold((0, 0), |(resolved, from_sheet), field| {
+            if headers.contains(&self.source(field)) {
+                (resolved + 1, from_sheet + 1)
+            } else if self.default_value(field).is_some() {
+                (resolved + 1, from_sheet)
+            } else {
+                (resolved, from_sheet)
+            }
+        })
+    }
+
+    /// Restituisce un dataframe con le sole colonne `fields`, lette dalle
+    /// colonne sorgente del profilo
+    pub fn apply(
+        &self,
+        df: &SimpleDataFrame,
+        fields: &[&str],
+    ) -> Result<SimpleDataFrame, ErrorImport> {
+        let mut columns = Vec::with_capacity(fields.len());
+        for field in fields {
+            let mapping = self.fields.get(*field);
+            let default = self.default_value(field);
+            let values = match (df.column(self.source(field).as_str()), default) {
+                (Ok(values), _) => values,
+                (Err(_), Some(_)) => vec![String::new(); df.height()],
+                (Err(e), None) => return Err(e),
+            };
+
+            let column = values
+                .into_iter()
+                .map(|value| {
+                    let value = mapping
+                        .map(|m| m.transforms.as_slice())
+                        .unwrap_or_default()
+                        .iter()
+                        .fold(value, |value, transform| transform.apply(value));
+                    match default {
+                        Some(default) if value.trim().is_empty() => default.to_string(),
+                        _ => value,
+                    }
+                })
+                .collect();
+            columns.push(column);
+        }
+
+        let headers = fields.iter().map(|field| field.to_string()).collect();
+        Ok(df.with_columns(headers, columns))
+    }
+
+    /// Sceglie il profilo che valorizza tutti i campi con le intestazioni del
+    /// foglio, preferendo quello che legge più campi dalle colonne. Se nessun
+    /// profilo fa meglio della lettura per nome delle colonne restituisce
+    /// `None`.
+    pub fn suggest<'a>(
+        profiles: &'a [MappingProfile],
+        rows: &[Vec<String>],
+        fields: &[&str],
+    ) -> Option<&'a MappingProfile> {
+        let candidates: Vec<HashSet<String>> = rows
+            .iter()
+            .take(HEADER_SEARCH_ROWS)
+            .map(|row| row.iter().map(|cell| normalize_header(cell)).collect())
+            .collect();
+        let best_score = |profile: &MappingProfile| {
+            candidates
+                .iter()
+                .map(|headers| profile.score(headers, fields))
+                .max()
+                .unwrap_or_default()
+        };
+
+        let baseline = best_score(&MappingProfile::default());
+        profiles
+            .iter()
+            .map(|profile| (profile, best_score(profile)))
+            .filter(|(_, (resolved, _))| *resolved == fields.len())
+            .filter(|(_, score)| *score > baseline)
+            .max_by_key(|(_, score)| *score)
+            .map(|(profile, _)| profile)
+    }
+}
+
+/// Profili di mapping salvati nello store dell'applicazione
+pub struct MappingProfileStore;
+
+impl MappingProfileStore {
+    const STORE: JsonStore = JsonStore::new("mapping_profiles.json", "profiles");
+
+    pub fn load<R: Runtime>(app_handle: &AppHandle<R>) -> AppResult<Vec<MappingProfile>> {
+        Ok(Self::STORE
+            .load(app_handle, |e| ErrorImport::InvalidProfile(e.to_string()))?
+            .unwrap_or_default())
+    }
+
+    pub fn get<R: Runtime>(app_handle: &AppHandle<R>, name: &str) -> AppResult<MappingProfile> {
+        Self::load(app_handle)?
+            .into_iter()
+            .find(|profile| profile.name == name)
+            .ok_or_else(|| ErrorImport::ProfileNotFound(name.to_string()).into())
+    }
+
+    /// Salva il profilo, sostituendo quello con lo stesso nome
+    pub fn save<R: Runtime>(app_handle: &AppHandle<R>, profile: MappingProfile) -> AppResult<()> {
+        if profile.name.trim().is_empty() {
+            return Err(ErrorImport::InvalidProfile("empty name".to_string()).into());
+        }
+        let mut profiles = Self::load(app_handle)?;
+        match profiles.iter_mut().find(|p| p.name == profile.name) {
+            Some(existing) => *existing = profile,
+            None => profiles.push(profile),
+        }
+        Self::write(app_handle, profiles)
+    }
+
+    /// Restituisce `false` se non esiste un profilo con il nome indicato
+    pub fn delete<R: Runtime>(app_handle: &AppHandle<R>, name: &str) -> AppResult<bool> {
+        let mut profiles = Self::load(app_handle)?;
+        let len = profiles.len();
+        profiles.retain(|profile| profile.name != name);
+        if profiles.len() == len {
+            return Ok(false);
+        }
+        Self::write(app_handle, profiles)?;
+        Ok(true)
+    }
+
+    fn write<R: Runtime>(
+        app_handle: &AppHandle<R>,
+        profiles: Vec<MappingProfile>,
+    ) -> AppResult<()> {
+        Self::STORE.save(app_handle, &profiles, |e| {
+            ErrorImport::InvalidProfile(e.to_string())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use app_utils::test::ResultTest;
+
+    use super::*;
+    use crate::import::options::{HeaderRow, ImportOptions};
+
+    const FIELDS: &[&str] = &["chiave", "piano", "destinazione_uso"];
+
+    fn rows(rows: &[&[&str]]) -> Vec<Vec<String>> {
+        rows.iter()
+            .map(|row| row.iter().map(|cell| cell.to_string()).collect())
+            .collect()
+    }
+
+    fn profile_comune() -> MappingProfile {
+        MappingProfile {
+            name: "Comune".to_string(),
+            fields: BTreeMap::from([
+                (
+                    "chiave".to_string(),
+                    FieldMapping {
+                        source: Some("Codice Edificio".to_string()),
+                        transforms: vec![Transform::Trim, Transform::Uppercase],
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "piano".to_string(),
+                    FieldMapping {
+                        source: Some("Livello".to_string()),
+                        transforms: vec![Transform::StripPrefix {
+                            prefix: "P".to_string(),
+                        }],
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "destinazione_uso".to_string(),
+                    FieldMapping {
+                        default: Some("Ufficio".to_string()),
+                        ..Default::default()
+                    },
+                ),
+            ]),
+        }
+    }
+
+    #[test]
+    fn test_apply() -> ResultTest {
+        let rows = rows(&[
+            &["Codice Edificio", "Livello", "Note"],
+            &[" ab-1 ", "P1", ""],
+            &["ab-2", "T", ""],
+        ]);
+        let df = SimpleDataFrame::from_rows(
+            rows,
+            &ImportOptions::default().header(HeaderRow::Index(0)),
+        )?;
+
+        let mapped = profile_comune().apply(&df, FIELDS)?;
+        assert_eq!(mapped.headers(), FIELDS);
+        assert_eq!(mapped.column("chiave")?, vec!["AB-1", "AB-2"]);
+        assert_eq!(mapped.column("piano")?, vec!["1", "T"]);
+        assert_eq!(
+            mapped.column("destinazione_uso")?,
+            vec!["Ufficio", "Ufficio"]
+        );
+        assert_eq!(mapped.row_number(0), df.row_number(0));
+
+        assert_eq!(
+            MappingProfile::default().apply(&df, FIELDS).err(),
+            Some(ErrorImport::ColumnNotFound("chiave".to_string()))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_suggest() {
+        let profiles = vec![
+            MappingProfile {
+                name: "Vuoto".to_string(),
+                fields: BTreeMap::new(),
+            },
+            profile_comune(),
+        ];
+        let comune = rows(&[&["Elenco stanze"], &["Codice edificio", "Livello"]]);
+        let standard = rows(&[&["Chiave", "Piano", "Destinazione uso"]]);
+
+        assert_eq!(
+            MappingProfile::suggest(&profiles, &comune, FIELDS).map(|p| p.name.as_str()),
+            Some("Comune")
+        );
+        assert_eq!(MappingProfile::suggest(&profiles, &standard, FIELDS), None);
+    }
+}
@@ -1,7 +1,18 @@
+mod csv_reader;
 mod dataframe;
+mod fascicolo;
+mod format;
 mod id_generator;
+mod infissi;
+mod mapping;
 //mod import_excel;
+mod options;
 
 pub use dataframe::*;
+pub use fascicolo::{COLONNE_FASCICOLO, ImportFascicolo, ImportPreview, RowError};
+pub use format::FileFormat;
 pub use id_generator::{Error, IdGeneratorStanza};
+pub use infissi::{AssegnazioneInfisso, COLONNE_INFISSI, ImportInfissi, ImportInfissiPreview};
+pub use mapping::{FieldMapping, MappingProfile, MappingProfileStore, Transform};
 //pub use import_excel::{ImportData, ImportDatiStanzaToExcel};
+pub use options::*;
@@ -0,0 +1,101 @@
+/// Foglio da cui leggere i dati
+#[derive(Debug, Clone, PartialEq)]
+pub enum SheetSelector {
+    Index(usize),
+    Name(String),
+}
+
+/// Posizione della riga di intestazione
+#[derive(Debug, Clone, PartialEq)]
+pub enum HeaderRow {
+    /// Indice (partendo da 0) della riga di intestazione
+    Index(usize),
+    /// La riga di intestazione è la prima, tra le prime `max_rows`, che
+    /// contiene tutte le colonne richieste
+    Detect {
+        required: Vec<String>,
+        max_rows: usize,
+    },
+}
+
+/// Regola per riconoscere la prima riga del piè di pagina, che insieme alle
+/// successive non viene importata
+#[derive(Debug, Clone, PartialEq)]
+pub enum FooterRule {
+    /// Una cella della riga contiene il testo indicato (case-insensitive)
+    Contains(String),
+    /// Tutte le celle della riga sono vuote
+    EmptyRow,
+}
+
+impl FooterRule {
+    pub(crate) fn matches(&self, row: &[String]) -> bool {
+        match self {
+            FooterRule::Contains(text) => row
+                .iter()
+                .any(|cell| cell.trim().eq_ignore_ascii_case(text.trim())),
+            FooterRule::EmptyRow => row.iter().all(|cell| cell.trim().is_empty()),
+        }
+    }
+}
+
+/// Opzioni per la lettura dei file CSV, i campi a `None` vengono rilevati dal
+/// contenuto del file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CsvOptions {
+    pub delimiter: Option<u8>,
+    /// Etichetta della codifica, ad esempio "utf-8" o "windows-1252"
+    pub encoding: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportOptions {
+    /// Ignorato per i file CSV, che hanno un solo foglio
+    pub sheet: SheetSelector,
+    pub header: HeaderRow,
+    pub footer: Vec<FooterRule>,
+    pub csv: CsvOptions,
+}
+
+impl Default for ImportOptions {
+    /// Layout dell'export dell'anagrafica edifici: primo foglio, intestazione
+    /// alla riga 6 e riga finale "Totale complessivo"
+    fn default() -> Self {
+        Self {
+            sheet: SheetSelector::Index(0),
+            header: HeaderRow::Index(5),
+            footer: vec![FooterRule::Contains("Totale complessivo".to_string())],
+            csv: CsvOptions::default(),
+        }
+    }
+}
+
+impl ImportOptions {
+    pub fn sheet(mut self, sheet: SheetSelector) -> Self {
+        self.sheet = sheet;
+        self
+    }
+
+    pub fn header(mut self, header: HeaderRow) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Cerca la riga di intestazione tra le prime 20 righe del foglio
+    pub fn detect_header(self, required: &[&str]) -> Self {
+        self.header(HeaderRow::Detect {
+            required: required.iter().map(|s| s.to_string()).collect(),
+            max_rows: 20,
+        })
+    }
+
+    pub fn footer(mut self, footer: Vec<FooterRule>) -> Self {
+        self.footer = footer;
+        self
+    }
+
+    pub fn csv(mut self, csv: CsvOptions) -> Self {
+        self.csv = csv;
+        self
+    }
+}
@@ -1,3 +1,15 @@
+mod backup;
+mod export;
+#[cfg(test)]
+mod fixtures;
 mod import;
+mod metrics;
+mod quality;
+mod scenario;
 
+pub use backup::*;
+pub use export::*;
 pub use import::*;
+pub use metrics::*;
+pub use quality::*;
+pub use scenario::*;
@@ -0,0 +1,300 @@
+use std::collections::BTreeMap;
+
+use app_models::models::StanzaConInfissi;
+use app_services::{
+    dao::{InfissoDAO, PareteEspostaDAO, StanzaConInfissiDao, StanzaDAO},
+    dto::{InfissoDTO, PareteEspostaDTO, StanzaDTO},
+};
+use app_utils::{
+    app_error::AppResult,
+    app_interface::{
+        dao_interface::crud_operations::Get,
+        database_interface::{DatabaseManagerTrait, PostgresPooled},
+    },
+};
+use serde::Serialize;
+use tauri::State;
+
+/// Misure di una stanza ricavate dai dati rilevati. Superfici in m², volumi
+/// in m³; `None` se mancano i dati per calcolarle.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StanzaMetrics {
+    pub id: u64,
+    pub piano: String,
+    pub cod_stanza: String,
+    pub destinazione_uso: String,
+    pub superficie: Option<f64>,
+    pub volume: Option<f64>,
+    /// Superficie lorda di tutte le pareti, dal perimetro
+    pub superficie_pareti: Option<f64>,
+    /// Superficie lorda delle pareti esposte, infissi compresi
+    pub superficie_disperdente: Option<f64>,
+    pub mq_infissi: f64,
+    /// Superficie degli infissi rispetto al pavimento
+    pub rapporto_aero_illuminante: Option<f64>,
+}
+
+/// Somme delle misure di un gruppo di stanze, calcolate solo sulle stanze in
+/// cui il dato è presente
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MetricsTotals {
+    pub stanze: usize,
+    /// Stanze di cui è nota la superficie
+    pub stanze_misurate: usize,
+    pub superficie: f64,
+    pub volume: f64,
+    pub superficie_pareti: f64,
+    pub superficie_disperdente: f64,
+    pub mq_infissi: f64,
+    /// Calcolato solo sulle stanze di cui è nota la superficie
+    pub rapporto_aero_illuminante: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PianoMetrics {
+    pub piano: String,
+    #[serde(flatten)]
+    pub totali: MetricsTotals,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EdificioMetrics {
+    pub edificio: String,
+    #[serde(flatten)]
+    pub totali: MetricsTotals,
+    pub piani: Vec<PianoMetrics>,
+    pub stanze: Vec<StanzaMetrics>,
+}
+
+impl MetricsTotals {
+    fn from_stanze<'a>(stanze: impl IntoIterator<Item = &'a StanzaMetrics>) -> Self {
+        let mut totali = MetricsTotals::default();
+        // infissi delle sole stanze misurate, per il rapporto aero-illuminante
+        let mut mq_infissi_misurati = 0.0;
+        for stanza in stanze {
+            totali.stanze += 1;
+            totali.mq_infissi += stanza.mq_infissi;
+            totali.volume += stanza.volume.unwrap_or_default();
+            totali.superficie_pareti += stanza.superficie_pareti.unwrap_or_default();
+            totali.superficie_disperdente += stanza.superficie_disperdente.unwrap_or_default();
+            if let Some(superficie) = stanza.superficie {
+                totali.stanze_misurate += 1;
+                totali.superficie += superficie;
+                mq_infissi_misurati += stanza.mq_infissi;
+            }
+        }
+        totali.rapporto_aero_illuminante =
+            (totali.superficie > 0.0).then(|| mq_infissi_misurati / totali.superficie);
+        totali
+    }
+}
+
+pub struct Geometry;
+
+impl Geometry {
+    /// Calcola le misure delle stanze dell'edificio e i totali per piano
+    pub async fn edificio(
+        db: State<'_, impl DatabaseManagerTrait + Send + Sync>,
+        edificio: &str,
+    ) -> AppResult<EdificioMetrics> {
+        let mut conn = db.get_connection().await?;
+        let stanze = Self::stanze(&mut conn, edificio)?;
+        let infissi: Vec<InfissoDTO> = InfissoDAO::get(&mut conn, edificio.to_string())?
+            .iter()
+            .map(InfissoDTO::from)
+            .collect();
+        let stanze_con_infissi = StanzaConInfissiDao::get(&mut conn, edificio)?;
+
+        Ok(Self::compute(
+            edificio,
+            &stanze,
+            &infissi,
+            &stanze_con_infissi,
+        ))
+    }
+
+    /// Stanze dell'edificio con le pareti esposte
+    pub(crate) fn stanze(conn: &mut PostgresPooled, edificio: &str) -> AppResult<Vec<StanzaDTO>> {
+        let stanze = StanzaDAO::get(conn, edificio)?;
+        let id_stanze: Vec<i32> = stanze.iter().map(|stanza| stanza.id).collect();
+        let pareti = PareteEspostaDAO::get(conn, id_stanze.as_slice())?;
+        stanze
+            .iter()
+            .map(|stanza| {
+                let pareti_esposte = pareti
+                    .iter()
+                    .filter(|parete| parete.stanza_id == stanza.id)
+                    .map(PareteEspostaDTO::try_from)
+                    .collect::<Result<Vec<_>, _>>()?;
+                let mut stanza = StanzaDTO::from(stanza);
+                stanza.pareti_esposte = Some(pareti_esposte);
+                Ok(stanza)
+            })
+            .collect()
+    }
+
+    pub(crate) fn compute(
+        edificio: &str,
+        stanze: &[StanzaDTO],
+        infissi: &[InfissoDTO],
+        stanze_con_infissi: &[StanzaConInfissi],
+    ) -> EdificioMetrics {
+        let mq_infisso: BTreeMap<&str, f64> = infissi
+            .iter()
+            .map(|infisso| (infisso.id.as_str(), Self::mq_infisso(infisso)))
+            .collect();
+
+        let stanze: Vec<StanzaMetrics> = stanze
+            .iter()
+            .map(|stanza| {
+                let mq_infissi = stanze_con_infissi
+                    .iter()
+                    .filter(|sci| sci.stanza_id as u64 == stanza.id)
+                    .map(|sci| {
+                        mq_infisso
+                            .get(sci.infisso_id.trim())
+                            .copied()
+                            .unwrap_or_default()
+                            * sci.num_infisso as f64
+                    })
+                    .sum();
+                Self::stanza(stanza, mq_infissi)
+            })
+            .collect();
+
+        let mut per_piano: BTreeMap<&str, Vec<&StanzaMetrics>> = BTreeMap::new();
+        for stanza in &stanze {
+            per_piano
+                .entry(stanza.piano.trim())
+                .or_default()
+                .push(stanza);
+        }
+        let piani = per_piano
+            .into_iter()
+            .map(|(piano, stanze)| PianoMetrics {
+                piano: piano.to_string(),
+                totali: MetricsTotals::from_stanze(stanze),
+            })
+            .collect();
+
+        EdificioMetrics {
+            edificio: edificio.to_string(),
+            totali: MetricsTotals::from_stanze(&stanze),
+            piani,
+            stanze,
+        }
+    }
+
+    fn stanza(stanza: &StanzaDTO, mq_infissi: f64) -> StanzaMetrics {
+        // l'altezza è in cm
+        let altezza = stanza.altezza.map(|altezza| altezza as f64 / 100.0);
+        let superficie = stanza.superficie.map(f64::from);
+        let lunghezza_esposta = stanza.pareti_esposte.as_ref().map(|pareti| {
+            pareti
+                .iter()
+                .map(|parete| parete.lunghezza as f64)
+                .sum::<f64>()
+        });
+
+        StanzaMetrics {
+            id: stanza.id,
+            piano: stanza.piano.trim().to_string(),
+            cod_stanza: stanza.cod_stanza.clone(),
+            destinazione_uso: stanza.destinazione_uso.clone(),
+            superficie,
+            volume: superficie.zip(altezza).map(|(s, h)| s * h),
+            superficie_pareti: stanza.perimetro.zip(altezza).map(|(p, h)| p as f64 * h),
+            superficie_disperdente: lunghezza_esposta.zip(altezza).map(|(l, h)| l * h),
+            mq_infissi,
+            rapporto_aero_illuminante: superficie
+                .filter(|superficie| *superficie > 0.0)
+                .map(|superficie| mq_infissi / superficie),
+        }
+    }
+
+    /// Superficie dell'infisso in m², le dimensioni sono in cm
+    pub(crate) fn mq_infisso(infisso: &InfissoDTO) -> f64 {
+        infisso.altezza as f64 * infisso.larghezza as f64 / 10_000.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use app_services::dto::Orientamento;
+
+    use super::*;
+    use crate::fixtures;
+
+    fn stanza(id: u64, piano: &str, superficie: Option<f32>) -> StanzaDTO {
+        fixtures::stanza(id)
+            .piano(piano.to_string())
+            .altezza(Some(300))
+            .spessore_muro(Some(30))
+            .superficie(superficie)
+            .perimetro(Some(20.0))
+            .pareti_esposte(Some(vec![
+                PareteEspostaDTO {
+                    orientamento: Orientamento::S,
+                    lunghezza: 5.0,
+                },
+                PareteEspostaDTO {
+                    orientamento: Orientamento::O,
+                    lunghezza: 4.0,
+                },
+            ]))
+            .build()
+    }
+
+    #[test]
+    fn test_compute() {
+        let stanze = vec![
+            stanza(1, "T ", Some(25.0)),
+            stanza(2, "T ", None),
+            stanza(3, "1 ", Some(15.0)),
+        ];
+        let stanze_con_infissi = vec![
+            StanzaConInfissi {
+                infisso_id: "A ".to_string(),
+                edificio_id: "500-1".to_string(),
+                stanza_id: 1,
+                num_infisso: 2,
+            },
+            StanzaConInfissi {
+                infisso_id: "A ".to_string(),
+                edificio_id: "500-1".to_string(),
+                stanza_id: 2,
+                num_infisso: 1,
+            },
+        ];
+
+        let metrics = Geometry::compute(
+            "500-1",
+            &stanze,
+            &[fixtures::infisso("A").build()],
+            &stanze_con_infissi,
+        );
+
+        let prima = &metrics.stanze[0];
+        assert_eq!(prima.volume, Some(75.0));
+        assert_eq!(prima.superficie_pareti, Some(60.0));
+        assert_eq!(prima.superficie_disperdente, Some(27.0));
+        assert_eq!(prima.mq_infissi, 3.0);
+        assert_eq!(prima.rapporto_aero_illuminante, Some(0.12));
+        assert_eq!(metrics.stanze[1].volume, None);
+
+        let totali = &metrics.totali;
+        assert_eq!((totali.stanze, totali.stanze_misurate), (3, 2));
+        assert_eq!(totali.superficie, 40.0);
+        assert_eq!(totali.volume, 120.0);
+        assert_eq!(totali.mq_infissi, 4.5);
+        // gli infissi della stanza senza superficie non contano nel rapporto
+        assert_eq!(totali.rapporto_aero_illuminante, Some(3.0 / 40.0));
+
+        let piani: Vec<(&str, usize)> = metrics
+            .piani
+            .iter()
+            .map(|piano| (piano.piano.as_str(), piano.totali.stanze))
+            .collect();
+        assert_eq!(piani, vec![("1", 1), ("T", 2)]);
+    }
+}
@@ -0,0 +1,12 @@
+mod geometry;
+mod score;
+mod transmittance;
+
+pub use geometry::{EdificioMetrics, Geometry, MetricsTotals, PianoMetrics, StanzaMetrics};
+pub use score::{
+    CatalogoEfficienza, ComponentScore, EdificioScore, EnergyScore, FascicoloScore, ScoreComponent,
+    ScoreWeights,
+};
+pub use transmittance::{
+    CatalogoTrasmittanze, EdificioHeatLoss, InfissoTransmittance, StanzaHeatLoss, Transmittance,
+};
@@ -0,0 +1,557 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+};
+
+use app_models::models::StanzaConInfissi;
+use app_services::{
+    dao::{
+        ClimatizzazioneDAO, EdificioDAO, FotovoltaicoDAO, IlluminazioneDAO, InfissoDAO,
+        MaterialeInfissoDAO, StanzaConInfissiDao, StanzaDAO, VetroInfissoDAO,
+    },
+    dto::{EdificioDTO, FotovoltaicoDTO, InfissoDTO, StanzaDTO},
+};
+use app_utils::{
+    app_error::{AppResult, DomainError, ErrorKind},
+    app_interface::{
+        dao_interface::crud_operations::{Get, GetAll},
+        database_interface::{DatabaseManagerTrait, PostgresPooled},
+    },
+};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::metrics::Geometry;
+
+/// Componenti del punteggio energetico
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoreComponent {
+    Illuminazione,
+    Climatizzazione,
+    Infissi,
+    IsolamentoTetto,
+    Cappotto,
+    Fotovoltaico,
+}
+
+impl ScoreComponent {
+    pub const ALL: [ScoreComponent; 6] = [
+        ScoreComponent::Illuminazione,
+        ScoreComponent::Climatizzazione,
+        ScoreComponent::Infissi,
+        ScoreComponent::IsolamentoTetto,
+        ScoreComponent::Cappotto,
+        ScoreComponent::Fotovoltaico,
+    ];
+}
+
+impl Display for ScoreComponent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let nome = match self {
+            ScoreComponent::Illuminazione => "illuminazione",
+            ScoreComponent::Climatizzazione => "climatizzazione",
+            ScoreComponent::Infissi => "infissi",
+            ScoreComponent::IsolamentoTetto => "isolamento_tetto",
+            ScoreComponent::Cappotto => "cappotto",
+            ScoreComponent::Fotovoltaico => "fotovoltaico",
+        };
+        write!(f, "{nome}")
+    }
+}
+
+/// Pesi dei componenti nel punteggio, non devono sommare a 100
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoreWeights {
+    pub illuminazione: f64,
+    pub climatizzazione: f64,
+    pub infissi: f64,
+    pub isolamento_tetto: f64,
+    pub cappotto: f64,
+    pub fotovoltaico: f64,
+}
+
+impl Default for ScoreWeights {
+    fn default() -> Self {
+        Self {
+            illuminazione: 20.0,
+            climatizzazione: 25.0,
+            infissi: 25.0,
+            isolamento_tetto: 10.0,
+            cappotto: 10.0,
+            fotovoltaico: 10.0,
+        }
+    }
+}
+
+impl ScoreWeights {
+    pub fn peso(&self, componente: ScoreComponent) -> f64 {
+        match componente {
+            ScoreComponent::Illuminazione => self.illuminazione,
+            ScoreComponent::Climatizzazione => self.climatizzazione,
+            ScoreComponent::Infissi => self.infissi,
+            ScoreComponent::IsolamentoTetto => self.isolamento_tetto,
+            ScoreComponent::Cappotto => self.cappotto,
+            ScoreComponent::Fotovoltaico => self.fotovoltaico,
+        }
+    }
+
+    /// I pesi non possono essere negativi e almeno uno deve essere positivo
+    pub fn validate(&self) -> AppResult<()> {
+        let pesi = ScoreComponent::ALL.map(|componente| self.peso(componente));
+        if pesi.iter().any(|peso| !peso.is_finite() || *peso < 0.0)
+            || pesi.iter().sum::<f64>() <= 0.0
+        {
+            return Err(DomainError::InvalidInput(
+                ErrorKind::InvalidField,
+                "pesi_punteggio".to_string(),
+            )
+            .into());
+        }
+        Ok(())
+    }
+}
+
+/// Punteggio di un componente da 0 a 100, `None` se mancano i dati per
+/// calcolarlo
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComponentScore {
+    pub componente: ScoreComponent,
+    pub peso: f64,
+    pub punteggio: Option<f64>,
+    /// Punti del componente nel punteggio complessivo
+    pub contributo: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EdificioScore {
+    pub edificio: String,
+    pub fascicolo: i32,
+    /// Media pesata dei componenti calcolati, da 0 a 100
+    pub punteggio: Option<f64>,
+    pub componenti: Vec<ComponentScore>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FascicoloScore {
+    pub fascicolo: i32,
+    /// Media dei punteggi degli edifici
+    pub punteggio: Option<f64>,
+    /// Media dei punteggi degli edifici per componente
+    pub componenti: Vec<ComponentScore>,
+    pub edifici: Vec<EdificioScore>,
+}
+
+/// Efficienza energetica dei tipi, rapportata alla migliore del catalogo.
+/// `None` se nessun tipo del catalogo ha un'efficienza positiva.
+#[derive(Debug, Clone, Default)]
+pub struct CatalogoEfficienza {
+    pub illuminazione: HashMap<String, Option<f64>>,
+    pub climatizzazione: HashMap<String, Option<f64>>,
+    pub materiale: HashMap<String, Option<f64>>,
+    pub vetro: HashMap<String, Option<f64>>,
+}
+
+impl CatalogoEfficienza {
+    pub(crate) fn load(conn: &mut PostgresPooled) -> AppResult<Self> {
+        Ok(Self {
+            illuminazione: Self::normalize(
+                IlluminazioneDAO::get_all(conn)?
+                    .into_iter()
+                    .map(|i| (i.lampadina, i.eff_energetica)),
+            ),
+            climatizzazione: Self::normalize(
+                ClimatizzazioneDAO::get_all(conn)?
+                    .into_iter()
+                    .map(|c| (c.nome, c.eff_energetica)),
+            ),
+            materiale: Self::normalize(
+                MaterialeInfissoDAO::get_all(conn)?
+                    .into_iter()
+                    .map(|m| (m.materiale, m.eff_energetica)),
+            ),
+            vetro: Self::normalize(
+                VetroInfissoDAO::get_all(conn)?
+                    .into_iter()
+                    .map(|v| (v.vetro, v.eff_energetica)),
+            ),
+        })
+    }
+
+    /// Efficienza da 0 a 100, 100 per il tipo più efficiente del catalogo
+    pub fn normalize(
+        tipi: impl IntoIterator<Item = (String, i16)>,
+    ) -> HashMap<String, Option<f64>> {
+        let tipi: Vec<(String, i16)> = tipi.into_iter().collect();
+        let max = tipi.iter().map(|(_, eff)| *eff).max().unwrap_or_default();
+        tipi.into_iter()
+            .map(|(nome, eff)| {
+                let efficienza = (max > 0).then(|| eff.max(0) as f64 * 100.0 / max as f64);
+                (nome, efficienza)
+            })
+            .collect()
+    }
+
+    fn efficienza(tipi: &HashMap<String, Option<f64>>, nome: &str) -> Option<f64> {
+        tipi.get(nome).copied().flatten()
+    }
+}
+
+pub struct EnergyScore;
+
+impl EnergyScore {
+    /// Punteggio energetico dell'edificio con il dettaglio dei componenti
+    pub async fn edificio(
+        db: State<'_, impl DatabaseManagerTrait + Send + Sync>,
+        chiave: &str,
+        pesi: &ScoreWeights,
+    ) -> AppResult<EdificioScore> {
+        pesi.validate()?;
+        let mut conn = db.get_connection().await?;
+        let edificio = EdificioDTO::from(&EdificioDAO::get(&mut conn, chiave.to_string())?);
+        let catalogo = CatalogoEfficienza::load(&mut conn)?;
+        Self::load_edificio(&mut conn, &edificio, &catalogo, pesi)
+    }
+
+    /// Punteggio energetico degli edifici del fascicolo
+    pub async fn fascicolo(
+        db: State<'_, impl DatabaseManagerTrait + Send + Sync>,
+        fascicolo: i32,
+        pesi: &ScoreWeights,
+    ) -> AppResult<FascicoloScore> {
+        pesi.validate()?;
+        let mut conn = db.get_connection().await?;
+        let catalogo = CatalogoEfficienza::load(&mut conn)?;
+        let edifici = EdificioDAO::get(&mut conn, fascicolo)?
+            .iter()
+            .map(|edificio| {
+                Self::load_edificio(&mut conn, &EdificioDTO::from(edificio), &catalogo, pesi)
+            })
+            .collect::<AppResult<Vec<_>>>()?;
+        Ok(Self::compute_fascicolo(fascicolo, edifici, pesi))
+    }
+
+    fn load_edificio(
+        conn: &mut PostgresPooled,
+        edificio: &EdificioDTO,
+        catalogo: &CatalogoEfficienza,
+        pesi: &ScoreWeights,
+    ) -> AppResult<EdificioScore> {
+        let stanze: Vec<StanzaDTO> = StanzaDAO::get(conn, edificio.chiave.as_str())?
+            .iter()
+            .map(StanzaDTO::from)
+            .collect();
+        let infissi: Vec<InfissoDTO> = InfissoDAO::get(conn, edificio.chiave.clone())?
+            .iter()
+            .map(InfissoDTO::from)
+            .collect();
+        let stanze_con_infissi = StanzaConInfissiDao::get(conn, edificio.chiave.as_str())?;
+        let fotovoltaici: Vec<FotovoltaicoDTO> =
+            FotovoltaicoDAO::get(conn, edificio.chiave.clone())?
+                .iter()
+                .map(FotovoltaicoDTO::from)
+                .collect();
+
+        Ok(Self::compute(
+            edificio,
+            &stanze,
+            &infissi,
+            &stanze_con_infissi,
+            &fotovoltaici,
+            catalogo,
+            pesi,
+        ))
+    }
+
+    pub(crate) fn compute(
+        edificio: &EdificioDTO,
+        stanze: &[StanzaDTO],
+        infissi: &[InfissoDTO],
+        stanze_con_infissi: &[StanzaConInfissi],
+        fotovoltaici: &[FotovoltaicoDTO],
+        catalogo: &CatalogoEfficienza,
+        pesi: &ScoreWeights,
+    ) -> EdificioScore {
+        let punteggi = ScoreComponent::ALL.map(|componente| {
+            let punteggio = match componente {
+                ScoreComponent::Illuminazione => media(
+                    stanze
+                        .iter()
+                        .filter_map(|stanza| stanza.illuminazione.as_ref())
+                        .filter_map(|lampadina| {
+                            CatalogoEfficienza::efficienza(&catalogo.illuminazione, lampadina)
+                        })
+                        .map(|eff| (eff, 1.0)),
+                ),
+                ScoreComponent::Climatizzazione => media(
+                    stanze
+                        .iter()
+                        .flat_map(|stanza| [&stanza.riscaldamento, &stanza.raffrescamento])
+                        .filter_map(|impianto| impianto.as_ref())
+                        .filter_map(|impianto| {
+                            CatalogoEfficienza::efficienza(&catalogo.climatizzazione, impianto)
+                        })
+                        .map(|eff| (eff, 1.0)),
+                ),
+                ScoreComponent::Infissi => Self::infissi(infissi, stanze_con_infissi, catalogo),
+                ScoreComponent::IsolamentoTetto => Some(punteggio_bool(edificio.isolamento_tetto)),
+                ScoreComponent::Cappotto => Some(punteggio_bool(edificio.cappotto)),
+                ScoreComponent::Fotovoltaico => Some(punteggio_bool(
+                    fotovoltaici.iter().any(|impianto| impianto.potenza > 0.0),
+                )),
+            };
+            (componente, punteggio)
+        });
+        let (punteggio, componenti) = Self::combine(punteggi, pesi);
+
+        EdificioScore {
+            edificio: edificio.chiave.clone(),
+            fascicolo: edificio.fascicolo,
+            punteggio,
+            componenti,
+        }
+    }
+
+    /// Media dell'efficienza di materiale e vetro, pesata sulla superficie
+    /// degli infissi montati
+    fn infissi(
+        infissi: &[InfissoDTO],
+        stanze_con_infissi: &[StanzaConInfissi],
+        catalogo: &CatalogoEfficienza,
+    ) -> Option<f64> {
+        let per_id: HashMap<&str, &InfissoDTO> = infissi
+            .iter()
+            .map(|infisso| (infisso.id.as_str(), infisso))
+            .collect();
+        media(stanze_con_infissi.iter().filter_map(|sci| {
+            let infisso = per_id.get(sci.infisso_id.trim())?;
+            let efficienza = media(
+                [
+                    CatalogoEfficienza::efficienza(&catalogo.materiale, &infisso.materiale),
+                    CatalogoEfficienza::efficienza(&catalogo.vetro, &infisso.vetro),
+                ]
+                .into_iter()
+                .flatten()
+                .map(|eff| (eff, 1.0)),
+            )?;
+            Some((
+                efficienza,
+                Geometry::mq_infisso(infisso) * sci.num_infisso as f64,
+            ))
+        }))
+    }
+
+    pub(crate) fn compute_fascicolo(
+        fascicolo: i32,
+        edifici: Vec<EdificioScore>,
+        pesi: &ScoreWeights,
+    ) -> FascicoloScore {
+        let punteggi = ScoreComponent::ALL.map(|componente| {
+            let punteggio = media(
+                edifici
+                    .iter()
+                    .flat_map(|edificio| &edificio.componenti)
+                    .filter(|c| c.componente == componente)
+                    .filter_map(|c| c.punteggio)
+                    .map(|punteggio| (punteggio, 1.0)),
+            );
+            (componente, punteggio)
+        });
+        let (_, componenti) = Self::combine(punteggi, pesi);
+
+        FascicoloScore {
+            fascicolo,
+            punteggio: media(
+                edifici
+                    .iter()
+                    .filter_map(|edificio| edificio.punteggio)
+                    .map(|punteggio| (punteggio, 1.0)),
+            ),
+            componenti,
+            edifici,
+        }
+    }
+
+    /// Media pesata dei componenti calcolati; i pesi dei componenti senza
+    /// dati vengono ridistribuiti sugli altri
+    fn combine(
+        punteggi: [(ScoreComponent, Option<f64>); 6],
+        pesi: &ScoreWeights,
+    ) -> (Option<f64>, Vec<ComponentScore>) {
+        let peso_totale: f64 = punteggi
+            .iter()
+            .filter(|(_, punteggio)| punteggio.is_some())
+            .map(|(componente, _)| pesi.peso(*componente))
+            .sum();
+        let componenti: Vec<ComponentScore> = punteggi
+            .into_iter()
+            .map(|(componente, punteggio)| {
+                let peso = pesi.peso(componente);
+                ComponentScore {
+                    componente,
+                    peso,
+                    punteggio,
+                    contributo: punteggio
+                        .filter(|_| peso_totale > 0.0)
+                        .map(|punteggio| punteggio * peso / peso_totale),
+                }
+            })
+            .collect();
+        let punteggio =
+            (peso_totale > 0.0).then(|| componenti.iter().filter_map(|c| c.contributo).sum());
+        (punteggio, componenti)
+    }
+}
+
+fn punteggio_bool(presente: bool) -> f64 { if presente { 100.0 } else { 0.0 } }
+
+/// Media pesata delle coppie (valore, peso), `None` se il peso totale è nullo
+fn media(valori: impl IntoIterator<Item = (f64, f64)>) -> Option<f64> {
+    let (somma, pesi) = valori
+        .into_iter()
+        .fold((0.0, 0.0), |(somma, pesi), (valore, peso)| {
+            (somma + valore * peso, pesi + peso)
+        });
+    (pesi > 0.0).then(|| somma / pesi)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    fn stanza(id: u64, illuminazione: Option<&str>, riscaldamento: Option<&str>) -> StanzaDTO {
+        fixtures::stanza(id)
+            .riscaldamento(riscaldamento.map(str::to_string))
+            .illuminazione(illuminazione.map(str::to_string))
+            .build()
+    }
+
+    fn infisso(id: &str, larghezza: u16, materiale: &str) -> InfissoDTO {
+        fixtures::infisso(id)
+            .altezza(100)
+            .larghezza(larghezza)
+            .materiale(materiale.to_string())
+            .build()
+    }
+
+    fn catalogo() -> CatalogoEfficienza {
+        CatalogoEfficienza {
+            illuminazione: fixtures::tipi(&[("LED", 4), ("Alogena", 1)]),
+            climatizzazione: fixtures::tipi(&[("Pompa di calore", 5), ("Caldaia", 2)]),
+            materiale: fixtures::tipi(&[("PVC", 4), ("Ferro", 0)]),
+            vetro: fixtures::tipi(&[("Triplo", 4), ("Doppio", 2)]),
+        }
+    }
+
+    fn componente(score: &EdificioScore, componente: ScoreComponent) -> Option<f64> {
+        score
+            .componenti
+            .iter()
+            .find(|c| c.componente == componente)
+            .and_then(|c| c.punteggio)
+    }
+
+    #[test]
+    fn test_compute() {
+        let stanze = vec![
+            stanza(1, Some("LED"), Some("Caldaia")),
+            stanza(2, Some("Alogena"), None),
+        ];
+        // 1 m² di PVC e 3 m² di ferro, entrambi con vetro doppio
+        let infissi = vec![infisso("A", 100, "PVC"), infisso("B", 300, "Ferro")];
+        let stanze_con_infissi = vec![
+            StanzaConInfissi {
+                infisso_id: "A ".to_string(),
+                edificio_id: "500-1".to_string(),
+                stanza_id: 1,
+                num_infisso: 1,
+            },
+            StanzaConInfissi {
+                infisso_id: "B ".to_string(),
+                edificio_id: "500-1".to_string(),
+                stanza_id: 2,
+                num_infisso: 1,
+            },
+        ];
+
+        let score = EnergyScore::compute(
+            &EdificioDTO {
+                isolamento_tetto: true,
+                ..fixtures::edificio()
+            },
+            &stanze,
+            &infissi,
+            &stanze_con_infissi,
+            &[],
+            &catalogo(),
+            &ScoreWeights::default(),
+        );
+
+        assert_eq!(
+            componente(&score, ScoreComponent::Illuminazione),
+            Some(62.5)
+        );
+        assert_eq!(
+            componente(&score, ScoreComponent::Climatizzazione),
+            Some(40.0)
+        );
+        assert_eq!(componente(&score, ScoreComponent::Infissi), Some(37.5));
+        assert_eq!(
+            componente(&score, ScoreComponent::IsolamentoTetto),
+            Some(100.0)
+        );
+        assert_eq!(componente(&score, ScoreComponent::Fotovoltaico), Some(0.0));
+        // (20·62.5 + 25·40 + 25·37.5 + 10·100) / 100
+        assert_eq!(score.punteggio, Some(41.875));
+    }
+
+    #[test]
+    fn test_compute_without_data() {
+        let pesi = ScoreWeights {
+            illuminazione: 50.0,
+            climatizzazione: 50.0,
+            infissi: 0.0,
+            isolamento_tetto: 0.0,
+            cappotto: 0.0,
+            fotovoltaico: 0.0,
+        };
+        let score = EnergyScore::compute(
+            &fixtures::edificio(),
+            &[stanza(1, Some("LED"), None)],
+            &[],
+            &[],
+            &[],
+            &catalogo(),
+            &pesi,
+        );
+
+        // il peso della climatizzazione, senza dati, passa all'illuminazione
+        assert_eq!(componente(&score, ScoreComponent::Climatizzazione), None);
+        assert_eq!(score.punteggio, Some(100.0));
+
+        let fascicolo = EnergyScore::compute_fascicolo(500, vec![score], &pesi);
+        assert_eq!(fascicolo.punteggio, Some(100.0));
+        assert_eq!(fascicolo.edifici.len(), 1);
+    }
+
+    #[test]
+    fn test_validate_weights() {
+        assert!(ScoreWeights::default().validate().is_ok());
+        let negativo = ScoreWeights {
+            cappotto: -1.0,
+            ..ScoreWeights::default()
+        };
+        assert!(negativo.validate().is_err());
+        let nulli = ScoreWeights {
+            illuminazione: 0.0,
+            climatizzazione: 0.0,
+            infissi: 0.0,
+            isolamento_tetto: 0.0,
+            cappotto: 0.0,
+            fotovoltaico: 0.0,
+        };
+        assert!(nulli.validate().is_err());
+    }
+}
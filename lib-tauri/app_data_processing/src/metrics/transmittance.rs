@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+
+use app_models::models::StanzaConInfissi;
+use app_services::{
+    dao::{InfissoDAO, MaterialeInfissoDAO, StanzaConInfissiDao, StanzaDAO, VetroInfissoDAO},
+    dto::{InfissoDTO, StanzaDTO},
+};
+use app_utils::{
+    app_error::AppResult,
+    app_interface::{
+        dao_interface::crud_operations::{Get, GetAll},
+        database_interface::{DatabaseManagerTrait, PostgresPooled},
+    },
+};
+use serde::Serialize;
+use tauri::State;
+
+use crate::metrics::Geometry;
+
+/// Larghezza del telaio usata per dividere l'infisso tra vetro e telaio, in m
+const LARGHEZZA_TELAIO: f64 = 0.1;
+/// Trasmittanza lineica del distanziatore tra vetro e telaio, in W/mK
+const PSI_DISTANZIATORE: f64 = 0.06;
+
+/// Trasmittanza di un infisso, in W/m²K. `None` se il catalogo non ha i
+/// valori del materiale o del vetro.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InfissoTransmittance {
+    pub id: String,
+    pub tipo: String,
+    pub materiale: String,
+    pub vetro: String,
+    /// Superficie in m²
+    pub mq: f64,
+    pub uf: Option<f64>,
+    pub ug: Option<f64>,
+    pub uw: Option<f64>,
+}
+
+/// Dispersione per trasmissione attraverso gli infissi della stanza
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StanzaHeatLoss {
+    pub id: u64,
+    pub piano: String,
+    pub cod_stanza: String,
+    pub mq_infissi: f64,
+    /// Coefficiente di dispersione degli infissi, in W/K
+    pub h_infissi: f64,
+    /// Infissi esclusi dal calcolo perché senza trasmittanza
+    pub infissi_senza_trasmittanza: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EdificioHeatLoss {
+    pub edificio: String,
+    pub mq_infissi: f64,
+    /// Coefficiente di dispersione degli infissi, in W/K
+    pub h_infissi: f64,
+    /// Trasmittanza media degli infissi con trasmittanza nota, pesata sulla
+    /// superficie
+    pub uw_medio: Option<f64>,
+    pub infissi_senza_trasmittanza: u32,
+    pub infissi: Vec<InfissoTransmittance>,
+    pub stanze: Vec<StanzaHeatLoss>,
+}
+
+/// Trasmittanze del catalogo, per nome
+#[derive(Debug, Clone, Default)]
+pub struct CatalogoTrasmittanze {
+    pub uf: HashMap<String, f64>,
+    pub ug: HashMap<String, f64>,
+}
+
+impl CatalogoTrasmittanze {
+    pub(crate) fn load(conn: &mut PostgresPooled) -> AppResult<Self> {
+        Ok(Self {
+            uf: MaterialeInfissoDAO::get_all(conn)?
+                .into_iter()
+                .filter_map(|m| m.uf.map(|uf| (m.materiale, uf as f64)))
+                .collect(),
+            ug: VetroInfissoDAO::get_all(conn)?
+                .into_iter()
+                .filter_map(|v| v.ug.map(|ug| (v.vetro, ug as f64)))
+                .collect(),
+        })
+    }
+}
+
+pub struct Transmittance;
+
+impl Transmittance {
+    /// Calcola la trasmittanza degli infissi dell'edificio e la dispersione
+    /// per stanza
+    pub async fn edificio(
+        db: State<'_, impl DatabaseManagerTrait + Send + Sync>,
+        edificio: &str,
+    ) -> AppResult<EdificioHeatLoss> {
+        let mut conn = db.get_connection().await?;
+        let catalogo = CatalogoTrasmittanze::load(&mut conn)?;
+        let stanze: Vec<StanzaDTO> = StanzaDAO::get(&mut conn, edificio)?
+            .iter()
+            .map(StanzaDTO::from)
+            .collect();
+        let infissi: Vec<InfissoDTO> = InfissoDAO::get(&mut conn, edificio.to_string())?
+            .iter()
+            .map(InfissoDTO::from)
+            .collect();
+        let stanze_con_infissi = StanzaConInfissiDao::get(&mut conn, edificio)?;
+
+        Ok(Self::compute(
+            edificio,
+            &stanze,
+            &infissi,
+            &stanze_con_infissi,
+            &catalogo,
+        ))
+    }
+
+    pub(crate) fn compute(
+        edificio: &str,
+        stanze: &[StanzaDTO],
+        infissi: &[InfissoDTO],
+        stanze_con_infissi: &[StanzaConInfissi],
+        catalogo: &CatalogoTrasmittanze,
+    ) -> EdificioHeatLoss {
+        let infissi: Vec<InfissoTransmittance> = infissi
+            .iter()
+            .map(|infisso| {
+                let uf = catalogo.uf.get(&infisso.materiale).copied();
+                let ug = catalogo.ug.get(&infisso.vetro).copied();
+                InfissoTransmittance {
+                    id: infisso.id.clone(),
+                    tipo: infisso.tipo.clone(),
+                    materiale: infisso.materiale.clone(),
+                    vetro: infisso.vetro.clone(),
+                    mq: Geometry::mq_infisso(infisso),
+                    uf,
+                    ug,
+                    uw: uf
+                        .zip(ug)
+                        .map(|(uf, ug)| Self::uw(infisso.altezza, infisso.larghezza, uf, ug)),
+                }
+            })
+            .collect();
+        let per_id: HashMap<&str, &InfissoTransmittance> = infissi
+            .iter()
+            .map(|infisso| (infisso.id.as_str(), infisso))
+            .collect();
+
+        let stanze: Vec<StanzaHeatLoss> = stanze
+            .iter()
+            .map(|stanza| {
+                let mut heat_loss = StanzaHeatLoss {
+                    id: stanza.id,
+                    piano: stanza.piano.trim().to_string(),
+                    cod_stanza: stanza.cod_stanza.clone(),
+                    mq_infissi: 0.0,
+                    h_infissi: 0.0,
+                    infissi_senza_trasmittanza: 0,
+                };
+                for sci in stanze_con_infissi
+                    .iter()
+                    .filter(|sci| sci.stanza_id as u64 == stanza.id)
+                {
+                    let Some(infisso) = per_id.get(sci.infisso_id.trim()) else {
+                        continue;
+                    };
+                    let numero = sci.num_infisso as f64;
+                    heat_loss.mq_infissi += infisso.mq * numero;
+                    match infisso.uw {
+                        Some(uw) => heat_loss.h_infissi += uw * infisso.mq * numero,
+                        None => heat_loss.infissi_senza_trasmittanza += sci.num_infisso as u32,
+                    }
+                }
+                heat_loss
+            })
+            .collect();
+
+        // superficie degli infissi di cui è nota la trasmittanza
+        let mq_con_trasmittanza: f64 = stanze_con_infissi
+            .iter()
+            .filter(|sci| {
+                stanze
+                    .iter()
+                    .any(|stanza| stanza.id == sci.stanza_id as u64)
+            })
+            .filter_map(|sci| {
+                per_id
+                    .get(sci.infisso_id.trim())
+                    .filter(|infisso| infisso.uw.is_some())
+                    .map(|infisso| infisso.mq * sci.num_infisso as f64)
+            })
+            .sum();
+        let h_infissi = stanze.iter().map(|stanza| stanza.h_infissi).sum();
+
+        EdificioHeatLoss {
+            edificio: edificio.to_string(),
+            mq_infissi: stanze.iter().map(|stanza| stanza.mq_infissi).sum(),
+            h_infissi,
+            uw_medio: (mq_con_trasmittanza > 0.0).then(|| h_infissi / mq_con_trasmittanza),
+            infissi_senza_trasmittanza: stanze
+                .iter()
+                .map(|stanza| stanza.infissi_senza_trasmittanza)
+                .sum(),
+            infissi,
+            stanze,
+        }
+    }
+
+    /// Trasmittanza dell'infisso secondo il metodo semplificato della
+    /// UNI EN ISO 10077-1, con un telaio di larghezza costante. Le dimensioni
+    /// sono in cm.
+    pub fn uw(altezza: u16, larghezza: u16, uf: f64, ug: f64) -> f64 {
+        let altezza = altezza as f64 / 100.0;
+        let larghezza = larghezza as f64 / 100.0;
+        let area = altezza * larghezza;
+        if area <= 0.0 {
+            return uf;
+        }
+        let altezza_vetro = (altezza - 2.0 * LARGHEZZA_TELAIO).max(0.0);
+        let larghezza_vetro = (larghezza - 2.0 * LARGHEZZA_TELAIO).max(0.0);
+        let area_vetro = altezza_vetro * larghezza_vetro;
+        let area_telaio = area - area_vetro;
+        let perimetro_vetro = if area_vetro > 0.0 {
+            2.0 * (altezza_vetro + larghezza_vetro)
+        } else {
+            0.0
+        };
+        (area_vetro * ug + area_telaio * uf + perimetro_vetro * PSI_DISTANZIATORE) / area
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    fn round(value: f64) -> f64 { (value * 100.0).round() / 100.0 }
+
+    fn infisso(id: &str, materiale: &str) -> InfissoDTO {
+        fixtures::infisso(id)
+            .materiale(materiale.to_string())
+            .build()
+    }
+
+    fn sci(infisso: &str, stanza: i32, numero: i32) -> StanzaConInfissi {
+        StanzaConInfissi {
+            infisso_id: format!("{infisso} "),
+            edificio_id: "500-1".to_string(),
+            stanza_id: stanza,
+            num_infisso: numero,
+        }
+    }
+
+    #[test]
+    fn test_uw() {
+        // vetro 1.3 x 0.8 m, telaio 0.46 m², perimetro del vetro 4.2 m
+        assert_eq!(round(Transmittance::uw(150, 100, 2.0, 3.3)), 3.07);
+        // infisso più stretto del telaio: solo telaio
+        assert_eq!(Transmittance::uw(15, 100, 2.0, 3.3), 2.0);
+    }
+
+    #[test]
+    fn test_compute() {
+        let catalogo = CatalogoTrasmittanze {
+            uf: HashMap::from([("Legno".to_string(), 2.0)]),
+            ug: HashMap::from([("Doppio".to_string(), 3.3)]),
+        };
+        let infissi = vec![infisso("A", "Legno"), infisso("B", "Bambù")];
+        let stanze_con_infissi = vec![sci("A", 1, 2), sci("B", 1, 1), sci("A", 2, 1)];
+
+        let report = Transmittance::compute(
+            "500-1",
+            &[fixtures::stanza(1).build(), fixtures::stanza(2).build()],
+            &infissi,
+            &stanze_con_infissi,
+            &catalogo,
+        );
+
+        assert_eq!(report.infissi[1].uw, None);
+        let prima = &report.stanze[0];
+        assert_eq!(prima.mq_infissi, 4.5);
+        assert_eq!(round(prima.h_infissi), 9.21);
+        assert_eq!(prima.infissi_senza_trasmittanza, 1);
+
+        assert_eq!(report.mq_infissi, 6.0);
+        assert_eq!(round(report.h_infissi), 13.81);
+        assert_eq!(report.infissi_senza_trasmittanza, 1);
+        assert_eq!(report.uw_medio.map(round), Some(3.07));
+    }
+}
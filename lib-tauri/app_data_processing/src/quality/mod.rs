@@ -0,0 +1,5 @@
+mod report;
+mod store;
+
+pub use report::{DataQuality, IssueKind, QualityIssue, QualityReport};
+pub use store::QualityReportStore;
@@ -0,0 +1,296 @@
+use std::collections::HashSet;
+
+use app_models::models::StanzaConInfissi;
+use app_services::{
+    dao::{EdificioDAO, InfissoDAO, StanzaConInfissiDao, StanzaDAO, UtenzeDAO},
+    dto::{InfissoDTO, StanzaDTO},
+};
+use app_utils::{
+    app_error::AppResult,
+    app_interface::{
+        dao_interface::crud_operations::{Get, GetAll},
+        database_interface::{DatabaseManagerTrait, PostgresPooled},
+    },
+};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+/// Altezza plausibile di una stanza, in cm
+const ALTEZZA_STANZA: (u16, u16) = (200, 1000);
+/// Spessore plausibile di un muro, in cm
+const SPESSORE_MURO: (u8, u8) = (10, 150);
+/// Lato plausibile di un infisso, in cm
+const LATO_INFISSO: (u16, u16) = (20, 600);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    /// Stanza con dati non ancora rilevati
+    StanzaIncompleta,
+    StanzaSenzaInfissi,
+    /// Infisso non collocato in nessuna stanza
+    InfissoNonAssegnato,
+    EdificioSenzaUtenze,
+    AltezzaNonPlausibile,
+    SpessoreMuroNonPlausibile,
+    DimensioniInfissoNonPlausibili,
+}
+
+/// Problema rilevato su un elemento dell'edificio
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QualityIssue {
+    pub kind: IssueKind,
+    /// Codice della stanza, id dell'infisso o chiave dell'edificio
+    pub riferimento: String,
+    pub descrizione: String,
+}
+
+/// Completezza e coerenza dei dati di un edificio
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QualityReport {
+    pub edificio: String,
+    pub scanned_at: NaiveDateTime,
+    pub stanze: usize,
+    pub stanze_complete: usize,
+    /// Percentuale delle stanze complete, 100 se l'edificio non ha stanze
+    pub completezza: f64,
+    pub issues: Vec<QualityIssue>,
+}
+
+pub struct DataQuality;
+
+impl DataQuality {
+    /// Analizza tutti gli edifici presenti nel database
+    pub async fn scan_all(
+        db: State<'_, impl DatabaseManagerTrait + Send + Sync>,
+    ) -> AppResult<Vec<QualityReport>> {
+        let mut conn = db.get_connection().await?;
+        let now = chrono::Local::now().naive_local();
+        EdificioDAO::get_all(&mut conn)?
+            .iter()
+            .map(|edificio| Self::scan(&mut conn, &edificio.chiave, now))
+            .collect()
+    }
+
+    fn scan(
+        conn: &mut PostgresPooled,
+        edificio: &str,
+        now: NaiveDateTime,
+    ) -> AppResult<QualityReport> {
+        let stanze: Vec<StanzaDTO> = StanzaDAO::get(conn, edificio)?
+            .iter()
+            .map(StanzaDTO::from)
+            .collect();
+        let infissi: Vec<InfissoDTO> = InfissoDAO::get(conn, edificio.to_string())?
+            .iter()
+            .map(InfissoDTO::from)
+            .collect();
+        let stanze_con_infissi = StanzaConInfissiDao::get(conn, edificio)?;
+        let utenze = UtenzeDAO::get(conn, edificio.to_string())?.len();
+
+        Ok(Self::check(
+            edificio,
+            &stanze,
+            &infissi,
+            &stanze_con_infissi,
+            utenze,
+            now,
+        ))
+    }
+
+    fn check(
+        edificio: &str,
+        stanze: &[StanzaDTO],
+        infissi: &[InfissoDTO],
+        stanze_con_infissi: &[StanzaConInfissi],
+        utenze: usize,
+        now: NaiveDateTime,
+    ) -> QualityReport {
+        let mut issues = Vec::new();
+        let mut issue = |kind, riferimento: &str, descrizione: String| {
+            issues.push(QualityIssue {
+                kind,
+                riferimento: riferimento.to_string(),
+                descrizione,
+            })
+        };
+
+        if utenze == 0 {
+            issue(
+                IssueKind::EdificioSenzaUtenze,
+                edificio,
+                "Nessuna utenza registrata".to_string(),
+            );
+        }
+
+        let stanze_con_infisso: HashSet<i32> =
+            stanze_con_infissi.iter().map(|sci| sci.stanza_id).collect();
+        let infissi_assegnati: HashSet<&str> = stanze_con_infissi
+            .iter()
+            .map(|sci| sci.infisso_id.trim())
+            .collect();
+
+        let mut stanze_complete = 0;
+        for stanza in stanze {
+            let codice = stanza.cod_stanza.as_str();
+            let mancanti: Vec<&str> = [
+                ("altezza", stanza.altezza.is_none()),
+                ("spessore muro", stanza.spessore_muro.is_none()),
+                ("riscaldamento", stanza.riscaldamento.is_none()),
+                ("raffrescamento", stanza.raffrescamento.is_none()),
+                ("illuminazione", stanza.illuminazione.is_none()),
+            ]
+            .into_iter()
+            .filter_map(|(campo, mancante)| mancante.then_some(campo))
+            .collect();
+            if mancanti.is_empty() {
+                stanze_complete += 1;
+            } else {
+                issue(
+                    IssueKind::StanzaIncompleta,
+                    codice,
+                    format!("Dati mancanti: {}", mancanti.join(", ")),
+                );
+            }
+
+            if !stanze_con_infisso.contains(&(stanza.id as i32)) {
+                issue(
+                    IssueKind::StanzaSenzaInfissi,
+                    codice,
+                    "Nessun infisso assegnato".to_string(),
+                );
+            }
+            if let Some(altezza) = stanza
+                .altezza
+                .filter(|h| !(ALTEZZA_STANZA.0..=ALTEZZA_STANZA.1).contains(h))
+            {
+                issue(
+                    IssueKind::AltezzaNonPlausibile,
+                    codice,
+                    format!("Altezza di {altezza} cm"),
+                );
+            }
+            if let Some(spessore) = stanza
+                .spessore_muro
+                .filter(|s| !(SPESSORE_MURO.0..=SPESSORE_MURO.1).contains(s))
+            {
+                issue(
+                    IssueKind::SpessoreMuroNonPlausibile,
+                    codice,
+                    format!("Spessore del muro di {spessore} cm"),
+                );
+            }
+        }
+
+        let lato = LATO_INFISSO.0..=LATO_INFISSO.1;
+        for infisso in infissi {
+            if !infissi_assegnati.contains(infisso.id.trim()) {
+                issue(
+                    IssueKind::InfissoNonAssegnato,
+                    &infisso.id,
+                    "Non collocato in nessuna stanza".to_string(),
+                );
+            }
+            if !lato.contains(&infisso.altezza) || !lato.contains(&infisso.larghezza) {
+                issue(
+                    IssueKind::DimensioniInfissoNonPlausibili,
+                    &infisso.id,
+                    format!("Dimensioni {}x{} cm", infisso.larghezza, infisso.altezza),
+                );
+            }
+        }
+
+        let completezza = if stanze.is_empty() {
+            100.0
+        } else {
+            stanze_complete as f64 * 100.0 / stanze.len() as f64
+        };
+
+        QualityReport {
+            edificio: edificio.to_string(),
+            scanned_at: now,
+            stanze: stanze.len(),
+            stanze_complete,
+            completezza,
+            issues,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    fn stanza(id: u64, cod_stanza: &str) -> StanzaDTO {
+        fixtures::stanza(id)
+            .cod_stanza(cod_stanza.to_string())
+            .altezza(Some(300))
+            .spessore_muro(Some(30))
+            .riscaldamento(Some("Radiatori".to_string()))
+            .raffrescamento(Some("Split".to_string()))
+            .illuminazione(Some("LED".to_string()))
+            .build()
+    }
+
+    fn infisso(id: &str, altezza: u16) -> InfissoDTO {
+        fixtures::infisso(id).altezza(altezza).larghezza(80).build()
+    }
+
+    #[test]
+    fn test_check() {
+        let completa = stanza(1, "PT_UFF_01");
+        let importata = StanzaDTO {
+            altezza: None,
+            spessore_muro: Some(2),
+            riscaldamento: None,
+            raffrescamento: None,
+            illuminazione: None,
+            ..stanza(2, "PT_UFF_02")
+        };
+        let stanze_con_infissi = vec![StanzaConInfissi {
+            infisso_id: "A ".to_string(),
+            edificio_id: "500-1".to_string(),
+            stanza_id: 1,
+            num_infisso: 2,
+        }];
+        let now = NaiveDateTime::default();
+
+        let report = DataQuality::check(
+            "500-1",
+            &[completa, importata],
+            &[infisso("A", 120), infisso("B", 5)],
+            &stanze_con_infissi,
+            0,
+            now,
+        );
+
+        assert_eq!((report.stanze, report.stanze_complete), (2, 1));
+        assert_eq!(report.completezza, 50.0);
+        let issues: Vec<(IssueKind, &str)> = report
+            .issues
+            .iter()
+            .map(|issue| (issue.kind, issue.riferimento.as_str()))
+            .collect();
+        assert_eq!(
+            issues,
+            vec![
+                (IssueKind::EdificioSenzaUtenze, "500-1"),
+                (IssueKind::StanzaIncompleta, "PT_UFF_02"),
+                (IssueKind::StanzaSenzaInfissi, "PT_UFF_02"),
+                (IssueKind::SpessoreMuroNonPlausibile, "PT_UFF_02"),
+                (IssueKind::InfissoNonAssegnato, "B"),
+                (IssueKind::DimensioniInfissoNonPlausibili, "B"),
+            ]
+        );
+        assert_eq!(
+            report.issues[1].descrizione,
+            "Dati mancanti: altezza, riscaldamento, raffrescamento, illuminazione"
+        );
+
+        let vuoto = DataQuality::check("500-2", &[], &[], &[], 1, now);
+        assert!(vuoto.issues.is_empty());
+        assert_eq!(vuoto.completezza, 100.0);
+    }
+}
@@ -0,0 +1,35 @@
+use app_utils::{
+    app_error::{AppResult, ErrorQuality},
+    store::JsonStore,
+};
+use tauri::{AppHandle, Runtime};
+
+use crate::quality::report::QualityReport;
+
+/// Ultimi report di qualità calcolati, salvati nello store dell'applicazione
+pub struct QualityReportStore;
+
+impl QualityReportStore {
+    const STORE: JsonStore = JsonStore::new("data_quality.json", "reports");
+
+    /// Report salvati, solo quello dell'edificio indicato se presente
+    pub fn load<R: Runtime>(
+        app_handle: &AppHandle<R>,
+        edificio: Option<&str>,
+    ) -> AppResult<Vec<QualityReport>> {
+        let reports: Vec<QualityReport> = Self::STORE
+            .load(app_handle, |e| ErrorQuality::InvalidReport(e.to_string()))?
+            .unwrap_or_default();
+        Ok(reports
+            .into_iter()
+            .filter(|report| edificio.is_none_or(|chiave| report.edificio == chiave))
+            .collect())
+    }
+
+    /// Sostituisce i report salvati
+    pub fn save<R: Runtime>(app_handle: &AppHandle<R>, reports: &[QualityReport]) -> AppResult<()> {
+        Self::STORE.save(app_handle, reports, |e| {
+            ErrorQuality::InvalidReport(e.to_string())
+        })
+    }
+}
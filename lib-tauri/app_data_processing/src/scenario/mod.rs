@@ -0,0 +1,5 @@
+mod simulation;
+mod store;
+
+pub use simulation::{Scenario, ScenarioComparison, ScenarioIndicators, Scenarios, Substitution};
+pub use store::ScenarioStore;
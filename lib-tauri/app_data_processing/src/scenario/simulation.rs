@@ -0,0 +1,418 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
+
+use app_models::models::StanzaConInfissi;
+use app_services::{
+    dao::{EdificioDAO, FotovoltaicoDAO, InfissoDAO, StanzaConInfissiDao, StanzaDAO},
+    dto::{EdificioDTO, FotovoltaicoDTO, InfissoDTO, StanzaDTO},
+};
+use app_utils::{
+    app_error::{AppResult, ApplicationError, ErrorScenario},
+    app_interface::{
+        dao_interface::crud_operations::Get,
+        database_interface::{DatabaseManagerTrait, PostgresPooled},
+    },
+};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::metrics::{
+    CatalogoEfficienza, CatalogoTrasmittanze, ComponentScore, EnergyScore, ScoreWeights,
+    Transmittance,
+};
+
+/// Modifica applicata allo stato attuale dell'edificio
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "tipo", rename_all = "snake_case")]
+pub enum Substitution {
+    /// Gli infissi con il materiale `da` passano al materiale `a`
+    Materiale {
+        da: String,
+        a: String,
+    },
+    /// Gli infissi con il vetro `da` passano al vetro `a`
+    Vetro {
+        da: String,
+        a: String,
+    },
+    /// Le stanze con la lampadina `da` passano alla lampadina `a`
+    Illuminazione {
+        da: String,
+        a: String,
+    },
+    /// Riscaldamento e raffrescamento delle stanze
+    Climatizzazione {
+        da: String,
+        a: String,
+    },
+    Cappotto {
+        presente: bool,
+    },
+    IsolamentoTetto {
+        presente: bool,
+    },
+    /// Aggiunge un impianto della potenza indicata, in kW
+    Fotovoltaico {
+        potenza: f32,
+    },
+}
+
+/// Insieme di modifiche da simulare su un edificio
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Scenario {
+    pub nome: String,
+    pub edificio: String,
+    #[serde(default)]
+    pub descrizione: Option<String>,
+    pub sostituzioni: Vec<Substitution>,
+}
+
+impl Scenario {
+    pub fn validate(&self) -> AppResult<()> {
+        if self.nome.trim().is_empty() {
+            return Err(invalid("empty name"));
+        }
+        if self.sostituzioni.is_empty() {
+            return Err(invalid("no substitutions"));
+        }
+        for sostituzione in &self.sostituzioni {
+            match sostituzione {
+                Substitution::Materiale { da, a }
+                | Substitution::Vetro { da, a }
+                | Substitution::Illuminazione { da, a }
+                | Substitution::Climatizzazione { da, a }
+                    if da == a =>
+                {
+                    return Err(invalid(format!("{da} is replaced with itself")));
+                }
+                Substitution::Fotovoltaico { potenza }
+                    if !potenza.is_finite() || *potenza <= 0.0 =>
+                {
+                    return Err(invalid("fotovoltaico potenza must be positive"));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Indicatori energetici dell'edificio
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScenarioIndicators {
+    pub punteggio: Option<f64>,
+    pub componenti: Vec<ComponentScore>,
+    /// Coefficiente di dispersione degli infissi, in W/K
+    pub h_infissi: f64,
+    pub uw_medio: Option<f64>,
+    pub infissi_senza_trasmittanza: u32,
+}
+
+/// Indicatori dell'edificio prima e dopo le modifiche dello scenario
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScenarioComparison {
+    pub scenario: Scenario,
+    pub prima: ScenarioIndicators,
+    pub dopo: ScenarioIndicators,
+    /// Tipi di infisso modificati dallo scenario
+    pub infissi_modificati: usize,
+    pub stanze_modificate: usize,
+}
+
+/// Cataloghi usati per calcolare gli indicatori
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Cataloghi {
+    efficienza: CatalogoEfficienza,
+    trasmittanze: CatalogoTrasmittanze,
+}
+
+/// Copia in memoria dei dati dell'edificio su cui applicare gli scenari
+#[derive(Clone)]
+pub(crate) struct EdificioState {
+    edificio: EdificioDTO,
+    stanze: Vec<StanzaDTO>,
+    infissi: Vec<InfissoDTO>,
+    stanze_con_infissi: Vec<StanzaConInfissi>,
+    fotovoltaici: Vec<FotovoltaicoDTO>,
+}
+
+impl EdificioState {
+    fn load(conn: &mut PostgresPooled, chiave: &str) -> AppResult<Self> {
+        Ok(Self {
+            edificio: EdificioDTO::from(&EdificioDAO::get(conn, chiave.to_string())?),
+            stanze: StanzaDAO::get(conn, chiave)?
+                .iter()
+                .map(StanzaDTO::from)
+                .collect(),
+            infissi: InfissoDAO::get(conn, chiave.to_string())?
+                .iter()
+                .map(InfissoDTO::from)
+                .collect(),
+            stanze_con_infissi: StanzaConInfissiDao::get(conn, chiave)?,
+            fotovoltaici: FotovoltaicoDAO::get(conn, chiave.to_string())?
+                .iter()
+                .map(FotovoltaicoDTO::from)
+                .collect(),
+        })
+    }
+
+    fn indicators(&self, cataloghi: &Cataloghi, pesi: &ScoreWeights) -> ScenarioIndicators {
+        let score = EnergyScore::compute(
+            &self.edificio,
+            &self.stanze,
+            &self.infissi,
+            &self.stanze_con_infissi,
+            &self.fotovoltaici,
+            &cataloghi.efficienza,
+            pesi,
+        );
+        let heat_loss = Transmittance::compute(
+            &self.edificio.chiave,
+            &self.stanze,
+            &self.infissi,
+            &self.stanze_con_infissi,
+            &cataloghi.trasmittanze,
+        );
+        ScenarioIndicators {
+            punteggio: score.punteggio,
+            componenti: score.componenti,
+            h_infissi: heat_loss.h_infissi,
+            uw_medio: heat_loss.uw_medio,
+            infissi_senza_trasmittanza: heat_loss.infissi_senza_trasmittanza,
+        }
+    }
+}
+
+pub struct Scenarios;
+
+impl Scenarios {
+    /// Confronta gli indicatori dell'edificio prima e dopo lo scenario
+    pub async fn simulate(
+        db: State<'_, impl DatabaseManagerTrait + Send + Sync>,
+        scenario: &Scenario,
+        pesi: &ScoreWeights,
+    ) -> AppResult<ScenarioComparison> {
+        let mut confronti =
+            Self::simulate_all(db, &scenario.edificio, std::slice::from_ref(scenario), pesi)
+                .await?;
+        Ok(confronti.remove(0))
+    }
+
+    /// Confronta gli scenari dello stesso edificio, leggendo i dati una sola
+    /// volta
+    pub async fn simulate_all(
+        db: State<'_, impl DatabaseManagerTrait + Send + Sync>,
+        edificio: &str,
+        scenari: &[Scenario],
+        pesi: &ScoreWeights,
+    ) -> AppResult<Vec<ScenarioComparison>> {
+        pesi.validate()?;
+        if let Some(scenario) = scenari.iter().find(|s| s.edificio != edificio) {
+            return Err(invalid(format!(
+                "scenario {} belongs to edificio {}",
+                scenario.nome, scenario.edificio
+            )));
+        }
+        let mut conn = db.get_connection().await?;
+        let cataloghi = Cataloghi {
+            efficienza: CatalogoEfficienza::load(&mut conn)?,
+            trasmittanze: CatalogoTrasmittanze::load(&mut conn)?,
+        };
+        let state = EdificioState::load(&mut conn, edificio)?;
+        scenari
+            .iter()
+            .map(|scenario| Self::compare(&state, scenario, &cataloghi, pesi))
+            .collect()
+    }
+
+    pub(crate) fn compare(
+        state: &EdificioState,
+        scenario: &Scenario,
+        cataloghi: &Cataloghi,
+        pesi: &ScoreWeights,
+    ) -> AppResult<ScenarioComparison> {
+        scenario.validate()?;
+        let mut dopo = state.clone();
+        let mut infissi_modificati = HashSet::new();
+        let mut stanze_modificate = HashSet::new();
+
+        for sostituzione in &scenario.sostituzioni {
+            match sostituzione {
+                Substitution::Materiale { da, a } => {
+                    check_tipo(&cataloghi.efficienza.materiale, "materiale", a)?;
+                    for infisso in dopo.infissi.iter_mut().filter(|i| &i.materiale == da) {
+                        infisso.materiale = a.clone();
+                        infissi_modificati.insert(infisso.id.clone());
+                    }
+                }
+                Substitution::Vetro { da, a } => {
+                    check_tipo(&cataloghi.efficienza.vetro, "vetro", a)?;
+                    for infisso in dopo.infissi.iter_mut().filter(|i| &i.vetro == da) {
+                        infisso.vetro = a.clone();
+                        infissi_modificati.insert(infisso.id.clone());
+                    }
+                }
+                Substitution::Illuminazione { da, a } => {
+                    check_tipo(&cataloghi.efficienza.illuminazione, "illuminazione", a)?;
+                    for stanza in dopo
+                        .stanze
+                        .iter_mut()
+                        .filter(|s| s.illuminazione.as_ref() == Some(da))
+                    {
+                        stanza.illuminazione = Some(a.clone());
+                        stanze_modificate.insert(stanza.id);
+                    }
+                }
+                Substitution::Climatizzazione { da, a } => {
+                    check_tipo(&cataloghi.efficienza.climatizzazione, "climatizzazione", a)?;
+                    for stanza in dopo.stanze.iter_mut() {
+                        for impianto in [&mut stanza.riscaldamento, &mut stanza.raffrescamento] {
+                            if impianto.as_ref() == Some(da) {
+                                *impianto = Some(a.clone());
+                                stanze_modificate.insert(stanza.id);
+                            }
+                        }
+                    }
+                }
+                Substitution::Cappotto { presente } => dopo.edificio.cappotto = *presente,
+                Substitution::IsolamentoTetto { presente } => {
+                    dopo.edificio.isolamento_tetto = *presente
+                }
+                Substitution::Fotovoltaico { potenza } => dopo.fotovoltaici.push(FotovoltaicoDTO {
+                    id: None,
+                    id_edificio: dopo.edificio.chiave.clone(),
+                    potenza: *potenza,
+                    proprietario: scenario.nome.clone(),
+                    orientamento: None,
+                    inclinazione: None,
+                    anno_installazione: None,
+                    provincia: None,
+                }),
+            }
+        }
+
+        Ok(ScenarioComparison {
+            scenario: scenario.clone(),
+            prima: state.indicators(cataloghi, pesi),
+            dopo: dopo.indicators(cataloghi, pesi),
+            infissi_modificati: infissi_modificati.len(),
+            stanze_modificate: stanze_modificate.len(),
+        })
+    }
+}
+
+/// Il tipo sostituito deve essere presente nel catalogo
+fn check_tipo<V>(catalogo: &HashMap<String, V>, campo: &str, nome: &str) -> AppResult<()> {
+    if !catalogo.contains_key(nome) {
+        return Err(invalid(format!("unknown {campo} {nome}")));
+    }
+    Ok(())
+}
+
+fn invalid(detail: impl Display) -> ApplicationError {
+    ErrorScenario::Invalid(detail.to_string()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    fn cataloghi() -> Cataloghi {
+        Cataloghi {
+            efficienza: CatalogoEfficienza {
+                illuminazione: fixtures::tipi(&[("LED", 4), ("Alogena", 1)]),
+                climatizzazione: fixtures::tipi(&[("Pompa di calore", 5), ("Caldaia", 2)]),
+                materiale: fixtures::tipi(&[("PVC", 4), ("Ferro", 1)]),
+                vetro: fixtures::tipi(&[("Triplo", 4), ("Singolo", 1)]),
+            },
+            trasmittanze: CatalogoTrasmittanze {
+                uf: HashMap::from([("PVC".to_string(), 1.6), ("Ferro".to_string(), 5.9)]),
+                ug: HashMap::from([("Triplo".to_string(), 2.0), ("Singolo".to_string(), 5.7)]),
+            },
+        }
+    }
+
+    fn state() -> EdificioState {
+        let stanza = |id: u64, illuminazione: &str| {
+            fixtures::stanza(id)
+                .riscaldamento(Some("Caldaia".to_string()))
+                .illuminazione(Some(illuminazione.to_string()))
+                .build()
+        };
+        EdificioState {
+            edificio: fixtures::edificio(),
+            stanze: vec![stanza(1, "Alogena"), stanza(2, "LED")],
+            infissi: vec![
+                fixtures::infisso("A")
+                    .materiale("Ferro".to_string())
+                    .vetro("Singolo".to_string())
+                    .build(),
+            ],
+            stanze_con_infissi: vec![StanzaConInfissi {
+                infisso_id: "A ".to_string(),
+                edificio_id: "500-1".to_string(),
+                stanza_id: 1,
+                num_infisso: 2,
+            }],
+            fotovoltaici: vec![],
+        }
+    }
+
+    fn scenario(sostituzioni: Vec<Substitution>) -> Scenario {
+        Scenario {
+            nome: "Riqualificazione".to_string(),
+            edificio: "500-1".to_string(),
+            descrizione: None,
+            sostituzioni,
+        }
+    }
+
+    #[test]
+    fn test_compare() -> AppResult<()> {
+        let state = state();
+        let scenario = scenario(vec![
+            Substitution::Materiale {
+                da: "Ferro".to_string(),
+                a: "PVC".to_string(),
+            },
+            Substitution::Illuminazione {
+                da: "Alogena".to_string(),
+                a: "LED".to_string(),
+            },
+            Substitution::Cappotto { presente: true },
+        ]);
+
+        let confronto =
+            Scenarios::compare(&state, &scenario, &cataloghi(), &ScoreWeights::default())?;
+
+        assert_eq!(confronto.infissi_modificati, 1);
+        assert_eq!(confronto.stanze_modificate, 1);
+        assert!(confronto.dopo.punteggio > confronto.prima.punteggio);
+        assert!(confronto.dopo.h_infissi < confronto.prima.h_infissi);
+        // lo stato attuale non viene modificato
+        assert_eq!(state.infissi[0].materiale, "Ferro");
+        assert!(!state.edificio.cappotto);
+        Ok(())
+    }
+
+    #[test]
+    fn test_compare_invalid() {
+        let cataloghi = cataloghi();
+        let pesi = ScoreWeights::default();
+
+        let sconosciuto = scenario(vec![Substitution::Vetro {
+            da: "Singolo".to_string(),
+            a: "Quadruplo".to_string(),
+        }]);
+        assert!(Scenarios::compare(&state(), &sconosciuto, &cataloghi, &pesi).is_err());
+
+        let vuoto = scenario(vec![]);
+        assert!(Scenarios::compare(&state(), &vuoto, &cataloghi, &pesi).is_err());
+
+        let fotovoltaico = scenario(vec![Substitution::Fotovoltaico { potenza: 0.0 }]);
+        assert!(Scenarios::compare(&state(), &fotovoltaico, &cataloghi, &pesi).is_err());
+    }
+}
@@ -0,0 +1,77 @@
+use app_utils::{
+    app_error::{AppResult, ErrorScenario},
+    store::JsonStore,
+};
+use tauri::{AppHandle, Runtime};
+
+use crate::scenario::simulation::Scenario;
+
+/// Scenari di riqualificazione salvati nello store dell'applicazione,
+/// identificati dall'edificio e dal nome
+pub struct ScenarioStore;
+
+impl ScenarioStore {
+    const STORE: JsonStore = JsonStore::new("scenari.json", "scenari");
+
+    /// Scenari salvati per l'edificio
+    pub fn load<R: Runtime>(app_handle: &AppHandle<R>, edificio: &str) -> AppResult<Vec<Scenario>> {
+        Ok(Self::load_all(app_handle)?
+            .into_iter()
+            .filter(|scenario| scenario.edificio == edificio)
+            .collect())
+    }
+
+    pub fn get<R: Runtime>(
+        app_handle: &AppHandle<R>,
+        edificio: &str,
+        nome: &str,
+    ) -> AppResult<Scenario> {
+        Self::load(app_handle, edificio)?
+            .into_iter()
+            .find(|scenario| scenario.nome == nome)
+            .ok_or_else(|| ErrorScenario::NotFound(nome.to_string()).into())
+    }
+
+    /// Salva lo scenario, sostituendo quello dell'edificio con lo stesso nome
+    pub fn save<R: Runtime>(app_handle: &AppHandle<R>, scenario: Scenario) -> AppResult<()> {
+        scenario.validate()?;
+        let mut scenari = Self::load_all(app_handle)?;
+        match scenari
+            .iter_mut()
+            .find(|s| s.edificio == scenario.edificio && s.nome == scenario.nome)
+        {
+            Some(existing) => *existing = scenario,
+            None => scenari.push(scenario),
+        }
+        Self::write(app_handle, &scenari)
+    }
+
+    /// Restituisce `false` se l'edificio non ha uno scenario con il nome
+    /// indicato
+    pub fn delete<R: Runtime>(
+        app_handle: &AppHandle<R>,
+        edificio: &str,
+        nome: &str,
+    ) -> AppResult<bool> {
+        let mut scenari = Self::load_all(app_handle)?;
+        let len = scenari.len();
+        scenari.retain(|s| !(s.edificio == edificio && s.nome == nome));
+        if scenari.len() == len {
+            return Ok(false);
+        }
+        Self::write(app_handle, &scenari)?;
+        Ok(true)
+    }
+
+    fn load_all<R: Runtime>(app_handle: &AppHandle<R>) -> AppResult<Vec<Scenario>> {
+        Ok(Self::STORE
+            .load(app_handle, |e| ErrorScenario::Invalid(e.to_string()))?
+            .unwrap_or_default())
+    }
+
+    fn write<R: Runtime>(app_handle: &AppHandle<R>, scenari: &[Scenario]) -> AppResult<()> {
+        Self::STORE.save(app_handle, scenari, |e| {
+            ErrorScenario::Invalid(e.to_string())
+        })
+    }
+}
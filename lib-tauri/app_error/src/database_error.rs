@@ -15,6 +15,10 @@ pub enum DbError {
     IoError(#[from] std::io::Error),
     #[error("Generic error: {0}")]
     GenericError(String),
+    #[error("Migration error: {0}")]
+    MigrationError(String),
+    #[error("Database schema {found} is newer than the latest known {supported}")]
+    SchemaTooNew { found: String, supported: String },
 }
 
 impl From<tokio::time::error::Elapsed> for DbError {
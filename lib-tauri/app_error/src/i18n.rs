@@ -0,0 +1,611 @@
+use std::{
+    collections::BTreeMap,
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ApplicationError, DomainError, ErrorBackup, ErrorExport, ErrorImport, ErrorKind, ErrorQuality,
+    ErrorScenario, ErrorTask, InfrastructureError, TauriError, database_error::DbError,
+};
+
+static CURRENT_LOCALE: AtomicU8 = AtomicU8::new(Locale::It as u8);
+
+/// Lingua dei messaggi mostrati all'utente
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[repr(u8)]
+pub enum Locale {
+    #[default]
+    It,
+    En,
+}
+
+impl Locale {
+    /// Lingua scelta nelle impostazioni dell'applicazione
+    pub fn current() -> Self {
+        match CURRENT_LOCALE.load(Ordering::Relaxed) {
+            x if x == Locale::En as u8 => Locale::En,
+            _ => Locale::It,
+        }
+    }
+
+    pub fn set_current(locale: Locale) { CURRENT_LOCALE.store(locale as u8, Ordering::Relaxed); }
+}
+
+/// Catalogo dei messaggi: chiave, testo italiano, testo inglese. I parametri
+/// sono indicati tra graffe.
+const CATALOG: &[(&str, &str, &str)] = &[
+    // Dominio
+    (
+        "domain.not_found",
+        "{entity} non presente",
+        "{entity} not found",
+    ),
+    (
+        "domain.already_exists",
+        "{entity} già presente",
+        "{entity} already exists",
+    ),
+    (
+        "domain.still_referenced",
+        "{entity} ancora in uso",
+        "{entity} is still referenced",
+    ),
+    (
+        "domain.tipo_invalid",
+        "Tipo non valido: {tipo}",
+        "Invalid tipo: {tipo}",
+    ),
+    (
+        "domain.empty_field",
+        "Il campo {field} è vuoto",
+        "Field {field} is empty",
+    ),
+    (
+        "domain.invalid_format",
+        "Formato non valido per {field}",
+        "Invalid format for {field}",
+    ),
+    (
+        "domain.invalid_field",
+        "Campo {field} non valido",
+        "Invalid field {field}",
+    ),
+    (
+        "domain.unexpected",
+        "Errore imprevisto del database: {detail}",
+        "Unexpected database error: {detail}",
+    ),
+    // Infrastruttura
+    (
+        "infrastructure.database",
+        "Errore del database: {detail}",
+        "Database error: {detail}",
+    ),
+    (
+        "infrastructure.connection_pool",
+        "Database non raggiungibile: {detail}",
+        "Database unavailable: {detail}",
+    ),
+    (
+        "infrastructure.connection_timeout",
+        "Tempo scaduto per la connessione al database",
+        "Database connection timed out",
+    ),
+    (
+        "infrastructure.migration",
+        "Errore nell'aggiornamento dello schema del database: {detail}",
+        "Error migrating the database schema: {detail}",
+    ),
+    (
+        "infrastructure.schema_too_new",
+        "Lo schema del database ({found}) è più recente di quello supportato ({supported}), aggiorna l'applicazione",
+        "The database schema ({found}) is newer than the supported one ({supported}), update the application",
+    ),
+    (
+        "infrastructure.connection_profile_not_found",
+        "Profilo di connessione {profile} non trovato",
+        "Connection profile {profile} not found",
+    ),
+    // Applicazione
+    ("app.unknown", "Errore sconosciuto", "Unknown error"),
+    (
+        "app.unexpected",
+        "Errore imprevisto: {detail}",
+        "Unexpected error: {detail}",
+    ),
+    (
+        "app.edificio_not_selected",
+        "Nessun edificio selezionato",
+        "No edificio selected",
+    ),
+    (
+        "app.tauri",
+        "Errore dell'applicazione: {detail}",
+        "Application error: {detail}",
+    ),
+    // Task in background
+    (
+        "task.generic",
+        "Errore del task: {detail}",
+        "Task error: {detail}",
+    ),
+    (
+        "task.already_registered",
+        "Task {task} già registrato",
+        "Task {task} already registered",
+    ),
+    (
+        "task.invalid_config",
+        "Configurazione del task non valida: {detail}",
+        "Invalid task configuration: {detail}",
+    ),
+    (
+        "task.invalid_schedule",
+        "Pianificazione del task non valida: {detail}",
+        "Invalid task schedule: {detail}",
+    ),
+    (
+        "task.invalid_history",
+        "Storico del task non valido: {detail}",
+        "Invalid task history: {detail}",
+    ),
+    (
+        "task.not_found",
+        "Task {task} non trovato",
+        "Task {task} not found",
+    ),
+    (
+        "task.invalid_state",
+        "Operazione non consentita per il task {task} nello stato {status}",
+        "Operation not allowed for task {task} in status {status}",
+    ),
+    // Export
+    (
+        "export.empty_fascicolo",
+        "Nessun dato da esportare per il fascicolo {fascicolo}",
+        "No data to export for fascicolo {fascicolo}",
+    ),
+    (
+        "export.excel",
+        "Errore nella scrittura del file excel: {detail}",
+        "Error writing the excel file: {detail}",
+    ),
+    (
+        "export.io",
+        "Errore di scrittura: {detail}",
+        "IO error: {detail}",
+    ),
+    // Import
+    (
+        "import.workbook",
+        "Errore nell'apertura del file: {detail}",
+        "Error opening the file: {detail}",
+    ),
+    (
+        "import.sheet_not_found",
+        "Foglio {sheet} non trovato",
+        "Sheet {sheet} not found",
+    ),
+    (
+        "import.sheet_index_out_of_range",
+        "Il foglio numero {index} non esiste",
+        "Sheet index {index} out of range",
+    ),
+    (
+        "import.empty_sheet",
+        "Il foglio {sheet} è vuoto",
+        "Sheet {sheet} is empty",
+    ),
+    (
+        "import.header_row_out_of_range",
+        "Riga di intestazione {row} oltre la fine del foglio, che ha {height} righe",
+        "Header row {row} out of range, the sheet has {height} rows",
+    ),
+    (
+        "import.header_not_found",
+        "Intestazione non trovata, colonne mancanti: {columns}",
+        "Header not found, missing columns: {columns}",
+    ),
+    (
+        "import.column_not_found",
+        "Colonna {column} non trovata",
+        "Column {column} not found",
+    ),
+    (
+        "import.column_length",
+        "La colonna {column} ha {found} valori invece di {expected}",
+        "Column {column} has {found} values, expected {expected}",
+    ),
+    (
+        "import.unsupported_format",
+        "Formato del file non supportato: {format}",
+        "Unsupported file format: {format}",
+    ),
+    (
+        "import.unknown_encoding",
+        "Codifica sconosciuta: {encoding}",
+        "Unknown encoding: {encoding}",
+    ),
+    (
+        "import.csv",
+        "Errore nella lettura del file csv: {detail}",
+        "Error reading the csv file: {detail}",
+    ),
+    (
+        "import.invalid_rows",
+        "Il file contiene {count} righe non valide",
+        "The file contains {count} invalid rows",
+    ),
+    (
+        "import.profile_not_found",
+        "Profilo di importazione {profile} non trovato",
+        "Mapping profile {profile} not found",
+    ),
+    (
+        "import.invalid_profile",
+        "Profilo di importazione non valido: {detail}",
+        "Invalid mapping profile: {detail}",
+    ),
+    (
+        "import.invalid_piano",
+        "Piano non valido o vuoto",
+        "Invalid or empty piano",
+    ),
+    (
+        "import.invalid_uso",
+        "Destinazione d'uso non valida o vuota",
+        "Invalid or empty destinazione d'uso",
+    ),
+    // Backup
+    (
+        "backup.fascicolo_not_found",
+        "Nessun edificio trovato per il fascicolo {fascicolo}",
+        "No edifici found for fascicolo {fascicolo}",
+    ),
+    (
+        "backup.unsupported_version",
+        "Versione del backup {found} non supportata, l'ultima supportata è {supported}",
+        "Unsupported backup version {found}, the latest supported is {supported}",
+    ),
+    (
+        "backup.invalid_archive",
+        "File di backup non valido: {detail}",
+        "Invalid backup file: {detail}",
+    ),
+    (
+        "backup.edifici_already_exist",
+        "Edifici già presenti nel database: {edifici}",
+        "Edifici already present in the database: {edifici}",
+    ),
+    (
+        "backup.integrity",
+        "Backup incoerente: {detail}",
+        "Inconsistent backup: {detail}",
+    ),
+    (
+        "backup.io",
+        "Errore di lettura o scrittura: {detail}",
+        "IO error: {detail}",
+    ),
+    // Qualità dei dati
+    (
+        "quality.invalid_report",
+        "Report di qualità salvato non valido: {detail}",
+        "Invalid stored quality report: {detail}",
+    ),
+    // Scenari di riqualificazione
+    (
+        "scenario.not_found",
+        "Scenario {scenario} non trovato",
+        "Scenario {scenario} not found",
+    ),
+    (
+        "scenario.invalid",
+        "Scenario non valido: {detail}",
+        "Invalid scenario: {detail}",
+    ),
+];
+
+/// Messaggio identificato da una chiave del catalogo e dai suoi parametri
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Message {
+    pub key: &'static str,
+    pub params: BTreeMap<&'static str, String>,
+}
+
+impl Message {
+    pub fn new(key: &'static str) -> Self {
+        Self {
+            key,
+            params: BTreeMap::new(),
+        }
+    }
+
+    pub fn param(mut self, name: &'static str, value: impl ToString) -> Self {
+        self.params.insert(name, value.to_string());
+        self
+    }
+
+    /// Testo del messaggio nella lingua indicata, la chiave stessa se non è
+    /// presente nel catalogo
+    pub fn translate(&self, locale: Locale) -> String {
+        let Some(&(_, it, en)) = CATALOG.iter().find(|(key, ..)| *key == self.key) else {
+            return self.key.to_string();
+        };
+        let template = match locale {
+            Locale::It => it,
+            Locale::En => en,
+        };
+        self.params
+            .iter()
+            .fold(template.to_string(), |text, (name, value)| {
+                text.replace(&format!("{{{name}}}"), value)
+            })
+    }
+}
+
+/// Errori che possono essere mostrati all'utente nella sua lingua
+pub trait Localized {
+    fn message(&self) -> Message;
+
+    fn localized(&self, locale: Locale) -> String { self.message().translate(locale) }
+}
+
+impl Localized for ApplicationError {
+    fn message(&self) -> Message {
+        match self {
+            ApplicationError::Domain(e) => e.message(),
+            ApplicationError::Infrastructure(e) => e.message(),
+            ApplicationError::Unknown => Message::new("app.unknown"),
+            ApplicationError::Unexpected(detail) => {
+                Message::new("app.unexpected").param("detail", detail)
+            }
+            ApplicationError::EdificioNotSelected => Message::new("app.edificio_not_selected"),
+            ApplicationError::BackgroundTask(e) => e.message(),
+            ApplicationError::Export(e) => e.message(),
+            ApplicationError::Import(e) => e.message(),
+            ApplicationError::Backup(e) => e.message(),
+            ApplicationError::Quality(e) => e.message(),
+            ApplicationError::Scenario(e) => e.message(),
+            ApplicationError::Tauri(e) => e.message(),
+        }
+    }
+}
+
+impl Localized for DomainError {
+    fn message(&self) -> Message {
+        if let Some(message) = self.entity_message() {
+            return message;
+        }
+        match self {
+            DomainError::TipoInvalid(tipo) => {
+                Message::new("domain.tipo_invalid").param("tipo", tipo)
+            }
+            DomainError::InvalidInput(kind, field) => {
+                let key = match kind {
+                    ErrorKind::EmptyField => "domain.empty_field",
+                    ErrorKind::InvalidFormat => "domain.invalid_format",
+                    ErrorKind::InvalidField => "domain.invalid_field",
+                };
+                Message::new(key).param("field", field)
+            }
+            DomainError::Unexpected(e) => Message::new("domain.unexpected").param("detail", e),
+            _ => Message::new("app.unknown"),
+        }
+    }
+}
+
+impl Localized for InfrastructureError {
+    fn message(&self) -> Message {
+        match self {
+            InfrastructureError::DatabaseError(DbError::SchemaTooNew { found, supported }) => {
+                Message::new("infrastructure.schema_too_new")
+                    .param("found", found)
+                    .param("supported", supported)
+            }
+            InfrastructureError::DatabaseError(DbError::MigrationError(detail)) => {
+                Message::new("infrastructure.migration").param("detail", detail)
+            }
+            InfrastructureError::DatabaseError(e) => {
+                Message::new("infrastructure.database").param("detail", e)
+            }
+            InfrastructureError::ConnectionPool(detail) => {
+                Message::new("infrastructure.connection_pool").param("detail", detail)
+            }
+            InfrastructureError::ConnectionTimeout => {
+                Message::new("infrastructure.connection_timeout")
+            }
+            InfrastructureError::ConnectionProfileNotFound(profile) => {
+                Message::new("infrastructure.connection_profile_not_found")
+                    .param("profile", profile)
+            }
+        }
+    }
+}
+
+impl Localized for ErrorTask {
+    fn message(&self) -> Message {
+        match self {
+            ErrorTask::Generic(detail) => Message::new("task.generic").param("detail", detail),
+            ErrorTask::AlreadyRegistered(task) => {
+                Message::new("task.already_registered").param("task", task)
+            }
+            ErrorTask::InvalidConfig(detail) => {
+                Message::new("task.invalid_config").param("detail", detail)
+            }
+            ErrorTask::InvalidSchedule(detail) => {
+                Message::new("task.invalid_schedule").param("detail", detail)
+            }
+            ErrorTask::InvalidHistory(detail) => {
+                Message::new("task.invalid_history").param("detail", detail)
+            }
+            ErrorTask::NotFound(task) => Message::new("task.not_found").param("task", task),
+            ErrorTask::InvalidState { task, status } => Message::new("task.invalid_state")
+                .param("task", task)
+                .param("status", status),
+        }
+    }
+}
+
+impl Localized for ErrorExport {
+    fn message(&self) -> Message {
+        match self {
+            ErrorExport::EmptyFascicolo(fascicolo) => {
+                Message::new("export.empty_fascicolo").param("fascicolo", fascicolo)
+            }
+            ErrorExport::Excel(detail) => Message::new("export.excel").param("detail", detail),
+            ErrorExport::Io(e) => Message::new("export.io").param("detail", e),
+        }
+    }
+}
+
+impl Localized for ErrorImport {
+    fn message(&self) -> Message {
+        match self {
+            ErrorImport::Workbook(detail) => {
+                Message::new("import.workbook").param("detail", detail)
+            }
+            ErrorImport::SheetNotFound(sheet) => {
+                Message::new("import.sheet_not_found").param("sheet", sheet)
+            }
+            ErrorImport::SheetIndexOutOfRange(index) => {
+                Message::new("import.sheet_index_out_of_range").param("index", index)
+            }
+            ErrorImport::EmptySheet(sheet) => {
+                Message::new("import.empty_sheet").param("sheet", sheet)
+            }
+            ErrorImport::HeaderRowOutOfRange { row, height } => {
+                Message::new("import.header_row_out_of_range")
+                    .param("row", row)
+                    .param("height", height)
+            }
+            ErrorImport::HeaderNotFound(columns) => {
+                Message::new("import.header_not_found").param("columns", columns.join(", "))
+            }
+            ErrorImport::ColumnNotFound(column) => {
+                Message::new("import.column_not_found").param("column", column)
+            }
+            ErrorImport::ColumnLength {
+                column,
+                expected,
+                found,
+            } => Message::new("import.column_length")
+                .param("column", column)
+                .param("expected", expected)
+                .param("found", found),
+            ErrorImport::UnsupportedFormat(format) => {
+                Message::new("import.unsupported_format").param("format", format)
+            }
+            ErrorImport::UnknownEncoding(encoding) => {
+                Message::new("import.unknown_encoding").param("encoding", encoding)
+            }
+            ErrorImport::Csv(detail) => Message::new("import.csv").param("detail", detail),
+            ErrorImport::InvalidRows(count) => {
+                Message::new("import.invalid_rows").param("count", count)
+            }
+            ErrorImport::ProfileNotFound(profile) => {
+                Message::new("import.profile_not_found").param("profile", profile)
+            }
+            ErrorImport::InvalidProfile(detail) => {
+                Message::new("import.invalid_profile").param("detail", detail)
+            }
+        }
+    }
+}
+
+impl Localized for ErrorBackup {
+    fn message(&self) -> Message {
+        match self {
+            ErrorBackup::FascicoloNotFound(fascicolo) => {
+                Message::new("backup.fascicolo_not_found").param("fascicolo", fascicolo)
+            }
+            ErrorBackup::UnsupportedVersion { found, supported } => {
+                Message::new("backup.unsupported_version")
+                    .param("found", found)
+                    .param("supported", supported)
+            }
+            ErrorBackup::InvalidArchive(detail) => {
+                Message::new("backup.invalid_archive").param("detail", detail)
+            }
+            ErrorBackup::EdificiAlreadyExist(edifici) => {
+                Message::new("backup.edifici_already_exist").param("edifici", edifici.join(", "))
+            }
+            ErrorBackup::Integrity(problemi) => {
+                Message::new("backup.integrity").param("detail", problemi.join("; "))
+            }
+            ErrorBackup::Io(detail) => Message::new("backup.io").param("detail", detail),
+        }
+    }
+}
+
+impl Localized for ErrorQuality {
+    fn message(&self) -> Message {
+        match self {
+            ErrorQuality::InvalidReport(detail) => {
+                Message::new("quality.invalid_report").param("detail", detail)
+            }
+        }
+    }
+}
+
+impl Localized for ErrorScenario {
+    fn message(&self) -> Message {
+        match self {
+            ErrorScenario::NotFound(scenario) => {
+                Message::new("scenario.not_found").param("scenario", scenario)
+            }
+            ErrorScenario::Invalid(detail) => {
+                Message::new("scenario.invalid").param("detail", detail)
+            }
+        }
+    }
+}
+
+impl Localized for TauriError {
+    fn message(&self) -> Message {
+        match self {
+            TauriError::Plugin(e) => Message::new("app.tauri").param("detail", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translate() {
+        let error = ApplicationError::from(ErrorImport::ColumnLength {
+            column: "piano".to_string(),
+            expected: 10,
+            found: 8,
+        });
+        assert_eq!(
+            error.localized(Locale::It),
+            "La colonna piano ha 8 valori invece di 10"
+        );
+        assert_eq!(
+            error.localized(Locale::En),
+            "Column piano has 8 values, expected 10"
+        );
+        assert_eq!(
+            DomainError::StanzaConInfissiNotFound.localized(Locale::It),
+            "Stanza con infissi non presente"
+        );
+        assert_eq!(
+            Message::new("missing.key").translate(Locale::En),
+            "missing.key"
+        );
+    }
+
+    #[test]
+    fn test_catalog_keys_are_unique() {
+        let mut keys: Vec<&str> = CATALOG.iter().map(|(key, ..)| *key).collect();
+        keys.sort();
+        keys.dedup();
+        assert_eq!(keys.len(), CATALOG.len());
+    }
+}
@@ -6,8 +6,14 @@ use std::{
 use thiserror::Error;
 
 use crate::database_error::DbError;
+pub use crate::{
+    i18n::{Locale, Localized, Message},
+    response::{ErrorCode, ErrorResponse},
+};
 
 pub mod database_error;
+mod i18n;
+mod response;
 
 pub type AppResult<T> = Result<T, ApplicationError>;
 
@@ -15,6 +21,7 @@ macro_rules! define_domain_errors {
     (
         entities: [$(($entity:ident, $display_name:literal)),* $(,)?],
         singles: [$(($single:ident, $single_display:literal)),* $(,)?],
+        referenced: [$(($referenced:ident, $referenced_display:literal)),* $(,)?],
         custom: [$($variant:ident $( ( $($field:ty),* ) )? => $msg:literal),* $(,)?]
     ) => {
         paste::paste! {
@@ -32,11 +39,68 @@ macro_rules! define_domain_errors {
                 [<$single NotFound>],
             )*
 
+            $(
+                #[error("{} is still referenced", $referenced_display)]
+                [<$referenced StillReferenced>],
+            )*
+
             $(
                 #[error($msg)]
                 $variant $( ( $($field), * ), )?
             )*
         }
+
+        impl DomainError {
+            /// Codice ed entità degli errori generati per le entità, `None` per
+            /// quelli definiti in `custom`
+            fn entity_code(&self) -> Option<(ErrorCode, &'static str)> {
+                match self {
+                    $(
+                        DomainError::[<$entity NotFound>] => {
+                            Some((ErrorCode::NotFound, stringify!([<$entity:snake>])))
+                        }
+                        DomainError::[<$entity AlreadyExists>] => {
+                            Some((ErrorCode::AlreadyExists, stringify!([<$entity:snake>])))
+                        }
+                    )*
+                    $(
+                        DomainError::[<$single NotFound>] => {
+                            Some((ErrorCode::NotFound, stringify!([<$single:snake>])))
+                        }
+                    )*
+                    $(
+                        DomainError::[<$referenced StillReferenced>] => {
+                            Some((ErrorCode::StillReferenced, stringify!([<$referenced:snake>])))
+                        }
+                    )*
+                    #[allow(unreachable_patterns)]
+                    _ => None,
+                }
+            }
+
+            /// Messaggio localizzabile degli errori generati per le entità
+            fn entity_message(&self) -> Option<Message> {
+                let (key, entity) = match self {
+                    $(
+                        DomainError::[<$entity NotFound>] => ("domain.not_found", $display_name),
+                        DomainError::[<$entity AlreadyExists>] => {
+                            ("domain.already_exists", $display_name)
+                        }
+                    )*
+                    $(
+                        DomainError::[<$single NotFound>] => ("domain.not_found", $single_display),
+                    )*
+                    $(
+                        DomainError::[<$referenced StillReferenced>] => {
+                            ("domain.still_referenced", $referenced_display)
+                        }
+                    )*
+                    #[allow(unreachable_patterns)]
+                    _ => return None,
+                };
+                Some(Message::new(key).param("entity", entity))
+            }
+        }
         }
     };
 }
@@ -55,6 +119,16 @@ pub enum ApplicationError {
     EdificioNotSelected,
     #[error("Task error: {0}")]
     BackgroundTask(#[from] ErrorTask),
+    #[error("Export error: {0}")]
+    Export(#[from] ErrorExport),
+    #[error("Import error: {0}")]
+    Import(#[from] ErrorImport),
+    #[error("Backup error: {0}")]
+    Backup(#[from] ErrorBackup),
+    #[error("Data quality error: {0}")]
+    Quality(#[from] ErrorQuality),
+    #[error("Scenario error: {0}")]
+    Scenario(#[from] ErrorScenario),
     #[error("Tauri error: {0}")]
     Tauri(TauriError),
 }
@@ -65,6 +139,12 @@ impl From<DbError> for ApplicationError {
     }
 }
 
+/// Necessario per usare `ApplicationError` come errore di una transazione
+/// diesel
+impl From<diesel::result::Error> for ApplicationError {
+    fn from(value: diesel::result::Error) -> Self { ApplicationError::Domain(value.into()) }
+}
+
 define_domain_errors! {
     entities: [
         (Edificio, "Edificio"),
@@ -75,11 +155,16 @@ define_domain_errors! {
         (Illuminazione, "Illuminazione"),
         (Climatizzazione, "Climatizzazione"),
         (MaterialeInfisso, "Materiale Infisso"),
+        (VetroInfisso, "Vetro Infisso"),
         (Utenza, "Utenza"),
     ],
     singles: [
         (StanzaConInfissi, "Stanza con infissi"),
     ],
+    referenced: [
+        (Climatizzazione, "Climatizzazione"),
+        (Illuminazione, "Illuminazione"),
+    ],
     custom: [
         TipoInvalid(String) => "Tipo invalid: {0}",
         InvalidInput(ErrorKind, String) => "Invalid input: {0}",
@@ -94,10 +179,17 @@ impl From<diesel::result::Error> for DomainError {
                 diesel::result::DatabaseErrorKind::Unknown,
                 ref msg,
             ) => {
-                if msg.message() == "Field content cannot be empty or contain only whitespace" {
-                    DomainError::InvalidInput(ErrorKind::EmptyField, msg.message().to_string())
-                } else {
-                    DomainError::Unexpected(value)
+                // eccezione sollevata dal trigger sui campi di testo obbligatori
+                let field = msg
+                    .message()
+                    .strip_prefix("Field ")
+                    .and_then(|rest| {
+                        rest.strip_suffix(" cannot be empty or contain only whitespace")
+                    })
+                    .map(str::to_string);
+                match field {
+                    Some(field) => DomainError::InvalidInput(ErrorKind::EmptyField, field),
+                    None => DomainError::Unexpected(value),
                 }
             }
             _ => DomainError::Unexpected(value),
@@ -131,12 +223,102 @@ pub enum InfrastructureError {
     ConnectionPool(String),
     #[error("Connection timeout")]
     ConnectionTimeout,
+    #[error("Connection profile {0} not found")]
+    ConnectionProfileNotFound(String),
 }
 
 #[derive(Error, Debug)]
 pub enum ErrorTask {
     #[error("Error: {0}")]
     Generic(String),
+    #[error("Task {0} already registered")]
+    AlreadyRegistered(String),
+    #[error("Invalid task configuration: {0}")]
+    InvalidConfig(String),
+    #[error("Invalid task schedule: {0}")]
+    InvalidSchedule(String),
+    #[error("Invalid task history: {0}")]
+    InvalidHistory(String),
+    #[error("Task {0} not found")]
+    NotFound(String),
+    #[error("Operation not allowed for task {task} in status {status}")]
+    InvalidState { task: String, status: String },
+}
+
+#[derive(Error, Debug)]
+pub enum ErrorExport {
+    #[error("No data to export for fascicolo {0}")]
+    EmptyFascicolo(i32),
+    #[error("Error writing the excel file: {0}")]
+    Excel(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ErrorImport {
+    #[error("Error opening the file: {0}")]
+    Workbook(String),
+    #[error("Sheet {0} not found")]
+    SheetNotFound(String),
+    #[error("Sheet index {0} out of range")]
+    SheetIndexOutOfRange(usize),
+    #[error("Sheet {0} is empty")]
+    EmptySheet(String),
+    #[error("Header row {row} out of range, the sheet has {height} rows")]
+    HeaderRowOutOfRange { row: usize, height: usize },
+    #[error("Header not found, missing columns: {}", .0.join(", "))]
+    HeaderNotFound(Vec<String>),
+    #[error("Column {0} not found")]
+    ColumnNotFound(String),
+    #[error("Column {column} has {found} values, expected {expected}")]
+    ColumnLength {
+        column: String,
+        expected: usize,
+        found: usize,
+    },
+    #[error("Unsupported file format: {0}")]
+    UnsupportedFormat(String),
+    #[error("Unknown encoding: {0}")]
+    UnknownEncoding(String),
+    #[error("Error reading the csv file: {0}")]
+    Csv(String),
+    #[error("The file contains {0} invalid rows")]
+    InvalidRows(usize),
+    #[error("Mapping profile {0} not found")]
+    ProfileNotFound(String),
+    #[error("Invalid mapping profile: {0}")]
+    InvalidProfile(String),
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ErrorBackup {
+    #[error("No edifici found for fascicolo {0}")]
+    FascicoloNotFound(i32),
+    #[error("Unsupported backup version {found}, the latest supported is {supported}")]
+    UnsupportedVersion { found: u32, supported: u32 },
+    #[error("Invalid backup file: {0}")]
+    InvalidArchive(String),
+    #[error("Edifici already present in the database: {}", .0.join(", "))]
+    EdificiAlreadyExist(Vec<String>),
+    #[error("Inconsistent backup: {}", .0.join("; "))]
+    Integrity(Vec<String>),
+    #[error("IO error: {0}")]
+    Io(String),
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ErrorQuality {
+    #[error("Invalid stored quality report: {0}")]
+    InvalidReport(String),
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ErrorScenario {
+    #[error("Scenario {0} not found")]
+    NotFound(String),
+    #[error("Invalid scenario: {0}")]
+    Invalid(String),
 }
 
 #[derive(Error, Debug)]
@@ -0,0 +1,206 @@
+use std::collections::BTreeMap;
+
+use serde::{Serialize, Serializer};
+
+use crate::{
+    ApplicationError, DomainError, ErrorBackup, ErrorExport, ErrorImport, ErrorKind, ErrorScenario,
+    ErrorTask, InfrastructureError, Locale, Localized, database_error::DbError,
+};
+
+/// Codice stabile dell'errore, da usare nel frontend al posto del messaggio
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    NotFound,
+    AlreadyExists,
+    StillReferenced,
+    InvalidInput,
+    InvalidState,
+    EdificioNotSelected,
+    Database,
+    DatabaseUnavailable,
+    /// Il database ha uno schema più recente dell'applicazione
+    SchemaTooNew,
+    Timeout,
+    Task,
+    Export,
+    Import,
+    Backup,
+    DataQuality,
+    Io,
+    Tauri,
+    Unexpected,
+}
+
+/// Forma serializzata di `ApplicationError` restituita dai command
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ErrorResponse {
+    pub code: ErrorCode,
+    /// Entità coinvolta, in snake_case (`stanza`, `infisso`, `task`, ...)
+    pub entity: Option<String>,
+    /// Campo non valido, se noto
+    pub field: Option<String>,
+    /// Chiave del messaggio nel catalogo delle traduzioni
+    pub key: &'static str,
+    pub params: BTreeMap<&'static str, String>,
+    /// Messaggio nella lingua scelta nelle impostazioni
+    pub message: String,
+}
+
+impl ErrorResponse {
+    fn new(code: ErrorCode, error: &ApplicationError) -> Self {
+        let message = error.message();
+        Self {
+            code,
+            entity: None,
+            field: None,
+            message: message.translate(Locale::current()),
+            key: message.key,
+            params: message.params,
+        }
+    }
+
+    fn entity(mut self, entity: &str) -> Self {
+        self.entity = Some(entity.to_string());
+        self
+    }
+
+    fn field(mut self, field: &str) -> Self {
+        self.field = Some(field.to_string());
+        self
+    }
+}
+
+impl From<&ApplicationError> for ErrorResponse {
+    fn from(error: &ApplicationError) -> Self {
+        let response = |code| ErrorResponse::new(code, error);
+        match error {
+            ApplicationError::Domain(domain) => match (domain.entity_code(), domain) {
+                (Some((code, entity)), _) => response(code).entity(entity),
+                (None, DomainError::TipoInvalid(_)) => {
+                    response(ErrorCode::InvalidInput).field("tipo")
+                }
+                (
+                    None,
+                    DomainError::InvalidInput(
+                        ErrorKind::EmptyField | ErrorKind::InvalidField,
+                        field,
+                    ),
+                ) => response(ErrorCode::InvalidInput).field(field),
+                (None, DomainError::InvalidInput(..)) => response(ErrorCode::InvalidInput),
+                (None, _) => response(ErrorCode::Database),
+            },
+            ApplicationError::Infrastructure(infrastructure) => match infrastructure {
+                InfrastructureError::DatabaseError(
+                    DbError::ConfigurationError(_) | DbError::ConnectionPoolError(_),
+                ) => response(ErrorCode::DatabaseUnavailable),
+                InfrastructureError::DatabaseError(DbError::SchemaTooNew { .. }) => {
+                    response(ErrorCode::SchemaTooNew)
+                }
+                InfrastructureError::DatabaseError(_) => response(ErrorCode::Database),
+                InfrastructureError::ConnectionPool(_) => response(ErrorCode::DatabaseUnavailable),
+                InfrastructureError::ConnectionTimeout => response(ErrorCode::Timeout),
+                InfrastructureError::ConnectionProfileNotFound(_) => {
+                    response(ErrorCode::NotFound).entity("connection_profile")
+                }
+            },
+            ApplicationError::Unknown | ApplicationError::Unexpected(_) => {
+                response(ErrorCode::Unexpected)
+            }
+            ApplicationError::EdificioNotSelected => response(ErrorCode::EdificioNotSelected),
+            ApplicationError::BackgroundTask(task) => match task {
+                ErrorTask::NotFound(_) => response(ErrorCode::NotFound).entity("task"),
+                ErrorTask::AlreadyRegistered(_) => {
+                    response(ErrorCode::AlreadyExists).entity("task")
+                }
+                ErrorTask::InvalidState { .. } => response(ErrorCode::InvalidState).entity("task"),
+                ErrorTask::InvalidConfig(_) | ErrorTask::InvalidSchedule(_) => {
+                    response(ErrorCode::InvalidInput).entity("task")
+                }
+                _ => response(ErrorCode::Task),
+            },
+            ApplicationError::Export(export) => match export {
+                ErrorExport::EmptyFascicolo(_) => response(ErrorCode::NotFound).entity("fascicolo"),
+                ErrorExport::Io(_) => response(ErrorCode::Io),
+                ErrorExport::Excel(_) => response(ErrorCode::Export),
+            },
+            ApplicationError::Import(import) => match import {
+                ErrorImport::ProfileNotFound(_) => {
+                    response(ErrorCode::NotFound).entity("mapping_profile")
+                }
+                ErrorImport::SheetNotFound(_) => response(ErrorCode::NotFound).entity("sheet"),
+                ErrorImport::ColumnNotFound(column) => {
+                    response(ErrorCode::InvalidInput).field(column)
+                }
+                _ => response(ErrorCode::Import),
+            },
+            ApplicationError::Backup(backup) => match backup {
+                ErrorBackup::FascicoloNotFound(_) => {
+                    response(ErrorCode::NotFound).entity("fascicolo")
+                }
+                ErrorBackup::EdificiAlreadyExist(_) => {
+                    response(ErrorCode::AlreadyExists).entity("edificio")
+                }
+                ErrorBackup::Io(_) => response(ErrorCode::Io),
+                _ => response(ErrorCode::Backup),
+            },
+            ApplicationError::Quality(_) => response(ErrorCode::DataQuality),
+            ApplicationError::Scenario(scenario) => match scenario {
+                ErrorScenario::NotFound(_) => response(ErrorCode::NotFound).entity("scenario"),
+                ErrorScenario::Invalid(_) => response(ErrorCode::InvalidInput),
+            },
+            ApplicationError::Tauri(_) => response(ErrorCode::Tauri),
+        }
+    }
+}
+
+/// I command restituiscono l'errore come `ErrorResponse`
+impl Serialize for ApplicationError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ErrorResponse::from(self).serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_response() {
+        let json = |error: ApplicationError| serde_json::to_value(&error).unwrap();
+
+        assert_eq!(
+            json(DomainError::StanzaConInfissiNotFound.into()),
+            serde_json::json!({
+                "code": "NOT_FOUND",
+                "entity": "stanza_con_infissi",
+                "field": null,
+                "key": "domain.not_found",
+                "params": { "entity": "Stanza con infissi" },
+                "message": "Stanza con infissi non presente"
+            })
+        );
+        assert_eq!(
+            json(ApplicationError::EdificioNotSelected)["code"],
+            "EDIFICIO_NOT_SELECTED"
+        );
+
+        let invalid = ErrorResponse::from(&ApplicationError::Domain(DomainError::InvalidInput(
+            ErrorKind::InvalidField,
+            "piano".to_string(),
+        )));
+        assert_eq!(
+            (invalid.code, invalid.field.as_deref()),
+            (ErrorCode::InvalidInput, Some("piano"))
+        );
+
+        let empty = ErrorResponse::from(&ApplicationError::Domain(DomainError::InvalidInput(
+            ErrorKind::EmptyField,
+            "content".to_string(),
+        )));
+        assert_eq!(
+            (empty.code, empty.field.as_deref()),
+            (ErrorCode::InvalidInput, Some("content"))
+        );
+    }
+}
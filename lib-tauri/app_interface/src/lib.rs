@@ -16,7 +16,7 @@ pub mod database_interface {
 
     #[async_trait]
     pub trait DatabaseConnector {
-        async fn create_postgres_pool(&self) -> PostgresPool;
+        async fn create_postgres_pool(&self) -> Result<PostgresPool, DbError>;
         fn as_any_mut(&mut self) -> &mut dyn Any;
     }
 
@@ -60,7 +60,6 @@ pub mod dao_interface {
             ) -> Result<Self::Output, DomainError>;
         }
 
-        #[allow(dead_code)]
         pub trait Delete<T, K>: DAO {
             type Output;
             fn delete(conn: &mut PostgresPooled, item: K) -> Result<Self::Output, DomainError>;
@@ -134,11 +133,12 @@ pub mod service_interface {
         T: DTO,
     {
         type Output;
+        /// Filtri, ordinamento e paginazione accettati dal servizio
+        type Query: Send;
 
         async fn retrieve_by(
             db_state: State<'_, impl DatabaseManagerTrait + Send + Sync>,
-            where_field: &str,
-            where_value: &str,
+            query: Self::Query,
         ) -> AppResult<Self::Output>;
     }
 
@@ -176,7 +176,6 @@ pub mod service_interface {
         ) -> AppResult<T>;
     }
 
-    #[allow(dead_code)]
     #[async_trait]
     pub trait DeleteService<T, K>
     where
@@ -0,0 +1,32 @@
+drop view v_dati_stanze;
+create view v_dati_stanze as
+select s.id,
+       e.fascicolo,
+       s.edificio_id                                  as chiave,
+       s.piano,
+       s.id_spazio,
+       s.cod_stanza,
+       s.destinazione_uso,
+       s.altezza,
+       s.spessore_muro,
+       s.riscaldamento,
+       s.raffrescamento,
+       s.illuminazione,
+       coalesce(round(dgs.mq_infissi::numeric, 2), 0)::float as mq_infissi,
+       dgs.materiale,
+       dgs.vetro
+from stanza as s
+         join edificio as e on e.chiave = s.edificio_id
+         left join (
+    select mq.stanza_id, mq.mq_infissi, m.materiale, v.vetro
+    from v_mq_infissi as mq
+             join v_mat_min_eff_stanza as m
+                  on mq.stanza_id = m.stanza_id
+             join v_vet_min_eff_stanza as v on m.stanza_id = v.stanza_id) as dgs
+                   on s.id = dgs.stanza_id;
+
+drop table parete_esposta;
+
+alter table stanza
+    drop column superficie,
+    drop column perimetro;
@@ -0,0 +1,43 @@
+alter table stanza
+    add column superficie real check ( superficie >= 0 ),
+    add column perimetro  real check ( perimetro >= 0 );
+
+-- lunghezza delle pareti verso l'esterno, una riga per orientamento
+create table if not exists parete_esposta
+(
+    stanza_id    integer    not null,
+    orientamento varchar(2) not null check ( orientamento in ('N', 'NE', 'E', 'SE', 'S', 'SO', 'O', 'NO') ),
+    lunghezza    real       not null check ( lunghezza > 0 ),
+    primary key (stanza_id, orientamento),
+    foreign key (stanza_id) references stanza (id) on delete cascade on update cascade
+);
+
+create or replace view v_dati_stanze as
+select s.id,
+       e.fascicolo,
+       s.edificio_id                                  as chiave,
+       s.piano,
+       s.id_spazio,
+       s.cod_stanza,
+       s.destinazione_uso,
+       s.altezza,
+       s.spessore_muro,
+       s.riscaldamento,
+       s.raffrescamento,
+       s.illuminazione,
+       coalesce(round(dgs.mq_infissi::numeric, 2), 0)::float as mq_infissi,
+       dgs.materiale,
+       dgs.vetro,
+       s.superficie::float                            as superficie,
+       round((s.superficie * s.altezza / 100)::numeric, 2)::float as volume
+from stanza as s
+         join edificio as e on e.chiave = s.edificio_id
+    -- aggiungo i dati degli infissi alle stanze che c'è li hanno
+         left join (
+    -- recupero i dati delle stanze che hanno degli infissi
+    select mq.stanza_id, mq.mq_infissi, m.materiale, v.vetro
+    from v_mq_infissi as mq
+             join v_mat_min_eff_stanza as m
+                  on mq.stanza_id = m.stanza_id
+             join v_vet_min_eff_stanza as v on m.stanza_id = v.stanza_id) as dgs
+                   on s.id = dgs.stanza_id;
@@ -0,0 +1,5 @@
+alter table vetro_infisso
+    drop column ug;
+
+alter table materiale_infisso
+    drop column uf;
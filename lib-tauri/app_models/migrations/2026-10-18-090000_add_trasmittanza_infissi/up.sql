@@ -0,0 +1,24 @@
+-- trasmittanza termica in W/m²K del telaio (Uf) e del vetro (Ug)
+alter table materiale_infisso
+    add column uf real check ( uf > 0 );
+
+alter table vetro_infisso
+    add column ug real check ( ug > 0 );
+
+-- valori tipici, modificabili dall'utente
+update materiale_infisso
+set uf = case materiale
+             when 'Legno' then 2.0
+             when 'Ferro' then 5.9
+             when 'Alluminio' then 3.2
+             when 'PVC' then 1.6
+    end;
+
+update vetro_infisso
+set ug = case vetro
+             when 'Singolo' then 5.7
+             when 'Doppio' then 3.3
+             when 'Camera' then 2.8
+             when 'Triplo' then 2.0
+             when 'Plexiglas' then 5.3
+    end;
@@ -0,0 +1,8 @@
+alter table utenze
+    drop column consumo_annuo;
+
+alter table fotovoltaico
+    drop column provincia,
+    drop column anno_installazione,
+    drop column inclinazione,
+    drop column orientamento;
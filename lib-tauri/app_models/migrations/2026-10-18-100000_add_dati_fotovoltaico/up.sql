@@ -0,0 +1,10 @@
+-- dati dell'impianto usati per stimarne la produzione
+alter table fotovoltaico
+    add column orientamento       varchar(2) check ( orientamento in ('N', 'NE', 'E', 'SE', 'S', 'SO', 'O', 'NO') ),
+    add column inclinazione       smallint check ( inclinazione between 0 and 90 ),
+    add column anno_installazione integer check ( anno_installazione >= 1970 ),
+    add column provincia          varchar(2);
+
+-- consumo annuo nell'unità del contatore (kWh per l'elettricità)
+alter table utenze
+    add column consumo_annuo real check ( consumo_annuo >= 0 );
@@ -3,14 +3,14 @@ use std::{borrow::Cow, fmt::Debug};
 use chrono::NaiveDateTime;
 use diesel::{
     AsChangeset, Associations, Identifiable, Insertable, Queryable, QueryableByName, Selectable,
-    sql_types::{Integer, Nullable, SmallInt, Text},
+    sql_types::{Float, Integer, Nullable, SmallInt, Text},
 };
 use serde::{Deserialize, Serialize};
 
 use crate::schema::{
     annotazione_edificio, annotazione_infisso, annotazione_stanza, climatizzazione, edificio,
-    fotovoltaico, illuminazione, infisso, materiale_infisso, stanza, stanza_con_infissi,
-    tipo_infisso, utenze, vetro_infisso,
+    fotovoltaico, illuminazione, infisso, materiale_infisso, parete_esposta, stanza,
+    stanza_con_infissi, tipo_infisso, utenze, vetro_infisso,
 };
 
 #[derive(Queryable, Selectable, Insertable, Debug, PartialEq)]
@@ -33,6 +33,16 @@ pub struct Climatizzazione {
 pub struct MaterialeInfisso {
     pub materiale: String,
     pub eff_energetica: i16,
+    /// Trasmittanza del telaio, in W/m²K
+    pub uf: Option<f32>,
+}
+
+/// `None` cancella la trasmittanza salvata
+#[derive(AsChangeset, Debug, PartialEq)]
+#[diesel(table_name = materiale_infisso)]
+#[diesel(treat_none_as_null = true)]
+pub struct UpdateMaterialeInfisso {
+    pub uf: Option<f32>,
 }
 
 #[derive(Queryable, Selectable, Debug, PartialEq)]
@@ -40,6 +50,16 @@ pub struct MaterialeInfisso {
 pub struct VetroInfisso {
     pub vetro: String,
     pub eff_energetica: i16,
+    /// Trasmittanza del vetro, in W/m²K
+    pub ug: Option<f32>,
+}
+
+/// `None` cancella la trasmittanza salvata
+#[derive(AsChangeset, Debug, PartialEq)]
+#[diesel(table_name = vetro_infisso)]
+#[diesel(treat_none_as_null = true)]
+pub struct UpdateVetroInfisso {
+    pub ug: Option<f32>,
 }
 
 #[derive(Queryable, Selectable, Debug, PartialEq)]
@@ -107,6 +127,12 @@ pub struct Stanza {
     pub raffrescamento: Option<String>,
     #[diesel(sql_type = Nullable<Text>)]
     pub illuminazione: Option<String>,
+    /// Superficie calpestabile, in m²
+    #[diesel(sql_type = Nullable<Float>)]
+    pub superficie: Option<f32>,
+    /// Perimetro, in m
+    #[diesel(sql_type = Nullable<Float>)]
+    pub perimetro: Option<f32>,
 }
 
 impl AsRef<Stanza> for Stanza {
@@ -126,11 +152,28 @@ pub struct NewStanza<'a> {
 #[derive(AsChangeset, Debug, PartialEq)]
 #[diesel(table_name = stanza)]
 pub struct UpdateStanza<'a> {
+    pub cod_stanza: Option<Cow<'a, str>>,
+    pub destinazione_uso: Option<Cow<'a, str>>,
     pub altezza: Option<i16>,
     pub spessore_muro: Option<i16>,
     pub riscaldamento: Option<Cow<'a, str>>,
     pub raffrescamento: Option<Cow<'a, str>>,
     pub illuminazione: Option<Cow<'a, str>>,
+    pub superficie: Option<f32>,
+    pub perimetro: Option<f32>,
+}
+
+/// Parete della stanza verso l'esterno, la lunghezza è in m
+#[derive(
+    Queryable, Selectable, Insertable, Identifiable, Associations, Debug, Clone, PartialEq,
+)]
+#[diesel(belongs_to(Stanza, foreign_key = stanza_id))]
+#[diesel(table_name = parete_esposta)]
+#[diesel(primary_key(stanza_id, orientamento))]
+pub struct PareteEsposta {
+    pub stanza_id: i32,
+    pub orientamento: String,
+    pub lunghezza: f32,
 }
 
 #[derive(Queryable, Selectable, Identifiable, Debug, PartialEq)]
@@ -195,6 +238,10 @@ pub struct Fotovoltaico {
     pub edificio_id: String,
     pub potenza: f32,
     pub proprietario: String,
+    pub orientamento: Option<String>,
+    pub inclinazione: Option<i16>,
+    pub anno_installazione: Option<i32>,
+    pub provincia: Option<String>,
 }
 
 #[cfg_attr(feature = "test-models", derive(Clone))]
@@ -204,6 +251,10 @@ pub struct NewFotovoltaico<'a> {
     pub edificio_id: Cow<'a, str>,
     pub potenza: f32,
     pub proprietario: Cow<'a, str>,
+    pub orientamento: Option<Cow<'a, str>>,
+    pub inclinazione: Option<i16>,
+    pub anno_installazione: Option<i32>,
+    pub provincia: Option<Cow<'a, str>>,
 }
 
 #[cfg_attr(feature = "test-models", derive(Clone))]
@@ -212,6 +263,10 @@ pub struct NewFotovoltaico<'a> {
 pub struct UpdateFotovoltaico<'a> {
     pub potenza: Option<f32>,
     pub proprietario: Option<Cow<'a, str>>,
+    pub orientamento: Option<Option<Cow<'a, str>>>,
+    pub inclinazione: Option<Option<i16>>,
+    pub anno_installazione: Option<Option<i32>>,
+    pub provincia: Option<Option<Cow<'a, str>>>,
 }
 
 #[derive(Queryable, Selectable, Identifiable, Debug, PartialEq)]
@@ -222,6 +277,7 @@ pub struct Utenza {
     pub tipo: TipoUtenza,
     pub cod_contatore: String,
     pub indirizzo_contatore: Option<String>,
+    pub consumo_annuo: Option<f32>,
 }
 
 #[derive(Insertable, Debug, PartialEq)]
@@ -231,6 +287,7 @@ pub struct NewUtenza<'a> {
     pub tipo: TipoUtenza,
     pub cod_contatore: Cow<'a, str>,
     pub indirizzo_contatore: Option<Cow<'a, str>>,
+    pub consumo_annuo: Option<f32>,
 }
 
 #[derive(AsChangeset, Debug, PartialEq)]
@@ -238,7 +295,8 @@ pub struct NewUtenza<'a> {
 pub struct UpdateUtenza<'a> {
     pub tipo: Option<TipoUtenza>,
     pub cod_contatore: Option<Cow<'a, str>>,
-    pub indirizzo_contatore: Option<Cow<'a, str>>,
+    pub indirizzo_contatore: Option<Option<Cow<'a, str>>>,
+    pub consumo_annuo: Option<Option<f32>>,
 }
 
 #[derive(Serialize, Deserialize, diesel_derive_enum::DbEnum, Debug, PartialEq, Clone)]
@@ -275,6 +333,8 @@ pub struct AnnotazioneEdificio {
 pub struct NewAnnotazioneEdificio<'a> {
     pub edificio_id: Cow<'a, str>,
     pub content: Cow<'a, str>,
+    /// Se `None` viene usata la data corrente
+    pub data: Option<NaiveDateTime>,
 }
 
 #[derive(Queryable, Selectable, Identifiable, Debug, PartialEq)]
@@ -292,6 +352,8 @@ pub struct AnnotazioneStanza {
 pub struct NewAnnotazioneStanza<'a> {
     pub stanza_id: i32,
     pub content: Cow<'a, str>,
+    /// Se `None` viene usata la data corrente
+    pub data: Option<NaiveDateTime>,
 }
 
 #[derive(Queryable, Selectable, Identifiable, Debug, PartialEq)]
@@ -310,6 +372,8 @@ pub struct NewAnnotazioneInfisso<'a> {
     pub infisso_id: Cow<'a, str>,
     pub edificio_id: Cow<'a, str>,
     pub content: Cow<'a, str>,
+    /// Se `None` viene usata la data corrente
+    pub data: Option<NaiveDateTime>,
 }
 
 #[derive(Queryable, QueryableByName, Debug)]
@@ -344,6 +408,10 @@ pub struct DatiStanza {
     pub materiale: Option<String>,
     #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Text>)]
     pub vetro: Option<String>,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Double>)]
+    pub superficie: Option<f64>,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Double>)]
+    pub volume: Option<f64>,
 }
 
 #[cfg(test)]
@@ -560,11 +628,15 @@ mod test {
         let stanza = insert_stanza_standard(&mut conn, edificio.chiave.as_str())?;
 
         let update_stanza = UpdateStanza {
+            cod_stanza: None,
+            destinazione_uso: None,
             altezza: Some(120),
             spessore_muro: None,
             riscaldamento: Some("Radiatori".into()),
             raffrescamento: None,
             illuminazione: None,
+            superficie: None,
+            perimetro: None,
         };
 
         let updated: Stanza = diesel::update(stanza::table.find(stanza.id))
@@ -720,6 +792,7 @@ mod test {
         let insert_annotazione = NewAnnotazioneEdificio {
             edificio_id: edificio.chiave.into(),
             content: "Sono una nuova annotazione".into(),
+            data: None,
         };
 
         let inserted: AnnotazioneEdificio = diesel::insert_into(annotazione_edificio::table)
@@ -741,6 +814,7 @@ mod test {
         let insert_annotazione = NewAnnotazioneStanza {
             stanza_id: stanza.id,
             content: "Sono una nuova annotazione".into(),
+            data: None,
         };
 
         let inserted: AnnotazioneStanza = diesel::insert_into(annotazione_stanza::table)
@@ -763,6 +837,7 @@ mod test {
             infisso_id: infisso.id.into(),
             edificio_id: infisso.edificio_id.into(),
             content: "Sono una nuova annotazione".into(),
+            data: None,
         };
 
         let inserted: AnnotazioneInfisso = diesel::insert_into(annotazione_infisso::table)
@@ -783,6 +858,10 @@ mod test {
             edificio_id: edificio.chiave.into(),
             potenza: 85.0,
             proprietario: "Ugo Ugolini".into(),
+            orientamento: Some("S".into()),
+            inclinazione: Some(30),
+            anno_installazione: Some(2015),
+            provincia: Some("RM".into()),
         };
 
         Ok(diesel::insert_into(fotovoltaico::table)
@@ -811,12 +890,18 @@ mod test {
         let update_entity = UpdateFotovoltaico {
             potenza: Some(100.0),
             proprietario: None,
+            orientamento: None,
+            inclinazione: Some(Some(20)),
+            anno_installazione: None,
+            provincia: None,
         };
 
         let updated: Fotovoltaico = diesel::update(fotovoltaico::table.find(inserted.id))
             .set(&update_entity)
             .get_result(&mut conn)?;
         assert_eq!(updated.potenza, 100.0);
+        assert_eq!(updated.inclinazione, Some(20));
+        assert_eq!(updated.orientamento.as_deref(), Some("S"));
 
         Ok(())
     }
@@ -829,6 +914,7 @@ mod test {
             tipo: TipoUtenza::Acqua,
             cod_contatore: "785452215588".into(),
             indirizzo_contatore: Some("Via Roma 1".into()),
+            consumo_annuo: None,
         };
 
         Ok(diesel::insert_into(utenze::table)
@@ -857,7 +943,8 @@ mod test {
         let update_entity = UpdateUtenza {
             tipo: Some(TipoUtenza::Elettricità),
             cod_contatore: Some("456789".into()),
-            indirizzo_contatore: None,
+            indirizzo_contatore: Some(None),
+            consumo_annuo: Some(Some(12500.0)),
         };
 
         let updated: Utenza = diesel::update(utenze::table.find(inserted.id))
@@ -865,6 +952,8 @@ mod test {
             .get_result(&mut conn)?;
         assert_eq!(updated.tipo, TipoUtenza::Elettricità);
         assert_eq!(updated.cod_contatore, "456789");
+        assert_eq!(updated.indirizzo_contatore, None);
+        assert_eq!(updated.consumo_annuo, Some(12500.0));
 
         Ok(())
     }
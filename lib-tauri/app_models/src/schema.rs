@@ -67,6 +67,12 @@ diesel::table! {
         potenza -> Float4,
         #[max_length = 50]
         proprietario -> Varchar,
+        #[max_length = 2]
+        orientamento -> Nullable<Varchar>,
+        inclinazione -> Nullable<Int2>,
+        anno_installazione -> Nullable<Int4>,
+        #[max_length = 2]
+        provincia -> Nullable<Varchar>,
     }
 }
 
@@ -101,6 +107,16 @@ diesel::table! {
         #[max_length = 20]
         materiale -> Varchar,
         eff_energetica -> Int2,
+        uf -> Nullable<Float4>,
+    }
+}
+
+diesel::table! {
+    parete_esposta (stanza_id, orientamento) {
+        stanza_id -> Int4,
+        #[max_length = 2]
+        orientamento -> Varchar,
+        lunghezza -> Float4,
     }
 }
 
@@ -124,6 +140,8 @@ diesel::table! {
         raffrescamento -> Nullable<Varchar>,
         #[max_length = 20]
         illuminazione -> Nullable<Varchar>,
+        superficie -> Nullable<Float4>,
+        perimetro -> Nullable<Float4>,
     }
 }
 
@@ -158,6 +176,7 @@ diesel::table! {
         cod_contatore -> Varchar,
         #[max_length = 50]
         indirizzo_contatore -> Nullable<Varchar>,
+        consumo_annuo -> Nullable<Float4>,
     }
 }
 
@@ -166,6 +185,7 @@ diesel::table! {
         #[max_length = 20]
         vetro -> Varchar,
         eff_energetica -> Int2,
+        ug -> Nullable<Float4>,
     }
 }
 
@@ -176,6 +196,7 @@ diesel::joinable!(infisso -> edificio (edificio_id));
 diesel::joinable!(infisso -> materiale_infisso (materiale));
 diesel::joinable!(infisso -> tipo_infisso (tipo));
 diesel::joinable!(infisso -> vetro_infisso (vetro));
+diesel::joinable!(parete_esposta -> stanza (stanza_id));
 diesel::joinable!(stanza -> edificio (edificio_id));
 diesel::joinable!(stanza -> illuminazione (illuminazione));
 diesel::joinable!(stanza_con_infissi -> stanza (stanza_id));
@@ -191,6 +212,7 @@ diesel::allow_tables_to_appear_in_same_query!(
     illuminazione,
     infisso,
     materiale_infisso,
+    parete_esposta,
     stanza,
     stanza_con_infissi,
     tipo_infisso,
@@ -7,12 +7,12 @@ use app_utils::{
     app_interface::{
         dao_interface::{
             DAO,
-            crud_operations::{GetAll, Insert},
+            crud_operations::{Delete, GetAll, Insert},
         },
         database_interface::PostgresPooled,
     },
 };
-use diesel::{RunQueryDsl, result::Error};
+use diesel::{QueryDsl, RunQueryDsl, result::Error};
 
 use crate::dao::utils::map_error_annotazione;
 
@@ -44,3 +44,13 @@ impl Insert<NewAnnotazioneEdificio<'_>> for AnnotazioneEdificioDAO {
             .map_err(map_error_annotazione)
     }
 }
+
+impl Delete<AnnotazioneEdificio, i32> for AnnotazioneEdificioDAO {
+    type Output = AnnotazioneEdificio;
+
+    fn delete(conn: &mut PostgresPooled, id: i32) -> Result<Self::Output, DomainError> {
+        diesel::delete(annotazione_edificio::table.find(id))
+            .get_result(conn)
+            .map_err(map_error_annotazione)
+    }
+}
@@ -7,12 +7,12 @@ use app_utils::{
     app_interface::{
         dao_interface::{
             DAO,
-            crud_operations::{GetAll, Insert},
+            crud_operations::{Delete, GetAll, Insert},
         },
         database_interface::PostgresPooled,
     },
 };
-use diesel::RunQueryDsl;
+use diesel::{QueryDsl, RunQueryDsl};
 
 use crate::dao::utils::map_error_annotazione;
 
@@ -43,3 +43,13 @@ impl Insert<NewAnnotazioneInfisso<'_>> for AnnotazioneInfissoDAO {
             .map_err(map_error_annotazione)
     }
 }
+
+impl Delete<AnnotazioneInfisso, i32> for AnnotazioneInfissoDAO {
+    type Output = AnnotazioneInfisso;
+
+    fn delete(conn: &mut PostgresPooled, id: i32) -> Result<Self::Output, DomainError> {
+        diesel::delete(annotazione_infisso::table.find(id))
+            .get_result(conn)
+            .map_err(map_error_annotazione)
+    }
+}
@@ -7,12 +7,12 @@ use app_utils::{
     app_interface::{
         dao_interface::{
             DAO,
-            crud_operations::{GetAll, Insert},
+            crud_operations::{Delete, GetAll, Insert},
         },
         database_interface::PostgresPooled,
     },
 };
-use diesel::RunQueryDsl;
+use diesel::{QueryDsl, RunQueryDsl};
 
 use crate::dao::utils::map_error_annotazione;
 
@@ -44,6 +44,16 @@ impl Insert<NewAnnotazioneStanza<'_>> for AnnotazioneStanzaDAO {
     }
 }
 
+impl Delete<AnnotazioneStanza, i32> for AnnotazioneStanzaDAO {
+    type Output = AnnotazioneStanza;
+
+    fn delete(conn: &mut PostgresPooled, id: i32) -> Result<Self::Output, DomainError> {
+        diesel::delete(annotazione_stanza::table.find(id))
+            .get_result(conn)
+            .map_err(map_error_annotazione)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use app_models::models::NewAnnotazioneStanza;
@@ -62,6 +72,7 @@ mod test {
         let item = NewAnnotazioneStanza {
             stanza_id: 1,
             content: "test".into(),
+            data: None,
         };
         match AnnotazioneStanzaDAO::insert(&mut conn, item.clone()) {
             Ok(res) => assert_eq!(res.stanza_id, item.stanza_id),
@@ -76,6 +87,7 @@ mod test {
         let item = NewAnnotazioneStanza {
             stanza_id: 1,
             content: "".into(),
+            data: None,
         };
         match AnnotazioneStanzaDAO::insert(&mut conn, item.clone()) {
             Ok(_) => panic!("Should not be able to insert"),
@@ -83,10 +95,7 @@ mod test {
                 println!("{e:?}");
                 assert_eq!(
                     e,
-                    DomainError::InvalidInput(
-                        EmptyField,
-                        "Field content cannot be empty or contain only whitespace".to_string()
-                    )
+                    DomainError::InvalidInput(EmptyField, "content".to_string())
                 )
             }
         }
@@ -1,15 +1,21 @@
-use app_models::{models::Climatizzazione, schema::climatizzazione};
+use app_models::{
+    models::Climatizzazione,
+    schema::{climatizzazione, stanza},
+};
 use app_utils::{
     app_error::DomainError,
     app_interface::{
         dao_interface::{
             DAO,
-            crud_operations::{GetAll, Insert},
+            crud_operations::{Delete, GetAll, Insert},
         },
         database_interface::PostgresPooled,
     },
 };
-use diesel::{RunQueryDsl, result::Error};
+use diesel::{
+    BoolExpressionMethods, ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl,
+    result::Error,
+};
 
 pub struct ClimatizzazioneDAO;
 
@@ -45,6 +51,35 @@ impl Insert<Climatizzazione> for ClimatizzazioneDAO {
     }
 }
 
+impl Delete<Climatizzazione, String> for ClimatizzazioneDAO {
+    type Output = Climatizzazione;
+
+    /// L'eliminazione viene rifiutata se la climatizzazione è ancora assegnata
+    /// ad almeno una stanza
+    fn delete(conn: &mut PostgresPooled, id: String) -> Result<Self::Output, DomainError> {
+        let referenced = stanza::table
+            .select(stanza::id)
+            .filter(
+                stanza::riscaldamento
+                    .eq(&id)
+                    .or(stanza::raffrescamento.eq(&id)),
+            )
+            .first::<i32>(conn)
+            .optional()
+            .map_err(DomainError::from)?;
+        if referenced.is_some() {
+            return Err(DomainError::ClimatizzazioneStillReferenced);
+        }
+
+        diesel::delete(climatizzazione::table.find(id))
+            .get_result(conn)
+            .map_err(|e| match e {
+                Error::NotFound => DomainError::ClimatizzazioneNotFound,
+                _ => DomainError::Unexpected(e),
+            })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use app_utils::{
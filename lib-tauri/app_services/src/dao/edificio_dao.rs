@@ -7,10 +7,10 @@ use app_utils::{
     app_error::DomainError,
     app_interface::dao_interface::{
         DAO,
-        crud_operations::{Get, GetAll, Insert, Update},
+        crud_operations::{Delete, Get, GetAll, Insert, Update},
     },
 };
-use diesel::{QueryDsl, RunQueryDsl};
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
 
 use crate::dao::utils::{EntityType, map_error_for_entity};
 
@@ -39,6 +39,19 @@ impl Get<Edificio, String> for EdificioDAO {
     }
 }
 
+/// Edifici del fascicolo
+impl Get<Edificio, i32> for EdificioDAO {
+    type Output = Vec<Edificio>;
+
+    fn get(conn: &mut PostgresPooled, fascicolo: i32) -> Result<Self::Output, DomainError> {
+        edificio::table
+            .filter(edificio::fascicolo.eq(fascicolo))
+            .order(edificio::chiave)
+            .load::<Edificio>(conn)
+            .map_err(|e| map_error_for_entity(e, EntityType::Edificio))
+    }
+}
+
 impl Insert<NewEdificio<'_>> for EdificioDAO {
     type Output = Edificio;
 
@@ -65,6 +78,18 @@ impl Update<UpdateEdificio<'_>, String> for EdificioDAO {
     }
 }
 
+impl Delete<Edificio, String> for EdificioDAO {
+    type Output = Edificio;
+
+    /// Le stanze, gli infissi, le utenze, il fotovoltaico e le annotazioni
+    /// dell'edificio vengono eliminati a cascata dal database
+    fn delete(conn: &mut PostgresPooled, id: String) -> Result<Self::Output, DomainError> {
+        diesel::delete(edificio::table.find(id))
+            .get_result(conn)
+            .map_err(|e| map_error_for_entity(e, EntityType::Edificio))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use app_utils::test::create_postgres_pool;
@@ -7,14 +7,18 @@ use app_utils::{
     app_interface::{
         dao_interface::{
             DAO,
-            crud_operations::{GetAll, Insert, Update},
+            crud_operations::{Delete, GetAll, Insert, Update},
         },
         database_interface::PostgresPooled,
     },
 };
-use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl, result::Error};
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl, pg::Pg, result::Error};
 
-use crate::service::Get;
+use crate::{
+    dao::utils::{paginate, then_order_by},
+    query::{FotovoltaicoFilter, FotovoltaicoQuery, FotovoltaicoSort},
+    service::Get,
+};
 
 pub struct FotovoltaicoDAO;
 
@@ -42,6 +46,43 @@ impl Get<Fotovoltaico, String> for FotovoltaicoDAO {
     }
 }
 
+impl Get<Fotovoltaico, &FotovoltaicoQuery> for FotovoltaicoDAO {
+    type Output = Vec<Fotovoltaico>;
+
+    fn get(
+        conn: &mut PostgresPooled,
+        search: &FotovoltaicoQuery,
+    ) -> Result<Self::Output, DomainError> {
+        let mut query = fotovoltaico::table.into_boxed::<Pg>();
+        for filter in &search.filters {
+            query = match filter {
+                FotovoltaicoFilter::Edificio(value) => {
+                    query.filter(fotovoltaico::edificio_id.eq(value))
+                }
+                FotovoltaicoFilter::Proprietario(value) => {
+                    query.filter(fotovoltaico::proprietario.eq(value))
+                }
+            };
+        }
+        for sort in &search.sort {
+            query = match sort.field {
+                FotovoltaicoSort::Potenza => {
+                    then_order_by!(query, fotovoltaico::potenza, sort.direction)
+                }
+                FotovoltaicoSort::Proprietario => {
+                    then_order_by!(query, fotovoltaico::proprietario, sort.direction)
+                }
+            };
+        }
+        // ordine stabile tra le pagine
+        query = query.then_order_by(fotovoltaico::id.asc());
+
+        paginate!(query, search)
+            .load(conn)
+            .map_err(DomainError::from)
+    }
+}
+
 impl Insert<NewFotovoltaico<'_>> for FotovoltaicoDAO {
     type Output = Fotovoltaico;
 
@@ -84,6 +125,19 @@ impl Update<UpdateFotovoltaico<'_>, i32> for FotovoltaicoDAO {
     }
 }
 
+impl Delete<Fotovoltaico, i32> for FotovoltaicoDAO {
+    type Output = Fotovoltaico;
+
+    fn delete(conn: &mut PostgresPooled, id: i32) -> Result<Self::Output, DomainError> {
+        diesel::delete(fotovoltaico::table.find(id))
+            .get_result(conn)
+            .map_err(|e| match e {
+                Error::NotFound => DomainError::FotovoltaicoNotFound,
+                _ => DomainError::Unexpected(e),
+            })
+    }
+}
+
 #[cfg(test)]
 mod test {
     use app_models::models::{NewFotovoltaico, UpdateFotovoltaico};
@@ -104,6 +158,10 @@ mod test {
             edificio_id: "9338-14".into(),
             potenza: 55f32,
             proprietario: "Ugo Ugolini".into(),
+            orientamento: Some("SE".into()),
+            inclinazione: Some(30),
+            anno_installazione: Some(2018),
+            provincia: Some("PR".into()),
         };
 
         let result = match FotovoltaicoDAO::insert(&mut conn, insert_data.clone()) {
@@ -120,6 +178,10 @@ mod test {
         let update_data = UpdateFotovoltaico {
             potenza: Some(85f32),
             proprietario: Some("Ugo Ugolini".into()),
+            orientamento: None,
+            inclinazione: None,
+            anno_installazione: None,
+            provincia: None,
         };
         match FotovoltaicoDAO::update(&mut conn, result.id, update_data.clone()) {
             Ok(res) => {
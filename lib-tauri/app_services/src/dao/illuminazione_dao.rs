@@ -1,15 +1,18 @@
-use app_models::{models::Illuminazione, schema::illuminazione};
+use app_models::{
+    models::Illuminazione,
+    schema::{illuminazione, stanza},
+};
 use app_utils::{
     app_error::DomainError,
     app_interface::{
         dao_interface::{
             DAO,
-            crud_operations::{GetAll, Insert},
+            crud_operations::{Delete, GetAll, Insert},
         },
         database_interface::PostgresPooled,
     },
 };
-use diesel::{RunQueryDsl, result::Error};
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl, result::Error};
 
 pub struct IlluminazioneDAO;
 
@@ -42,6 +45,31 @@ impl Insert<Illuminazione> for IlluminazioneDAO {
     }
 }
 
+impl Delete<Illuminazione, String> for IlluminazioneDAO {
+    type Output = Illuminazione;
+
+    /// L'eliminazione viene rifiutata se l'illuminazione è ancora assegnata ad
+    /// almeno una stanza
+    fn delete(conn: &mut PostgresPooled, id: String) -> Result<Self::Output, DomainError> {
+        let referenced = stanza::table
+            .select(stanza::id)
+            .filter(stanza::illuminazione.eq(&id))
+            .first::<i32>(conn)
+            .optional()
+            .map_err(DomainError::from)?;
+        if referenced.is_some() {
+            return Err(DomainError::IlluminazioneStillReferenced);
+        }
+
+        diesel::delete(illuminazione::table.find(id))
+            .get_result(conn)
+            .map_err(|e| match e {
+                Error::NotFound => DomainError::IlluminazioneNotFound,
+                _ => DomainError::Unexpected(e),
+            })
+    }
+}
+
 /*
 impl Insert<NewIlluminazione> for IlluminazioneDAO {
     fn insert<C: DatabaseConnection>(
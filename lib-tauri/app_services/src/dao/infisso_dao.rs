@@ -7,14 +7,17 @@ use app_utils::{
     app_interface::{
         dao_interface::{
             DAO,
-            crud_operations::{Get, GetAll, Insert, Update},
+            crud_operations::{Delete, Get, GetAll, Insert, Update},
         },
         database_interface::PostgresPooled,
     },
 };
-use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl, pg::Pg};
 
-use crate::dao::utils::{EntityType, map_error_for_entity};
+use crate::{
+    dao::utils::{EntityType, map_error_for_entity, paginate, then_order_by},
+    query::{InfissoFilter, InfissoQuery, InfissoSort},
+};
 
 pub struct InfissoDAO;
 
@@ -41,6 +44,43 @@ impl Get<Infisso, String> for InfissoDAO {
     }
 }
 
+impl Get<Infisso, &InfissoQuery> for InfissoDAO {
+    type Output = Vec<Infisso>;
+
+    fn get(conn: &mut PostgresPooled, search: &InfissoQuery) -> Result<Self::Output, DomainError> {
+        let mut query = infisso::table.into_boxed::<Pg>();
+        for filter in &search.filters {
+            query = match filter {
+                InfissoFilter::Edificio(value) => query.filter(infisso::edificio_id.eq(value)),
+                InfissoFilter::Tipo(value) => query.filter(infisso::tipo.eq(value)),
+                InfissoFilter::Materiale(value) => query.filter(infisso::materiale.eq(value)),
+                InfissoFilter::Vetro(value) => query.filter(infisso::vetro.eq(value)),
+            };
+        }
+        for sort in &search.sort {
+            query = match sort.field {
+                InfissoSort::Id => then_order_by!(query, infisso::id, sort.direction),
+                InfissoSort::Tipo => then_order_by!(query, infisso::tipo, sort.direction),
+                InfissoSort::Materiale => {
+                    then_order_by!(query, infisso::materiale, sort.direction)
+                }
+                InfissoSort::Vetro => then_order_by!(query, infisso::vetro, sort.direction),
+                InfissoSort::Altezza => then_order_by!(query, infisso::altezza, sort.direction),
+                InfissoSort::Larghezza => {
+                    then_order_by!(query, infisso::larghezza, sort.direction)
+                }
+                InfissoSort::Mq => then_order_by!(query, infisso::mq, sort.direction),
+            };
+        }
+        // ordine stabile tra le pagine
+        query = query.then_order_by((infisso::edificio_id.asc(), infisso::id.asc()));
+
+        paginate!(query, search)
+            .load(conn)
+            .map_err(|e| map_error_for_entity(e, EntityType::Infisso))
+    }
+}
+
 impl Insert<NewInfisso<'_>> for InfissoDAO {
     type Output = Infisso;
 
@@ -70,3 +110,17 @@ impl Update<UpdateInfisso<'_>, (String, String)> for InfissoDAO {
             .map_err(|e| map_error_for_entity(e, EntityType::Infisso))
     }
 }
+
+impl Delete<Infisso, (String, String)> for InfissoDAO {
+    type Output = Infisso;
+
+    /// id -> (infisso, edificio)
+    fn delete(
+        conn: &mut PostgresPooled,
+        id: (String, String),
+    ) -> Result<Self::Output, DomainError> {
+        diesel::delete(infisso::table.find(id))
+            .get_result(conn)
+            .map_err(|e| map_error_for_entity(e, EntityType::Infisso))
+    }
+}
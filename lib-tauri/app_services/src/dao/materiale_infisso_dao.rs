@@ -1,12 +1,18 @@
-use app_models::{models::MaterialeInfisso, schema::materiale_infisso};
+use app_models::{
+    models::{MaterialeInfisso, UpdateMaterialeInfisso},
+    schema::materiale_infisso,
+};
 use app_utils::{
     app_error::DomainError,
     app_interface::{
-        dao_interface::{DAO, crud_operations::GetAll},
+        dao_interface::{
+            DAO,
+            crud_operations::{GetAll, Update},
+        },
         database_interface::PostgresPooled,
     },
 };
-use diesel::RunQueryDsl;
+use diesel::{QueryDsl, RunQueryDsl, result::Error};
 
 pub struct MaterialeInfissoDAO;
 
@@ -22,6 +28,24 @@ impl GetAll<MaterialeInfisso> for MaterialeInfissoDAO {
     }
 }
 
+impl Update<UpdateMaterialeInfisso, &str> for MaterialeInfissoDAO {
+    type Output = MaterialeInfisso;
+
+    fn update(
+        conn: &mut PostgresPooled,
+        materiale: &str,
+        item: UpdateMaterialeInfisso,
+    ) -> Result<Self::Output, DomainError> {
+        diesel::update(materiale_infisso::table.find(materiale))
+            .set(&item)
+            .get_result(conn)
+            .map_err(|e| match e {
+                Error::NotFound => DomainError::MaterialeInfissoNotFound,
+                _ => DomainError::from(e),
+            })
+    }
+}
+
 /*
 impl Insert<MaterialeInfisso> for MaterialeInfissoDAO {
     fn insert(
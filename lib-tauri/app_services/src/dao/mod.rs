@@ -8,6 +8,7 @@ mod fotovoltaico_dao;
 mod illuminazione_dao;
 mod infisso_dao;
 mod materiale_infisso_dao;
+mod parete_esposta_dao;
 mod stanza_dao;
 mod stanze_con_infissi_dao;
 mod tipo_infisso_dao;
@@ -25,6 +26,7 @@ pub use fotovoltaico_dao::*;
 pub use illuminazione_dao::*;
 pub use infisso_dao::*;
 pub use materiale_infisso_dao::*;
+pub use parete_esposta_dao::*;
 pub use stanza_dao::*;
 pub use stanze_con_infissi_dao::*;
 pub use tipo_infisso_dao::*;
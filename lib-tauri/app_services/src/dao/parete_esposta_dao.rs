@@ -0,0 +1,53 @@
+use app_models::{models::PareteEsposta, schema::parete_esposta};
+use app_utils::{
+    app_error::DomainError,
+    app_interface::{
+        dao_interface::{
+            DAO,
+            crud_operations::{Delete, Get, Insert},
+        },
+        database_interface::PostgresPooled,
+    },
+};
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+
+pub struct PareteEspostaDAO;
+
+impl DAO for PareteEspostaDAO {}
+
+impl<'a> Get<PareteEsposta, &'a [i32]> for PareteEspostaDAO {
+    type Output = Vec<PareteEsposta>;
+
+    /// Recupera le pareti esposte delle stanze indicate
+    fn get(conn: &mut PostgresPooled, id: &'a [i32]) -> Result<Self::Output, DomainError> {
+        parete_esposta::table
+            .filter(parete_esposta::stanza_id.eq_any(id))
+            .get_results(conn)
+            .map_err(DomainError::from)
+    }
+}
+
+impl Insert<Vec<PareteEsposta>> for PareteEspostaDAO {
+    type Output = Vec<PareteEsposta>;
+
+    fn insert(
+        conn: &mut PostgresPooled,
+        item: Vec<PareteEsposta>,
+    ) -> Result<Self::Output, DomainError> {
+        diesel::insert_into(parete_esposta::table)
+            .values(&item)
+            .get_results(conn)
+            .map_err(DomainError::from)
+    }
+}
+
+impl Delete<PareteEsposta, i32> for PareteEspostaDAO {
+    type Output = Vec<PareteEsposta>;
+
+    /// Rimuove tutte le pareti esposte di una stanza
+    fn delete(conn: &mut PostgresPooled, id_stanza: i32) -> Result<Self::Output, DomainError> {
+        diesel::delete(parete_esposta::table.filter(parete_esposta::stanza_id.eq(id_stanza)))
+            .get_results(conn)
+            .map_err(DomainError::from)
+    }
+}
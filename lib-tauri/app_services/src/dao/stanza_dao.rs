@@ -7,14 +7,17 @@ use app_utils::{
     app_interface::{
         dao_interface::{
             DAO,
-            crud_operations::{Get, Insert, Update},
+            crud_operations::{Delete, Get, Insert, Update},
         },
         database_interface::PostgresPooled,
     },
 };
-use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl, result::Error};
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl, pg::Pg, result::Error};
 
-use crate::dao::utils::{EntityType::Stanza as StanzaType, map_error_for_entity};
+use crate::{
+    dao::utils::{EntityType::Stanza as StanzaType, map_error_for_entity, paginate, then_order_by},
+    query::{CampoStanza, StanzaFilter, StanzaQuery, StanzaSort},
+};
 
 pub struct StanzaDAO;
 
@@ -34,6 +37,69 @@ impl Get<Stanza, &str> for StanzaDAO {
     }
 }
 
+impl Get<Stanza, &StanzaQuery> for StanzaDAO {
+    type Output = Vec<Stanza>;
+
+    fn get(conn: &mut PostgresPooled, search: &StanzaQuery) -> Result<Self::Output, DomainError> {
+        let mut query = stanza::table.into_boxed::<Pg>();
+        for filter in &search.filters {
+            query = match filter {
+                StanzaFilter::Edificio(value) => query.filter(stanza::edificio_id.eq(value)),
+                StanzaFilter::Piano(value) => query.filter(stanza::piano.eq(value)),
+                StanzaFilter::DestinazioneUso(value) => {
+                    query.filter(stanza::destinazione_uso.eq(value))
+                }
+                StanzaFilter::Riscaldamento(value) => query.filter(stanza::riscaldamento.eq(value)),
+                StanzaFilter::Raffrescamento(value) => {
+                    query.filter(stanza::raffrescamento.eq(value))
+                }
+                StanzaFilter::Illuminazione(value) => query.filter(stanza::illuminazione.eq(value)),
+                StanzaFilter::Missing(CampoStanza::Altezza) => {
+                    query.filter(stanza::altezza.is_null())
+                }
+                StanzaFilter::Missing(CampoStanza::SpessoreMuro) => {
+                    query.filter(stanza::spessore_muro.is_null())
+                }
+                StanzaFilter::Missing(CampoStanza::Superficie) => {
+                    query.filter(stanza::superficie.is_null())
+                }
+                StanzaFilter::Missing(CampoStanza::Riscaldamento) => {
+                    query.filter(stanza::riscaldamento.is_null())
+                }
+                StanzaFilter::Missing(CampoStanza::Raffrescamento) => {
+                    query.filter(stanza::raffrescamento.is_null())
+                }
+                StanzaFilter::Missing(CampoStanza::Illuminazione) => {
+                    query.filter(stanza::illuminazione.is_null())
+                }
+            };
+        }
+        for sort in &search.sort {
+            query = match sort.field {
+                StanzaSort::Piano => then_order_by!(query, stanza::piano, sort.direction),
+                StanzaSort::IdSpazio => then_order_by!(query, stanza::id_spazio, sort.direction),
+                StanzaSort::CodStanza => then_order_by!(query, stanza::cod_stanza, sort.direction),
+                StanzaSort::DestinazioneUso => {
+                    then_order_by!(query, stanza::destinazione_uso, sort.direction)
+                }
+                StanzaSort::Altezza => then_order_by!(query, stanza::altezza, sort.direction),
+                StanzaSort::SpessoreMuro => {
+                    then_order_by!(query, stanza::spessore_muro, sort.direction)
+                }
+                StanzaSort::Superficie => {
+                    then_order_by!(query, stanza::superficie, sort.direction)
+                }
+            };
+        }
+        // ordine stabile tra le pagine
+        query = query.then_order_by(stanza::id.asc());
+
+        paginate!(query, search)
+            .load(conn)
+            .map_err(|e| map_error_for_entity(e, StanzaType))
+    }
+}
+
 impl Insert<NewStanza<'_>> for StanzaDAO {
     type Output = Stanza;
 
@@ -77,5 +143,55 @@ impl Update<UpdateStanza<'_>, i32> for StanzaDAO {
     }
 }
 
+impl Delete<Stanza, i32> for StanzaDAO {
+    type Output = Stanza;
+
+    fn delete(conn: &mut PostgresPooled, id: i32) -> Result<Self::Output, DomainError> {
+        diesel::delete(stanza::table.find(id))
+            .get_result(conn)
+            .map_err(|e| map_error_for_entity(e, StanzaType))
+    }
+}
+
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use app_models::models::NewEdificio;
+    use app_utils::test::{ResultTest, TestDaoEnvironment};
+
+    use super::*;
+    use crate::{
+        dao::EdificioDAO,
+        dto::{EdificioDTO, StanzaDTO},
+        query::SortDirection,
+    };
+
+    const ID_EDIFICIO: &str = "4693-182";
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn test_get_by_query() -> ResultTest {
+        let env = TestDaoEnvironment::new().await?;
+        env.insert_data::<EdificioDAO, EdificioDTO, NewEdificio>("edificiFake")?;
+        env.insert_data::<StanzaDAO, StanzaDTO, NewStanza>("stanzeFake")?;
+        let mut conn = env.get_pooled_connection()?;
+
+        let query = StanzaQuery::new()
+            .filter(StanzaFilter::Edificio(ID_EDIFICIO.to_string()))
+            .sort_by(StanzaSort::CodStanza, SortDirection::Desc);
+        let all = StanzaDAO::get(&mut conn, &query)?;
+        assert!(!all.is_empty());
+        assert!(all.iter().all(|stanza| stanza.edificio_id == ID_EDIFICIO));
+        assert!(all.is_sorted_by(|a, b| a.cod_stanza >= b.cod_stanza));
+
+        let first = StanzaDAO::get(&mut conn, &query.clone().paginate(2, 0))?;
+        let second = StanzaDAO::get(&mut conn, &query.clone().paginate(2, 2))?;
+        assert_eq!(first.as_slice(), &all[..first.len()]);
+        assert!(second.iter().all(|stanza| !first.contains(stanza)));
+
+        let missing = StanzaDAO::get(
+            &mut conn,
+            &query.filter(StanzaFilter::Missing(CampoStanza::Altezza)),
+        )?;
+        assert!(missing.iter().all(|stanza| stanza.altezza.is_none()));
+        Ok(())
+    }
+}
@@ -7,7 +7,7 @@ use app_utils::{
     app_interface::{
         dao_interface::{
             DAO,
-            crud_operations::{Get, Insert, Update},
+            crud_operations::{Delete, Get, Insert, Update},
         },
         database_interface::PostgresPooled,
     },
@@ -33,6 +33,18 @@ impl<'a> Get<StanzaConInfissi, &'a str> for StanzaConInfissiDao {
     }
 }
 
+impl<'a> Get<StanzaConInfissi, &'a [i32]> for StanzaConInfissiDao {
+    type Output = Vec<StanzaConInfissi>;
+
+    /// Recupera gli infissi delle stanze indicate
+    fn get(conn: &mut PostgresPooled, id: &'a [i32]) -> Result<Self::Output, DomainError> {
+        stanza_con_infissi::table
+            .filter(stanza_con_infissi::stanza_id.eq_any(id))
+            .get_results(conn)
+            .map_err(DomainError::from)
+    }
+}
+
 impl Get<StanzaConInfissi, (String, i32)> for StanzaConInfissiDao {
     type Output = Vec<StanzaConInfissi>;
 
@@ -103,6 +115,38 @@ impl Update<UpdateStanzaConInfissi, (String, i32, String)> for StanzaConInfissiD
     }
 }
 
+impl Delete<StanzaConInfissi, i32> for StanzaConInfissiDao {
+    type Output = Vec<StanzaConInfissi>;
+
+    /// Rimuove tutti gli infissi collegati a una stanza
+    fn delete(conn: &mut PostgresPooled, id_stanza: i32) -> Result<Self::Output, DomainError> {
+        diesel::delete(
+            stanza_con_infissi::table.filter(stanza_con_infissi::stanza_id.eq(id_stanza)),
+        )
+        .get_results(conn)
+        .map_err(DomainError::from)
+    }
+}
+
+impl Delete<StanzaConInfissi, (String, String)> for StanzaConInfissiDao {
+    type Output = Vec<StanzaConInfissi>;
+
+    /// id -> (edificio, infisso)
+    /// Rimuove l'infisso da tutte le stanze in cui è presente
+    fn delete(
+        conn: &mut PostgresPooled,
+        id: (String, String),
+    ) -> Result<Self::Output, DomainError> {
+        diesel::delete(
+            stanza_con_infissi::table
+                .filter(stanza_con_infissi::edificio_id.eq(id.0))
+                .filter(stanza_con_infissi::infisso_id.eq(id.1)),
+        )
+        .get_results(conn)
+        .map_err(DomainError::from)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use app_models::models::{NewEdificio, NewInfisso, NewStanza};
@@ -216,4 +260,23 @@ mod test {
 
         Ok(())
     }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn test_delete_by_infisso() -> ResultTest {
+        let env = setup_env().await?;
+        let mut conn = env.get_pooled_connection()?;
+
+        match StanzaConInfissiDao::delete(&mut conn, (ID_EDIFICIO.to_string(), "C".to_string())) {
+            Ok(deleted) => {
+                assert!(!deleted.is_empty());
+                assert!(deleted.iter().all(|x| x.infisso_id.trim() == "C"));
+            }
+            Err(e) => panic!("{e:?}"),
+        }
+
+        let stanza_con_infissi =
+            StanzaConInfissiDao::get(&mut conn, (ID_EDIFICIO.to_string(), ID_STANZA))?;
+        assert_eq!(stanza_con_infissi.len(), 2);
+        Ok(())
+    }
 }
@@ -7,14 +7,18 @@ use app_utils::{
     app_interface::{
         dao_interface::{
             DAO,
-            crud_operations::{GetAll, Insert, Update},
+            crud_operations::{Delete, GetAll, Insert, Update},
         },
         database_interface::PostgresPooled,
     },
 };
-use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl, result::Error};
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl, pg::Pg, result::Error};
 
-use crate::service::Get;
+use crate::{
+    dao::utils::{paginate, then_order_by},
+    query::{UtenzaFilter, UtenzaQuery, UtenzaSort},
+    service::Get,
+};
 
 pub struct UtenzeDAO;
 
@@ -43,6 +47,34 @@ impl Get<Utenza, String> for UtenzeDAO {
     }
 }
 
+impl Get<Utenza, &UtenzaQuery> for UtenzeDAO {
+    type Output = Vec<Utenza>;
+
+    fn get(conn: &mut PostgresPooled, search: &UtenzaQuery) -> Result<Self::Output, DomainError> {
+        let mut query = utenze::table.into_boxed::<Pg>();
+        for filter in &search.filters {
+            query = match filter {
+                UtenzaFilter::Edificio(value) => query.filter(utenze::edificio_id.eq(value)),
+                UtenzaFilter::Tipo(value) => query.filter(utenze::tipo.eq(value)),
+            };
+        }
+        for sort in &search.sort {
+            query = match sort.field {
+                UtenzaSort::Tipo => then_order_by!(query, utenze::tipo, sort.direction),
+                UtenzaSort::CodContatore => {
+                    then_order_by!(query, utenze::cod_contatore, sort.direction)
+                }
+            };
+        }
+        // ordine stabile tra le pagine
+        query = query.then_order_by(utenze::id.asc());
+
+        paginate!(query, search)
+            .load(conn)
+            .map_err(DomainError::from)
+    }
+}
+
 impl Insert<NewUtenza<'_>> for UtenzeDAO {
     type Output = Utenza;
 
@@ -72,11 +104,24 @@ impl Update<UpdateUtenza<'_>, i32> for UtenzeDAO {
         id: i32,
         item: UpdateUtenza,
     ) -> Result<Self::Output, DomainError> {
-        diesel::update(utenze::table)
+        diesel::update(utenze::table.find(id))
             .set(&item)
             .get_result(conn)
             .map_err(|e| match e {
-                Error::NotFound => DomainError::StanzaNotFound,
+                Error::NotFound => DomainError::UtenzaNotFound,
+                _ => DomainError::Unexpected(e),
+            })
+    }
+}
+
+impl Delete<Utenza, i32> for UtenzeDAO {
+    type Output = Utenza;
+
+    fn delete(conn: &mut PostgresPooled, id: i32) -> Result<Self::Output, DomainError> {
+        diesel::delete(utenze::table.find(id))
+            .get_result(conn)
+            .map_err(|e| match e {
+                Error::NotFound => DomainError::UtenzaNotFound,
                 _ => DomainError::Unexpected(e),
             })
     }
@@ -36,3 +36,31 @@ pub(crate) fn map_error_for_entity(e: Error, entity: EntityType) -> DomainError
         _ => DomainError::Unexpected(e),
     }
 }
+
+/// Aggiunge a una query boxed l'ordinamento sulla colonna nella direzione
+/// indicata
+macro_rules! then_order_by {
+    ($query:expr, $column:expr, $direction:expr) => {
+        match $direction {
+            $crate::query::SortDirection::Asc => $query.then_order_by($column.asc()),
+            $crate::query::SortDirection::Desc => $query.then_order_by($column.desc()),
+        }
+    };
+}
+
+/// Applica limit e offset della ricerca a una query boxed
+macro_rules! paginate {
+    ($query:expr, $search:expr) => {{
+        let mut query = $query;
+        if let Some(limit) = $search.limit {
+            query = query.limit(i64::from(limit));
+        }
+        if let Some(offset) = $search.offset {
+            query = query.offset(i64::from(offset));
+        }
+        query
+    }};
+}
+
+pub(crate) use paginate;
+pub(crate) use then_order_by;
@@ -1,12 +1,18 @@
-use app_models::{models::VetroInfisso, schema::vetro_infisso};
+use app_models::{
+    models::{UpdateVetroInfisso, VetroInfisso},
+    schema::vetro_infisso,
+};
 use app_utils::{
     app_error::DomainError,
     app_interface::{
-        dao_interface::{DAO, crud_operations::GetAll},
+        dao_interface::{
+            DAO,
+            crud_operations::{GetAll, Update},
+        },
         database_interface::PostgresPooled,
     },
 };
-use diesel::RunQueryDsl;
+use diesel::{QueryDsl, RunQueryDsl, result::Error};
 
 pub struct VetroInfissoDAO;
 
@@ -19,6 +25,24 @@ impl GetAll<VetroInfisso> for VetroInfissoDAO {
         vetro_infisso::table.load(conn).map_err(DomainError::from)
     }
 }
+
+impl Update<UpdateVetroInfisso, &str> for VetroInfissoDAO {
+    type Output = VetroInfisso;
+
+    fn update(
+        conn: &mut PostgresPooled,
+        vetro: &str,
+        item: UpdateVetroInfisso,
+    ) -> Result<Self::Output, DomainError> {
+        diesel::update(vetro_infisso::table.find(vetro))
+            .set(&item)
+            .get_result(conn)
+            .map_err(|e| match e {
+                Error::NotFound => DomainError::VetroInfissoNotFound,
+                _ => DomainError::from(e),
+            })
+    }
+}
 /*
 impl Insert<VetroInfisso> for VetroInfissoDAO {
     fn insert<C: DatabaseConnection>(
@@ -90,6 +90,7 @@ impl From<AnnotazioneEdificioDTO> for NewAnnotazioneEdificio<'_> {
         Self {
             edificio_id: dto.edificio_id.into(),
             content: dto.content.into(),
+            data: None,
         }
     }
 }
@@ -138,6 +139,7 @@ impl From<AnnotazioneStanzaDTO> for NewAnnotazioneStanza<'_> {
         Self {
             stanza_id: value.stanza_id as i32,
             content: value.content.into(),
+            data: None,
         }
     }
 }
@@ -189,6 +191,7 @@ impl From<AnnotazioneInfissoDTO> for NewAnnotazioneInfisso<'_> {
             infisso_id: value.infisso_id.into(),
             edificio_id: value.edificio_id.into(),
             content: value.content.into(),
+            data: None,
         }
     }
 }
@@ -38,7 +38,7 @@ impl From<EdificioDTO> for UpdateEdificio<'_> {
             anno_riqualificazione: value.anno_riqualificazione,
             note_riqualificazione: value.note_riqualificazione.map(|x| x.into()),
             isolamento_tetto: Some(value.isolamento_tetto),
-            cappotto: Some(value.isolamento_tetto),
+            cappotto: Some(value.cappotto),
         }
     }
 }
@@ -1,13 +1,25 @@
-use app_models::models::{Fotovoltaico, NewFotovoltaico};
+use app_models::models::{Fotovoltaico, NewFotovoltaico, UpdateFotovoltaico};
 use app_utils::app_interface::dto_interface::DTO;
 use serde::{Deserialize, Serialize};
 
+use crate::dto::Orientamento;
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct FotovoltaicoDTO {
     pub id: Option<u64>,
     pub id_edificio: String,
     pub potenza: f32,
     pub proprietario: String,
+    #[serde(default)]
+    pub orientamento: Option<Orientamento>,
+    /// Inclinazione dei pannelli rispetto all'orizzontale, in gradi
+    #[serde(default)]
+    pub inclinazione: Option<u8>,
+    #[serde(default)]
+    pub anno_installazione: Option<i32>,
+    /// Sigla della provincia dell'impianto
+    #[serde(default)]
+    pub provincia: Option<String>,
 }
 
 impl DTO for FotovoltaicoDTO {}
@@ -19,6 +31,14 @@ impl From<&Fotovoltaico> for FotovoltaicoDTO {
             id_edificio: value.edificio_id.clone(),
             potenza: value.potenza,
             proprietario: value.proprietario.clone(),
+            // il vincolo sulla tabella ammette solo orientamenti validi
+            orientamento: value
+                .orientamento
+                .as_deref()
+                .and_then(|o| Orientamento::try_from(o).ok()),
+            inclinazione: value.inclinazione.map(|i| i as u8),
+            anno_installazione: value.anno_installazione,
+            provincia: value.provincia.clone(),
         }
     }
 }
@@ -29,6 +49,52 @@ impl From<FotovoltaicoDTO> for NewFotovoltaico<'_> {
             edificio_id: value.id_edificio.into(),
             potenza: value.potenza,
             proprietario: value.proprietario.into(),
+            orientamento: value.orientamento.map(|o| o.to_string().into()),
+            inclinazione: value.inclinazione.map(|i| i as i16),
+            anno_installazione: value.anno_installazione,
+            provincia: value.provincia.map(|p| p.trim().to_uppercase().into()),
+        }
+    }
+}
+
+impl From<FotovoltaicoDTO> for UpdateFotovoltaico<'_> {
+    fn from(value: FotovoltaicoDTO) -> Self {
+        Self {
+            potenza: Some(value.potenza),
+            proprietario: Some(value.proprietario.into()),
+            orientamento: Some(value.orientamento.map(|o| o.to_string().into())),
+            inclinazione: Some(value.inclinazione.map(|i| i as i16)),
+            anno_installazione: Some(value.anno_installazione),
+            provincia: Some(value.provincia.map(|p| p.trim().to_uppercase().into())),
         }
     }
 }
+
+/// Produzione stimata di un impianto
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct StimaImpiantoDTO {
+    pub id: Option<u64>,
+    pub potenza: f32,
+    /// Produzione attesa nel primo anno di esercizio, in kWh
+    pub produzione_iniziale: Option<f64>,
+    /// Produzione attesa nell'anno corrente, al netto del degrado, in kWh
+    pub produzione_annua: Option<f64>,
+    /// Orientamento o inclinazione non rilevati e ipotizzati ottimali
+    pub esposizione_ipotizzata: bool,
+}
+
+/// Produzione stimata degli impianti dell'edificio confrontata con il
+/// consumo elettrico delle utenze
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct StimaProduzioneDTO {
+    pub edificio: String,
+    /// Produzione annua attesa degli impianti stimati, in kWh
+    pub produzione_annua: f64,
+    /// Impianti senza provincia o con provincia sconosciuta
+    pub impianti_senza_stima: u32,
+    /// Consumo annuo delle utenze elettriche, in kWh
+    pub consumo_elettrico: Option<f64>,
+    /// Frazione del consumo elettrico coperta dalla produzione
+    pub copertura: Option<f64>,
+    pub impianti: Vec<StimaImpiantoDTO>,
+}
@@ -1,8 +1,9 @@
+use app_macro::Builder;
 use app_models::models::{Infisso, NewInfisso, UpdateInfisso};
 use app_utils::app_interface::dto_interface::DTO;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Builder)]
 #[cfg_attr(test, derive(PartialEq))]
 pub struct InfissoDTO {
     pub id: String,
@@ -6,6 +6,8 @@ use serde::{Deserialize, Serialize};
 pub struct MaterialeInfissoDTO {
     pub materiale: String,
     pub efficienza_energetica: u8,
+    /// Trasmittanza del telaio, in W/m²K
+    pub uf: Option<f32>,
 }
 
 impl DTO for MaterialeInfissoDTO {}
@@ -15,6 +17,7 @@ impl From<MaterialeInfisso> for MaterialeInfissoDTO {
         Self {
             materiale: materiali_infisso.materiale,
             efficienza_energetica: materiali_infisso.eff_energetica as u8,
+            uf: materiali_infisso.uf,
         }
     }
 }
@@ -1,6 +1,11 @@
+use std::fmt::{Display, Formatter};
+
 use app_macro::Builder;
-use app_models::models::{NewStanza, Stanza, UpdateStanza};
-use app_utils::app_interface::dto_interface::DTO;
+use app_models::models::{NewStanza, PareteEsposta, Stanza, UpdateStanza};
+use app_utils::{
+    app_error::{DomainError, ErrorKind},
+    app_interface::dto_interface::DTO,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone, Builder, PartialEq)]
@@ -13,10 +18,16 @@ pub struct StanzaDTO {
     pub destinazione_uso: String,
     pub altezza: Option<u16>,
     pub spessore_muro: Option<u8>,
+    /// Superficie calpestabile, in m²
+    pub superficie: Option<f32>,
+    /// Perimetro, in m
+    pub perimetro: Option<f32>,
     pub riscaldamento: Option<String>,
     pub raffrescamento: Option<String>,
     pub illuminazione: Option<String>,
     pub infissi: Option<Vec<String>>,
+    /// Se presenti in aggiornamento sostituiscono quelle salvate
+    pub pareti_esposte: Option<Vec<PareteEspostaDTO>>,
 }
 
 impl DTO for StanzaDTO {}
@@ -37,10 +48,13 @@ where
             destinazione_uso: stanza.destinazione_uso.clone(),
             altezza: stanza.altezza.map(|t| t as u16),
             spessore_muro: stanza.spessore_muro.map(|t| t as u8),
+            superficie: stanza.superficie,
+            perimetro: stanza.perimetro,
             riscaldamento: stanza.riscaldamento.clone(),
             raffrescamento: stanza.raffrescamento.clone(),
             illuminazione: stanza.illuminazione.clone(),
             infissi: None,
+            pareti_esposte: None,
         }
     }
 }
@@ -59,6 +73,8 @@ impl From<StanzaDTO> for Stanza {
             riscaldamento: value.riscaldamento.clone(),
             raffrescamento: value.raffrescamento.clone(),
             illuminazione: value.illuminazione.clone(),
+            superficie: value.superficie,
+            perimetro: value.perimetro,
         }
     }
 }
@@ -78,11 +94,81 @@ impl From<StanzaDTO> for NewStanza<'_> {
 impl From<StanzaDTO> for UpdateStanza<'_> {
     fn from(value: StanzaDTO) -> Self {
         Self {
+            cod_stanza: None,
+            destinazione_uso: None,
             altezza: value.altezza.map(|v| v as i16),
             spessore_muro: value.spessore_muro.map(|v| v as i16),
             riscaldamento: value.riscaldamento.map(|x| x.into()),
             raffrescamento: value.raffrescamento.map(|x| x.into()),
             illuminazione: value.illuminazione.map(|x| x.into()),
+            superficie: value.superficie,
+            perimetro: value.perimetro,
+        }
+    }
+}
+
+/// Orientamento di una parete esposta
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Orientamento {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SO,
+    O,
+    NO,
+}
+
+impl Display for Orientamento {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result { write!(f, "{self:?}") }
+}
+
+impl TryFrom<&str> for Orientamento {
+    type Error = DomainError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.trim().to_ascii_uppercase().as_str() {
+            "N" => Ok(Orientamento::N),
+            "NE" => Ok(Orientamento::NE),
+            "E" => Ok(Orientamento::E),
+            "SE" => Ok(Orientamento::SE),
+            "S" => Ok(Orientamento::S),
+            "SO" => Ok(Orientamento::SO),
+            "O" => Ok(Orientamento::O),
+            "NO" => Ok(Orientamento::NO),
+            _ => Err(DomainError::InvalidInput(
+                ErrorKind::InvalidField,
+                "orientamento".to_string(),
+            )),
+        }
+    }
+}
+
+/// Parete della stanza verso l'esterno, la lunghezza è in m
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PareteEspostaDTO {
+    pub orientamento: Orientamento,
+    pub lunghezza: f32,
+}
+
+impl TryFrom<&PareteEsposta> for PareteEspostaDTO {
+    type Error = DomainError;
+
+    fn try_from(value: &PareteEsposta) -> Result<Self, Self::Error> {
+        Ok(Self {
+            orientamento: Orientamento::try_from(value.orientamento.as_str())?,
+            lunghezza: value.lunghezza,
+        })
+    }
+}
+
+impl PareteEspostaDTO {
+    pub fn into_model(self, stanza_id: i32) -> PareteEsposta {
+        PareteEsposta {
+            stanza_id,
+            orientamento: self.orientamento.to_string(),
+            lunghezza: self.lunghezza,
         }
     }
 }
@@ -24,6 +24,26 @@ impl TipoDTO {
     }
 }
 
+/// Catalogo degli infissi con una trasmittanza termica
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CatalogoInfisso {
+    /// Uf del materiale del telaio
+    Materiale,
+    /// Ug del vetro
+    Vetro,
+}
+
+/// Trasmittanza di una voce del catalogo, in W/m²K. `None` la cancella
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrasmittanzaDTO {
+    pub catalogo: CatalogoInfisso,
+    pub nome: String,
+    pub valore: Option<f32>,
+}
+
+impl DTO for TrasmittanzaDTO {}
+
 impl From<Climatizzazione> for TipoDTO {
     fn from(value: Climatizzazione) -> Self {
         Self {
@@ -1,4 +1,4 @@
-use app_models::models::{NewUtenza, TipoUtenza, Utenza};
+use app_models::models::{NewUtenza, TipoUtenza, UpdateUtenza, Utenza};
 use app_utils::app_interface::dto_interface::DTO;
 use serde::{Deserialize, Serialize};
 
@@ -9,6 +9,9 @@ pub struct UtenzaDTO {
     pub tipo: TipoUtenza,
     pub cod_contatore: String,
     pub indirizzo_contatore: Option<String>,
+    /// Consumo annuo nell'unità del contatore (kWh per l'elettricità)
+    #[serde(default)]
+    pub consumo_annuo: Option<f32>,
 }
 
 impl DTO for UtenzaDTO {}
@@ -21,6 +24,7 @@ impl From<&Utenza> for UtenzaDTO {
             tipo: value.tipo.clone(),
             cod_contatore: value.cod_contatore.clone(),
             indirizzo_contatore: value.indirizzo_contatore.clone(),
+            consumo_annuo: value.consumo_annuo,
         }
     }
 }
@@ -32,6 +36,18 @@ impl From<UtenzaDTO> for NewUtenza<'_> {
             tipo: value.tipo,
             cod_contatore: value.cod_contatore.into(),
             indirizzo_contatore: value.indirizzo_contatore.map(|x| x.into()),
+            consumo_annuo: value.consumo_annuo,
+        }
+    }
+}
+
+impl From<UtenzaDTO> for UpdateUtenza<'_> {
+    fn from(value: UtenzaDTO) -> Self {
+        Self {
+            tipo: Some(value.tipo),
+            cod_contatore: Some(value.cod_contatore.into()),
+            indirizzo_contatore: Some(value.indirizzo_contatore.map(|x| x.into())),
+            consumo_annuo: Some(value.consumo_annuo),
         }
     }
 }
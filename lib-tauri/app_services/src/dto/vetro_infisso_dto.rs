@@ -6,6 +6,8 @@ use serde::{Deserialize, Serialize};
 pub struct VetroInfissoDTO {
     pub vetro: String,
     pub efficienza_energetica: u8,
+    /// Trasmittanza del vetro, in W/m²K
+    pub ug: Option<f32>,
 }
 
 impl DTO for VetroInfissoDTO {}
@@ -15,6 +17,7 @@ impl From<VetroInfisso> for VetroInfissoDTO {
         Self {
             vetro: value.vetro,
             efficienza_energetica: value.eff_energetica as u8,
+            ug: value.ug,
         }
     }
 }
@@ -1,3 +1,4 @@
 pub mod dao;
 pub mod dto;
+pub mod query;
 pub mod service;
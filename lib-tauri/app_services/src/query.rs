@@ -0,0 +1,203 @@
+use app_models::models::TipoUtenza;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    #[default]
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Sort<S> {
+    pub field: S,
+    #[serde(default)]
+    pub direction: SortDirection,
+}
+
+/// Ricerca tipizzata usata da `RetrieveBy`. I filtri sono in AND, l'ordine
+/// segue quello delle chiavi di ordinamento.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Query<F, S> {
+    pub filters: Vec<F>,
+    pub sort: Vec<Sort<S>>,
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
+impl<F, S> Default for Query<F, S> {
+    fn default() -> Self {
+        Self {
+            filters: Vec::new(),
+            sort: Vec::new(),
+            limit: None,
+            offset: None,
+        }
+    }
+}
+
+impl<F, S> Query<F, S> {
+    pub fn new() -> Self { Self::default() }
+
+    pub fn filter(mut self, filter: F) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    pub fn sort_by(mut self, field: S, direction: SortDirection) -> Self {
+        self.sort.push(Sort { field, direction });
+        self
+    }
+
+    pub fn paginate(mut self, limit: u32, offset: u32) -> Self {
+        self.limit = Some(limit);
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Limita la ricerca a `limit` righe se non ne indica già un limite
+    pub fn limit_or(mut self, limit: u32) -> Self {
+        self.limit.get_or_insert(limit);
+        self
+    }
+}
+
+/// Campi facoltativi della stanza, non valorizzati dall'import
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CampoStanza {
+    Altezza,
+    SpessoreMuro,
+    Superficie,
+    Riscaldamento,
+    Raffrescamento,
+    Illuminazione,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "field", content = "value", rename_all = "snake_case")]
+pub enum StanzaFilter {
+    Edificio(String),
+    Piano(String),
+    DestinazioneUso(String),
+    Riscaldamento(String),
+    Raffrescamento(String),
+    Illuminazione(String),
+    /// Stanze in cui il campo non è stato ancora rilevato
+    Missing(CampoStanza),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StanzaSort {
+    Piano,
+    IdSpazio,
+    CodStanza,
+    DestinazioneUso,
+    Altezza,
+    SpessoreMuro,
+    Superficie,
+}
+
+pub type StanzaQuery = Query<StanzaFilter, StanzaSort>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "field", content = "value", rename_all = "snake_case")]
+pub enum InfissoFilter {
+    Edificio(String),
+    Tipo(String),
+    Materiale(String),
+    Vetro(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InfissoSort {
+    Id,
+    Tipo,
+    Materiale,
+    Vetro,
+    Altezza,
+    Larghezza,
+    Mq,
+}
+
+pub type InfissoQuery = Query<InfissoFilter, InfissoSort>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "field", content = "value", rename_all = "snake_case")]
+pub enum UtenzaFilter {
+    Edificio(String),
+    Tipo(TipoUtenza),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UtenzaSort {
+    Tipo,
+    CodContatore,
+}
+
+pub type UtenzaQuery = Query<UtenzaFilter, UtenzaSort>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "field", content = "value", rename_all = "snake_case")]
+pub enum FotovoltaicoFilter {
+    Edificio(String),
+    Proprietario(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FotovoltaicoSort {
+    Potenza,
+    Proprietario,
+}
+
+pub type FotovoltaicoQuery = Query<FotovoltaicoFilter, FotovoltaicoSort>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_query() -> Result<(), serde_json::Error> {
+        let query: StanzaQuery = serde_json::from_value(serde_json::json!({
+            "filters": [
+                { "field": "edificio", "value": "500-1" },
+                { "field": "missing", "value": "spessore_muro" }
+            ],
+            "sort": [{ "field": "piano" }, { "field": "altezza", "direction": "desc" }],
+            "limit": 50
+        }))?;
+
+        let expected = StanzaQuery::new()
+            .filter(StanzaFilter::Edificio("500-1".to_string()))
+            .filter(StanzaFilter::Missing(CampoStanza::SpessoreMuro))
+            .sort_by(StanzaSort::Piano, SortDirection::Asc)
+            .sort_by(StanzaSort::Altezza, SortDirection::Desc);
+        assert_eq!(
+            query,
+            StanzaQuery {
+                limit: Some(50),
+                ..expected
+            }
+        );
+        assert_eq!(
+            serde_json::from_str::<InfissoQuery>("{}")?,
+            InfissoQuery::new()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_limit_or() {
+        assert_eq!(InfissoQuery::new().limit_or(100).limit, Some(100));
+        assert_eq!(
+            InfissoQuery::new().paginate(20, 40).limit_or(100).limit,
+            Some(20)
+        );
+    }
+}
@@ -1,9 +1,9 @@
 use app_utils::{
     app_error::AppResult,
     app_interface::{
-        dao_interface::crud_operations::{GetAll, Insert},
+        dao_interface::crud_operations::{Delete, GetAll, Insert},
         database_interface::DatabaseManagerTrait,
-        service_interface::{CreateService, RetrieveManyService},
+        service_interface::{CreateService, DeleteService, RetrieveManyService},
     },
 };
 use async_trait::async_trait;
@@ -91,6 +91,42 @@ impl CreateService<AnnotazioneInfissoDTO> for AnnotazioneService {
     }
 }
 
+#[async_trait]
+impl DeleteService<AnnotazioneEdificioDTO, u64> for AnnotazioneService {
+    async fn delete(
+        db: State<'_, impl DatabaseManagerTrait + Send + Sync>,
+        id: u64,
+    ) -> AppResult<bool> {
+        let mut conn = db.get_connection().await?;
+        AnnotazioneEdificioDAO::delete(&mut conn, id as i32)?;
+        Ok(true)
+    }
+}
+
+#[async_trait]
+impl DeleteService<AnnotazioneStanzaDTO, u64> for AnnotazioneService {
+    async fn delete(
+        db: State<'_, impl DatabaseManagerTrait + Send + Sync>,
+        id: u64,
+    ) -> AppResult<bool> {
+        let mut conn = db.get_connection().await?;
+        AnnotazioneStanzaDAO::delete(&mut conn, id as i32)?;
+        Ok(true)
+    }
+}
+
+#[async_trait]
+impl DeleteService<AnnotazioneInfissoDTO, u64> for AnnotazioneService {
+    async fn delete(
+        db: State<'_, impl DatabaseManagerTrait + Send + Sync>,
+        id: u64,
+    ) -> AppResult<bool> {
+        let mut conn = db.get_connection().await?;
+        AnnotazioneInfissoDAO::delete(&mut conn, id as i32)?;
+        Ok(true)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use app_models::models::NewStanza;
@@ -2,11 +2,11 @@ use app_state::selected_edificio::EdificioSelected;
 use app_utils::{
     app_error::{AppResult, ApplicationError},
     app_interface::{
-        dao_interface::crud_operations::{Get, GetAll, Insert, Update},
+        dao_interface::crud_operations::{Delete, Get, GetAll, Insert, Update},
         database_interface::DatabaseManagerTrait,
         service_interface::{
-            CreateService, RetrieveManyService, RetrieveOneService, SelectedEdificioState,
-            SelectedEdificioTrait, UpdateService,
+            CreateService, DeleteService, RetrieveManyService, RetrieveOneService,
+            SelectedEdificioState, SelectedEdificioTrait, UpdateService,
         },
     },
 };
@@ -89,6 +89,18 @@ impl UpdateService<EdificioDTO> for EdificioService {
     }
 }
 
+#[async_trait]
+impl DeleteService<EdificioDTO, String> for EdificioService {
+    async fn delete(
+        db: State<'_, impl DatabaseManagerTrait + Send + Sync>,
+        id: String,
+    ) -> AppResult<bool> {
+        let mut conn = db.get_connection().await?;
+        EdificioDAO::delete(&mut conn, id)?;
+        Ok(true)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     //! The tests were created based on the data in the `dataFake` folder.
@@ -1,24 +1,193 @@
 use std::ops::Deref;
 
+use app_models::models::TipoUtenza;
 use app_state::selected_edificio::SelectedEdificioTrait;
 use app_utils::{
     app_error::{AppResult, ApplicationError, DomainError, ErrorKind},
     app_interface::{
-        dao_interface::crud_operations::{Get, GetAll, Insert},
+        dao_interface::crud_operations::{Delete, Get, GetAll, Insert, Update},
         database_interface::DatabaseManagerTrait,
         service_interface::{
-            CreateService, RetrieveBy, RetrieveByEdificioSelected, RetrieveManyService,
-            SelectedEdificioState,
+            CreateService, DeleteService, RetrieveBy, RetrieveByEdificioSelected,
+            RetrieveManyService, SelectedEdificioState, UpdateService,
         },
     },
 };
 use async_trait::async_trait;
+use chrono::Datelike;
 use tauri::State;
 
-use crate::{dao::FotovoltaicoDAO, dto::FotovoltaicoDTO};
+use crate::{
+    dao::{FotovoltaicoDAO, UtenzeDAO},
+    dto::{FotovoltaicoDTO, Orientamento, StimaImpiantoDTO, StimaProduzioneDTO, UtenzaDTO},
+    query::{FotovoltaicoFilter, FotovoltaicoQuery},
+    service::irraggiamento::irraggiamento,
+};
+
+/// Rapporto tra l'energia prodotta e quella teorica dei pannelli
+const PERFORMANCE_RATIO: f64 = 0.8;
+/// Perdita di produzione per ogni anno di esercizio
+const DEGRADO_ANNUO: f64 = 0.005;
+/// Primo anno di installazione accettato, come nel vincolo della tabella
+const PRIMO_ANNO_INSTALLAZIONE: i32 = 1970;
+/// Inclinazioni della tabella dei fattori di esposizione, in gradi
+const INCLINAZIONI: [f64; 6] = [0.0, 15.0, 30.0, 45.0, 60.0, 90.0];
+/// Rapporto tra l'irraggiamento sui pannelli e quello orizzontale, per
+/// orientamento (S, SE/SO, E/O, NE/NO, N) e inclinazione
+const FATTORI_ESPOSIZIONE: [[f64; 6]; 5] = [
+    [1.0, 1.08, 1.13, 1.12, 1.05, 0.75],
+    [1.0, 1.06, 1.09, 1.06, 0.98, 0.72],
+    [1.0, 0.98, 0.95, 0.90, 0.83, 0.60],
+    [1.0, 0.90, 0.80, 0.70, 0.60, 0.42],
+    [1.0, 0.86, 0.72, 0.58, 0.45, 0.30],
+];
 
 pub struct FotovoltaicoService;
 
+impl FotovoltaicoService {
+    /// Stima la produzione annua degli impianti dell'edificio e la confronta
+    /// con il consumo delle utenze elettriche
+    pub async fn stima_produzione(
+        db: State<'_, impl DatabaseManagerTrait + Send + Sync>,
+        edificio: &str,
+    ) -> AppResult<StimaProduzioneDTO> {
+        let mut conn = db.get_connection().await?;
+        let fotovoltaici: Vec<FotovoltaicoDTO> =
+            FotovoltaicoDAO::get(&mut conn, edificio.to_string())?
+                .iter()
+                .map(FotovoltaicoDTO::from)
+                .collect();
+        let utenze: Vec<UtenzaDTO> = UtenzeDAO::get(&mut conn, edificio.to_string())?
+            .iter()
+            .map(UtenzaDTO::from)
+            .collect();
+
+        Ok(Self::stima(
+            edificio,
+            &fotovoltaici,
+            &utenze,
+            chrono::Local::now().year(),
+        ))
+    }
+
+    pub(crate) fn stima(
+        edificio: &str,
+        fotovoltaici: &[FotovoltaicoDTO],
+        utenze: &[UtenzaDTO],
+        anno: i32,
+    ) -> StimaProduzioneDTO {
+        let impianti: Vec<StimaImpiantoDTO> = fotovoltaici
+            .iter()
+            .map(|impianto| Self::stima_impianto(impianto, anno))
+            .collect();
+        let produzione_annua = impianti.iter().filter_map(|i| i.produzione_annua).sum();
+
+        let consumi: Vec<f64> = utenze
+            .iter()
+            .filter(|utenza| utenza.tipo == TipoUtenza::Elettricità)
+            .filter_map(|utenza| utenza.consumo_annuo.map(|c| c as f64))
+            .collect();
+        let consumo_elettrico = (!consumi.is_empty()).then(|| consumi.iter().sum::<f64>());
+
+        StimaProduzioneDTO {
+            edificio: edificio.to_string(),
+            produzione_annua,
+            impianti_senza_stima: impianti
+                .iter()
+                .filter(|i| i.produzione_annua.is_none())
+                .count() as u32,
+            consumo_elettrico,
+            copertura: consumo_elettrico
+                .filter(|consumo| *consumo > 0.0)
+                .map(|consumo| produzione_annua / consumo),
+            impianti,
+        }
+    }
+
+    /// Produzione dell'impianto come potenza × irraggiamento sui pannelli ×
+    /// performance ratio, ridotta linearmente con gli anni di esercizio.
+    /// Senza orientamento o inclinazione si ipotizza l'esposizione a sud a
+    /// 30°.
+    fn stima_impianto(impianto: &FotovoltaicoDTO, anno: i32) -> StimaImpiantoDTO {
+        let orientamento = impianto.orientamento.unwrap_or(Orientamento::S);
+        let inclinazione = impianto.inclinazione.unwrap_or(30);
+        let produzione_iniziale =
+            impianto
+                .provincia
+                .as_deref()
+                .and_then(irraggiamento)
+                .map(|irraggiamento| {
+                    impianto.potenza as f64
+                        * irraggiamento
+                        * Self::fattore_esposizione(orientamento, inclinazione)
+                        * PERFORMANCE_RATIO
+                });
+        let anni_esercizio = impianto
+            .anno_installazione
+            .map_or(0, |installazione| (anno - installazione).max(0));
+        let degrado = (1.0 - DEGRADO_ANNUO * anni_esercizio as f64).max(0.0);
+
+        StimaImpiantoDTO {
+            id: impianto.id,
+            potenza: impianto.potenza,
+            produzione_iniziale,
+            produzione_annua: produzione_iniziale.map(|produzione| produzione * degrado),
+            esposizione_ipotizzata: impianto.orientamento.is_none()
+                || impianto.inclinazione.is_none(),
+        }
+    }
+
+    /// Fattore di esposizione interpolato linearmente sull'inclinazione
+    pub fn fattore_esposizione(orientamento: Orientamento, inclinazione: u8) -> f64 {
+        let fattori = match orientamento {
+            Orientamento::S => &FATTORI_ESPOSIZIONE[0],
+            Orientamento::SE | Orientamento::SO => &FATTORI_ESPOSIZIONE[1],
+            Orientamento::E | Orientamento::O => &FATTORI_ESPOSIZIONE[2],
+            Orientamento::NE | Orientamento::NO => &FATTORI_ESPOSIZIONE[3],
+            Orientamento::N => &FATTORI_ESPOSIZIONE[4],
+        };
+        let inclinazione = (inclinazione as f64).min(90.0);
+        let i = INCLINAZIONI
+            .windows(2)
+            .position(|w| inclinazione <= w[1])
+            .unwrap_or(INCLINAZIONI.len() - 2);
+        let t = (inclinazione - INCLINAZIONI[i]) / (INCLINAZIONI[i + 1] - INCLINAZIONI[i]);
+        fattori[i] + t * (fattori[i + 1] - fattori[i])
+    }
+
+    /// `anno` è l'anno corrente, ultimo anno di installazione accettato
+    fn validate(item: &FotovoltaicoDTO, anno: i32) -> Result<(), DomainError> {
+        if item.anno_installazione.is_some_and(|installazione| {
+            !(PRIMO_ANNO_INSTALLAZIONE..=anno).contains(&installazione)
+        }) {
+            return Err(DomainError::InvalidInput(
+                ErrorKind::InvalidField,
+                "anno_installazione".to_string(),
+            ));
+        }
+        if item
+            .inclinazione
+            .is_some_and(|inclinazione| inclinazione > 90)
+        {
+            return Err(DomainError::InvalidInput(
+                ErrorKind::InvalidField,
+                "inclinazione".to_string(),
+            ));
+        }
+        if item
+            .provincia
+            .as_deref()
+            .is_some_and(|provincia| irraggiamento(provincia).is_none())
+        {
+            return Err(DomainError::InvalidInput(
+                ErrorKind::InvalidField,
+                "provincia".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
 #[async_trait]
 impl RetrieveManyService<FotovoltaicoDTO> for FotovoltaicoService {
     async fn retrieve_many(
@@ -33,25 +202,14 @@ impl RetrieveManyService<FotovoltaicoDTO> for FotovoltaicoService {
 #[async_trait]
 impl RetrieveBy<FotovoltaicoDTO> for FotovoltaicoService {
     type Output = Vec<FotovoltaicoDTO>;
+    type Query = FotovoltaicoQuery;
 
     async fn retrieve_by(
         db_state: State<'_, impl DatabaseManagerTrait + Send + Sync>,
-        where_field: &str,
-        where_value: &str,
+        query: FotovoltaicoQuery,
     ) -> AppResult<Self::Output> {
         let mut conn = db_state.get_connection().await?;
-
-        let result = match where_field {
-            "edificio" => FotovoltaicoDAO::get(&mut conn, where_value.to_string())?,
-            _ => {
-                return Err(DomainError::InvalidInput(
-                    ErrorKind::InvalidField,
-                    where_field.to_string(),
-                )
-                .into());
-            }
-        };
-
+        let result = FotovoltaicoDAO::get(&mut conn, &query)?;
         Ok(result.iter().map(FotovoltaicoDTO::from).collect())
     }
 }
@@ -66,11 +224,15 @@ impl RetrieveByEdificioSelected<FotovoltaicoDTO> for FotovoltaicoService {
         S: SelectedEdificioTrait + Send + Sync,
     {
         let edificio_selected = edificio_selected_state.read().await.deref().get_chiave();
-        if edificio_selected.is_none() {
+        let Some(edificio_id) = edificio_selected else {
             return Err(ApplicationError::EdificioNotSelected);
-        }
+        };
 
-        Self::retrieve_by(db_state, "edificio", edificio_selected.unwrap().deref()).await
+        Self::retrieve_by(
+            db_state,
+            FotovoltaicoQuery::new().filter(FotovoltaicoFilter::Edificio(edificio_id)),
+        )
+        .await
     }
 }
 
@@ -80,8 +242,130 @@ impl CreateService<FotovoltaicoDTO> for FotovoltaicoService {
         db: State<'_, impl DatabaseManagerTrait + Send + Sync>,
         item: FotovoltaicoDTO,
     ) -> AppResult<FotovoltaicoDTO> {
+        Self::validate(&item, chrono::Local::now().year())?;
         let mut conn = db.get_connection().await?;
         let utenza = FotovoltaicoDAO::insert(&mut conn, item.into())?;
         Ok(FotovoltaicoDTO::from(&utenza))
     }
 }
+
+#[async_trait]
+impl UpdateService<FotovoltaicoDTO> for FotovoltaicoService {
+    async fn update(
+        db: State<'_, impl DatabaseManagerTrait + Send + Sync>,
+        item: FotovoltaicoDTO,
+    ) -> AppResult<FotovoltaicoDTO> {
+        let Some(id) = item.id else {
+            return Err(DomainError::InvalidInput(ErrorKind::EmptyField, "id".to_string()).into());
+        };
+        Self::validate(&item, chrono::Local::now().year())?;
+        let mut conn = db.get_connection().await?;
+        let result = FotovoltaicoDAO::update(&mut conn, id as i32, item.into())?;
+        Ok(FotovoltaicoDTO::from(&result))
+    }
+}
+
+#[async_trait]
+impl DeleteService<FotovoltaicoDTO, u64> for FotovoltaicoService {
+    async fn delete(
+        db: State<'_, impl DatabaseManagerTrait + Send + Sync>,
+        id: u64,
+    ) -> AppResult<bool> {
+        let mut conn = db.get_connection().await?;
+        FotovoltaicoDAO::delete(&mut conn, id as i32)?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round(value: f64) -> f64 { (value * 100.0).round() / 100.0 }
+
+    fn impianto(provincia: Option<&str>, anno_installazione: Option<i32>) -> FotovoltaicoDTO {
+        FotovoltaicoDTO {
+            id: Some(1),
+            id_edificio: "500-1".to_string(),
+            potenza: 10.0,
+            proprietario: "Comune".to_string(),
+            orientamento: Some(Orientamento::S),
+            inclinazione: Some(30),
+            anno_installazione,
+            provincia: provincia.map(str::to_string),
+        }
+    }
+
+    fn utenza(tipo: TipoUtenza, consumo_annuo: Option<f32>) -> UtenzaDTO {
+        UtenzaDTO {
+            id: 1,
+            edificio_id: "500-1".to_string(),
+            tipo,
+            cod_contatore: "0001".to_string(),
+            indirizzo_contatore: None,
+            consumo_annuo,
+        }
+    }
+
+    #[test]
+    fn test_fattore_esposizione() {
+        assert_eq!(
+            round(FotovoltaicoService::fattore_esposizione(
+                Orientamento::S,
+                30
+            )),
+            1.13
+        );
+        assert_eq!(
+            FotovoltaicoService::fattore_esposizione(Orientamento::N, 0),
+            1.0
+        );
+        assert_eq!(
+            round(FotovoltaicoService::fattore_esposizione(
+                Orientamento::SO,
+                40
+            )),
+            1.07
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let valid = |impianto: FotovoltaicoDTO| FotovoltaicoService::validate(&impianto, 2026);
+        assert!(valid(impianto(Some("RM"), Some(1970))).is_ok());
+        assert!(valid(impianto(Some("RM"), Some(2026))).is_ok());
+        assert!(valid(impianto(None, None)).is_ok());
+        for anno in [1900, 2027] {
+            assert_eq!(
+                valid(impianto(None, Some(anno))).unwrap_err(),
+                DomainError::InvalidInput(
+                    ErrorKind::InvalidField,
+                    "anno_installazione".to_string()
+                )
+            );
+        }
+        assert!(valid(impianto(Some("XX"), None)).is_err());
+    }
+
+    #[test]
+    fn test_stima() {
+        let fotovoltaici = vec![impianto(Some("rm"), Some(2016)), impianto(None, None)];
+        let utenze = vec![
+            utenza(TipoUtenza::Elettricità, Some(12000.0)),
+            utenza(TipoUtenza::Elettricità, Some(8000.0)),
+            utenza(TipoUtenza::Acqua, Some(300.0)),
+        ];
+
+        let stima = FotovoltaicoService::stima("500-1", &fotovoltaici, &utenze, 2026);
+
+        // 10 kW × 1600 kWh/m² × 1.13 × 0.8, con 10 anni di degrado
+        assert_eq!(
+            stima.impianti[0].produzione_iniziale.map(round),
+            Some(14464.0)
+        );
+        assert_eq!(round(stima.produzione_annua), 13740.8);
+        assert_eq!(stima.impianti_senza_stima, 1);
+        assert_eq!(stima.consumo_elettrico, Some(20000.0));
+        assert_eq!(stima.copertura.map(round), Some(0.69));
+    }
+}
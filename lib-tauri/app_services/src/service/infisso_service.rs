@@ -1,41 +1,38 @@
 use app_state::selected_edificio::{SelectedEdificioState, SelectedEdificioTrait};
 use app_utils::{
-    app_error::{AppResult, ErrorKind},
+    app_error::AppResult,
     app_interface::{
-        dao_interface::crud_operations::{Get, Insert, Update},
+        dao_interface::crud_operations::{Delete, Get, Insert, Update},
         database_interface::DatabaseManagerTrait,
-        service_interface::{CreateService, RetrieveBy, RetrieveByEdificioSelected, UpdateService},
+        service_interface::{
+            CreateService, DeleteService, RetrieveBy, RetrieveByEdificioSelected, UpdateService,
+        },
     },
 };
 use async_trait::async_trait;
+use diesel::Connection;
 use tauri::State;
 
-use crate::{dao::InfissoDAO, dto::InfissoDTO, service::DomainError};
+use crate::{
+    dao::{InfissoDAO, StanzaConInfissiDao},
+    dto::InfissoDTO,
+    query::{InfissoFilter, InfissoQuery},
+    service::DomainError,
+};
 
 pub struct InfissoService;
 
 #[async_trait]
 impl RetrieveBy<InfissoDTO> for InfissoService {
     type Output = Vec<InfissoDTO>;
+    type Query = InfissoQuery;
 
     async fn retrieve_by(
         db_state: State<'_, impl DatabaseManagerTrait + Send + Sync>,
-        where_field: &str,
-        where_value: &str,
+        query: InfissoQuery,
     ) -> AppResult<Self::Output> {
         let mut conn = db_state.get_connection().await?;
-
-        let result = match where_field {
-            "edificio" => InfissoDAO::get(&mut conn, where_value.to_string())?,
-            _ => {
-                return Err(DomainError::InvalidInput(
-                    ErrorKind::InvalidField,
-                    where_field.to_string(),
-                )
-                .into());
-            }
-        };
-
+        let result = InfissoDAO::get(&mut conn, &query)?;
         Ok(result.iter().map(InfissoDTO::from).collect())
     }
 }
@@ -54,7 +51,11 @@ impl RetrieveByEdificioSelected<InfissoDTO> for InfissoService {
             None => return Ok(Vec::new()),
         };
 
-        Self::retrieve_by(db_state, "edificio", &edificio_id).await
+        Self::retrieve_by(
+            db_state,
+            InfissoQuery::new().filter(InfissoFilter::Edificio(edificio_id)),
+        )
+        .await
     }
 }
 
@@ -86,6 +87,26 @@ impl UpdateService<InfissoDTO> for InfissoService {
     }
 }
 
+#[async_trait]
+impl DeleteService<InfissoDTO, (String, String)> for InfissoService {
+    /// id -> (infisso, edificio)
+    async fn delete(
+        db: State<'_, impl DatabaseManagerTrait + Send + Sync>,
+        id: (String, String),
+    ) -> AppResult<bool> {
+        let mut conn = db.get_connection().await?;
+        let (id_infisso, id_edificio) = id;
+
+        conn.transaction::<_, DomainError, _>(|tx| {
+            // Remove the infisso from every stanza where it is mounted
+            StanzaConInfissiDao::delete(tx, (id_edificio.clone(), id_infisso.clone()))?;
+            InfissoDAO::delete(tx, (id_infisso, id_edificio))?;
+            Ok(true)
+        })
+        .map_err(|e| e.into())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::ops::Deref;
@@ -95,6 +116,7 @@ mod test {
         selected_edificio::{EdificioSelected, SelectedEdificioTrait},
     };
     use app_utils::{
+        app_error::ApplicationError,
         app_interface::{
             database_interface::DatabaseManagerTrait as DatabaseManagerInterface,
             service_interface::SelectedEdificioState,
@@ -215,4 +237,29 @@ mod test {
         }
         Ok(())
     }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn test_delete_infisso() -> ResultTest {
+        let env = setup_env_infissi().await?;
+        let state_db = env.database();
+        let selected_edificio = env.state_app::<SelectedEdificioState<EdificioSelected>>();
+
+        match InfissoService::delete(state_db.clone(), ("A".to_string(), "8361-122".to_string()))
+            .await
+        {
+            Ok(result) => assert!(result),
+            Err(e) => panic!("{:?}", e),
+        }
+
+        let infissi =
+            InfissoService::retrieve_by_edificio_selected(state_db.clone(), selected_edificio)
+                .await?;
+        assert_eq!(infissi.len(), 1);
+
+        match InfissoService::delete(state_db, ("A".to_string(), "8361-122".to_string())).await {
+            Err(ApplicationError::Domain(DomainError::InfissoNotFound)) => {}
+            other => panic!("Expected InfissoNotFound, got {:?}", other),
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,142 @@
+//! Irraggiamento solare globale annuo su superficie orizzontale per
+//! provincia, in kWh/m². Valori medi indicativi ricavati dalle medie mensili
+//! della UNI 10349 per i capoluoghi.
+
+const IRRAGGIAMENTO: &[(&str, f64)] = &[
+    // Piemonte
+    ("AL", 1390.0),
+    ("AT", 1390.0),
+    ("BI", 1350.0),
+    ("CN", 1420.0),
+    ("NO", 1360.0),
+    ("TO", 1380.0),
+    ("VB", 1300.0),
+    ("VC", 1370.0),
+    // Valle d'Aosta
+    ("AO", 1400.0),
+    // Lombardia
+    ("BG", 1320.0),
+    ("BS", 1350.0),
+    ("CO", 1300.0),
+    ("CR", 1360.0),
+    ("LC", 1300.0),
+    ("LO", 1350.0),
+    ("MB", 1320.0),
+    ("MI", 1330.0),
+    ("MN", 1370.0),
+    ("PV", 1350.0),
+    ("SO", 1290.0),
+    ("VA", 1310.0),
+    // Trentino-Alto Adige
+    ("BZ", 1400.0),
+    ("TN", 1380.0),
+    // Veneto
+    ("BL", 1300.0),
+    ("PD", 1370.0),
+    ("RO", 1390.0),
+    ("TV", 1350.0),
+    ("VE", 1380.0),
+    ("VI", 1350.0),
+    ("VR", 1390.0),
+    // Friuli-Venezia Giulia
+    ("GO", 1360.0),
+    ("PN", 1340.0),
+    ("TS", 1390.0),
+    ("UD", 1340.0),
+    // Liguria
+    ("GE", 1450.0),
+    ("IM", 1520.0),
+    ("SP", 1460.0),
+    ("SV", 1480.0),
+    // Emilia-Romagna
+    ("BO", 1420.0),
+    ("FC", 1440.0),
+    ("FE", 1410.0),
+    ("MO", 1400.0),
+    ("PC", 1370.0),
+    ("PR", 1390.0),
+    ("RA", 1430.0),
+    ("RE", 1390.0),
+    ("RN", 1450.0),
+    // Toscana
+    ("AR", 1480.0),
+    ("FI", 1480.0),
+    ("GR", 1560.0),
+    ("LI", 1540.0),
+    ("LU", 1460.0),
+    ("MS", 1450.0),
+    ("PI", 1500.0),
+    ("PO", 1470.0),
+    ("PT", 1460.0),
+    ("SI", 1500.0),
+    // Umbria
+    ("PG", 1500.0),
+    ("TR", 1520.0),
+    // Marche
+    ("AN", 1480.0),
+    ("AP", 1510.0),
+    ("FM", 1500.0),
+    ("MC", 1490.0),
+    ("PU", 1460.0),
+    // Lazio
+    ("FR", 1570.0),
+    ("LT", 1620.0),
+    ("RI", 1520.0),
+    ("RM", 1600.0),
+    ("VT", 1550.0),
+    // Abruzzo
+    ("AQ", 1520.0),
+    ("CH", 1550.0),
+    ("PE", 1540.0),
+    ("TE", 1510.0),
+    // Molise
+    ("CB", 1570.0),
+    ("IS", 1540.0),
+    // Campania
+    ("AV", 1580.0),
+    ("BN", 1600.0),
+    ("CE", 1620.0),
+    ("NA", 1650.0),
+    ("SA", 1660.0),
+    // Puglia
+    ("BA", 1660.0),
+    ("BR", 1690.0),
+    ("BT", 1650.0),
+    ("FG", 1640.0),
+    ("LE", 1720.0),
+    ("TA", 1700.0),
+    // Basilicata
+    ("MT", 1670.0),
+    ("PZ", 1600.0),
+    // Calabria
+    ("CS", 1670.0),
+    ("CZ", 1720.0),
+    ("KR", 1740.0),
+    ("RC", 1770.0),
+    ("VV", 1730.0),
+    // Sicilia
+    ("AG", 1810.0),
+    ("CL", 1790.0),
+    ("CT", 1780.0),
+    ("EN", 1760.0),
+    ("ME", 1730.0),
+    ("PA", 1760.0),
+    ("RG", 1820.0),
+    ("SR", 1830.0),
+    ("TP", 1800.0),
+    // Sardegna
+    ("CA", 1720.0),
+    ("NU", 1660.0),
+    ("OR", 1690.0),
+    ("SS", 1640.0),
+    ("SU", 1710.0),
+];
+
+/// Irraggiamento annuo della provincia, per sigla
+pub(crate) fn irraggiamento(provincia: &str) -> Option<f64> {
+    let provincia = provincia.trim().to_uppercase();
+    IRRAGGIAMENTO
+        .iter()
+        .find(|(sigla, _)| *sigla == provincia)
+        .map(|(_, valore)| *valore)
+}
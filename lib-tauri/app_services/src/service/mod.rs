@@ -2,6 +2,7 @@ mod annotazione_service;
 mod edificio_service;
 mod fotovoltaico_service;
 mod infisso_service;
+mod irraggiamento;
 mod stanza_service;
 mod type_service;
 mod utenze_service;
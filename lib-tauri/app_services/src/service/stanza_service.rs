@@ -2,18 +2,15 @@ use std::collections::HashMap;
 
 use app_models::models::{NewStanza, StanzaConInfissi, UpdateStanzaConInfissi};
 use app_state::selected_edificio::SelectedEdificioState;
-use app_utils::{
-    app_error::ErrorKind,
-    app_interface::service_interface::{
-        CreateBatchService, RetrieveBy, RetrieveByEdificioSelected, SelectedEdificioTrait,
-    },
+use app_utils::app_interface::service_interface::{
+    CreateBatchService, RetrieveBy, RetrieveByEdificioSelected, SelectedEdificioTrait,
 };
 pub use app_utils::{
     app_error::{AppResult, ApplicationError, DomainError},
     app_interface::{
-        dao_interface::crud_operations::{Get, Insert, Update},
+        dao_interface::crud_operations::{Delete, Get, Insert, Update},
         database_interface::DatabaseManagerTrait,
-        service_interface::{CreateService, UpdateService},
+        service_interface::{CreateService, DeleteService, UpdateService},
     },
 };
 use async_trait::async_trait;
@@ -21,8 +18,9 @@ use diesel::Connection;
 use tauri::State;
 
 use crate::{
-    dao::{StanzaConInfissiDao, StanzaDAO},
-    dto::StanzaDTO,
+    dao::{PareteEspostaDAO, StanzaConInfissiDao, StanzaDAO},
+    dto::{PareteEspostaDTO, StanzaDTO},
+    query::{StanzaFilter, StanzaQuery},
 };
 
 pub struct StanzaService;
@@ -30,61 +28,59 @@ pub struct StanzaService;
 #[async_trait]
 impl RetrieveBy<StanzaDTO> for StanzaService {
     type Output = Vec<StanzaDTO>;
+    type Query = StanzaQuery;
 
     async fn retrieve_by(
         db_state: State<'_, impl DatabaseManagerTrait + Send + Sync>,
-        where_field: &str,
-        where_value: &str,
+        query: StanzaQuery,
     ) -> AppResult<Self::Output> {
         let mut conn = db_state.get_connection().await?;
-        match where_field {
-            "edificio" => {
-                let edificio_id = where_value.to_string();
+        conn.transaction::<_, DomainError, _>(|conn| {
+            let stanze = StanzaDAO::get(conn, &query)?;
+            let mut stanze_dto: Vec<StanzaDTO> = stanze.iter().map(StanzaDTO::from).collect();
 
-                conn.transaction::<_, DomainError, _>(|conn| {
-                    let stanze = StanzaDAO::get(conn, &edificio_id)?;
-                    let mut stanze_dto: Vec<StanzaDTO> =
-                        stanze.iter().map(StanzaDTO::from).collect();
+            let id_stanze: Vec<i32> = stanze.iter().map(|stanza| stanza.id).collect();
+            let infissi = StanzaConInfissiDao::get(conn, id_stanze.as_slice())?;
+            let pareti = PareteEspostaDAO::get(conn, id_stanze.as_slice())?;
 
-                    let infissi = StanzaConInfissiDao::get(conn, edificio_id.as_str())?;
+            for stanza_dto in &mut stanze_dto {
+                let pareti_esposte = pareti
+                    .iter()
+                    .filter(|parete| parete.stanza_id == (stanza_dto.id as i32))
+                    .map(PareteEspostaDTO::try_from)
+                    .collect::<Result<Vec<_>, _>>()?;
+                if !pareti_esposte.is_empty() {
+                    stanza_dto.pareti_esposte = Some(pareti_esposte);
+                }
 
-                    for stanza_dto in &mut stanze_dto {
-                        // Retrieve only the infissi of the current stanza
-                        let infissi: Vec<&StanzaConInfissi> = infissi
-                            .iter()
-                            .filter(|x| {
-                                x.stanza_id == (stanza_dto.id as i32)
-                                    && x.edificio_id == edificio_id.as_str()
-                            })
-                            .collect();
-
-                        if infissi.is_empty() {
-                            continue;
-                        }
-
-                        // Retrieve the infissi id of the current stanza and add them to the stanza
-                        // dto
-                        let infissi_id = infissi
-                            .iter()
-                            .flat_map(|infisso| {
-                                std::iter::repeat_n(
-                                    infisso.infisso_id.clone(),
-                                    infisso.num_infisso as usize,
-                                )
-                            })
-                            .collect();
-
-                        stanza_dto.infissi = Some(infissi_id);
-                    }
+                // Retrieve only the infissi of the current stanza
+                let infissi: Vec<&StanzaConInfissi> = infissi
+                    .iter()
+                    .filter(|x| x.stanza_id == (stanza_dto.id as i32))
+                    .collect();
+
+                if infissi.is_empty() {
+                    continue;
+                }
 
-                    Ok(stanze_dto)
-                })
-                .map_err(|e| e.into())
+                // Retrieve the infissi id of the current stanza and add them to the stanza
+                // dto
+                let infissi_id = infissi
+                    .iter()
+                    .flat_map(|infisso| {
+                        std::iter::repeat_n(
+                            infisso.infisso_id.clone(),
+                            infisso.num_infisso as usize,
+                        )
+                    })
+                    .collect();
+
+                stanza_dto.infissi = Some(infissi_id);
             }
-            _ => Err(
-                DomainError::InvalidInput(ErrorKind::InvalidField, where_field.to_string()).into(),
-            ),
-        }
+
+            Ok(stanze_dto)
+        })
+        .map_err(|e| e.into())
     }
 }
 
@@ -102,7 +98,11 @@ impl RetrieveByEdificioSelected<StanzaDTO> for StanzaService {
             None => return Err(ApplicationError::EdificioNotSelected),
         };
 
-        Self::retrieve_by(db_state, "edificio", &edificio_id).await
+        Self::retrieve_by(
+            db_state,
+            StanzaQuery::new().filter(StanzaFilter::Edificio(edificio_id)),
+        )
+        .await
     }
 }
 
@@ -144,8 +144,32 @@ impl UpdateService<StanzaDTO> for StanzaService {
 
         conn.transaction::<_, DomainError, _>(|tx| {
             let updated_stanza = StanzaDAO::update(tx, item.id as i32, item.clone().into())?;
+            let pareti_esposte = match item.pareti_esposte {
+                Some(pareti) => {
+                    // Replace the saved walls with the new ones
+                    PareteEspostaDAO::delete(tx, updated_stanza.id)?;
+                    let pareti = pareti
+                        .into_iter()
+                        .map(|parete| parete.into_model(updated_stanza.id))
+                        .collect::<Vec<_>>();
+                    let inserted = if pareti.is_empty() {
+                        pareti
+                    } else {
+                        PareteEspostaDAO::insert(tx, pareti)?
+                    };
+                    Some(
+                        inserted
+                            .iter()
+                            .map(PareteEspostaDTO::try_from)
+                            .collect::<Result<Vec<_>, _>>()?,
+                    )
+                }
+                None => None,
+            };
             if item.infissi.is_none() {
-                return Ok(StanzaDTO::from(&updated_stanza));
+                let mut stanza_dto = StanzaDTO::from(&updated_stanza);
+                stanza_dto.pareti_esposte = pareti_esposte;
+                return Ok(stanza_dto);
             }
 
             // Collect the infissi and count them by infisso
@@ -169,8 +193,9 @@ impl UpdateService<StanzaDTO> for StanzaService {
                     .entry(infisso)
                     .and_modify(|value| *value = result.num_infisso);
             }
-            // Create the stanza dto and set the infissi field
+            // Create the stanza dto and set the infissi and walls fields
             let mut stanza_dto = StanzaDTO::from(&updated_stanza);
+            stanza_dto.pareti_esposte = pareti_esposte;
             stanza_dto.infissi = Some(
                 count_infissi
                     .iter()
@@ -185,6 +210,24 @@ impl UpdateService<StanzaDTO> for StanzaService {
     }
 }
 
+#[async_trait]
+impl DeleteService<StanzaDTO, u64> for StanzaService {
+    async fn delete(
+        db: State<'_, impl DatabaseManagerTrait + Send + Sync>,
+        id: u64,
+    ) -> AppResult<bool> {
+        let mut conn = db.get_connection().await?;
+
+        conn.transaction::<_, DomainError, _>(|tx| {
+            // Detach the infissi before removing the stanza
+            StanzaConInfissiDao::delete(tx, id as i32)?;
+            StanzaDAO::delete(tx, id as i32)?;
+            Ok(true)
+        })
+        .map_err(|e| e.into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use app_state::{database::DatabaseManager, selected_edificio::EdificioSelected};
@@ -201,7 +244,7 @@ mod tests {
     use super::*;
     use crate::{
         dao::{EdificioDAO, InfissoDAO, StanzaDAO},
-        dto::{EdificioDTO, InfissoDTO, StanzaDTO},
+        dto::{EdificioDTO, InfissoDTO, Orientamento, StanzaDTO},
     };
 
     const SELECTED_EDIFICIO_ID: &str = "6192-81";
@@ -273,10 +316,13 @@ mod tests {
             destinazione_uso: "Bagno".to_string(),
             altezza: None,
             spessore_muro: None,
+            superficie: None,
+            perimetro: None,
             riscaldamento: None,
             raffrescamento: None,
             illuminazione: None,
             infissi: None,
+            pareti_esposte: None,
         };
 
         match StanzaService::create(state_db, stanza_dto).await {
@@ -304,10 +350,13 @@ mod tests {
             destinazione_uso: "Bagno d'epoca".to_string(),
             altezza: Some(25u16),
             spessore_muro: None,
+            superficie: None,
+            perimetro: None,
             riscaldamento: Some("Ventilconvettori".to_string()),
             raffrescamento: None,
             illuminazione: None,
             infissi: None,
+            pareti_esposte: None,
         };
 
         match StanzaService::update(state_db, stanza_dto).await {
@@ -322,6 +371,59 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn test_update_stanza_with_pareti_esposte() -> ResultTest {
+        let env = setup_env_stanze().await?;
+        let state_db = env.database();
+        let selected_edificio = env.state_app::<SelectedEdificioState<EdificioSelected>>();
+
+        let parete = |orientamento, lunghezza| PareteEspostaDTO {
+            orientamento,
+            lunghezza,
+        };
+        let stanza_dto = StanzaDTO {
+            id: 50,
+            edificio_id: "6192-81".to_string(),
+            piano: "1".to_string(),
+            id_spazio: "SP050".to_string(),
+            cod_stanza: "ST050".to_string(),
+            destinazione_uso: "Bagno d'epoca".to_string(),
+            altezza: Some(300u16),
+            spessore_muro: None,
+            superficie: Some(18.5),
+            perimetro: Some(17.2),
+            riscaldamento: None,
+            raffrescamento: None,
+            illuminazione: None,
+            infissi: None,
+            pareti_esposte: Some(vec![
+                parete(Orientamento::N, 4.5),
+                parete(Orientamento::E, 3.8),
+            ]),
+        };
+
+        let updated = StanzaService::update(state_db.clone(), stanza_dto.clone()).await?;
+        assert_eq!(updated.superficie, Some(18.5));
+        assert_eq!(updated.pareti_esposte.as_ref().map(Vec::len), Some(2));
+
+        // the walls are replaced, not added
+        let stanza_dto = StanzaDTO {
+            pareti_esposte: Some(vec![parete(Orientamento::S, 6.0)]),
+            ..stanza_dto
+        };
+        StanzaService::update(state_db.clone(), stanza_dto).await?;
+
+        let stanze =
+            StanzaService::retrieve_by_edificio_selected(state_db, selected_edificio).await?;
+        let stanza = stanze.iter().find(|stanza| stanza.id == 50).unwrap();
+        assert_eq!(
+            stanza.pareti_esposte,
+            Some(vec![parete(Orientamento::S, 6.0)])
+        );
+
+        Ok(())
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
     async fn test_update_stanza_with_infissi() -> ResultTest {
         let env = setup_env_stanze().await?;
@@ -336,6 +438,8 @@ mod tests {
             destinazione_uso: "Bagno d'epoca".to_string(),
             altezza: Some(25u16),
             spessore_muro: None,
+            superficie: None,
+            perimetro: None,
             riscaldamento: Some("Ventilconvettori".to_string()),
             raffrescamento: None,
             illuminazione: None,
@@ -345,6 +449,7 @@ mod tests {
                 "B".to_string(),
                 "B".to_string(),
             ]),
+            pareti_esposte: None,
         };
 
         match StanzaService::update(state_db, stanza_dto).await {
@@ -369,4 +474,28 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn test_delete_stanza() -> ResultTest {
+        let env = setup_env_stanze().await?;
+        let state_db = env.database();
+        let selected_edificio = env.state_app::<SelectedEdificioState<EdificioSelected>>();
+
+        match StanzaService::delete(state_db.clone(), 46).await {
+            Ok(result) => assert!(result),
+            Err(e) => panic!("{:?}", e),
+        }
+
+        let stanze =
+            StanzaService::retrieve_by_edificio_selected(state_db.clone(), selected_edificio)
+                .await?;
+        assert_eq!(stanze.len(), 4);
+        assert!(stanze.iter().all(|stanza| stanza.id != 46));
+
+        match StanzaService::delete(state_db, 46).await {
+            Err(ApplicationError::Domain(DomainError::StanzaNotFound)) => {}
+            other => panic!("Expected StanzaNotFound, got {:?}", other),
+        }
+        Ok(())
+    }
 }
@@ -1,9 +1,10 @@
 use std::collections::HashMap;
 
+use app_models::models::{UpdateMaterialeInfisso, UpdateVetroInfisso};
 use app_utils::{
-    app_error::{AppResult, ApplicationError},
+    app_error::{AppResult, ApplicationError, ErrorKind},
     app_interface::{
-        dao_interface::crud_operations::{GetAll, Insert},
+        dao_interface::crud_operations::{Delete, GetAll, Insert, Update},
         database_interface::DatabaseManagerTrait,
         dto_interface::DTO,
         service_interface::RetrieveManyService,
@@ -19,8 +20,8 @@ use crate::{
         ClimatizzazioneDAO, IlluminazioneDAO, MaterialeInfissoDAO, TipoInfissoDAO, VetroInfissoDAO,
     },
     dto::{
-        ClimatizzazioneDTO, IlluminazioneDTO, MaterialeInfissoDTO, TipoDTO, TipoInfissiDTO,
-        VetroInfissoDTO,
+        CatalogoInfisso, ClimatizzazioneDTO, IlluminazioneDTO, MaterialeInfissoDTO, TipoDTO,
+        TipoInfissiDTO, TrasmittanzaDTO, VetroInfissoDTO,
     },
     service::DomainError,
 };
@@ -72,6 +73,15 @@ pub trait TypeService {
         db: State<'_, impl DatabaseManagerTrait + Send + Sync>,
         dto: TipoDTO,
     ) -> Result<TipoDTO, ApplicationError>;
+    async fn delete_type(
+        db: State<'_, impl DatabaseManagerTrait + Send + Sync>,
+        tipo: TypeDTO,
+        name: String,
+    ) -> Result<bool, ApplicationError>;
+    async fn update_trasmittanza(
+        db: State<'_, impl DatabaseManagerTrait + Send + Sync>,
+        dto: TrasmittanzaDTO,
+    ) -> AppResult<TrasmittanzaDTO>;
 }
 
 pub struct TypeServiceImpl;
@@ -119,6 +129,49 @@ impl TypeService for TypeServiceImpl {
             }
         }
     }
+
+    async fn delete_type(
+        db: State<'_, impl DatabaseManagerTrait + Send + Sync>,
+        tipo: TypeDTO,
+        name: String,
+    ) -> Result<bool, ApplicationError> {
+        let mut conn = db.get_connection().await?;
+        match tipo {
+            TypeDTO::Climatizzazione => {
+                ClimatizzazioneDAO::delete(&mut conn, name)?;
+            }
+            TypeDTO::Illuminazione => {
+                IlluminazioneDAO::delete(&mut conn, name)?;
+            }
+        }
+        Ok(true)
+    }
+
+    async fn update_trasmittanza(
+        db: State<'_, impl DatabaseManagerTrait + Send + Sync>,
+        dto: TrasmittanzaDTO,
+    ) -> AppResult<TrasmittanzaDTO> {
+        if dto
+            .valore
+            .is_some_and(|valore| !valore.is_finite() || valore <= 0.0)
+        {
+            return Err(
+                DomainError::InvalidInput(ErrorKind::InvalidField, "valore".to_string()).into(),
+            );
+        }
+        let mut conn = db.get_connection().await?;
+        let valore = match dto.catalogo {
+            CatalogoInfisso::Materiale => {
+                let update = UpdateMaterialeInfisso { uf: dto.valore };
+                MaterialeInfissoDAO::update(&mut conn, dto.nome.as_str(), update)?.uf
+            }
+            CatalogoInfisso::Vetro => {
+                let update = UpdateVetroInfisso { ug: dto.valore };
+                VetroInfissoDAO::update(&mut conn, dto.nome.as_str(), update)?.ug
+            }
+        };
+        Ok(TrasmittanzaDTO { valore, ..dto })
+    }
 }
 
 struct MaterialeInfissoService;
@@ -136,6 +189,7 @@ impl RetrieveManyService<MaterialeInfissoDTO> for MaterialeInfissoService {
             .map(|x| MaterialeInfissoDTO {
                 materiale: x.materiale.clone(),
                 efficienza_energetica: x.eff_energetica as u8,
+                uf: x.uf,
             })
             .collect())
     }
@@ -156,6 +210,7 @@ impl RetrieveManyService<VetroInfissoDTO> for VetroInfissoService {
             .map(|x| VetroInfissoDTO {
                 vetro: x.vetro.clone(),
                 efficienza_energetica: x.eff_energetica as u8,
+                ug: x.ug,
             })
             .collect())
     }
@@ -222,18 +277,48 @@ impl RetrieveManyService<TipoInfissiDTO> for TipoInfissoService {
 
 #[cfg(test)]
 mod test {
+    use app_models::models::NewStanza;
     use app_state::database::DatabaseManager;
-    use app_utils::test::{ResultTest, TestServiceEnvironment};
+    use app_utils::{
+        app_error::{ApplicationError, DomainError},
+        app_interface::{
+            dao_interface::crud_operations::Insert, database_interface::DatabaseManagerTrait,
+            service_interface::UpdateService,
+        },
+        path_data_fake,
+        test::{ResultTest, TestServiceEnvironment, utils::read_json_file},
+    };
 
     use crate::{
-        dto::TipoDTO,
-        service::{TypeDTO, TypeService, TypeServiceImpl},
+        dao::{EdificioDAO, StanzaDAO},
+        dto::{CatalogoInfisso, EdificioDTO, StanzaDTO, TipoDTO, TrasmittanzaDTO},
+        service::{StanzaService, TypeDTO, TypeService, TypeServiceImpl},
     };
 
     async fn setup_env_type() -> ResultTest<TestServiceEnvironment<DatabaseManager>> {
         TestServiceEnvironment::new::<_, _>(|_db_manager: DatabaseManager| async { Ok(()) }).await
     }
 
+    async fn setup_env_type_with_stanze() -> ResultTest<TestServiceEnvironment<DatabaseManager>> {
+        TestServiceEnvironment::new::<_, _>(|db_manager: DatabaseManager| async move {
+            let edifici_dto =
+                read_json_file::<EdificioDTO>(path_data_fake!("edificiFake").as_str())?;
+            let stanze_dto = read_json_file::<StanzaDTO>(path_data_fake!("stanzeFake").as_str())?;
+            {
+                let mut conn = db_manager.get_connection().await?;
+                for edificio_dto in edifici_dto {
+                    let _ = EdificioDAO::insert(&mut conn, edificio_dto.into());
+                }
+                for stanza_dto in stanze_dto {
+                    let new_stanza: NewStanza = stanza_dto.into();
+                    let _ = StanzaDAO::insert(&mut conn, new_stanza);
+                }
+            }
+            Ok(())
+        })
+        .await
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
     async fn test_retrieve_type() -> ResultTest {
         let env = setup_env_type().await?;
@@ -296,4 +381,95 @@ mod test {
         let type_str = "MaterialeInfisso".to_string();
         TypeDTO::try_from(type_str).unwrap();
     }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn test_update_trasmittanza() -> ResultTest {
+        let env = setup_env_type().await?;
+        let state_db = env.database();
+
+        let trasmittanza = |catalogo, nome: &str, valore| TrasmittanzaDTO {
+            catalogo,
+            nome: nome.to_string(),
+            valore,
+        };
+
+        let updated = TypeServiceImpl::update_trasmittanza(
+            state_db.clone(),
+            trasmittanza(CatalogoInfisso::Vetro, "Doppio", Some(2.9)),
+        )
+        .await?;
+        assert_eq!(updated.valore, Some(2.9));
+
+        let cleared = TypeServiceImpl::update_trasmittanza(
+            state_db.clone(),
+            trasmittanza(CatalogoInfisso::Materiale, "PVC", None),
+        )
+        .await?;
+        assert_eq!(cleared.valore, None);
+
+        match TypeServiceImpl::update_trasmittanza(
+            state_db.clone(),
+            trasmittanza(CatalogoInfisso::Materiale, "Carta", Some(1.0)),
+        )
+        .await
+        {
+            Err(ApplicationError::Domain(DomainError::MaterialeInfissoNotFound)) => {}
+            other => panic!("Expected MaterialeInfissoNotFound, got {:?}", other),
+        }
+        assert!(
+            TypeServiceImpl::update_trasmittanza(
+                state_db,
+                trasmittanza(CatalogoInfisso::Vetro, "Doppio", Some(-1.0)),
+            )
+            .await
+            .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn test_delete_illuminazione_type() -> ResultTest {
+        let env = setup_env_type_with_stanze().await?;
+        let state_db = env.database();
+
+        let stanza = StanzaDTO {
+            id: 1,
+            edificio_id: "8361-122".to_string(),
+            piano: "T".to_string(),
+            id_spazio: "SP001".to_string(),
+            cod_stanza: "ST001".to_string(),
+            destinazione_uso: "Soggiorno".to_string(),
+            altezza: None,
+            spessore_muro: None,
+            superficie: None,
+            perimetro: None,
+            riscaldamento: None,
+            raffrescamento: None,
+            illuminazione: Some("Led".to_string()),
+            infissi: None,
+            pareti_esposte: None,
+        };
+        StanzaService::update(state_db.clone(), stanza).await?;
+
+        match TypeServiceImpl::delete_type(
+            state_db.clone(),
+            TypeDTO::Illuminazione,
+            "Led".to_string(),
+        )
+        .await
+        {
+            Err(ApplicationError::Domain(DomainError::IlluminazioneStillReferenced)) => {}
+            other => panic!("Expected IlluminazioneStillReferenced, got {:?}", other),
+        }
+
+        match TypeServiceImpl::delete_type(state_db, TypeDTO::Illuminazione, "Neon".to_string())
+            .await
+        {
+            Ok(result) => assert!(result),
+            Err(e) => panic!("{:?}", e),
+        }
+
+        Ok(())
+    }
 }
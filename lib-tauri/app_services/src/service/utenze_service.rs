@@ -2,20 +2,24 @@ use std::ops::Deref;
 
 use app_state::selected_edificio::SelectedEdificioTrait;
 use app_utils::{
-    app_error::{AppResult, ApplicationError, ErrorKind},
+    app_error::{AppResult, ApplicationError},
     app_interface::{
-        dao_interface::crud_operations::{Get, GetAll, Insert},
+        dao_interface::crud_operations::{Delete, Get, GetAll, Insert, Update},
         database_interface::DatabaseManagerTrait,
         service_interface::{
-            CreateService, RetrieveBy, RetrieveByEdificioSelected, RetrieveManyService,
-            SelectedEdificioState,
+            CreateService, DeleteService, RetrieveBy, RetrieveByEdificioSelected,
+            RetrieveManyService, SelectedEdificioState, UpdateService,
         },
     },
 };
 use async_trait::async_trait;
 use tauri::State;
 
-use crate::{dao::UtenzeDAO, dto::UtenzaDTO, service::DomainError};
+use crate::{
+    dao::UtenzeDAO,
+    dto::UtenzaDTO,
+    query::{UtenzaFilter, UtenzaQuery},
+};
 
 pub struct UtenzeService;
 
@@ -33,25 +37,14 @@ impl RetrieveManyService<UtenzaDTO> for UtenzeService {
 #[async_trait]
 impl RetrieveBy<UtenzaDTO> for UtenzeService {
     type Output = Vec<UtenzaDTO>;
+    type Query = UtenzaQuery;
 
     async fn retrieve_by(
         db_state: State<'_, impl DatabaseManagerTrait + Send + Sync>,
-        where_field: &str,
-        where_value: &str,
+        query: UtenzaQuery,
     ) -> AppResult<Self::Output> {
         let mut conn = db_state.get_connection().await?;
-
-        let result = match where_field {
-            "edificio" => UtenzeDAO::get(&mut conn, where_value.to_string())?,
-            _ => {
-                return Err(DomainError::InvalidInput(
-                    ErrorKind::InvalidField,
-                    where_field.to_string(),
-                )
-                .into());
-            }
-        };
-
+        let result = UtenzeDAO::get(&mut conn, &query)?;
         Ok(result.iter().map(UtenzaDTO::from).collect())
     }
 }
@@ -66,11 +59,15 @@ impl RetrieveByEdificioSelected<UtenzaDTO> for UtenzeService {
         S: SelectedEdificioTrait + Send + Sync,
     {
         let edificio_selected = edificio_selected_state.read().await.deref().get_chiave();
-        if edificio_selected.is_none() {
+        let Some(edificio_id) = edificio_selected else {
             return Err(ApplicationError::EdificioNotSelected);
-        }
+        };
 
-        Self::retrieve_by(db_state, "edificio", edificio_selected.unwrap().deref()).await
+        Self::retrieve_by(
+            db_state,
+            UtenzaQuery::new().filter(UtenzaFilter::Edificio(edificio_id)),
+        )
+        .await
     }
 }
 
@@ -86,6 +83,30 @@ impl CreateService<UtenzaDTO> for UtenzeService {
     }
 }
 
+#[async_trait]
+impl UpdateService<UtenzaDTO> for UtenzeService {
+    async fn update(
+        db: State<'_, impl DatabaseManagerTrait + Send + Sync>,
+        utenza: UtenzaDTO,
+    ) -> AppResult<UtenzaDTO> {
+        let mut conn = db.get_connection().await?;
+        let utenza = UtenzeDAO::update(&mut conn, utenza.id as i32, utenza.into())?;
+        Ok(UtenzaDTO::from(&utenza))
+    }
+}
+
+#[async_trait]
+impl DeleteService<UtenzaDTO, u64> for UtenzeService {
+    async fn delete(
+        db: State<'_, impl DatabaseManagerTrait + Send + Sync>,
+        id: u64,
+    ) -> AppResult<bool> {
+        let mut conn = db.get_connection().await?;
+        UtenzeDAO::delete(&mut conn, id as i32)?;
+        Ok(true)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::ops::Deref;
@@ -170,6 +191,7 @@ mod tests {
             tipo: TipoUtenza::Acqua,
             cod_contatore: "TEST-COD-ACQUA".to_string(),
             indirizzo_contatore: None,
+            consumo_annuo: None,
         };
 
         match UtenzeService::create(state_db, insert_utenza).await {
@@ -182,4 +204,22 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn test_update_utenze() -> ResultTest {
+        let env = setup_utenze_env().await?;
+
+        let mut utenza = UtenzeService::retrieve_many(env.database())
+            .await?
+            .remove(0);
+        utenza.indirizzo_contatore = None;
+        utenza.consumo_annuo = Some(1500.0);
+
+        let result = UtenzeService::update(env.database(), utenza.clone()).await?;
+        assert_eq!(result.id, utenza.id);
+        assert_eq!(result.indirizzo_contatore, None);
+        assert_eq!(result.consumo_annuo, Some(1500.0));
+
+        Ok(())
+    }
 }
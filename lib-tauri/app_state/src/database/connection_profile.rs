@@ -0,0 +1,157 @@
+use std::time::Duration;
+
+use app_utils::{
+    app_error::database_error::DbError, app_interface::database_interface::PostgresPool,
+};
+use diesel::{
+    PgConnection,
+    r2d2::{ConnectionManager, Pool},
+};
+use serde::{Deserialize, Serialize};
+
+/// Attesa massima per stabilire una connessione
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
+
+fn default_pool_size() -> u32 { 10 }
+
+/// Parametri di connessione a un database PostgreSQL, salvati dall'utente
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConnectionProfile {
+    pub name: String,
+    pub host: String,
+    pub port: u16,
+    pub database: String,
+    pub user: String,
+    pub password: String,
+    #[serde(default = "default_pool_size")]
+    pub pool_size: u32,
+}
+
+impl ConnectionProfile {
+    /// Stringa di connessione nel formato chiave/valore di libpq, che non
+    /// richiede di codificare i caratteri speciali della password
+    pub fn connection_string(&self) -> String {
+        let quote = |value: &str| format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"));
+        format!(
+            "host={} port={} dbname={} user={} password={}",
+            quote(&self.host),
+            self.port,
+            quote(&self.database),
+            quote(&self.user),
+            quote(&self.password)
+        )
+    }
+
+    /// Indica se i due profili si collegano allo stesso database con lo
+    /// stesso utente
+    pub fn same_server(&self, other: &ConnectionProfile) -> bool {
+        self.host == other.host
+            && self.port == other.port
+            && self.database == other.database
+            && self.user == other.user
+    }
+
+    pub fn validate(&self) -> Result<(), DbError> {
+        let missing: Vec<&str> = [
+            ("name", &self.name),
+            ("host", &self.host),
+            ("database", &self.database),
+            ("user", &self.user),
+        ]
+        .into_iter()
+        .filter_map(|(field, value)| value.trim().is_empty().then_some(field))
+        .collect();
+        if !missing.is_empty() {
+            return Err(DbError::ConfigurationError(format!(
+                "missing {}",
+                missing.join(", ")
+            )));
+        }
+        if self.pool_size == 0 {
+            return Err(DbError::ConfigurationError(
+                "pool size must be greater than 0".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Crea il pool di connessioni. Bloccante: attende che le connessioni
+    /// iniziali siano stabilite o che scada il timeout.
+    pub fn build_pool(&self) -> Result<PostgresPool, DbError> {
+        self.validate()?;
+        let manager = ConnectionManager::<PgConnection>::new(self.connection_string());
+        Pool::builder()
+            .max_size(self.pool_size)
+            .connection_timeout(CONNECTION_TIMEOUT)
+            .build(manager)
+            .map_err(|e| DbError::ConnectionPoolError(e.to_string()))
+    }
+
+    /// Verifica che il database sia raggiungibile con una sola connessione
+    pub fn test(&self) -> Result<(), DbError> {
+        Self {
+            pool_size: 1,
+            ..self.clone()
+        }
+        .build_pool()?
+        .get()
+        .map(|_| ())
+        .map_err(|e| DbError::ConnectionPoolError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile() -> ConnectionProfile {
+        ConnectionProfile {
+            name: "Ufficio".to_string(),
+            host: "localhost".to_string(),
+            port: 5432,
+            database: "sopralluogo".to_string(),
+            user: "postgres".to_string(),
+            password: "p@ss 'word'".to_string(),
+            pool_size: 10,
+        }
+    }
+
+    #[test]
+    fn test_connection_string() {
+        assert_eq!(
+            profile().connection_string(),
+            "host='localhost' port=5432 dbname='sopralluogo' user='postgres' password='p@ss \\'word\\''"
+        );
+    }
+
+    #[test]
+    fn test_same_server() {
+        let renamed = ConnectionProfile {
+            name: "Casa".to_string(),
+            password: String::new(),
+            pool_size: 2,
+            ..profile()
+        };
+        assert!(profile().same_server(&renamed));
+        let other_host = ConnectionProfile {
+            host: "db.example.com".to_string(),
+            ..profile()
+        };
+        assert!(!profile().same_server(&other_host));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(profile().validate().is_ok());
+
+        let invalid = ConnectionProfile {
+            host: " ".to_string(),
+            user: String::new(),
+            ..profile()
+        };
+        assert_eq!(
+            invalid.validate().unwrap_err().to_string(),
+            "Configuration error: missing host, user"
+        );
+    }
+}
@@ -1,3 +1,7 @@
+mod connection_profile;
 pub mod database_manager;
+mod migrations;
 
+pub use connection_profile::ConnectionProfile;
 pub use database_manager::DatabaseManager;
+pub use migrations::{MigrationReport, SchemaState};
@@ -0,0 +1,53 @@
+use async_trait::async_trait;
+use chrono::{Local, NaiveDateTime};
+
+/// Orologio usato dal manager per pianificare i task
+#[async_trait]
+pub trait Clock: Send + Sync {
+    fn now(&self) -> NaiveDateTime;
+
+    async fn sleep_until(&self, deadline: NaiveDateTime);
+}
+
+/// Ora locale del sistema
+pub struct SystemClock;
+
+#[async_trait]
+impl Clock for SystemClock {
+    fn now(&self) -> NaiveDateTime { Local::now().naive_local() }
+
+    async fn sleep_until(&self, deadline: NaiveDateTime) {
+        let delay = (deadline - self.now()).to_std().unwrap_or_default();
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Orologio che avanza solo con `advance`, per i test
+#[cfg(test)]
+pub(crate) struct ManualClock {
+    now: tokio::sync::watch::Sender<NaiveDateTime>,
+}
+
+#[cfg(test)]
+impl ManualClock {
+    pub(crate) fn new(now: NaiveDateTime) -> Self {
+        Self {
+            now: tokio::sync::watch::Sender::new(now),
+        }
+    }
+
+    pub(crate) fn advance(&self, duration: chrono::TimeDelta) {
+        self.now.send_modify(|now| *now += duration);
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl Clock for ManualClock {
+    fn now(&self) -> NaiveDateTime { *self.now.borrow() }
+
+    async fn sleep_until(&self, deadline: NaiveDateTime) {
+        let mut receiver = self.now.subscribe();
+        let _ = receiver.wait_for(|now| *now >= deadline).await;
+    }
+}
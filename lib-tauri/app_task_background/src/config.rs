@@ -0,0 +1,97 @@
+use std::{collections::HashMap, time::Duration};
+
+use app_utils::{app_error::ErrorTask, store::JsonStore};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+
+use crate::{ResultTask, Schedule};
+
+/// Configurazione di un task letta all'avvio. I campi non valorizzati
+/// mantengono i valori indicati alla registrazione.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TaskConfig {
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    /// Equivale a una pianificazione `Interval`, ignorato se è indicata
+    /// `schedule`
+    #[serde(default)]
+    pub interval_secs: Option<u64>,
+    #[serde(default)]
+    pub schedule: Option<Schedule>,
+    #[serde(default)]
+    pub retry: Option<RetryPolicy>,
+}
+
+/// Tentativi eseguiti quando un task termina con errore. L'attesa prima di
+/// ogni nuovo tentativo raddoppia a partire da `initial_backoff_secs`, fino a
+/// `max_backoff_secs`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct RetryPolicy {
+    /// Numero massimo di esecuzioni, compresa la prima
+    pub max_attempts: u32,
+    pub initial_backoff_secs: u64,
+    pub max_backoff_secs: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff_secs: 5,
+            max_backoff_secs: 300,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Nessun nuovo tentativo dopo un errore
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Attesa prima del tentativo successivo a `attempt`
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u64.saturating_pow(attempt.saturating_sub(1));
+        Duration::from_secs(
+            self.initial_backoff_secs
+                .saturating_mul(factor)
+                .min(self.max_backoff_secs),
+        )
+    }
+}
+
+/// Configurazione dei task salvata nello store dell'applicazione, indicizzata
+/// per nome del task
+pub struct TaskConfigStore;
+
+impl TaskConfigStore {
+    const STORE: JsonStore = JsonStore::new("background_tasks.json", "tasks");
+
+    pub fn load<R: Runtime>(app_handle: &AppHandle<R>) -> ResultTask<HashMap<String, TaskConfig>> {
+        Ok(Self::STORE
+            .load(app_handle, |e| ErrorTask::InvalidConfig(e.to_string()))?
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            initial_backoff_secs: 5,
+            max_backoff_secs: 60,
+        };
+        let delays: Vec<u64> = (1..=6)
+            .map(|attempt| policy.backoff(attempt).as_secs())
+            .collect();
+        assert_eq!(delays, vec![5, 10, 20, 40, 60, 60]);
+        assert_eq!(policy.backoff(u32::MAX).as_secs(), 60);
+    }
+}
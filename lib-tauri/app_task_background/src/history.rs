@@ -0,0 +1,70 @@
+use app_utils::{app_error::ErrorTask, store::JsonStore};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+
+use crate::{ResultTask, StatusTask};
+
+/// Esito di un'esecuzione di un task
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskRun {
+    pub task: String,
+    pub started_at: NaiveDateTime,
+    pub ended_at: NaiveDateTime,
+    pub outcome: StatusTask,
+    /// Tentativo dell'esecuzione, parte da 1 e aumenta a ogni retry
+    pub attempt: u32,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// Storico delle esecuzioni dei task
+pub trait RunHistory: Send + Sync {
+    fn record(&self, run: TaskRun) -> ResultTask;
+
+    /// Esecuzioni dalla più recente, eventualmente filtrate per task
+    fn load(&self, task_name: Option<&str>) -> ResultTask<Vec<TaskRun>>;
+}
+
+/// Storico salvato nello store dell'applicazione, limitato alle ultime
+/// `MAX_RUNS` esecuzioni
+pub struct TaskRunStore<R: Runtime> {
+    app_handle: AppHandle<R>,
+}
+
+impl<R: Runtime> TaskRunStore<R> {
+    const MAX_RUNS: usize = 500;
+    const STORE: JsonStore = JsonStore::new("background_tasks_history.json", "runs");
+
+    pub fn new(app_handle: AppHandle<R>) -> Self { Self { app_handle } }
+
+    fn runs(&self) -> ResultTask<Vec<TaskRun>> {
+        Ok(Self::STORE
+            .load(&self.app_handle, |e| {
+                ErrorTask::InvalidHistory(e.to_string())
+            })?
+            .unwrap_or_default())
+    }
+}
+
+impl<R: Runtime> RunHistory for TaskRunStore<R> {
+    fn record(&self, run: TaskRun) -> ResultTask {
+        let mut runs = self.runs()?;
+        runs.push(run);
+        if runs.len() > Self::MAX_RUNS {
+            runs.drain(..runs.len() - Self::MAX_RUNS);
+        }
+        Self::STORE.save(&self.app_handle, &runs, |e| {
+            ErrorTask::InvalidHistory(e.to_string())
+        })
+    }
+
+    fn load(&self, task_name: Option<&str>) -> ResultTask<Vec<TaskRun>> {
+        Ok(self
+            .runs()?
+            .into_iter()
+            .rev()
+            .filter(|run| task_name.is_none_or(|name| run.task == name))
+            .collect())
+    }
+}
@@ -1,3 +1,7 @@
+mod clock;
+mod config;
+mod history;
+mod schedule;
 mod task_manager;
 mod tasks;
 
@@ -7,7 +11,15 @@ use app_services::service::ApplicationError;
 use async_trait::async_trait;
 use tokio::sync::Mutex;
 
-use crate::task_manager::{BackgroundManager as InnerBackgroundManager, StatusTask, TaskInfo};
+use crate::task_manager::BackgroundManager as InnerBackgroundManager;
+pub use crate::{
+    clock::{Clock, SystemClock},
+    config::{RetryPolicy, TaskConfig, TaskConfigStore},
+    history::{RunHistory, TaskRun, TaskRunStore},
+    schedule::Schedule,
+    task_manager::{StatusListener, StatusTask, TaskInfo, TaskState},
+    tasks::{DataQualityScanner, IdStanzeProcessor, LocalBackupTask, QualityListener},
+};
 
 pub type BackgroundManager = Arc<Mutex<InnerBackgroundManager>>;
 type ResultTask<T = ()> = Result<T, ApplicationError>;
@@ -42,8 +54,10 @@ pub fn get_background_manager() -> BackgroundManager {
         .clone()
 }
 
+/// Task eseguito dal `BackgroundManager`. Nome, pianificazione e abilitazione
+/// sono quelli del `TaskInfo` restituito da `info`.
 #[async_trait]
-pub(crate) trait BackgroundTask: Send + Sync + 'static {
+pub trait BackgroundTask: Send + Sync + 'static {
     async fn run(&mut self) -> ResultTask<StatusTask>;
     fn info(&self) -> &TaskInfo;
     fn info_mut(&mut self) -> &mut TaskInfo;
@@ -0,0 +1,167 @@
+use std::str::FromStr;
+
+use app_utils::app_error::ErrorTask;
+use chrono::{NaiveDateTime, TimeDelta, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Attesa massima prima di ricontrollare un task `Idle` che è già stato
+/// eseguito dall'ultima attività dell'utente
+const IDLE_POLL_SECS: u64 = 60;
+
+/// Quando eseguire un task
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Schedule {
+    /// Ogni `secs` secondi, a partire dall'avvio
+    Interval { secs: u64 },
+    /// Espressione cron con i secondi (`sec min ora giorno mese giorno_sett
+    /// [anno]`), valutata sull'ora locale
+    Cron { expression: String },
+    /// Una sola volta all'avvio del manager
+    Startup,
+    /// Quando l'utente è inattivo da almeno `idle_secs` secondi, una volta per
+    /// ogni periodo di inattività
+    Idle { idle_secs: u64 },
+}
+
+/// Prossima esecuzione prevista da una pianificazione
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum NextRun {
+    At(NaiveDateTime),
+    /// Il task è già stato eseguito nel periodo di inattività corrente
+    WaitActivity,
+    Never,
+}
+
+impl Schedule {
+    pub fn validate(&self) -> Result<(), ErrorTask> {
+        match self {
+            Schedule::Interval { secs: 0 } | Schedule::Idle { idle_secs: 0 } => Err(
+                ErrorTask::InvalidSchedule("duration must be greater than 0".to_string()),
+            ),
+            Schedule::Cron { expression } => cron::Schedule::from_str(expression)
+                .map(|_| ())
+                .map_err(|e| ErrorTask::InvalidSchedule(format!("{expression}: {e}"))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Calcola la prossima esecuzione. `last_run` è l'ultima esecuzione dalla
+    /// partenza del loop del task.
+    pub(crate) fn next_run(
+        &self,
+        now: NaiveDateTime,
+        last_run: Option<NaiveDateTime>,
+        last_activity: NaiveDateTime,
+    ) -> NextRun {
+        match self {
+            Schedule::Interval { secs } => NextRun::At(now + seconds(*secs)),
+            Schedule::Cron { expression } => cron::Schedule::from_str(expression)
+                .ok()
+                .and_then(|schedule| {
+                    schedule
+                        .after(&Utc.from_utc_datetime(&now))
+                        .next()
+                        .map(|next| next.naive_utc())
+                })
+                .map_or(NextRun::Never, NextRun::At),
+            Schedule::Startup if last_run.is_none() => NextRun::At(now),
+            Schedule::Startup => NextRun::Never,
+            Schedule::Idle { .. } if last_run.is_some_and(|run| run >= last_activity) => {
+                NextRun::WaitActivity
+            }
+            Schedule::Idle { idle_secs } => {
+                NextRun::At((last_activity + seconds(*idle_secs)).max(now))
+            }
+        }
+    }
+
+    /// Indica se il task può partire ora. Solo i task `Idle` possono essere
+    /// rimandati, se nel frattempo l'utente è tornato attivo.
+    pub(crate) fn is_due(&self, now: NaiveDateTime, last_activity: NaiveDateTime) -> bool {
+        match self {
+            Schedule::Idle { idle_secs } => last_activity + seconds(*idle_secs) <= now,
+            _ => true,
+        }
+    }
+
+    /// Attesa prima di ricontrollare un task in attesa di attività
+    pub(crate) fn idle_poll(&self) -> TimeDelta {
+        match self {
+            Schedule::Idle { idle_secs } => seconds((*idle_secs).min(IDLE_POLL_SECS)),
+            _ => seconds(IDLE_POLL_SECS),
+        }
+    }
+}
+
+fn seconds(secs: u64) -> TimeDelta { TimeDelta::seconds(i64::try_from(secs).unwrap_or(i64::MAX)) }
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn at(hour: u32, min: u32, sec: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 3, 10)
+            .unwrap()
+            .and_hms_opt(hour, min, sec)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_next_run() {
+        let now = at(10, 0, 0);
+        assert_eq!(
+            Schedule::Interval { secs: 90 }.next_run(now, None, now),
+            NextRun::At(at(10, 1, 30))
+        );
+
+        let cron = Schedule::Cron {
+            expression: "0 30 2 * * *".to_string(),
+        };
+        assert_eq!(
+            cron.next_run(now, None, now),
+            NextRun::At(at(2, 30, 0) + TimeDelta::days(1))
+        );
+
+        assert_eq!(Schedule::Startup.next_run(now, None, now), NextRun::At(now));
+        assert_eq!(
+            Schedule::Startup.next_run(now, Some(now), now),
+            NextRun::Never
+        );
+
+        let idle = Schedule::Idle { idle_secs: 300 };
+        let activity = at(9, 58, 0);
+        assert_eq!(
+            idle.next_run(now, None, activity),
+            NextRun::At(at(10, 3, 0))
+        );
+        assert_eq!(
+            idle.next_run(now, Some(at(9, 59, 0)), activity),
+            NextRun::WaitActivity
+        );
+        assert!(!idle.is_due(at(10, 2, 59), activity));
+        assert!(idle.is_due(at(10, 3, 0), activity));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(Schedule::Interval { secs: 10 }.validate().is_ok());
+        assert!(Schedule::Interval { secs: 0 }.validate().is_err());
+        assert!(
+            Schedule::Cron {
+                expression: "0 0 * * * *".to_string()
+            }
+            .validate()
+            .is_ok()
+        );
+        assert!(
+            Schedule::Cron {
+                expression: "every day".to_string()
+            }
+            .validate()
+            .is_err()
+        );
+    }
+}
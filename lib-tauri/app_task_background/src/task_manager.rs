@@ -1,21 +1,28 @@
 use std::{
     cmp::PartialEq,
+    collections::HashMap,
     fmt::{Display, Formatter},
-    sync::{
-        Arc,
-        atomic::{AtomicBool, Ordering},
-    },
+    sync::{Arc, Mutex, PoisonError, RwLock},
     time::Duration,
 };
 
-use log::{error, info};
-use tauri::AppHandle;
-use tokio::{sync::RwLock, task::JoinHandle, time::interval};
+use app_utils::app_error::{ApplicationError, ErrorTask};
+use chrono::{NaiveDateTime, TimeDelta};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+use tokio::{
+    sync::{Mutex as TaskMutex, watch},
+    task::JoinHandle,
+};
 
-use crate::{BackgroundTask, ResultTask, tasks};
+use crate::{
+    BackgroundTask, Clock, ResultTask, RetryPolicy, RunHistory, Schedule, SystemClock, TaskConfig,
+    TaskConfigStore, TaskRun, TaskRunStore, schedule::NextRun,
+};
 
-#[derive(Default, Clone, Copy, PartialEq, Debug)]
-pub(crate) enum StatusTask {
+#[derive(Default, Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum StatusTask {
     #[default]
     /// Task waiting to be started
     Pending,
@@ -50,76 +57,314 @@ impl Display for StatusTask {
     }
 }
 
-#[derive(Debug)]
-pub(crate) struct TaskInfo {
+#[derive(Debug, Clone)]
+pub struct TaskInfo {
     name: String,
-    interval: Duration,
+    schedule: Schedule,
+    enabled: bool,
+    retry: RetryPolicy,
     status: StatusTask,
+    last_run: Option<NaiveDateTime>,
+    next_run: Option<NaiveDateTime>,
+    last_error: Option<String>,
 }
 
 impl Default for TaskInfo {
-    fn default() -> Self {
-        Self {
-            name: "".to_string(),
-            interval: Duration::from_secs(60),
-            status: StatusTask::Pending,
-        }
-    }
+    fn default() -> Self { Self::new("".to_string(), Schedule::Interval { secs: 60 }) }
 }
 
 impl TaskInfo {
-    pub fn new(name: String, interval: Option<Duration>) -> Self {
+    pub fn new(name: String, schedule: Schedule) -> Self {
         Self {
             name,
-            interval: interval.unwrap_or(Duration::from_secs(60)),
+            schedule,
+            enabled: true,
+            retry: RetryPolicy::default(),
             status: StatusTask::Pending,
+            last_run: None,
+            next_run: None,
+            last_error: None,
         }
     }
 
+    /// Indica se il task viene avviato insieme al manager
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
     #[inline]
     pub fn name(&self) -> String { self.name.clone() }
 
     #[inline]
-    pub fn interval(&self) -> Duration { self.interval }
+    pub fn schedule(&self) -> &Schedule { &self.schedule }
+
+    #[inline]
+    pub fn enabled(&self) -> bool { self.enabled }
+
+    #[inline]
+    pub fn retry(&self) -> RetryPolicy { self.retry }
 
     #[inline]
-    pub(crate) fn status(&self) -> StatusTask { self.status }
+    pub fn status(&self) -> StatusTask { self.status }
 
     pub(crate) fn set_status(&mut self, status: StatusTask) { self.status = status; }
+
+    fn apply_config(&mut self, config: &TaskConfig) {
+        if let Some(enabled) = config.enabled {
+            self.enabled = enabled;
+        }
+        let schedule = config
+            .schedule
+            .clone()
+            .or(config.interval_secs.map(|secs| Schedule::Interval { secs }));
+        if let Some(schedule) = schedule {
+            match schedule.validate() {
+                Ok(()) => self.schedule = schedule,
+                Err(e) => warn!("Ignored schedule of task {}: {}", self.name, e),
+            }
+        }
+        if let Some(retry) = config.retry {
+            self.retry = RetryPolicy {
+                max_attempts: retry.max_attempts.max(1),
+                ..retry
+            };
+        }
+    }
+}
+
+/// Stato di un task registrato
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TaskState {
+    pub name: String,
+    pub schedule: Schedule,
+    pub enabled: bool,
+    pub status: StatusTask,
+    pub last_run: Option<NaiveDateTime>,
+    pub next_run: Option<NaiveDateTime>,
+    pub last_error: Option<String>,
+}
+
+impl From<&TaskInfo> for TaskState {
+    fn from(info: &TaskInfo) -> Self {
+        Self {
+            name: info.name(),
+            schedule: info.schedule().clone(),
+            enabled: info.enabled(),
+            status: info.status(),
+            last_run: info.last_run,
+            next_run: info.next_run,
+            last_error: info.last_error.clone(),
+        }
+    }
+}
+
+/// Funzione chiamata a ogni cambio di stato di un task
+pub type StatusListener = Arc<dyn Fn(TaskState) + Send + Sync>;
+
+/// Task registrato nel manager. Le informazioni sono tenute separate dal
+/// task, così restano leggibili e modificabili mentre il task è in
+/// esecuzione: il lock sulle informazioni non viene mai tenuto durante `run`.
+#[derive(Clone)]
+struct RegisteredTask {
+    name: String,
+    info: Arc<RwLock<TaskInfo>>,
+    /// Bloccato per tutta la durata di un'esecuzione
+    task: Arc<TaskMutex<dyn BackgroundTask>>,
+}
+
+impl RegisteredTask {
+    fn new<T: BackgroundTask>(task: T) -> Self {
+        let info = task.info().clone();
+        Self {
+            name: info.name(),
+            info: Arc::new(RwLock::new(info)),
+            task: Arc::new(TaskMutex::new(task)),
+        }
+    }
+
+    fn read<F, T>(&self, read: F) -> T
+    where
+        F: FnOnce(&TaskInfo) -> T,
+    {
+        read(&self.info.read().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    fn state(&self) -> TaskState { self.read(|info| TaskState::from(info)) }
+
+    /// Applica `change` alle informazioni del task e, se lo stato è cambiato,
+    /// notifica il listener. Restituisce lo stato aggiornato del task.
+    fn update<F>(&self, listener: &Option<StatusListener>, change: F) -> TaskState
+    where
+        F: FnOnce(&mut TaskInfo),
+    {
+        let (changed, state) = {
+            let mut info = self.info.write().unwrap_or_else(PoisonError::into_inner);
+            let previous = info.status();
+            change(&mut info);
+            (previous != info.status(), TaskState::from(&*info))
+        };
+        if let Some(listener) = listener.as_ref().filter(|_| changed) {
+            listener(state.clone());
+        }
+        state
+    }
+
+    fn set_next_run(&self, next_run: Option<NaiveDateTime>) {
+        self.info
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .next_run = next_run;
+    }
+
+    fn invalid_state(&self) -> ApplicationError {
+        ErrorTask::InvalidState {
+            task: self.name.clone(),
+            status: self.read(|info| info.status()).to_string(),
+        }
+        .into()
+    }
+}
+
+/// Stato condiviso tra il manager e i loop dei task
+#[derive(Clone)]
+struct TaskContext {
+    running: watch::Sender<bool>,
+    listener: Option<StatusListener>,
+    history: Option<Arc<dyn RunHistory>>,
+    clock: Arc<dyn Clock>,
+    /// Ultima attività dell'utente, usata dai task `Idle`
+    last_activity: Arc<Mutex<NaiveDateTime>>,
+}
+
+impl Default for TaskContext {
+    fn default() -> Self {
+        let clock = Arc::new(SystemClock);
+        Self {
+            running: watch::Sender::new(false),
+            listener: None,
+            history: None,
+            last_activity: Arc::new(Mutex::new(clock.now())),
+            clock,
+        }
+    }
+}
+
+impl TaskContext {
+    fn is_running(&self) -> bool { *self.running.borrow() }
+
+    fn last_activity(&self) -> NaiveDateTime {
+        *self
+            .last_activity
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn set_last_activity(&self, value: NaiveDateTime) {
+        *self
+            .last_activity
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = value;
+    }
+
+    /// Attende fino a `deadline`. Restituisce `false` se nel frattempo il
+    /// manager è stato fermato.
+    async fn sleep_until(&self, deadline: NaiveDateTime) -> bool {
+        let mut running = self.running.subscribe();
+        tokio::select! {
+            _ = self.clock.sleep_until(deadline) => self.is_running(),
+            _ = running.wait_for(|running| !running) => false,
+        }
+    }
 }
 
 #[derive(Default)]
 pub struct BackgroundManager {
-    is_running: Arc<AtomicBool>,
-    task_handles: Vec<Arc<JoinHandle<()>>>,
-    task_running: Vec<Arc<RwLock<dyn BackgroundTask>>>,
-    task_paused: Vec<Arc<RwLock<dyn BackgroundTask>>>,
-    task_stopped: Vec<Arc<RwLock<dyn BackgroundTask>>>,
-    task_done: Vec<Arc<RwLock<dyn BackgroundTask>>>,
+    /// Tutti i task registrati, nell'ordine di registrazione
+    registry: Vec<RegisteredTask>,
+    config: HashMap<String, TaskConfig>,
+    /// Loop di esecuzione dei task avviati, per nome del task
+    task_handles: HashMap<String, JoinHandle<()>>,
+    /// Esecuzioni avviate manualmente, per nome del task
+    trigger_handles: HashMap<String, JoinHandle<()>>,
+    context: TaskContext,
 }
 
 impl BackgroundManager {
-    pub fn new() -> Self {
-        Self {
-            is_running: Arc::new(AtomicBool::new(false)),
-            ..Self::default()
+    pub fn new() -> Self { Self::default() }
+
+    pub fn set_listener(&mut self, listener: StatusListener) {
+        self.context.listener = Some(listener);
+    }
+
+    pub fn set_history(&mut self, history: Arc<dyn RunHistory>) {
+        self.context.history = Some(history);
+    }
+
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.context.set_last_activity(clock.now());
+        self.context.clock = clock;
+    }
+
+    /// Segnala un'attività dell'utente, che rimanda i task `Idle`
+    pub fn record_activity(&self) { self.context.set_last_activity(self.context.clock.now()); }
+
+    /// Registra un task. Se il manager è già avviato e il task è abilitato
+    /// viene avviato subito.
+    pub async fn register<T: BackgroundTask>(&mut self, task: T) -> ResultTask {
+        let name = task.info().name();
+        if self.find_task(&name).is_some() {
+            return Err(ErrorTask::AlreadyRegistered(name).into());
+        }
+        task.info().schedule().validate()?;
+
+        let task = RegisteredTask::new(task);
+        self.registry.push(task.clone());
+        info!("Task registered: {}", name);
+
+        if self.context.is_running() && self.configure(&task) {
+            self.start_task(task);
         }
+        Ok(())
     }
 
-    pub async fn start(&mut self, app_handle: Arc<AppHandle>) -> ResultTask {
-        if self
-            .is_running
-            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
-            .is_err()
-        {
+    /// Avvia i task abilitati, applicando la configurazione salvata nello
+    /// store. Se non è stato impostato uno storico le esecuzioni vengono
+    /// salvate nello store.
+    pub async fn start<R: Runtime>(&mut self, app_handle: &AppHandle<R>) -> ResultTask {
+        if self.context.history.is_none() {
+            self.context.history = Some(Arc::new(TaskRunStore::new(app_handle.clone())));
+        }
+        let config = TaskConfigStore::load(app_handle).unwrap_or_else(|e| {
+            warn!("Task configuration not loaded, using defaults: {}", e);
+            HashMap::new()
+        });
+        self.start_with_config(config).await
+    }
+
+    async fn start_with_config(&mut self, config: HashMap<String, TaskConfig>) -> ResultTask {
+        let started = self.context.running.send_if_modified(|running| {
+            let started = !*running;
+            *running = true;
+            started
+        });
+        if !started {
             return Ok(()); // Just started
         }
         info!("Starting Background Manager");
 
-        let task = Arc::new(RwLock::new(tasks::IdStanzeProcessor::new(app_handle)));
-        self.task_stopped.push(task.clone());
-
-        self.start_task(task).await;
+        self.config = config;
+        self.record_activity();
+        for task in self.registry.clone() {
+            if self.configure(&task) {
+                self.start_task(task);
+            }
+        }
 
         info!(
             "Starting Background Manager with {} tasks",
@@ -128,244 +373,718 @@ impl BackgroundManager {
         Ok(())
     }
 
-    async fn start_task(&mut self, task: Arc<RwLock<dyn BackgroundTask>>) {
-        // Rimuovi il task dalle altre liste se presente
-        self.remove_task_from_lists(&task);
+    /// Applica la configurazione al task e restituisce se è abilitato
+    fn configure(&self, task: &RegisteredTask) -> bool {
+        let mut info = task.info.write().unwrap_or_else(PoisonError::into_inner);
+        if let Some(config) = self.config.get(&task.name) {
+            info.apply_config(config);
+        }
+        info.enabled()
+    }
 
-        // Aggiungi alla lista running
-        {
-            let mut task_write = task.write().await;
-            task_write.info_mut().set_status(StatusTask::Running);
+    pub fn status(&self, task_name: &str) -> Option<StatusTask> {
+        let task = self.find_task(task_name)?;
+        Some(task.read(|info| info.status()))
+    }
+
+    /// Stato di tutti i task registrati
+    pub fn tasks(&self) -> Vec<TaskState> {
+        self.registry.iter().map(RegisteredTask::state).collect()
+    }
+
+    /// Esecuzioni salvate, dalla più recente
+    pub fn history(&self, task_name: Option<&str>) -> ResultTask<Vec<TaskRun>> {
+        match &self.context.history {
+            Some(history) => history.load(task_name),
+            None => Ok(Vec::new()),
         }
-        self.task_running.push(task.clone());
+    }
 
-        // Crea l'handle
-        let is_running_clone = self.is_running.clone();
-        let task_clone = task.clone();
-        let mut manager_clone = self.clone();
+    fn start_task(&mut self, task: RegisteredTask) {
+        task.update(&self.context.listener, |info| {
+            info.set_status(StatusTask::Running)
+        });
 
+        let task_name = task.name.clone();
+        let context = self.context.clone();
         let handle = tokio::spawn(async move {
-            manager_clone
-                .run_task_loop(task_clone, is_running_clone)
-                .await;
+            Self::run_task_loop(task, context).await;
         });
 
-        self.task_handles.push(Arc::new(handle));
-    }
-
-    async fn run_task_loop(
-        &mut self,
-        task: Arc<RwLock<dyn BackgroundTask>>,
-        is_running: Arc<AtomicBool>,
-    ) {
-        if task.read().await.info().status() != StatusTask::Running {
-            return;
+        if let Some(previous) = self.task_handles.insert(task_name, handle) {
+            previous.abort();
         }
+    }
 
-        let mut interval_timer = interval(task.read().await.info().interval());
-        // skip first tick
-        interval_timer.tick().await;
+    async fn run_task_loop(task: RegisteredTask, context: TaskContext) {
+        let (schedule, retry) = task.read(|info| (info.schedule().clone(), info.retry()));
+        let task_name = task.name.as_str();
+        let mut last_run = None;
 
-        loop {
-            let task_name = task.read().await.info().name();
-            interval_timer.tick().await;
+        'schedule: loop {
+            let now = context.clock.now();
+            let next_run = match schedule.next_run(now, last_run, context.last_activity()) {
+                NextRun::At(next_run) => Some(next_run),
+                NextRun::WaitActivity => None,
+                NextRun::Never => break,
+            };
+            task.set_next_run(next_run);
 
-            if !is_running.load(Ordering::SeqCst) {
+            if !context
+                .sleep_until(next_run.unwrap_or(now + schedule.idle_poll()))
+                .await
+            {
                 info!("Task stopped due to manager shutdown: {}", task_name);
                 break;
             }
+            let now = context.clock.now();
+            if next_run.is_none() || !schedule.is_due(now, context.last_activity()) {
+                continue;
+            }
 
-            let result_task = {
-                let mut guard = task.write().await;
-                guard.run().await
-            };
+            last_run = Some(now);
+            let mut attempt = 1;
+            let mut status = Self::execute(&task, &context, attempt).await;
+            while status == StatusTask::Error && attempt < retry.max_attempts {
+                let delay = retry.backoff(attempt);
+                warn!(
+                    "Task {} failed, attempt {}/{} in {}s",
+                    task_name,
+                    attempt + 1,
+                    retry.max_attempts,
+                    delay.as_secs()
+                );
+                let retry_at = context.clock.now() + TimeDelta::from_std(delay).unwrap_or_default();
+                task.set_next_run(Some(retry_at));
+                if !context.sleep_until(retry_at).await {
+                    info!("Task stopped due to manager shutdown: {}", task_name);
+                    break 'schedule;
+                }
+                attempt += 1;
+                status = Self::execute(&task, &context, attempt).await;
+            }
 
-            match result_task {
-                Ok(StatusTask::Completed) => {
-                    task.write()
-                        .await
-                        .info_mut()
-                        .set_status(StatusTask::Completed);
+            match status {
+                StatusTask::Running | StatusTask::Completed => {
                     info!("Task completed: {}", task_name);
                 }
-                Ok(StatusTask::Done) => {
-                    self.done_task(&task_name).await;
-                    break;
-                }
-                Ok(StatusTask::Paused) => {
-                    self.pause_task(&task_name).await;
-                    break;
-                }
-                Ok(StatusTask::Stopped) => {
-                    self.stop_task(&task_name).await;
-                    break;
+                // i task pianificati riprovano alla prossima esecuzione
+                StatusTask::Error if schedule != Schedule::Startup => {
+                    error!(
+                        "Task {} failed after {} attempts, waiting for the next run",
+                        task_name, attempt
+                    );
                 }
-                Err(e) => {
-                    task.write().await.info_mut().set_status(StatusTask::Error);
-                    error!("Error in task {}: {}", task_name, e);
+                status => {
+                    info!("Task {}: {}", status, task_name);
                     break;
                 }
-                _ => {
-                    unreachable!()
-                }
             }
         }
+        task.set_next_run(None);
     }
 
-    fn remove_task_from_lists(&mut self, target_task: &Arc<RwLock<dyn BackgroundTask>>) {
-        self.task_running
-            .retain(|task| !Arc::ptr_eq(task, target_task));
-        self.task_paused
-            .retain(|task| !Arc::ptr_eq(task, target_task));
-        self.task_stopped
-            .retain(|task| !Arc::ptr_eq(task, target_task));
-        self.task_done
-            .retain(|task| !Arc::ptr_eq(task, target_task));
+    /// Esegue una volta il task e restituisce lo stato in cui si trova al
+    /// termine. Se il task è già in esecuzione attende che termini.
+    async fn execute(task: &RegisteredTask, context: &TaskContext, attempt: u32) -> StatusTask {
+        let mut guard = task.task.lock().await;
+        Self::execute_locked(task, &mut *guard, context, attempt).await
     }
 
-    async fn pause_task(&mut self, task_name: &str) {
-        if let Some(task) = self.find_task_in_running(task_name).await {
-            self.remove_task_from_lists(&task);
-            task.write().await.info_mut().set_status(StatusTask::Paused);
-            self.task_paused.push(task);
-            info!("Task paused: {}", task_name);
+    async fn execute_locked(
+        task: &RegisteredTask,
+        background_task: &mut dyn BackgroundTask,
+        context: &TaskContext,
+        attempt: u32,
+    ) -> StatusTask {
+        task.update(&context.listener, |info| {
+            info.set_status(StatusTask::Running)
+        });
+        let started_at = context.clock.now();
+        let result = background_task.run().await;
+
+        let ended_at = context.clock.now();
+        let state = task.update(&context.listener, |info| {
+            info.last_run = Some(ended_at);
+            match result {
+                Ok(result) => {
+                    info.last_error = None;
+                    info.set_status(result);
+                }
+                Err(e) => {
+                    error!("Error in task {}: {}", info.name(), e);
+                    info.last_error = Some(e.to_string());
+                    info.set_status(StatusTask::Error);
+                }
+            }
+        });
+
+        let run = TaskRun {
+            task: state.name,
+            started_at,
+            ended_at,
+            outcome: state.status,
+            attempt,
+            error: state.last_error,
+        };
+        let status = run.outcome;
+        if let Some(history) = &context.history {
+            if let Err(e) = history.record(run) {
+                warn!("Run of task not saved in the history: {}", e);
+            }
         }
+        status
     }
 
-    async fn resume_task(&mut self, task_name: &str) {
-        if let Some(task) = self.find_task_in_paused(task_name).await {
-            self.start_task(task).await;
-            info!("Task resumed: {}", task_name);
+    /// Ferma il loop del task e lo porta nello stato indicato. Un'esecuzione
+    /// in corso, pianificata o avviata manualmente, viene interrotta.
+    fn halt_task(&mut self, task_name: &str, status: StatusTask) -> ResultTask {
+        let task = self
+            .find_task(task_name)
+            .ok_or_else(|| ErrorTask::NotFound(task_name.to_string()))?;
+        if !self.is_active(task_name) && !self.is_triggered(task_name) {
+            return Err(task.invalid_state());
+        }
+        for handle in [
+            self.task_handles.remove(task_name),
+            self.trigger_handles.remove(task_name),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            handle.abort();
         }
+        task.update(&self.context.listener, |info| {
+            info.next_run = None;
+            info.set_status(status);
+        });
+        info!("Task {}: {}", status, task_name);
+        Ok(())
     }
 
-    async fn stop_task(&mut self, task_name: &str) {
-        if let Some(task) = self.find_task_in_running(task_name).await {
-            self.remove_task_from_lists(&task);
-            task.write()
-                .await
-                .info_mut()
-                .set_status(StatusTask::Stopped);
-            self.task_stopped.push(task);
-            info!("Task stopped: {}", task_name);
-        }
+    pub async fn pause_task(&mut self, task_name: &str) -> ResultTask {
+        self.halt_task(task_name, StatusTask::Paused)
     }
 
-    async fn done_task(&mut self, task_name: &str) {
-        if let Some(task) = self.find_task_in_running(task_name).await {
-            self.remove_task_from_lists(&task);
-            task.write().await.info_mut().set_status(StatusTask::Done);
-            self.task_done.push(task);
-            info!("Task done: {}", task_name);
+    pub async fn stop_task(&mut self, task_name: &str) -> ResultTask {
+        self.halt_task(task_name, StatusTask::Stopped)
+    }
+
+    /// Riavvia il loop di un task non in esecuzione
+    pub async fn resume_task(&mut self, task_name: &str) -> ResultTask {
+        let task = self
+            .find_task(task_name)
+            .ok_or_else(|| ErrorTask::NotFound(task_name.to_string()))?;
+        if self.is_active(task_name) {
+            return Err(task.invalid_state());
         }
+        self.start_task(task);
+        info!("Task resumed: {}", task_name);
+        Ok(())
     }
 
-    async fn restart_task(&mut self, task_name: &str) {
-        // Cerca nelle liste stopped e done
-        let task = {
-            let mut task = self.find_task_in_stopped(task_name).await;
-            if task.is_none() {
-                task = self.find_task_in_done(task_name).await;
-            }
-            task
+    /// Esegue subito il task una volta, senza modificarne la pianificazione.
+    /// Non è possibile se il task è già in esecuzione.
+    pub async fn trigger_task(&mut self, task_name: &str) -> ResultTask {
+        let task = self
+            .find_task(task_name)
+            .ok_or_else(|| ErrorTask::NotFound(task_name.to_string()))?;
+        let Ok(mut guard) = task.task.clone().try_lock_owned() else {
+            return Err(task.invalid_state());
         };
+        let context = self.context.clone();
+        let handle = tokio::spawn(async move {
+            Self::execute_locked(&task, &mut *guard, &context, 1).await;
+        });
+        self.trigger_handles.insert(task_name.to_string(), handle);
+        info!("Task triggered: {}", task_name);
+        Ok(())
+    }
 
-        if let Some(task) = task {
-            self.start_task(task).await;
-            info!("Task restarted: {}", task_name);
-        }
+    fn is_active(&self, task_name: &str) -> bool {
+        self.task_handles
+            .get(task_name)
+            .is_some_and(|handle| !handle.is_finished())
+    }
+
+    fn is_triggered(&self, task_name: &str) -> bool {
+        self.trigger_handles
+            .get(task_name)
+            .is_some_and(|handle| !handle.is_finished())
     }
 
-    async fn find_task_in_running(
-        &self,
-        task_name: &str,
-    ) -> Option<Arc<RwLock<dyn BackgroundTask>>> {
-        for task in &self.task_running {
-            if task.read().await.info().name() == task_name {
-                return Some(task.clone());
+    fn find_task(&self, task_name: &str) -> Option<RegisteredTask> {
+        self.registry
+            .iter()
+            .find(|task| task.name == task_name)
+            .cloned()
+    }
+
+    pub async fn stop(&mut self) {
+        if !self.context.running.send_replace(false) {
+            return;
+        }
+        info!("Stopping Background Manager ...");
+
+        let graceful_timeout = Duration::from_secs(5);
+        let deadline = tokio::time::Instant::now() + graceful_timeout;
+
+        let handles: Vec<(String, JoinHandle<()>)> = self
+            .task_handles
+            .drain()
+            .chain(self.trigger_handles.drain())
+            .collect();
+        for (name, mut handle) in handles {
+            if tokio::time::timeout_at(deadline, &mut handle)
+                .await
+                .is_err()
+            {
+                handle.abort();
+                info!("Task {} aborted", name);
             }
         }
-        None
+
+        for task in &self.registry {
+            task.update(&self.context.listener, |info| {
+                info.next_run = None;
+                if matches!(info.status(), StatusTask::Running | StatusTask::Completed) {
+                    info.set_status(StatusTask::Stopped);
+                }
+            });
+        }
+
+        info!("Stopped Background Manager");
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use app_utils::{app_error::ApplicationError, test::ResultTest};
+    use async_trait::async_trait;
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::clock::ManualClock;
 
-    async fn find_task_in_paused(
-        &self,
-        task_name: &str,
-    ) -> Option<Arc<RwLock<dyn BackgroundTask>>> {
-        for task in &self.task_paused {
-            if task.read().await.info().name() == task_name {
-                return Some(task.clone());
+    struct CounterTask {
+        task_info: TaskInfo,
+        runs: Arc<AtomicUsize>,
+    }
+
+    impl CounterTask {
+        fn new(name: &str, schedule: Schedule, runs: Arc<AtomicUsize>) -> Self {
+            Self {
+                task_info: TaskInfo::new(name.to_string(), schedule),
+                runs,
             }
         }
-        None
     }
 
-    async fn find_task_in_stopped(
-        &self,
-        task_name: &str,
-    ) -> Option<Arc<RwLock<dyn BackgroundTask>>> {
-        for task in &self.task_stopped {
-            if task.read().await.info().name() == task_name {
-                return Some(task.clone());
-            }
+    #[async_trait]
+    impl BackgroundTask for CounterTask {
+        async fn run(&mut self) -> ResultTask<StatusTask> {
+            self.runs.fetch_add(1, Ordering::SeqCst);
+            Ok(StatusTask::Completed)
         }
-        None
+
+        fn info(&self) -> &TaskInfo { &self.task_info }
+
+        fn info_mut(&mut self) -> &mut TaskInfo { &mut self.task_info }
+    }
+
+    /// Fallisce le prime `failures` esecuzioni
+    struct FlakyTask {
+        task_info: TaskInfo,
+        failures: u32,
     }
 
-    async fn find_task_in_done(&self, task_name: &str) -> Option<Arc<RwLock<dyn BackgroundTask>>> {
-        for task in &self.task_done {
-            if task.read().await.info().name() == task_name {
-                return Some(task.clone());
+    #[async_trait]
+    impl BackgroundTask for FlakyTask {
+        async fn run(&mut self) -> ResultTask<StatusTask> {
+            if self.failures > 0 {
+                self.failures -= 1;
+                return Err(ErrorTask::Generic("flaky".to_string()).into());
             }
+            Ok(StatusTask::Done)
         }
-        None
+
+        fn info(&self) -> &TaskInfo { &self.task_info }
+
+        fn info_mut(&mut self) -> &mut TaskInfo { &mut self.task_info }
     }
 
-    pub async fn stop(&mut self) {
-        if !self.is_running.load(Ordering::SeqCst) {
-            return;
+    /// Non termina mai, come un backup molto lungo
+    struct SlowTask {
+        task_info: TaskInfo,
+    }
+
+    #[async_trait]
+    impl BackgroundTask for SlowTask {
+        async fn run(&mut self) -> ResultTask<StatusTask> {
+            std::future::pending::<()>().await;
+            Ok(StatusTask::Completed)
         }
 
-        self.is_running.store(false, Ordering::SeqCst);
-        info!("Stopping Background Manager ...");
+        fn info(&self) -> &TaskInfo { &self.task_info }
 
-        let graceful_timeout = Duration::from_secs(5);
-        let start_time = std::time::Instant::now();
+        fn info_mut(&mut self) -> &mut TaskInfo { &mut self.task_info }
+    }
 
-        while !self.task_handles.is_empty() && start_time.elapsed() < graceful_timeout {
-            self.task_handles.retain(|handle| !handle.is_finished());
-            if !self.task_handles.is_empty() {
-                tokio::time::sleep(Duration::from_millis(100)).await;
-            }
+    #[derive(Default)]
+    struct MemoryHistory(Mutex<Vec<TaskRun>>);
+
+    impl RunHistory for MemoryHistory {
+        fn record(&self, run: TaskRun) -> ResultTask {
+            self.0.lock().unwrap().push(run);
+            Ok(())
         }
 
-        if !self.task_handles.is_empty() {
-            info!("Force aborting {} remaining tasks", self.task_handles.len());
+        fn load(&self, task_name: Option<&str>) -> ResultTask<Vec<TaskRun>> {
+            Ok(self
+                .0
+                .lock()
+                .unwrap()
+                .iter()
+                .rev()
+                .filter(|run| task_name.is_none_or(|name| run.task == name))
+                .cloned()
+                .collect())
+        }
+    }
 
-            for (i, handle_arc) in self.task_handles.iter().enumerate() {
-                handle_arc.abort();
-                info!("Task {} aborted", i);
-            }
+    fn start_time() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 3, 10)
+            .unwrap()
+            .and_hms_opt(10, 0, 0)
+            .unwrap()
+    }
+
+    fn manager_with_clock() -> (BackgroundManager, Arc<ManualClock>) {
+        let clock = Arc::new(ManualClock::new(start_time()));
+        let mut manager = BackgroundManager::new();
+        manager.set_clock(clock.clone());
+        (manager, clock)
+    }
 
-            tokio::time::sleep(Duration::from_millis(500)).await;
+    /// Lascia avanzare i loop dei task fino al prossimo punto di attesa
+    async fn settle() {
+        for _ in 0..100 {
+            tokio::task::yield_now().await;
         }
+    }
 
-        self.task_handles.clear();
-        self.task_running.clear();
+    async fn state(manager: &BackgroundManager, task_name: &str) -> TaskState {
+        manager
+            .tasks()
+            .into_iter()
+            .find(|task| task.name == task_name)
+            .expect("task not registered")
+    }
 
-        info!("Stopped Background Manager");
+    fn is_invalid_state(result: ResultTask) -> bool {
+        matches!(
+            result,
+            Err(ApplicationError::BackgroundTask(
+                ErrorTask::InvalidState { .. }
+            ))
+        )
     }
-}
 
-impl Clone for BackgroundManager {
-    fn clone(&self) -> Self {
-        Self {
-            is_running: self.is_running.clone(),
-            task_handles: self.task_handles.clone(),
-            task_running: self.task_running.clone(),
-            task_paused: self.task_paused.clone(),
-            task_stopped: self.task_stopped.clone(),
-            task_done: self.task_done.clone(),
-        }
+    #[tokio::test]
+    async fn test_register_and_status() -> ResultTest {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let (mut manager, clock) = manager_with_clock();
+        let every_minute = Schedule::Interval { secs: 60 };
+        manager
+            .register(CounterTask::new(
+                "counter",
+                every_minute.clone(),
+                runs.clone(),
+            ))
+            .await?;
+        manager
+            .register(CounterTask::new(
+                "disabled",
+                every_minute.clone(),
+                runs.clone(),
+            ))
+            .await?;
+        assert!(matches!(
+            manager
+                .register(CounterTask::new("counter", every_minute, runs.clone()))
+                .await,
+            Err(ApplicationError::BackgroundTask(
+                ErrorTask::AlreadyRegistered(_)
+            ))
+        ));
+        assert!(matches!(
+            manager
+                .register(CounterTask::new(
+                    "invalid",
+                    Schedule::Interval { secs: 0 },
+                    runs.clone()
+                ))
+                .await,
+            Err(ApplicationError::BackgroundTask(
+                ErrorTask::InvalidSchedule(_)
+            ))
+        ));
+
+        let config = HashMap::from([(
+            "disabled".to_string(),
+            TaskConfig {
+                enabled: Some(false),
+                interval_secs: Some(5),
+                ..Default::default()
+            },
+        )]);
+        manager.start_with_config(config).await?;
+        settle().await;
+
+        let counter = state(&manager, "counter").await;
+        assert_eq!(counter.status, StatusTask::Running);
+        assert_eq!(
+            counter.next_run,
+            Some(start_time() + TimeDelta::seconds(60))
+        );
+        let disabled = state(&manager, "disabled").await;
+        assert_eq!(
+            (disabled.schedule, disabled.enabled, disabled.status),
+            (Schedule::Interval { secs: 5 }, false, StatusTask::Pending)
+        );
+        assert_eq!(manager.status("missing"), None);
+
+        clock.advance(TimeDelta::seconds(60));
+        settle().await;
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        let counter = state(&manager, "counter").await;
+        assert_eq!(
+            counter.last_run,
+            Some(start_time() + TimeDelta::seconds(60))
+        );
+        assert_eq!(
+            counter.next_run,
+            Some(start_time() + TimeDelta::seconds(120))
+        );
+
+        manager.stop().await;
+        assert_eq!(manager.status("counter"), Some(StatusTask::Stopped));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_control() -> ResultTest {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let (mut manager, _clock) = manager_with_clock();
+        let listener_events = events.clone();
+        manager.set_listener(Arc::new(move |state: TaskState| {
+            listener_events.lock().unwrap().push(state.status);
+        }));
+        manager
+            .register(CounterTask::new(
+                "counter",
+                Schedule::Interval { secs: 60 },
+                runs.clone(),
+            ))
+            .await?;
+        manager.start_with_config(HashMap::new()).await?;
+        settle().await;
+
+        manager.pause_task("counter").await?;
+        assert_eq!(manager.status("counter"), Some(StatusTask::Paused));
+        assert!(is_invalid_state(manager.pause_task("counter").await));
+
+        manager.trigger_task("counter").await?;
+        settle().await;
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+        manager.resume_task("counter").await?;
+        assert!(is_invalid_state(manager.resume_task("counter").await));
+        manager.stop_task("counter").await?;
+        assert_eq!(manager.status("counter"), Some(StatusTask::Stopped));
+        assert!(matches!(
+            manager.trigger_task("missing").await,
+            Err(ApplicationError::BackgroundTask(ErrorTask::NotFound(_)))
+        ));
+
+        let events = events.lock().unwrap().clone();
+        assert_eq!(events.first(), Some(&StatusTask::Running));
+        assert_eq!(events.last(), Some(&StatusTask::Stopped));
+        assert!(events.contains(&StatusTask::Paused));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_control_while_running() -> ResultTest {
+        let (mut manager, _clock) = manager_with_clock();
+        manager
+            .register(SlowTask {
+                task_info: TaskInfo::new("slow".to_string(), Schedule::Startup),
+            })
+            .await?;
+        manager.start_with_config(HashMap::new()).await?;
+        settle().await;
+
+        // lo stato resta leggibile durante l'esecuzione
+        assert_eq!(state(&manager, "slow").await.status, StatusTask::Running);
+        assert!(is_invalid_state(manager.trigger_task("slow").await));
+
+        manager.stop_task("slow").await?;
+        assert_eq!(manager.status("slow"), Some(StatusTask::Stopped));
+        settle().await;
+
+        // l'esecuzione interrotta non blocca più il task
+        manager.trigger_task("slow").await?;
+        settle().await;
+        assert_eq!(manager.status("slow"), Some(StatusTask::Running));
+        assert!(is_invalid_state(manager.trigger_task("slow").await));
+        manager.pause_task("slow").await?;
+        assert_eq!(manager.status("slow"), Some(StatusTask::Paused));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_retry_and_history() -> ResultTest {
+        let retry = RetryPolicy {
+            max_attempts: 3,
+            initial_backoff_secs: 5,
+            max_backoff_secs: 60,
+        };
+        let (mut manager, clock) = manager_with_clock();
+        manager.set_history(Arc::new(MemoryHistory::default()));
+        manager
+            .register(FlakyTask {
+                task_info: TaskInfo::new("flaky".to_string(), Schedule::Interval { secs: 60 })
+                    .with_retry(retry),
+                failures: 2,
+            })
+            .await?;
+        manager
+            .register(FlakyTask {
+                task_info: TaskInfo::new("broken".to_string(), Schedule::Interval { secs: 60 })
+                    .with_retry(RetryPolicy::none()),
+                failures: 5,
+            })
+            .await?;
+        manager.start_with_config(HashMap::new()).await?;
+        settle().await;
+
+        clock.advance(TimeDelta::seconds(60));
+        settle().await;
+        assert_eq!(
+            state(&manager, "flaky").await.next_run,
+            Some(start_time() + TimeDelta::seconds(65))
+        );
+        clock.advance(TimeDelta::seconds(5));
+        settle().await;
+        assert_eq!(
+            state(&manager, "flaky").await.next_run,
+            Some(start_time() + TimeDelta::seconds(75))
+        );
+        clock.advance(TimeDelta::seconds(10));
+        settle().await;
+
+        let flaky: Vec<(u32, StatusTask)> = manager
+            .history(Some("flaky"))?
+            .into_iter()
+            .map(|run| (run.attempt, run.outcome))
+            .collect();
+        assert_eq!(
+            flaky,
+            vec![
+                (3, StatusTask::Done),
+                (2, StatusTask::Error),
+                (1, StatusTask::Error)
+            ]
+        );
+        assert_eq!(manager.status("flaky"), Some(StatusTask::Done));
+
+        let broken = manager.history(Some("broken"))?;
+        assert_eq!(broken.len(), 1);
+        assert_eq!(broken[0].error.as_deref(), Some("Task error: Error: flaky"));
+        assert_eq!(manager.status("broken"), Some(StatusTask::Error));
+        assert_eq!(manager.history(None)?.len(), 4);
+
+        // esauriti i tentativi il task attende la prossima esecuzione
+        assert_eq!(
+            state(&manager, "broken").await.next_run,
+            Some(start_time() + TimeDelta::seconds(120))
+        );
+        clock.advance(TimeDelta::seconds(45));
+        settle().await;
+        assert_eq!(manager.history(Some("broken"))?.len(), 2);
+
+        manager.stop().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_schedules() -> ResultTest {
+        let startup_runs = Arc::new(AtomicUsize::new(0));
+        let idle_runs = Arc::new(AtomicUsize::new(0));
+        let (mut manager, clock) = manager_with_clock();
+        manager
+            .register(CounterTask::new(
+                "startup",
+                Schedule::Startup,
+                startup_runs.clone(),
+            ))
+            .await?;
+        manager
+            .register(CounterTask::new(
+                "cron",
+                Schedule::Cron {
+                    expression: "0 0 12 * * *".to_string(),
+                },
+                Arc::new(AtomicUsize::new(0)),
+            ))
+            .await?;
+        manager
+            .register(CounterTask::new(
+                "idle",
+                Schedule::Idle { idle_secs: 300 },
+                idle_runs.clone(),
+            ))
+            .await?;
+        manager.start_with_config(HashMap::new()).await?;
+        settle().await;
+
+        assert_eq!(startup_runs.load(Ordering::SeqCst), 1);
+        assert_eq!(state(&manager, "startup").await.next_run, None);
+        assert_eq!(
+            state(&manager, "cron").await.next_run,
+            Some(start_time() + TimeDelta::hours(2))
+        );
+        assert_eq!(
+            state(&manager, "idle").await.next_run,
+            Some(start_time() + TimeDelta::seconds(300))
+        );
+
+        // L'attività dell'utente rimanda il task idle
+        clock.advance(TimeDelta::seconds(200));
+        manager.record_activity();
+        clock.advance(TimeDelta::seconds(100));
+        settle().await;
+        assert_eq!(idle_runs.load(Ordering::SeqCst), 0);
+        assert_eq!(
+            state(&manager, "idle").await.next_run,
+            Some(start_time() + TimeDelta::seconds(500))
+        );
+
+        clock.advance(TimeDelta::seconds(200));
+        settle().await;
+        assert_eq!(idle_runs.load(Ordering::SeqCst), 1);
+        assert_eq!(state(&manager, "idle").await.next_run, None);
+
+        clock.advance(TimeDelta::seconds(600));
+        settle().await;
+        assert_eq!(idle_runs.load(Ordering::SeqCst), 1);
+        assert_eq!(startup_runs.load(Ordering::SeqCst), 1);
+
+        manager.stop().await;
+        Ok(())
     }
 }
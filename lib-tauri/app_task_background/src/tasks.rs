@@ -1,26 +1,33 @@
 use std::{collections::HashSet, sync::Arc};
 
-use app_data_processing::IdGeneratorStanza;
+use app_data_processing::{
+    BackupFascicoli, DataQuality, IdGeneratorStanza, LocalBackups, QualityReport,
+    QualityReportStore,
+};
 use app_models::models::Stanza;
-use app_services::service::{DomainError, EdificioService, StanzaService};
+use app_services::{
+    query::{StanzaFilter, StanzaQuery},
+    service::{DomainError, EdificioService, StanzaService},
+};
 use app_state::database::DatabaseManager;
 use app_utils::{
-    app_error::{ApplicationError, ErrorTask, TauriError},
+    app_error::{ApplicationError, ErrorTask},
     app_interface::{
         database_interface::DatabaseManagerTrait,
         service_interface::{RetrieveBy, RetrieveManyService},
     },
+    store::map_store_error,
 };
 use async_trait::async_trait;
 use diesel::{
     RunQueryDsl,
     sql_types::{Integer, Text},
 };
-use log::{debug, info};
-use tauri::{AppHandle, Manager, Runtime};
+use log::{debug, info, warn};
+use tauri::{AppHandle, Manager, Runtime, State};
 use tauri_plugin_store::StoreExt;
 
-use crate::{BackgroundTask, ResultTask, StatusTask, TaskInfo};
+use crate::{BackgroundTask, ResultTask, Schedule, StatusTask, TaskInfo};
 
 pub struct IdStanzeProcessor<R: Runtime> {
     app_handle: Arc<AppHandle<R>>,
@@ -29,141 +36,214 @@ pub struct IdStanzeProcessor<R: Runtime> {
 
 impl<R: Runtime> IdStanzeProcessor<R> {
     const FILE_SAVE_EDIFICI: &'static str = "migration_id_stanze.json";
-    const INTERVAL_SEC: u64 = 10;
     const KEY_EDIFICI: &'static str = "edifici_processed";
     const TASK_NAME: &'static str = "id_stanze_processor";
 
     pub fn new(app_handle: Arc<AppHandle<R>>) -> Self {
         Self {
             app_handle,
-            task_info: TaskInfo::new(
-                Self::TASK_NAME.to_string(),
-                Some(std::time::Duration::from_secs(Self::INTERVAL_SEC)),
-            ),
+            task_info: TaskInfo::new(Self::TASK_NAME.to_string(), Schedule::Startup),
         }
     }
+
+    async fn process_edificio(db_state: &State<'_, DatabaseManager>, edificio: &str) -> ResultTask {
+        info!("Processing edificio with id {}", edificio);
+
+        let query = StanzaQuery::new().filter(StanzaFilter::Edificio(edificio.to_string()));
+        let stanze = StanzaService::retrieve_by(db_state.clone(), query).await?;
+
+        let mut id_generator_stanza = IdGeneratorStanza::new();
+        let mut conn = db_state.get_connection().await?;
+
+        stanze.into_iter().try_for_each::<_, ResultTask>(|stanza| {
+            debug!("Before: {}", stanza.cod_stanza);
+            let new_stanza = id_generator_stanza.generate_id(stanza.into())?;
+
+            let stanza_updated: Stanza =
+                diesel::sql_query("UPDATE stanza SET cod_stanza = $1 WHERE id = $2 RETURNING *;")
+                    .bind::<Text, _>(new_stanza.cod_stanza)
+                    .bind::<Integer, _>(new_stanza.id)
+                    .get_result(&mut conn)
+                    .map_err(|e| ApplicationError::Domain(DomainError::from(e)))?;
+
+            debug!("After: {:?}", stanza_updated.cod_stanza);
+
+            Ok(())
+        })?;
+
+        Ok(())
+    }
+
+    /// Legge le chiavi degli edifici già elaborati. I valori non validi
+    /// vengono ignorati senza cancellare quelli salvati.
+    fn processed_edifici(value: Option<serde_json::Value>) -> ResultTask<Vec<String>> {
+        let Some(value) = value else {
+            return Ok(Vec::new());
+        };
+        let Some(values) = value.as_array() else {
+            return Err(ApplicationError::BackgroundTask(ErrorTask::Generic(
+                "The stored value is not an array".to_string(),
+            )));
+        };
+
+        Ok(values
+            .iter()
+            .filter_map(|v| {
+                let chiave = v.as_str().map(str::to_string);
+                if chiave.is_none() {
+                    warn!("Ignored stored value {} that is not a string", v);
+                }
+                chiave
+            })
+            .collect())
+    }
 }
 
 #[async_trait]
 impl<R: Runtime> BackgroundTask for IdStanzeProcessor<R> {
     async fn run(&mut self) -> ResultTask<StatusTask> {
         /*
-        Ogni volta che viene eseguita si sceglie un nuovo id edificio da aggiornare
+        Migrazione eseguita all'avvio: aggiorna il codice delle stanze degli edifici non ancora
+        elaborati, salvando l'avanzamento dopo ogni edificio
          */
         info!("Starting task processor id stanze");
 
         let db_state = self.app_handle.state::<DatabaseManager>();
         let edifici = EdificioService::retrieve_many(db_state.clone()).await?;
 
-        // Find the first element that has not been changed
         let store = self
             .app_handle
             .store(Self::FILE_SAVE_EDIFICI)
-            .map_err(|e| ApplicationError::Tauri(TauriError::Plugin(e.into())))?;
-
-        let edifici_stored = store.get(Self::KEY_EDIFICI);
-        #[allow(unused_assignments)]
-        let mut edificio_processing: Option<String> = None;
-
-        match edifici_stored {
-            Some(value) => match value.as_array() {
-                Some(edifici_array) => {
-                    let mut edifici_saved = edifici_array
-                        .iter()
-                        .map(|v| {
-                            let res = match v.as_str() {
-                                Some(n) => n.to_string(),
-                                None => {
-                                    store.clear();
-                                    return Err(ApplicationError::BackgroundTask(
-                                        ErrorTask::Generic(
-                                            "The value in the stored value is not a string"
-                                                .to_string(),
-                                        ),
-                                    ));
-                                }
-                            };
-                            Ok(res)
-                        })
-                        .collect::<ResultTask<Vec<String>>>()?;
-
-                    let edifici_saved_set: HashSet<String> =
-                        HashSet::from_iter(edifici_saved.clone());
-                    let edifici_set: HashSet<String> = HashSet::from_iter(
-                        edifici
-                            .iter()
-                            .map(|v| v.chiave.clone())
-                            .collect::<Vec<String>>(),
-                    );
-
-                    let difference = edifici_set
-                        .difference(&edifici_saved_set)
-                        .collect::<Vec<_>>();
-
-                    if difference.is_empty() {
-                        info!("All edifici are already processed, nothing to do");
-                        return Ok(StatusTask::Done);
-                    }
-
-                    edificio_processing = Some(difference[0].clone());
-
-                    println!("edifici saved: {:?}", edificio_processing);
-                    edifici_saved.push(edificio_processing.clone().unwrap());
-                    store.set(Self::KEY_EDIFICI, edifici_saved);
-                }
-                None => {
-                    store.clear();
-                    return Err(ApplicationError::BackgroundTask(ErrorTask::Generic(
-                        "The stored value is not an array".to_string(),
-                    )));
-                }
-            },
-            None => {
-                edificio_processing = Some(edifici[0].chiave.clone());
-                store.set(
-                    Self::KEY_EDIFICI,
-                    vec![edificio_processing.clone().unwrap()],
-                );
-            }
+            .map_err(map_store_error)?;
+
+        let mut edifici_saved = Self::processed_edifici(store.get(Self::KEY_EDIFICI))?;
+        let processed: HashSet<&String> = edifici_saved.iter().collect();
+        let pending: Vec<String> = edifici
+            .into_iter()
+            .map(|edificio| edificio.chiave)
+            .filter(|chiave| !processed.contains(chiave))
+            .collect();
+        if pending.is_empty() {
+            info!("All edifici are already processed, nothing to do");
+            return Ok(StatusTask::Done);
         }
 
-        if let Some(edificio) = edificio_processing {
-            info!("Processing edificio with id {}", edificio);
+        for edificio in pending {
+            Self::process_edificio(&db_state, &edificio).await?;
+
+            // L'edificio viene segnato come elaborato solo dopo l'aggiornamento
+            edifici_saved.push(edificio);
+            store.set(Self::KEY_EDIFICI, edifici_saved.clone());
+            store.save().map_err(map_store_error)?;
+        }
+        store.close_resource();
+
+        info!("Finish processing");
 
-            let stanze = StanzaService::retrieve_by(
-                db_state.clone(),
-                "edificio",
-                edificio.to_string().as_str(),
-            )
-            .await?;
+        Ok(StatusTask::Done)
+    }
 
-            let mut id_generator_stanza = IdGeneratorStanza::new();
-            let mut conn = db_state.get_connection().await?;
+    fn info(&self) -> &TaskInfo { &self.task_info }
 
-            stanze.into_iter().try_for_each::<_, ResultTask>(|stanza| {
-                debug!("Before: {}", stanza.cod_stanza);
-                let new_stanza = id_generator_stanza.generate_id(stanza.into())?;
+    fn info_mut(&mut self) -> &mut TaskInfo { &mut self.task_info }
+}
 
-                let stanza_updated: Stanza = diesel::sql_query(
-                    "UPDATE stanza SET cod_stanza = $1 WHERE id = $2 RETURNING *;",
-                )
-                .bind::<Text, _>(new_stanza.cod_stanza)
-                .bind::<Integer, _>(new_stanza.id)
-                .get_result(&mut conn)
-                .map_err(|e| ApplicationError::Domain(DomainError::from(e)))?;
+/// Backup periodico di tutti i fascicoli nella cartella dei backup locali,
+/// gestita come stato dell'applicazione
+pub struct LocalBackupTask<R: Runtime> {
+    app_handle: Arc<AppHandle<R>>,
+    task_info: TaskInfo,
+}
 
-                debug!("After: {:?}", stanza_updated.cod_stanza);
+impl<R: Runtime> LocalBackupTask<R> {
+    const INTERVAL_SECS: u64 = 4 * 60 * 60;
+    const TASK_NAME: &'static str = "local_backup";
 
-                Ok(())
-            })?;
+    pub fn new(app_handle: Arc<AppHandle<R>>) -> Self {
+        Self {
+            app_handle,
+            task_info: TaskInfo::new(
+                Self::TASK_NAME.to_string(),
+                Schedule::Interval {
+                    secs: Self::INTERVAL_SECS,
+                },
+            ),
         }
+    }
+}
 
-        store
-            .save()
-            .map_err(|e| ApplicationError::Tauri(TauriError::Plugin(e.into())))?;
-        store.close_resource();
+#[async_trait]
+impl<R: Runtime> BackgroundTask for LocalBackupTask<R> {
+    async fn run(&mut self) -> ResultTask<StatusTask> {
+        let db_state = self.app_handle.state::<DatabaseManager>();
+        let backups = self.app_handle.state::<LocalBackups>();
 
-        info!("Finish processing");
+        let archive = BackupFascicoli::snapshot_all(db_state).await?;
+        if archive.edifici.is_empty() {
+            // Un database vuoto non deve far ruotare i backup esistenti
+            warn!("No edifici in the database, backup skipped");
+            return Ok(StatusTask::Completed);
+        }
+
+        let backup = backups.write(&archive)?;
+        info!(
+            "Backup {} saved in {}",
+            backup.file_name,
+            backups.dir().display()
+        );
+        backups.rotate(archive.created_at)?;
+
+        Ok(StatusTask::Completed)
+    }
+
+    fn info(&self) -> &TaskInfo { &self.task_info }
+
+    fn info_mut(&mut self) -> &mut TaskInfo { &mut self.task_info }
+}
+
+/// Riceve i report calcolati da `DataQualityScanner`
+pub type QualityListener = Arc<dyn Fn(Vec<QualityReport>) + Send + Sync>;
+
+/// Analizza completezza e coerenza dei dati di ogni edificio quando l'utente
+/// è inattivo. I report vengono salvati nello store e inviati al listener.
+pub struct DataQualityScanner<R: Runtime> {
+    app_handle: Arc<AppHandle<R>>,
+    listener: QualityListener,
+    task_info: TaskInfo,
+}
+
+impl<R: Runtime> DataQualityScanner<R> {
+    const IDLE_SECS: u64 = 120;
+    const TASK_NAME: &'static str = "data_quality_scanner";
+
+    pub fn new(app_handle: Arc<AppHandle<R>>, listener: QualityListener) -> Self {
+        Self {
+            app_handle,
+            listener,
+            task_info: TaskInfo::new(
+                Self::TASK_NAME.to_string(),
+                Schedule::Idle {
+                    idle_secs: Self::IDLE_SECS,
+                },
+            ),
+        }
+    }
+}
+
+#[async_trait]
+impl<R: Runtime> BackgroundTask for DataQualityScanner<R> {
+    async fn run(&mut self) -> ResultTask<StatusTask> {
+        let db_state = self.app_handle.state::<DatabaseManager>();
+        let reports = DataQuality::scan_all(db_state).await?;
+        QualityReportStore::save(&self.app_handle, &reports)?;
+
+        let issues: usize = reports.iter().map(|report| report.issues.len()).sum();
+        info!(
+            "Data quality scan completed: {} edifici, {} issues",
+            reports.len(),
+            issues
+        );
+        (self.listener)(reports);
 
         Ok(StatusTask::Completed)
     }
@@ -273,4 +353,17 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_processed_edifici() -> ResultTest {
+        type Processor = IdStanzeProcessor<MockRuntime>;
+
+        assert!(Processor::processed_edifici(None)?.is_empty());
+        assert_eq!(
+            Processor::processed_edifici(Some(serde_json::json!(["A", 1, "B"])))?,
+            vec!["A".to_string(), "B".to_string()]
+        );
+        assert!(Processor::processed_edifici(Some(serde_json::json!({"A": 1}))).is_err());
+        Ok(())
+    }
 }
@@ -1,6 +1,9 @@
 #[cfg(feature = "test-utils")]
 pub mod test;
 
+#[cfg(feature = "store")]
+pub mod store;
+
 #[cfg(feature = "error")]
 pub use app_error;
 #[cfg(feature = "interface")]
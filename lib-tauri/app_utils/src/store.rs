@@ -0,0 +1,58 @@
+use app_error::{AppResult, ApplicationError, TauriError};
+use serde::{Serialize, de::DeserializeOwned};
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_store::StoreExt;
+
+pub fn map_store_error(e: impl std::error::Error + Send + Sync + 'static) -> ApplicationError {
+    ApplicationError::Tauri(TauriError::Plugin(e.into()))
+}
+
+/// Valore JSON salvato nello store dell'applicazione `file` sotto la chiave
+/// `key`
+pub struct JsonStore {
+    file: &'static str,
+    key: &'static str,
+}
+
+impl JsonStore {
+    pub const fn new(file: &'static str, key: &'static str) -> Self { Self { file, key } }
+
+    /// Valore salvato, `None` se non ancora salvato. `invalid` converte
+    /// l'errore di un valore che non può essere letto
+    pub fn load<R, T, E>(
+        &self,
+        app_handle: &AppHandle<R>,
+        invalid: impl FnOnce(serde_json::Error) -> E,
+    ) -> AppResult<Option<T>>
+    where
+        R: Runtime,
+        T: DeserializeOwned,
+        E: Into<ApplicationError>,
+    {
+        let store = app_handle.store(self.file).map_err(map_store_error)?;
+        match store.get(self.key) {
+            Some(value) => serde_json::from_value(value)
+                .map(Some)
+                .map_err(|e| invalid(e).into()),
+            None => Ok(None),
+        }
+    }
+
+    /// Sostituisce il valore salvato
+    pub fn save<R, T, E>(
+        &self,
+        app_handle: &AppHandle<R>,
+        value: &T,
+        invalid: impl FnOnce(serde_json::Error) -> E,
+    ) -> AppResult<()>
+    where
+        R: Runtime,
+        T: Serialize + ?Sized,
+        E: Into<ApplicationError>,
+    {
+        let store = app_handle.store(self.file).map_err(map_store_error)?;
+        let value = serde_json::to_value(value).map_err(|e| invalid(e).into())?;
+        store.set(self.key, value);
+        store.save().map_err(map_store_error)
+    }
+}
@@ -44,6 +44,7 @@ pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("../app_models/migr
 pub mod impl_database_connector {
     use std::{any::Any, sync::Arc, time::Duration};
 
+    use app_error::database_error::DbError;
     use app_interface::database_interface::{DatabaseConnector, PostgresPool};
     use async_trait::async_trait;
     use diesel::{
@@ -65,9 +66,11 @@ pub mod impl_database_connector {
 
     #[async_trait]
     impl DatabaseConnector for MockDatabaseConnector {
-        async fn create_postgres_pool(&self) -> PostgresPool {
+        async fn create_postgres_pool(&self) -> Result<PostgresPool, DbError> {
             if self.postgres_should_fail {
-                panic!("Postgres should fail");
+                return Err(DbError::ConnectionPoolError(
+                    "Postgres should fail".to_string(),
+                ));
             }
             // Crea un pool di test (nota: richiede un DB PostgreSQL di test)
             let manager = ConnectionManager::<PgConnection>::new(get_connection_string().await);
@@ -82,7 +85,7 @@ pub mod impl_database_connector {
                 conn.run_pending_migrations(MIGRATIONS).unwrap();
             }
 
-            pool
+            Ok(pool)
         }
 
         //fn as_any(&self) -> &dyn std::any::Any {
@@ -119,9 +122,11 @@ pub mod impl_database_connector {
 
     #[async_trait]
     impl DatabaseConnector for IsolatedTestDatabaseConnector {
-        async fn create_postgres_pool(&self) -> PostgresPool {
+        async fn create_postgres_pool(&self) -> Result<PostgresPool, DbError> {
             if self.postgres_should_fail {
-                panic!("Postgres should fail");
+                return Err(DbError::ConnectionPoolError(
+                    "Postgres should fail".to_string(),
+                ));
             }
 
             let container = self.container.read().await;
@@ -147,7 +152,7 @@ pub mod impl_database_connector {
                 conn.run_pending_migrations(MIGRATIONS).unwrap();
             }
 
-            pool
+            Ok(pool)
         }
 
         fn as_any_mut(&mut self) -> &mut dyn Any { self }
@@ -12,6 +12,9 @@ import {toast} from "sonner";
 import PageDashboard from "./pages/DashboardPage/page-dashboard.tsx";
 import {ThemeProvider} from "./theme/theme-provider";
 
+/** Intervallo minimo tra due segnalazioni di attività al backend */
+const ACTIVITY_THROTTLE_MS = 30_000;
+
 function App() {
     const notificationContext = useNotification();
     const processedMessages = useRef<Set<string>>(new Set());
@@ -76,6 +79,27 @@ function App() {
         };
     }, []);
 
+    useEffect(() => {
+        // I processi in background pianificati durante l'inattività vengono rimandati
+        let lastNotified = 0;
+        const handleActivity = () => {
+            const now = Date.now();
+            if (now - lastNotified < ACTIVITY_THROTTLE_MS) {
+                return;
+            }
+            lastNotified = now;
+            invoke("notify_user_activity").catch(console.error);
+        };
+
+        window.addEventListener("pointerdown", handleActivity);
+        window.addEventListener("keydown", handleActivity);
+
+        return () => {
+            window.removeEventListener("pointerdown", handleActivity);
+            window.removeEventListener("keydown", handleActivity);
+        };
+    }, []);
+
     return <ThemeProvider>
         <div className="[--header-height:calc(theme(spacing.14))]">
             <SidebarProvider className="flex flex-col" defaultOpen={false}>
@@ -1,3 +1,4 @@
+import {LocaleToggle} from "@/components/locale-toggle.tsx";
 import {ModeToggle} from "@/components/mode-toggle.tsx";
 import NotificationSidebar from "@/components/notification-sidebar.tsx";
 import {Button} from "@/components/ui/button";
@@ -71,6 +72,7 @@ export default function SiteHeader() {
                 </NavigationMenu>
             </div>
             <Separator orientation="vertical" className="mx-1"/>
+            <LocaleToggle/>
             <ModeToggle/>
             <NotificationSidebar/>
         </div>
@@ -1,3 +1,5 @@
+import {NavBackgroundTasks} from "@/components/nav-background-tasks.tsx";
+import {NavBackup} from "@/components/nav-backup.tsx";
 import {NavMain} from "@/components/nav-main";
 import {SearchForm} from "@/components/search-form.tsx";
 import {
@@ -64,6 +66,8 @@ export function AppSidebar({...props}: React.ComponentProps<typeof Sidebar>) {
             {/*<NavSecondary items={ data.navSecondary } className="mt-auto"/>*/}
         </SidebarContent>
             <SidebarFooter>
+            <NavBackgroundTasks/>
+            <NavBackup/>
             {/*    <NavUser user={ data.user }/>*/}
             </SidebarFooter>
     </Sidebar>);
@@ -0,0 +1,52 @@
+import {IDatabaseStatus} from "@/models/models.tsx";
+import PageDatabaseSetup from "@/pages/page-database-setup.tsx";
+import {invoke} from "@tauri-apps/api/core";
+import {listen} from "@tauri-apps/api/event";
+import * as React from "react";
+import {useEffect, useState} from "react";
+
+interface DatabaseStatusChanged {
+    type_event: string;
+    status: IDatabaseStatus;
+}
+
+const DISCONNECTED: IDatabaseStatus = {connected: false, active: null, schema: {state: "unknown"}};
+
+/** Mostra la configurazione del database finché l'applicazione non è connessa con uno schema aggiornato */
+const DatabaseConnectionGate = ({children}: { children: React.ReactNode }) => {
+    const [status, setStatus] = useState<IDatabaseStatus | null>(null);
+
+    useEffect(() => {
+        invoke<IDatabaseStatus>("get_database_status")
+            .then(setStatus)
+            .catch(e => {
+                console.error(e);
+                setStatus(DISCONNECTED);
+            });
+
+        const statusListener = listen<DatabaseStatusChanged>("database_status", ({payload}) => {
+            setStatus(payload.status);
+        });
+
+        return () => {
+            statusListener
+                .then(callback => callback())
+                .catch(console.error);
+        };
+    }, []);
+
+    if (status === null) {
+        return null;
+    }
+    if (!status.connected || status.schema.state === "failed") {
+        const error = status.schema.state === "failed" ? status.schema.error : undefined;
+        return <PageDatabaseSetup error={error} onConnected={() => {
+            invoke<IDatabaseStatus>("get_database_status")
+                .then(setStatus)
+                .catch(console.error);
+        }}/>;
+    }
+    return children;
+};
+
+export default DatabaseConnectionGate;
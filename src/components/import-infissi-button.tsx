@@ -0,0 +1,109 @@
+import ImportPreviewDialog from "@/components/import-preview-dialog.tsx";
+import { Button } from "@/components/ui/button.tsx";
+import { useNotification } from "@/context/NotificationProvider.tsx";
+import { useInfissi } from "@/context/UseProvider.tsx";
+import { IImportInfissiPreview, IMappingProfile } from "@/models/models.tsx";
+import { invoke } from "@tauri-apps/api/core";
+import { open } from "@tauri-apps/plugin-dialog";
+import { FileUp } from "lucide-react";
+import { useState } from "react";
+import { errorMessage } from "@/helpers/helpers.ts";
+
+/** Importa da foglio di calcolo gli infissi dell'edificio selezionato */
+const ImportInfissiButton = ({ disabled }: Readonly<{ disabled: boolean }>) => {
+    const infissi = useInfissi();
+    const { addNotification } = useNotification();
+    const [importPath, setImportPath] = useState<string | null>(null);
+    const [preview, setPreview] = useState<IImportInfissiPreview | null>(null);
+    const [profiles, setProfiles] = useState<IMappingProfile[]>([]);
+    const [profile, setProfile] = useState<string | null>(null);
+
+    const loadPreview = async (path: string, profile: string | null) => {
+        const preview: IImportInfissiPreview = await invoke("preview_infissi_from_file", {
+            path   : path,
+            profile: profile
+        });
+        setProfile(profile);
+        setPreview(preview);
+    };
+
+    const selectFile = async () => {
+        const path = await open({
+            title    : "Seleziona il file degli infissi",
+            multiple : false,
+            directory: false,
+            filters  : [
+                {
+                    name      : "Foglio di calcolo",
+                    extensions: [ "xlsx", "xls", "ods", "csv" ]
+                }
+            ]
+        });
+        if (!path) {
+            return;
+        }
+        try {
+            setProfiles(await invoke("get_mapping_profiles"));
+            setImportPath(path);
+            await loadPreview(path, null);
+        } catch (e) {
+            addNotification(errorMessage(e), "error");
+        }
+    };
+
+    const changeProfile = async (profile: string | null) => {
+        if (!importPath) {
+            return;
+        }
+        try {
+            await loadPreview(importPath, profile);
+        } catch (e) {
+            addNotification(errorMessage(e), "error");
+        }
+    };
+
+    const close = () => {
+        setImportPath(null);
+        setPreview(null);
+        setProfile(null);
+    };
+
+    const confirm = async () => {
+        const path = importPath;
+        const selectedProfile = profile;
+        close();
+        try {
+            await invoke("import_infissi_from_file", {
+                path   : path,
+                profile: selectedProfile
+            });
+            await infissi.reloadInfissi();
+            addNotification("Infissi importati con successo", "success");
+        } catch (e) {
+            addNotification(errorMessage(e), "error");
+        }
+    };
+
+    return <>
+        <Button type="button" className="dark:text-white" variant="secondary" disabled={ disabled }
+                onClick={ () => void selectFile() }>
+            <FileUp/> Importa da file
+        </Button>
+        <ImportPreviewDialog open={ preview !== null }
+                             description={ `${ preview?.infissi.length ?? 0 } infissi da inserire, ${ preview?.assegnazioni.length ?? 0 } assegnazioni a stanze da aggiornare` }
+                             errors={ preview?.errors ?? [] }
+                             profiles={ profiles }
+                             profile={ profile }
+                             onProfileChange={ (profile) => void changeProfile(profile) }
+                             onOpenChange={ (open) => !open && close() }
+                             onConfirm={ () => void confirm() }>
+            { (preview?.assegnazioni.length ?? 0) > 0 && <p className="text-sm text-muted-foreground">
+                { preview?.assegnazioni
+                         .map((a) => `${ a.cod_stanza }: ${ a.num_infisso } × ${ a.infisso_id }`)
+                         .join(", ") }
+            </p> }
+        </ImportPreviewDialog>
+    </>;
+};
+
+export default ImportInfissiButton;
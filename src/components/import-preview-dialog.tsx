@@ -0,0 +1,95 @@
+import {
+    Dialog,
+    DialogClose,
+    DialogContent,
+    DialogDescription,
+    DialogFooter,
+    DialogHeader,
+    DialogTitle
+} from "@/components/ui/dialog.tsx";
+import { Button } from "@/components/ui/button.tsx";
+import { ScrollArea } from "@/components/ui/scroll-area.tsx";
+import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from "@/components/ui/select.tsx";
+import { Table, TableBody, TableCell, TableHead, TableHeader, TableRow } from "@/components/ui/table.tsx";
+import { IMappingProfile, IRowError } from "@/models/models.tsx";
+import * as React from "react";
+
+/** Valore della select per la lettura delle colonne con i nomi standard */
+const NO_PROFILE = "__standard__";
+
+interface ImportPreviewDialogProps {
+    open: boolean;
+    /** Riepilogo di ciò che verrebbe scritto nel database */
+    description: string;
+    errors: IRowError[];
+    profiles: IMappingProfile[];
+    profile: string | null;
+    onProfileChange: (profile: string | null) => void;
+    onOpenChange: (open: boolean) => void;
+    onConfirm: () => void;
+    children?: React.ReactNode;
+}
+
+const ImportPreviewDialog = ({
+                                 open,
+                                 description,
+                                 errors,
+                                 profiles,
+                                 profile,
+                                 onProfileChange,
+                                 onOpenChange,
+                                 onConfirm,
+                                 children
+                             }: ImportPreviewDialogProps) => {
+    const hasErrors = errors.length > 0;
+
+    return <Dialog open={ open } onOpenChange={ onOpenChange }>
+        <DialogContent className="sm:max-w-2xl">
+            <DialogHeader>
+                <DialogTitle>Anteprima importazione</DialogTitle>
+                <DialogDescription>{ description }</DialogDescription>
+            </DialogHeader>
+            { profiles.length > 0 && <Select value={ profile ?? NO_PROFILE }
+                                             onValueChange={ (value) => onProfileChange(value === NO_PROFILE ? null : value) }>
+                <SelectTrigger className="w-full">
+                    <SelectValue placeholder="Profilo colonne"/>
+                </SelectTrigger>
+                <SelectContent>
+                    <SelectItem value={ NO_PROFILE }>Colonne standard</SelectItem>
+                    { profiles.map((p) => <SelectItem key={ p.name } value={ p.name }>{ p.name }</SelectItem>) }
+                </SelectContent>
+            </Select> }
+            { children }
+            { hasErrors && <ScrollArea className="max-h-80">
+                <Table>
+                    <TableHeader>
+                        <TableRow>
+                            <TableHead>Riga</TableHead>
+                            <TableHead>Colonna</TableHead>
+                            <TableHead>Errore</TableHead>
+                        </TableRow>
+                    </TableHeader>
+                    <TableBody>
+                        { errors.map((error) => (
+                            <TableRow key={ `${ error.row }-${ error.column }` }>
+                                <TableCell>{ error.row }</TableCell>
+                                <TableCell>{ error.column ?? "" }</TableCell>
+                                <TableCell>{ error.message }</TableCell>
+                            </TableRow>
+                        )) }
+                    </TableBody>
+                </Table>
+            </ScrollArea> }
+            <DialogFooter className="sm:justify-end">
+                <DialogClose asChild>
+                    <Button type="button" variant="outline">Annulla</Button>
+                </DialogClose>
+                <Button type="button" className="text-white" disabled={ hasErrors } onClick={ onConfirm }>
+                    Conferma importazione
+                </Button>
+            </DialogFooter>
+        </DialogContent>
+    </Dialog>;
+};
+
+export default ImportPreviewDialog;
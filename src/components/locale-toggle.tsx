@@ -0,0 +1,55 @@
+import { Languages } from "lucide-react";
+import { invoke } from "@tauri-apps/api/core";
+
+import { Button } from "@/components/ui/button";
+import {
+    DropdownMenu,
+    DropdownMenuContent,
+    DropdownMenuRadioGroup,
+    DropdownMenuRadioItem,
+    DropdownMenuTrigger
+} from "@/components/ui/dropdown-menu";
+import { useNotification } from "@/context/NotificationProvider.tsx";
+import { errorMessage } from "@/helpers/helpers.ts";
+import { IAppSettings, Locale } from "@/models/models.tsx";
+import { useEffect, useState } from "react";
+
+/** Lingua dei messaggi di errore restituiti dall'applicazione */
+export function LocaleToggle() {
+    const { addNotification } = useNotification();
+    const [ settings, setSettings ] = useState<IAppSettings | null>(null);
+
+    useEffect(() => {
+        invoke<IAppSettings>("get_settings")
+            .then(setSettings)
+            .catch((e) => addNotification(errorMessage(e), "error"));
+    }, [ addNotification ]);
+
+    const changeLocale = async (locale: string) => {
+        if (!settings) return;
+        const updated = { ...settings, locale: locale as Locale };
+        try {
+            await invoke("save_settings", { settings: updated });
+            setSettings(updated);
+        } catch (e) {
+            addNotification(errorMessage(e), "error");
+        }
+    };
+
+    return (
+        <DropdownMenu>
+            <DropdownMenuTrigger asChild>
+                <Button variant="ghost" size="icon">
+                    <Languages className="h-[1.2rem] w-[1.2rem]"/>
+                    <span className="sr-only">Lingua</span>
+                </Button>
+            </DropdownMenuTrigger>
+            <DropdownMenuContent align="end">
+                <DropdownMenuRadioGroup value={ settings?.locale } onValueChange={ changeLocale }>
+                    <DropdownMenuRadioItem value="it">Italiano</DropdownMenuRadioItem>
+                    <DropdownMenuRadioItem value="en">English</DropdownMenuRadioItem>
+                </DropdownMenuRadioGroup>
+            </DropdownMenuContent>
+        </DropdownMenu>
+    );
+}
@@ -0,0 +1,131 @@
+import {Collapsible, CollapsibleContent, CollapsibleTrigger} from "@/components/ui/collapsible.tsx";
+import {SidebarMenu, SidebarMenuButton, SidebarMenuItem, SidebarMenuSub} from "@/components/ui/sidebar";
+import {useNotification} from "@/context/NotificationProvider.tsx";
+import {ITaskRun, ITaskState} from "@/models/models.tsx";
+import {invoke} from "@tauri-apps/api/core";
+import {listen} from "@tauri-apps/api/event";
+import {ChevronRight, History, Pause, Play, Square, Timer, Zap} from "lucide-react";
+import {useCallback, useEffect, useState} from "react";
+import {errorMessage} from "@/helpers/helpers.ts";
+
+interface TaskStatusChanged {
+    type_event: string;
+    task: ITaskState;
+}
+
+const ACTIVE_STATUS = ["Running", "Completed"];
+
+const HISTORY_SIZE = 5;
+
+const formatDate = (value: string | null) => value ? new Date(value).toLocaleString() : "-";
+
+/** Stato dei processi in background con i comandi per controllarli */
+export function NavBackgroundTasks() {
+    const {addNotification} = useNotification();
+    const [tasks, setTasks] = useState<ITaskState[]>([]);
+    const [history, setHistory] = useState<{ task: string, runs: ITaskRun[] } | null>(null);
+
+    const loadTasks = useCallback(async () => {
+        try {
+            setTasks(await invoke("get_background_tasks"));
+        } catch (e) {
+            addNotification(errorMessage(e), "error");
+        }
+    }, [addNotification]);
+
+    useEffect(() => {
+        void loadTasks();
+        const taskListener = listen<TaskStatusChanged>("background_task", ({payload}) => {
+            setTasks(prev => prev.map(task => task.name === payload.task.name ? payload.task : task));
+        });
+
+        return () => {
+            taskListener
+                .then(callback => callback())
+                .catch(console.error);
+        };
+    }, [loadTasks]);
+
+    const command = async (name: string, action: "pause" | "resume" | "stop" | "trigger") => {
+        try {
+            await invoke(`${action}_background_task`, {name: name});
+            await loadTasks();
+        } catch (e) {
+            addNotification(errorMessage(e), "error");
+        }
+    };
+
+    const toggleHistory = async (name: string) => {
+        if (history?.task === name) {
+            setHistory(null);
+            return;
+        }
+        try {
+            const runs: ITaskRun[] = await invoke("get_background_task_history", {name: name});
+            setHistory({task: name, runs: runs.slice(0, HISTORY_SIZE)});
+        } catch (e) {
+            addNotification(errorMessage(e), "error");
+        }
+    };
+
+    return (
+        <SidebarMenu>
+            <Collapsible defaultOpen={false} className="group/collapsible">
+                <SidebarMenuItem>
+                    <CollapsibleTrigger asChild>
+                        <SidebarMenuButton>
+                            <Timer/> <span>Processi in background</span>
+                            <ChevronRight
+                                className="ml-auto transition-transform duration-200 group-data-[state=open]/collapsible:rotate-90"/>
+                        </SidebarMenuButton>
+                    </CollapsibleTrigger>
+                    <CollapsibleContent>
+                        <SidebarMenuSub>
+                            {tasks.map(task => {
+                                const active = ACTIVE_STATUS.includes(task.status);
+                                return <div key={task.name} className="flex flex-col gap-1 py-1 text-xs">
+                                    <div className="flex items-center justify-between">
+                                        <span className="font-medium">{task.name}</span>
+                                        <span>{task.status}</span>
+                                    </div>
+                                    <span className="text-muted-foreground">
+                                        Ultima esecuzione: {formatDate(task.last_run)}
+                                    </span>
+                                    <span className="text-muted-foreground">
+                                        Prossima esecuzione: {formatDate(task.next_run)}
+                                    </span>
+                                    {task.last_error && <span className="text-destructive">{task.last_error}</span>}
+                                    <div className="flex gap-2">
+                                        {active
+                                         ? <button title="Metti in pausa" onClick={() => void command(task.name, "pause")}>
+                                             <Pause size="14"/>
+                                         </button>
+                                         : <button title="Avvia" onClick={() => void command(task.name, "resume")}>
+                                             <Play size="14"/>
+                                         </button>}
+                                        <button title="Ferma" disabled={!active}
+                                                onClick={() => void command(task.name, "stop")}>
+                                            <Square size="14"/>
+                                        </button>
+                                        <button title="Esegui ora" onClick={() => void command(task.name, "trigger")}>
+                                            <Zap size="14"/>
+                                        </button>
+                                        <button title="Storico" onClick={() => void toggleHistory(task.name)}>
+                                            <History size="14"/>
+                                        </button>
+                                    </div>
+                                    {history?.task === task.name && history.runs.map(run => {
+                                        return <span key={run.started_at + run.attempt} className="text-muted-foreground">
+                                            {formatDate(run.started_at)} · {run.outcome}
+                                            {run.attempt > 1 && ` (tentativo ${run.attempt})`}
+                                            {run.error && `: ${run.error}`}
+                                        </span>;
+                                    })}
+                                </div>;
+                            })}
+                        </SidebarMenuSub>
+                    </CollapsibleContent>
+                </SidebarMenuItem>
+            </Collapsible>
+        </SidebarMenu>);
+}
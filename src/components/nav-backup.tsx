@@ -0,0 +1,147 @@
+import {Collapsible, CollapsibleContent, CollapsibleTrigger} from "@/components/ui/collapsible.tsx";
+import {SidebarMenu, SidebarMenuButton, SidebarMenuItem, SidebarMenuSub} from "@/components/ui/sidebar";
+import {useNotification} from "@/context/NotificationProvider.tsx";
+import {useSelectedEdificio} from "@/context/SelectedEdificioProvider.tsx";
+import {ILocalBackup, IRestoreReport} from "@/models/models.tsx";
+import {invoke} from "@tauri-apps/api/core";
+import {confirm, open, save} from "@tauri-apps/plugin-dialog";
+import {ArchiveRestore, ChevronRight, DatabaseBackup, History} from "lucide-react";
+import {useState} from "react";
+import {errorMessage} from "@/helpers/helpers.ts";
+
+const BACKUP_FILTERS = [
+    {
+        name      : "Backup",
+        extensions: ["zip", "json"]
+    }
+];
+
+/** Backup del fascicolo selezionato, ripristino da file e dai backup automatici */
+export function NavBackup() {
+    const selectedEdificio = useSelectedEdificio();
+    const {addNotification} = useNotification();
+    const [localBackups, setLocalBackups] = useState<ILocalBackup[]>([]);
+
+    const notifyRestore = (report: IRestoreReport) => {
+        addNotification(
+            `Ripristinati ${report.edifici.length} edifici, ${report.stanze} stanze e ${report.infissi} infissi`,
+            "success"
+        );
+        if (report.trasmittanze_diverse.length > 0) {
+            const voci = report.trasmittanze_diverse
+                .map(t => `${t.catalogo} ${t.nome}: ${t.backup} nel backup, ${t.database ?? "non indicata"} nel database`)
+                .join("; ");
+            addNotification(`Trasmittanze non ripristinate, il catalogo è condiviso con gli altri edifici: ${voci}`, "warning");
+        }
+    };
+
+    const exportBackup = async () => {
+        const fascicolo = selectedEdificio.edificio?.fascicolo;
+        if (fascicolo === undefined) {
+            return;
+        }
+        const path = await save({
+            title      : "Salva il backup del fascicolo",
+            defaultPath: `fascicolo_${fascicolo}.zip`,
+            filters    : BACKUP_FILTERS
+        });
+        if (!path) {
+            return;
+        }
+        try {
+            await invoke("export_fascicoli_backup", {
+                fascicoli: [fascicolo],
+                path     : path
+            });
+            addNotification(`Backup del fascicolo ${fascicolo} salvato`, "success");
+        } catch (e) {
+            addNotification(errorMessage(e), "error");
+        }
+    };
+
+    const restoreBackup = async () => {
+        const path = await open({
+            title    : "Seleziona il backup da ripristinare",
+            multiple : false,
+            directory: false,
+            filters  : BACKUP_FILTERS
+        });
+        if (!path) {
+            return;
+        }
+        try {
+            const report: IRestoreReport = await invoke("restore_fascicoli_backup", {path: path});
+            notifyRestore(report);
+        } catch (e) {
+            addNotification(errorMessage(e), "error");
+        }
+    };
+
+    const loadLocalBackups = async (open: boolean) => {
+        if (!open) {
+            return;
+        }
+        try {
+            setLocalBackups(await invoke<ILocalBackup[]>("get_local_backups"));
+        } catch (e) {
+            addNotification(errorMessage(e), "error");
+        }
+    };
+
+    const restoreLocalBackup = async (backup: ILocalBackup) => {
+        const confirmed = await confirm(
+            "I dati attuali dei fascicoli del backup verranno eliminati e sostituiti da quelli del backup.",
+            {title: "Ripristina backup automatico", kind: "warning"}
+        );
+        if (!confirmed) {
+            return;
+        }
+        try {
+            const report: IRestoreReport = await invoke("restore_local_backup", {fileName: backup.file_name});
+            notifyRestore(report);
+        } catch (e) {
+            addNotification(errorMessage(e), "error");
+        }
+    };
+
+    return (
+        <SidebarMenu>
+            <SidebarMenuItem>
+                <SidebarMenuButton disabled={!selectedEdificio.edificio} onClick={() => void exportBackup()}>
+                    <DatabaseBackup/> <span>Backup fascicolo</span>
+                </SidebarMenuButton>
+            </SidebarMenuItem>
+            <SidebarMenuItem>
+                <SidebarMenuButton onClick={() => void restoreBackup()}>
+                    <ArchiveRestore/> <span>Ripristina backup</span>
+                </SidebarMenuButton>
+            </SidebarMenuItem>
+            <Collapsible defaultOpen={false} className="group/collapsible"
+                         onOpenChange={open => void loadLocalBackups(open)}>
+                <SidebarMenuItem>
+                    <CollapsibleTrigger asChild>
+                        <SidebarMenuButton>
+                            <History/> <span>Backup automatici</span>
+                            <ChevronRight
+                                className="ml-auto transition-transform duration-200 group-data-[state=open]/collapsible:rotate-90"/>
+                        </SidebarMenuButton>
+                    </CollapsibleTrigger>
+                    <CollapsibleContent>
+                        <SidebarMenuSub>
+                            {localBackups.length === 0 &&
+                                <span className="py-1 text-xs text-muted-foreground">Nessun backup</span>}
+                            {localBackups.map(backup => {
+                                return <div key={backup.file_name}
+                                            className="flex items-center justify-between py-1 text-xs">
+                                    <span>{new Date(backup.created_at).toLocaleString()}</span>
+                                    <button title="Ripristina" onClick={() => void restoreLocalBackup(backup)}>
+                                        <ArchiveRestore size="14"/>
+                                    </button>
+                                </div>;
+                            })}
+                        </SidebarMenuSub>
+                    </CollapsibleContent>
+                </SidebarMenuItem>
+            </Collapsible>
+        </SidebarMenu>);
+}
@@ -1,5 +1,6 @@
 "use client";
 
+import ImportPreviewDialog from "@/components/import-preview-dialog.tsx";
 import {Collapsible, CollapsibleContent, CollapsibleTrigger} from "@/components/ui/collapsible.tsx";
 import {
     SidebarGroup,
@@ -13,11 +14,12 @@ import {
 import {useNotification} from "@/context/NotificationProvider.tsx";
 import {useSelectedEdificio} from "@/context/SelectedEdificioProvider.tsx";
 import {useEdifici} from "@/context/UseProvider.tsx";
-import {IEdificio} from "@/models/models.tsx";
+import {IEdificio, IImportPreview, IMappingProfile} from "@/models/models.tsx";
 import {invoke} from "@tauri-apps/api/core";
 import {open} from "@tauri-apps/plugin-dialog";
 import {Building, Check, ChevronRight, FileText, Plus} from "lucide-react";
 import {useEffect, useMemo, useState} from "react";
+import {errorMessage} from "@/helpers/helpers.ts";
 
 export function NavMain({valueSearch}: Readonly<{ valueSearch: string | null }>) {
     const selectedEdificio = useSelectedEdificio();
@@ -33,6 +35,10 @@ export function NavMain({valueSearch}: Readonly<{ valueSearch: string | null }>)
         return Array.from(fascicoli);
     }, [valueSearch, fascicoli, filteredFascicolo]);
     const [selectedFascicolo, setSelectedFascicolo] = useState<number>();
+    const [importPath, setImportPath] = useState<string | null>(null);
+    const [importPreview, setImportPreview] = useState<IImportPreview | null>(null);
+    const [mappingProfiles, setMappingProfiles] = useState<IMappingProfile[]>([]);
+    const [importProfile, setImportProfile] = useState<string | null>(null);
 
     useEffect(() => {
         const newFascicoli = new Map<number, IEdificio[]>();
@@ -72,25 +78,67 @@ export function NavMain({valueSearch}: Readonly<{ valueSearch: string | null }>)
             directory: false,
             filters  : [
                 {
-                    name      : "Excel file",
-                    extensions: ["xlsx", "xls"]
+                    name      : "Foglio di calcolo",
+                    extensions: ["xlsx", "xls", "ods", "csv"]
                 }
             ]
         });
         if (!path_file) {
             return;
         }
-        console.log(path_file);
 
-
-        /* Passare il path a rust che ne elabora il contenuto (con polars) e imposta il database */
+        /* Rust analizza il file e restituisce cosa verrebbe inserito, senza scrivere nel database */
         try {
-            await invoke("add_new_fascicolo_from_xlsx", {
+            const profiles: IMappingProfile[] = await invoke("get_mapping_profiles");
+            const suggested: string | null = await invoke("suggest_mapping_profile", {
                 path: path_file
             });
+            setMappingProfiles(profiles);
+            setImportPath(path_file);
+            await loadImportPreview(path_file, suggested);
+        } catch (e) {
+            addNotification(errorMessage(e), "error");
+        }
+    };
+
+    const loadImportPreview = async (path: string, profile: string | null) => {
+        const preview: IImportPreview = await invoke("preview_fascicolo_from_file", {
+            path   : path,
+            profile: profile
+        });
+        setImportProfile(profile);
+        setImportPreview(preview);
+    };
+
+    const changeImportProfile = async (profile: string | null) => {
+        if (!importPath) {
+            return;
+        }
+        try {
+            await loadImportPreview(importPath, profile);
+        } catch (e) {
+            addNotification(errorMessage(e), "error");
+        }
+    };
+
+    const closeImportPreview = () => {
+        setImportPath(null);
+        setImportPreview(null);
+        setImportProfile(null);
+    };
+
+    const confirmImport = async () => {
+        const path = importPath;
+        const profile = importProfile;
+        closeImportPreview();
+        try {
+            await invoke("add_new_fascicolo_from_file", {
+                path   : path,
+                profile: profile
+            });
             addNotification("Inserimento avvenuto con successo", "success");
         } catch (e) {
-            addNotification(e as string, "error");
+            addNotification(errorMessage(e), "error");
         }
     };
 
@@ -116,6 +164,19 @@ export function NavMain({valueSearch}: Readonly<{ valueSearch: string | null }>)
         <SidebarGroupAction title="Aggiungi Fascicolo" onClick={() => void addNewFascicolo()}>
             <Plus/> <span className="sr-only">Aggiungi Fascicolo</span>
         </SidebarGroupAction>
+        <ImportPreviewDialog open={importPreview !== null}
+                             description={`${importPreview?.edifici.length ?? 0} edifici e ${importPreview?.stanze.length ?? 0} stanze da inserire, ${importPreview?.stanze_aggiornate.length ?? 0} stanze da aggiornare`}
+                             errors={importPreview?.errors ?? []}
+                             profiles={mappingProfiles}
+                             profile={importProfile}
+                             onProfileChange={(profile) => void changeImportProfile(profile)}
+                             onOpenChange={(open) => !open && closeImportPreview()}
+                             onConfirm={() => void confirmImport()}>
+            {(importPreview?.stanze_mancanti.length ?? 0) > 0 && <p className="text-sm text-muted-foreground">
+                Stanze non più presenti nel file, che verranno mantenute:{" "}
+                {importPreview?.stanze_mancanti.map((stanza) => `${stanza.edificio_id} ${stanza.cod_stanza}`).join(", ")}
+            </p>}
+        </ImportPreviewDialog>
         <SidebarMenu>
             {fascicoliRender.map(([fascicolo, edifici]) => {
                 return <Collapsible defaultOpen={false} className="group/collapsible" key={fascicolo}>
@@ -1,5 +1,5 @@
 import {createContext, Dispatch, SetStateAction} from "react";
-import {IEdificio, IFotovoltaico, IInfisso, IStanza, IUtenza, NuovoTipo} from "../models/models.tsx";
+import {IEdificio, IFotovoltaico, IInfisso, IStanza, IUtenza, NuovoTipo, TipoKey} from "../models/models.tsx";
 
 export interface DatabaseContextType {
     isLoading: boolean;
@@ -15,6 +15,8 @@ export interface InfissiContextType {
     data: IInfisso[];
     insertInfisso: (newInfisso: IInfisso) => Promise<void>;
     modifyInfisso: (infisso: Partial<IInfisso>) => Promise<void>;
+    deleteInfisso: (infisso: IInfisso) => Promise<void>;
+    reloadInfissi: () => Promise<void>;
     isLoading: boolean;
 }
 
@@ -23,6 +25,7 @@ export const InfissiContext = createContext<InfissiContextType | null>(null);
 export interface IStanzaContext {
     data: IStanza[];
     updateStanza: (newStanza: IStanza) => void;
+    deleteStanza: (stanza: IStanza) => Promise<void>;
 }
 
 export const StanzeContext = createContext<IStanzaContext | null>(null);
@@ -35,6 +38,7 @@ export interface TypeContextType {
     tipoInfissi: string[];
     isLoading: boolean;
     insertType: (newType: NuovoTipo) => Promise<void>;
+    deleteType: (tipo: TipoKey, name: string) => Promise<void>;
 }
 
 export const TypesContext = createContext<TypeContextType | null>(null);
@@ -43,6 +47,7 @@ export interface EdificioContextType {
     data: IEdificio[];
     setEdifici: Dispatch<SetStateAction<IEdificio[]>>;
     modifyEdificio: (edificio: IEdificio) => Promise<void>;
+    deleteEdificio: (edificio: IEdificio) => Promise<void>;
     error: string | null;
     isLoading: boolean;
 }
@@ -54,6 +59,8 @@ export interface UtenzeContextType {
     error: string | null;
     isLoading: boolean;
     insertUtenza: (value: IUtenza) => Promise<void>;
+    updateUtenza: (value: IUtenza) => Promise<void>;
+    deleteUtenza: (value: IUtenza) => Promise<void>;
 }
 
 export const UtenzeContext = createContext<UtenzeContextType | null>(null);
@@ -63,6 +70,8 @@ export interface FotovoltaicoContextType {
     error: string | null;
     isLoading: boolean;
     insertFotovoltaico: (value: IFotovoltaico) => Promise<void>;
+    updateFotovoltaico: (value: IFotovoltaico) => Promise<void>;
+    deleteFotovoltaico: (value: IFotovoltaico) => Promise<void>;
 }
 
 export const FotovoltaicoContext = createContext<FotovoltaicoContextType | null>(null);
\ No newline at end of file
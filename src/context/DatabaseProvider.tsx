@@ -4,6 +4,7 @@ import {invoke} from "@tauri-apps/api/core";
 import * as React from "react";
 import {useCallback, useEffect, useMemo, useState} from "react";
 import {DatabaseContext, DatabaseContextType} from "./Context.tsx";
+import {errorMessage} from "@/helpers/helpers.ts";
 
 export const RELOAD_END = "reload-end";
 
@@ -21,8 +22,8 @@ const DatabaseProvider = ({children}: { children: React.ReactNode }) => {
             await invoke("switch_database", {chiave: chiave});
             addNotification("Cambio file avvenuto con successo", "success");
         } catch (e) {
-            setError(e as string);
-            addNotification(e as string, "error");
+            setError(errorMessage(e));
+            addNotification(errorMessage(e), "error");
         } finally {
             setIsLoading(false);
         }
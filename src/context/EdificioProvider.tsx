@@ -1,10 +1,12 @@
 import {EdificioContext, EdificioContextType} from "@/context/Context.tsx";
 import {useNotification} from "@/context/NotificationProvider.tsx";
 import {useDatabase} from "@/context/UseProvider.tsx";
+import {useDeletedEvent} from "@/hooks/use-deleted-event.ts";
 import {IEdificio} from "@/models/models.tsx";
 import {invoke} from "@tauri-apps/api/core";
 import * as React from "react";
 import {useCallback, useEffect, useMemo, useRef, useState} from "react";
+import {errorMessage} from "@/helpers/helpers.ts";
 
 
 const EdificioProvider = ({children}: { children: React.ReactNode }) => {
@@ -28,7 +30,7 @@ const EdificioProvider = ({children}: { children: React.ReactNode }) => {
             setEdifici(edifici);
             addNotification("Edifici caricarti correttamente", "success");
         } catch (e) {
-            addNotification(e as string, "error");
+            addNotification(errorMessage(e), "error");
         } finally {
             setIsLoading(false);
         }
@@ -55,7 +57,25 @@ const EdificioProvider = ({children}: { children: React.ReactNode }) => {
             });
             addNotification("Edificio modificato correttamente", "success");
         } catch (e) {
-            addNotification(e as string, "error");
+            addNotification(errorMessage(e), "error");
+        } finally {
+            setIsLoading(false);
+        }
+    }, [addNotification]);
+
+    const removeEdificio = useCallback((chiave: string) => {
+        setEdifici((prev) => prev.filter(value => value.chiave !== chiave));
+    }, []);
+
+    useDeletedEvent<string>("edificio", "DeletedEdificio", removeEdificio);
+
+    const deleteEdificio = useCallback(async (edificio: IEdificio) => {
+        try {
+            setIsLoading(true);
+            await invoke("delete_edificio", {chiave: edificio.chiave});
+            addNotification(`Edificio ${edificio.chiave} eliminato correttamente`, "success");
+        } catch (e) {
+            addNotification(errorMessage(e), "error");
         } finally {
             setIsLoading(false);
         }
@@ -81,10 +101,11 @@ const EdificioProvider = ({children}: { children: React.ReactNode }) => {
             data          : edifici,
             isLoading     : isLoading,
             modifyEdificio: modifyEdificio,
+            deleteEdificio: deleteEdificio,
             setEdifici    : setEdifici,
             error         : null
         };
-    }, [edifici, isLoading, modifyEdificio]);
+    }, [deleteEdificio, edifici, isLoading, modifyEdificio]);
 
     return <EdificioContext.Provider value={obj}>
         {children}
@@ -5,6 +5,8 @@ import { IFotovoltaico } from "@/models/models.tsx";
 import { useDatabase } from "@/context/UseProvider.tsx";
 import { invoke } from "@tauri-apps/api/core";
 import { useNotification } from "@/context/NotificationProvider.tsx";
+import { useDeletedEvent } from "@/hooks/use-deleted-event.ts";
+import { errorMessage } from "@/helpers/helpers.ts";
 
 const FotovoltaicoProvider = ({ children }: { children: React.ReactNode }) => {
     const {
@@ -27,7 +29,7 @@ const FotovoltaicoProvider = ({ children }: { children: React.ReactNode }) => {
             setFotovoltaico(fotovoltaico);
             addNotification("Fotovoltaico caricato correttamente", "success");
         } catch (e) {
-            addNotification(e as string, "error");
+            addNotification(errorMessage(e), "error");
         } finally {
             setLoading(false);
         }
@@ -43,12 +45,37 @@ const FotovoltaicoProvider = ({ children }: { children: React.ReactNode }) => {
             })
             addNotification("Fotovoltaico inserito correttamente", "success");
         } catch (e) {
-            addNotification(e as string, "error");
+            addNotification(errorMessage(e), "error");
         } finally {
             setLoading(false);
         }
     }, [ addNotification ])
 
+    const updateFotovoltaico = useCallback(async (fotovoltaico: IFotovoltaico) => {
+        try {
+            const updated_fotovoltaico: IFotovoltaico = await invoke("update_fotovoltaico", { fotovoltaico });
+            setFotovoltaico((prev) => prev.map(value => value.id === updated_fotovoltaico.id ? updated_fotovoltaico : value));
+            addNotification("Fotovoltaico modificato correttamente", "success");
+        } catch (e) {
+            addNotification(errorMessage(e), "error");
+        }
+    }, [ addNotification ]);
+
+    const removeFotovoltaico = useCallback((id: number) => {
+        setFotovoltaico((prev) => prev.filter(value => value.id !== id));
+    }, []);
+
+    useDeletedEvent<number>("fotovoltaico", "DeletedFotovoltaico", removeFotovoltaico);
+
+    const deleteFotovoltaico = useCallback(async (fotovoltaico: IFotovoltaico) => {
+        try {
+            await invoke("delete_fotovoltaico", { id: fotovoltaico.id });
+            addNotification("Fotovoltaico eliminato correttamente", "success");
+        } catch (e) {
+            addNotification(errorMessage(e), "error");
+        }
+    }, [ addNotification ]);
+
     useEffect(() => {
         if (needReload) {
             loadFotovoltaico().then(() => {
@@ -65,7 +92,9 @@ const FotovoltaicoProvider = ({ children }: { children: React.ReactNode }) => {
         data              : fotovoltaico,
         isLoading         : loading,
         insertFotovoltaico: insertFotovoltaico,
-    } as FotovoltaicoContextType), [ fotovoltaico, loading, insertFotovoltaico ]);
+        updateFotovoltaico: updateFotovoltaico,
+        deleteFotovoltaico: deleteFotovoltaico,
+    } as FotovoltaicoContextType), [ fotovoltaico, loading, insertFotovoltaico, updateFotovoltaico, deleteFotovoltaico ]);
 
     return <FotovoltaicoContext.Provider value={ obj }>
         { children }
@@ -1,3 +1,4 @@
+import DatabaseConnectionGate from "@/components/database-connection-gate.tsx";
 import EdificioProvider from "@/context/EdificioProvider.tsx";
 import FotovoltaicoProvider from "@/context/FotovoltaicoProvider.tsx";
 import {NotificationProvider} from "@/context/NotificationProvider.tsx";
@@ -11,23 +12,25 @@ import UtenzeProvider from "./UtenzeProvider.tsx";
 
 const GlobalProvider = ({children}: { children: React.ReactNode }) => {
     return <NotificationProvider>
-        <DatabaseProvider>
-            <TypesProvider>
-                <EdificioProvider>
-                    <SelectedEdificioProvider>
-                        <InfissiProvider>
-                            <StanzeProvider>
-                                <UtenzeProvider>
-                                    <FotovoltaicoProvider>
-                                        {children}
-                                    </FotovoltaicoProvider>
-                                </UtenzeProvider>
-                            </StanzeProvider>
-                        </InfissiProvider>
-                    </SelectedEdificioProvider>
-                </EdificioProvider>
-            </TypesProvider>
-        </DatabaseProvider>
+        <DatabaseConnectionGate>
+            <DatabaseProvider>
+                <TypesProvider>
+                    <EdificioProvider>
+                        <SelectedEdificioProvider>
+                            <InfissiProvider>
+                                <StanzeProvider>
+                                    <UtenzeProvider>
+                                        <FotovoltaicoProvider>
+                                            {children}
+                                        </FotovoltaicoProvider>
+                                    </UtenzeProvider>
+                                </StanzeProvider>
+                            </InfissiProvider>
+                        </SelectedEdificioProvider>
+                    </EdificioProvider>
+                </TypesProvider>
+            </DatabaseProvider>
+        </DatabaseConnectionGate>
     </NotificationProvider>;
 };
 
@@ -1,4 +1,5 @@
 import {useSelectedEdificio} from "@/context/SelectedEdificioProvider.tsx";
+import {useDeletedEvent} from "@/hooks/use-deleted-event.ts";
 import {invoke} from "@tauri-apps/api/core";
 import * as React from "react";
 import {useCallback, useEffect, useMemo, useRef, useState} from "react";
@@ -6,6 +7,7 @@ import {IInfisso} from "../models/models.tsx";
 import {InfissiContext, InfissiContextType} from "./Context.tsx";
 import {useNotification} from "./NotificationProvider.tsx";
 import {useDatabase} from "./UseProvider.tsx";
+import {errorMessage} from "@/helpers/helpers.ts";
 
 const InfissiProvider = ({children}: { children: React.ReactNode }) => {
     const {
@@ -29,7 +31,7 @@ const InfissiProvider = ({children}: { children: React.ReactNode }) => {
             setInfissi(data);
             addNotification("Infissi caricati correttamente", "success");
         } catch (e) {
-            addNotification(e as string, "error");
+            addNotification(errorMessage(e), "error");
         } finally {
             setLoading(false);
         }
@@ -55,7 +57,7 @@ const InfissiProvider = ({children}: { children: React.ReactNode }) => {
             setInfissi((prev) => [...prev, insertedInfisso]);
             addNotification(`Infisso ${insertedInfisso.id} inserito correttamente`, "success");
         } catch (e) {
-            addNotification(e as string, "error");
+            addNotification(errorMessage(e), "error");
         }
     }, [addNotification]);
 
@@ -65,10 +67,24 @@ const InfissiProvider = ({children}: { children: React.ReactNode }) => {
             setInfissi((prev) => [...prev.filter(i => i.id !== infisso.id), updatedInfisso]);
             addNotification(`Infisso ${updatedInfisso.id} modificato correttamente`, "success");
         } catch (e) {
-            addNotification(e as string, "error");
+            addNotification(errorMessage(e), "error");
         }
     }, [addNotification]);
 
+    const removeInfisso = useCallback(([id, idEdificio]: [string, string]) => {
+        setInfissi((prev) => prev.filter(i => !(i.id === id && i.id_edificio === idEdificio)));
+    }, []);
+
+    useDeletedEvent<[string, string]>("infisso", "DeletedInfisso", removeInfisso);
+
+    const deleteInfisso = useCallback(async (infisso: IInfisso) => {
+        try {
+            await invoke("delete_infisso", {id: infisso.id, idEdificio: infisso.id_edificio});
+            addNotification(`Infisso ${infisso.id} eliminato correttamente`, "success");
+        } catch (e) {
+            addNotification(errorMessage(e), "error");
+        }
+    }, [addNotification]);
 
     const obj = useMemo(() => {
         return {
@@ -76,9 +92,11 @@ const InfissiProvider = ({children}: { children: React.ReactNode }) => {
                                                                                  edificio.chiave),
             insertInfisso: insertInfisso,
             modifyInfisso: modifyInfisso,
+            deleteInfisso: deleteInfisso,
+            reloadInfissi: loadInfissi,
             isLoading    : loading
         } as InfissiContextType;
-    }, [edificio, infissi, insertInfisso, loading, modifyInfisso]);
+    }, [deleteInfisso, edificio, infissi, insertInfisso, loadInfissi, loading, modifyInfisso]);
 
     return <InfissiContext.Provider value={obj}>
         {children}
@@ -6,6 +6,7 @@ import {invoke} from "@tauri-apps/api/core";
 import {listen} from "@tauri-apps/api/event";
 import * as React from "react";
 import {createContext, useCallback, useContext, useEffect, useMemo, useState} from "react";
+import {errorMessage} from "@/helpers/helpers.ts";
 
 export interface SelectedEdificioContextType {
     edificio: IEdificio | undefined;
@@ -22,6 +23,10 @@ interface EdificioChange extends EventBackend {
     chiave: string;
 }
 
+interface DeletedEdificio extends EventBackend {
+    id: string;
+}
+
 interface NewEdificio extends EventBackend {
     edifici: IEdificio[];
     edificio_selected: string;
@@ -46,7 +51,7 @@ export const SelectedEdificioProvider = ({children}: { children: React.ReactNode
             setEdificio(edificio);
             addNotification("Cambio edificio avvenuto con successo", "success");
         } catch (e) {
-            addNotification(e as string, "error");
+            addNotification(errorMessage(e), "error");
         }
     }, [addNotification]);
 
@@ -84,6 +89,11 @@ export const SelectedEdificioProvider = ({children}: { children: React.ReactNode
                 });
                 const edificio = event.edifici.find(edificio => edificio.chiave === event.edificio_selected)!;
                 setEdificioSelected(edificio);
+            } else if (payload.type_event === "DeletedEdificio") {
+                console.log("Event deleted edificio:", payload);
+                const event = payload as DeletedEdificio;
+
+                setEdificio(prev => prev?.chiave === event.id ? undefined : prev);
             } else {
                 setIsLoading(false);
                 setError("Edificio non esiste");
@@ -1,5 +1,6 @@
 import {useNotification} from "@/context/NotificationProvider.tsx";
 import {useSelectedEdificio} from "@/context/SelectedEdificioProvider.tsx";
+import {useDeletedEvent} from "@/hooks/use-deleted-event.ts";
 import {invoke} from "@tauri-apps/api/core";
 import * as React from "react";
 import {useCallback, useEffect, useMemo, useRef, useState} from "react";
@@ -7,6 +8,7 @@ import {toast} from "sonner";
 import {IStanza} from "../models/models.tsx";
 import {IStanzaContext, StanzeContext} from "./Context.tsx";
 import {useDatabase} from "./UseProvider.tsx";
+import {errorMessage, isErrorResponse} from "@/helpers/helpers.ts";
 
 const StanzeProvider = ({children}: { children: React.ReactNode }) => {
     const {
@@ -30,7 +32,7 @@ const StanzeProvider = ({children}: { children: React.ReactNode }) => {
             setStanze(data);
             addNotification("Stanze caricate correttamente", "success");
         } catch (e) {
-            addNotification(e as string, "error");
+            addNotification(errorMessage(e), "error");
         } finally {
             setLoading(false);
         }
@@ -71,23 +73,38 @@ const StanzeProvider = ({children}: { children: React.ReactNode }) => {
                                                                  newStanza.id ? updateStanzaProperties(stanza, newStanza) : stanza));
             addNotification(`Stanza ${newStanza.cod_stanza} aggiornata`, "success");
         } catch (e) {
-            if (e === "Nessun record aggiornato") {
-                toast.info(e as string);
+            if (isErrorResponse(e) && e.code === "NOT_FOUND") {
+                toast.info(e.message);
                 return;
             }
-            addNotification(e as string, "error");
+            addNotification(errorMessage(e), "error");
         }
 
     }, [addNotification]);
 
+    const removeStanza = useCallback((id: number) => {
+        setStanze((prev) => prev.filter(stanza => stanza.id !== id));
+    }, []);
+
+    useDeletedEvent<number>("stanza", "DeletedStanza", removeStanza);
+
+    const deleteStanza = useCallback(async (stanza: IStanza) => {
+        try {
+            await invoke("delete_stanza", {id: stanza.id});
+            addNotification(`Stanza ${stanza.cod_stanza} eliminata`, "success");
+        } catch (e) {
+            addNotification(errorMessage(e), "error");
+        }
+    }, [addNotification]);
 
     const obj: IStanzaContext = useMemo(() => {
         return {
             data        : stanze.filter(value => value.edificio_id === edificio?.chiave) ?? [],
             updateStanza: updateStanza,
+            deleteStanza: deleteStanza,
             loading     : loading
         };
-    }, [edificio?.chiave, loading, stanze, updateStanza]);
+    }, [deleteStanza, edificio?.chiave, loading, stanze, updateStanza]);
 
     return <StanzeContext.Provider value={obj}>
         {children}
@@ -8,10 +8,13 @@ import {
     MaterialeInfisso,
     NuovoTipo,
     TipoInfisso,
+    TipoKey,
     VetroInfisso
 } from "../models/models.tsx";
 import { useDatabase } from "@/context/UseProvider.tsx";
 import { useNotification } from "@/context/NotificationProvider.tsx";
+import { useDeletedEvent } from "@/hooks/use-deleted-event.ts";
+import { errorMessage } from "@/helpers/helpers.ts";
 
 interface TypePayload {
     "materiale_infissi": MaterialeInfisso[],
@@ -58,7 +61,7 @@ const TypesProvider = ({ children }: { children: React.ReactNode }) => {
             setTipoInfissi(data["tipo_infissi"].map(value => value.nome));
             addNotification("Tipi caricati correttamente", "success");
         } catch (e) {
-            addNotification(e as string, "error");
+            addNotification(errorMessage(e), "error");
         } finally {
             setIsLoading(false);
         }
@@ -89,10 +92,28 @@ const TypesProvider = ({ children }: { children: React.ReactNode }) => {
             addTypeToState(inserted_type.tipo, inserted_type.name);
             addNotification(`Tipo ${ inserted_type.name } inserito correttamente`, "success");
         } catch (e) {
-            addNotification(e as string, "error");
+            addNotification(errorMessage(e), "error");
         }
     }, [ addTypeToState, addNotification ])
 
+    const removeTypeFromState = useCallback(([ tipo, name ]: [ string, string ]) => {
+        const setter = typeSetters[tipo.toLowerCase()];
+        if (setter) {
+            setter((prev) => prev.filter(value => value !== name));
+        }
+    }, [ typeSetters ]);
+
+    useDeletedEvent<[ string, string ]>("tipo", "DeletedTipo", removeTypeFromState);
+
+    const deleteType = useCallback(async (tipo: TipoKey, name: string) => {
+        try {
+            await invoke("delete_tipo", { tipo, name });
+            addNotification(`Tipo ${ name } eliminato correttamente`, "success");
+        } catch (e) {
+            addNotification(errorMessage(e), "error");
+        }
+    }, [ addNotification ])
+
 
     const obj = useMemo(() => {
         return {
@@ -103,8 +124,9 @@ const TypesProvider = ({ children }: { children: React.ReactNode }) => {
             tipoInfissi,
             isLoading,
             insertType: insertType,
+            deleteType: deleteType,
         } as TypeContextType;
-    }, [ materialiInfissiType, vetroInfissiType, climatizzazioneType, illuminazioneType, tipoInfissi, isLoading, insertType ]);
+    }, [ materialiInfissiType, vetroInfissiType, climatizzazioneType, illuminazioneType, tipoInfissi, isLoading, insertType, deleteType ]);
 
     return <TypesContext.Provider value={ obj }>
         { children }
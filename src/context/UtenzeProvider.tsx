@@ -1,10 +1,12 @@
 import {UtenzeContext, UtenzeContextType} from "@/context/Context.tsx";
 import {useNotification} from "@/context/NotificationProvider.tsx";
 import {useDatabase} from "@/context/UseProvider.tsx";
+import {useDeletedEvent} from "@/hooks/use-deleted-event.ts";
 import {IUtenza} from "@/models/models.tsx";
 import {invoke} from "@tauri-apps/api/core";
 import * as React from "react";
 import {useCallback, useEffect, useMemo, useRef, useState} from "react";
+import {errorMessage} from "@/helpers/helpers.ts";
 
 const UtenzeProvider = ({children}: { children: React.ReactNode }) => {
     const {
@@ -27,7 +29,7 @@ const UtenzeProvider = ({children}: { children: React.ReactNode }) => {
             setUtenze(utenze);
             addNotification("Utenze caricate correttamente", "success");
         } catch (e) {
-            addNotification(e as string, "error");
+            addNotification(errorMessage(e), "error");
         } finally {
             setLoading(false);
         }
@@ -43,12 +45,37 @@ const UtenzeProvider = ({children}: { children: React.ReactNode }) => {
             });
             addNotification("Utenza inserita correttamente", "success");
         } catch (e) {
-            addNotification(e as string, "error");
+            addNotification(errorMessage(e), "error");
         } finally {
             setLoading(false);
         }
     }, [addNotification]);
 
+    const updateUtenza = useCallback(async (utenza: IUtenza) => {
+        try {
+            const updatedUtenza: IUtenza = await invoke("update_utenza", {utenza});
+            setUtenze((prev) => prev.map(value => value.id === updatedUtenza.id ? updatedUtenza : value));
+            addNotification("Utenza modificata correttamente", "success");
+        } catch (e) {
+            addNotification(errorMessage(e), "error");
+        }
+    }, [addNotification]);
+
+    const removeUtenza = useCallback((id: number) => {
+        setUtenze((prev) => prev.filter(value => value.id !== id));
+    }, []);
+
+    useDeletedEvent<number>("utenza", "DeletedUtenza", removeUtenza);
+
+    const deleteUtenza = useCallback(async (utenza: IUtenza) => {
+        try {
+            await invoke("delete_utenza", {id: utenza.id});
+            addNotification("Utenza eliminata correttamente", "success");
+        } catch (e) {
+            addNotification(errorMessage(e), "error");
+        }
+    }, [addNotification]);
+
     useEffect(() => {
         if (needReload) {
             loadUtenze().then(() => {
@@ -65,8 +92,10 @@ const UtenzeProvider = ({children}: { children: React.ReactNode }) => {
         {
             data        : utenze,
             isLoading   : loading,
-            insertUtenza: insertUtenza
-        } as UtenzeContextType), [insertUtenza, loading, utenze]);
+            insertUtenza: insertUtenza,
+            updateUtenza: updateUtenza,
+            deleteUtenza: deleteUtenza
+        } as UtenzeContextType), [deleteUtenza, insertUtenza, loading, updateUtenza, utenze]);
 
     return <UtenzeContext.Provider value={obj}>
         {children}
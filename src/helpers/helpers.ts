@@ -1,4 +1,5 @@
 import { ChangeEvent } from "react";
+import { IErrorResponse } from "@/models/models.tsx";
 
 export const capitalize = (str: string) => {
     return str.charAt(0).toUpperCase() + str.slice(1).toLowerCase();
@@ -43,4 +44,13 @@ export const handleInputNumericChange = <T extends (value: number) => void>(even
         const newCursorPosition = Math.max(0, cursorPosition - nonNumericBeforeCursor);
         input.setSelectionRange(newCursorPosition, newCursorPosition);
     }, 0);
-};
\ No newline at end of file
+};
+export const isErrorResponse = (e: unknown): e is IErrorResponse => {
+    return typeof e === "object" && e !== null && "code" in e && "message" in e;
+};
+
+/** Messaggio da mostrare per un errore restituito da `invoke` */
+export const errorMessage = (e: unknown): string => {
+    if (isErrorResponse(e)) return e.message;
+    return String(e);
+};
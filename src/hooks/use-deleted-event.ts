@@ -0,0 +1,27 @@
+import { listen } from "@tauri-apps/api/event";
+import { useEffect } from "react";
+
+export interface DeletedEvent<K> {
+    type_event: string;
+    id: K;
+}
+
+/**
+ * Ascolta gli eventi di eliminazione emessi dal backend sul canale indicato
+ * e invoca la callback con l'id dell'elemento eliminato
+ */
+export function useDeletedEvent<K>(channel: string, typeEvent: string, onDeleted: (id: K) => void) {
+    useEffect(() => {
+        const listener = listen<DeletedEvent<K>>(channel, (e) => {
+            if (e.payload.type_event === typeEvent) {
+                onDeleted(e.payload.id);
+            }
+        });
+
+        return () => {
+            listener
+                .then(callback => callback())
+                .catch(console.error);
+        };
+    }, [ channel, typeEvent, onDeleted ]);
+}
@@ -17,10 +17,20 @@ export interface IStanza {
     destinazione_uso: string;
     altezza?: number,
     spessore_muro?: number,
+    superficie?: number,
+    perimetro?: number,
     riscaldamento?: string,
     raffrescamento?: string,
     illuminazione?: string,
     infissi?: string[];
+    pareti_esposte?: IPareteEsposta[];
+}
+
+export type Orientamento = "N" | "NE" | "E" | "SE" | "S" | "SO" | "O" | "NO";
+
+export interface IPareteEsposta {
+    orientamento: Orientamento,
+    lunghezza: number,
 }
 
 export interface IEdificio {
@@ -42,11 +52,19 @@ export interface IStanzaConInfissi {
 export interface VetroInfisso {
     vetro: string;
     eff_energetica: number;
+    ug: number | null;
 }
 
 export interface MaterialeInfisso {
     materiale: string;
     eff_energetica: number;
+    uf: number | null;
+}
+
+export interface ITrasmittanza {
+    catalogo: "materiale" | "vetro";
+    nome: string;
+    valore: number | null;
 }
 
 export interface Illuminazione {
@@ -78,6 +96,8 @@ export interface IUtenza {
     tipo: string;
     cod_contatore: string;
     indirizzo_contatore?: string;
+    /** Consumo annuo nell'unità del contatore (kWh per l'elettricità) */
+    consumo_annuo?: number;
 }
 
 export interface IFotovoltaico {
@@ -85,6 +105,28 @@ export interface IFotovoltaico {
     id_edificio: string;
     potenza: number,
     proprietario: string,
+    orientamento?: Orientamento,
+    inclinazione?: number,
+    anno_installazione?: number,
+    /** Sigla della provincia */
+    provincia?: string,
+}
+
+export interface IStimaImpianto {
+    id: number | null,
+    potenza: number,
+    produzione_iniziale: number | null,
+    produzione_annua: number | null,
+    esposizione_ipotizzata: boolean,
+}
+
+export interface IStimaProduzione {
+    edificio: string,
+    produzione_annua: number,
+    impianti_senza_stima: number,
+    consumo_elettrico: number | null,
+    copertura: number | null,
+    impianti: IStimaImpianto[],
 }
 
 type PrimaryKey = | { Edificio: string } | { Stanza: number } | { Infisso: [string, string] }
@@ -94,4 +136,327 @@ export interface IAnnotazione {
     ref_table: "edificio" | "stanza" | "infisso",
     id_ref_table: PrimaryKey,
     content: string,
-}
\ No newline at end of file
+}
+
+export interface IRowError {
+    row: number,
+    column?: string,
+    message: string,
+}
+
+export interface IImportPreview {
+    edifici: IEdificio[],
+    stanze: IStanza[],
+    stanze_aggiornate: IStanza[],
+    stanze_mancanti: IStanza[],
+    errors: IRowError[],
+}
+
+export interface IAssegnazioneInfisso {
+    stanza_id: number,
+    cod_stanza: string,
+    infisso_id: string,
+    num_infisso: number,
+    num_precedente: number,
+}
+
+export interface IImportInfissiPreview {
+    infissi: IInfisso[],
+    assegnazioni: IAssegnazioneInfisso[],
+    errors: IRowError[],
+}
+
+export interface IRestoreReport {
+    fascicoli: number[],
+    edifici: IEdificio[],
+    sostituiti: string[],
+    stanze: number,
+    infissi: number,
+    annotazioni: number,
+    voci_catalogo: number,
+    trasmittanze_diverse: ITrasmittanzaDiversa[],
+}
+
+export interface ITrasmittanzaDiversa {
+    catalogo: string,
+    nome: string,
+    backup: number,
+    database: number | null,
+}
+
+export interface ILocalBackup {
+    file_name: string,
+    created_at: string,
+    size: number,
+}
+
+export type IssueKind =
+    | "stanza_incompleta"
+    | "stanza_senza_infissi"
+    | "infisso_non_assegnato"
+    | "edificio_senza_utenze"
+    | "altezza_non_plausibile"
+    | "spessore_muro_non_plausibile"
+    | "dimensioni_infisso_non_plausibili";
+
+export interface IQualityIssue {
+    kind: IssueKind,
+    riferimento: string,
+    descrizione: string,
+}
+
+export interface IQualityReport {
+    edificio: string,
+    scanned_at: string,
+    stanze: number,
+    stanze_complete: number,
+    completezza: number,
+    issues: IQualityIssue[],
+}
+
+export interface IStanzaMetrics {
+    id: number,
+    piano: string,
+    cod_stanza: string,
+    destinazione_uso: string,
+    superficie: number | null,
+    volume: number | null,
+    superficie_pareti: number | null,
+    superficie_disperdente: number | null,
+    mq_infissi: number,
+    rapporto_aero_illuminante: number | null,
+}
+
+export interface IMetricsTotals {
+    stanze: number,
+    stanze_misurate: number,
+    superficie: number,
+    volume: number,
+    superficie_pareti: number,
+    superficie_disperdente: number,
+    mq_infissi: number,
+    rapporto_aero_illuminante: number | null,
+}
+
+export interface IEdificioMetrics extends IMetricsTotals {
+    edificio: string,
+    piani: ({ piano: string } & IMetricsTotals)[],
+    stanze: IStanzaMetrics[],
+}
+
+export interface IInfissoTransmittance {
+    id: string,
+    tipo: string,
+    materiale: string,
+    vetro: string,
+    mq: number,
+    uf: number | null,
+    ug: number | null,
+    uw: number | null,
+}
+
+export interface IStanzaHeatLoss {
+    id: number,
+    piano: string,
+    cod_stanza: string,
+    mq_infissi: number,
+    h_infissi: number,
+    infissi_senza_trasmittanza: number,
+}
+
+export interface IEdificioHeatLoss {
+    edificio: string,
+    mq_infissi: number,
+    h_infissi: number,
+    uw_medio: number | null,
+    infissi_senza_trasmittanza: number,
+    infissi: IInfissoTransmittance[],
+    stanze: IStanzaHeatLoss[],
+}
+
+export type StatusTask = "Pending" | "Running" | "Paused" | "Stopped" | "Done" | "Completed" | "Canceled" | "Error";
+
+export type Schedule =
+    | { type: "interval", secs: number }
+    | { type: "cron", expression: string }
+    | { type: "startup" }
+    | { type: "idle", idle_secs: number };
+
+export interface ITaskState {
+    name: string,
+    schedule: Schedule,
+    enabled: boolean,
+    status: StatusTask,
+    last_run: string | null,
+    next_run: string | null,
+    last_error: string | null,
+}
+
+export interface ITaskRun {
+    task: string,
+    started_at: string,
+    ended_at: string,
+    outcome: StatusTask,
+    attempt: number,
+    error: string | null,
+}
+
+export type Transform =
+    | { type: "trim" }
+    | { type: "uppercase" }
+    | { type: "lowercase" }
+    | { type: "replace", from: string, to: string }
+    | { type: "strip_prefix", prefix: string };
+
+export interface IFieldMapping {
+    source?: string,
+    default?: string,
+    transforms?: Transform[],
+}
+
+export interface IMappingProfile {
+    name: string,
+    fields: Record<string, IFieldMapping>,
+}
+
+export type SortDirection = "asc" | "desc";
+
+/** Ricerca accettata dai command `query_*`: filtri in AND, ordinamento e paginazione */
+export interface IQuery<F, S extends string> {
+    filters?: F[],
+    sort?: { field: S, direction?: SortDirection }[],
+    limit?: number,
+    offset?: number,
+}
+
+export type CampoStanza = "altezza" | "spessore_muro" | "superficie" | "riscaldamento" | "raffrescamento" | "illuminazione";
+
+export type StanzaFilter =
+    | { field: "edificio" | "piano" | "destinazione_uso" | "riscaldamento" | "raffrescamento" | "illuminazione", value: string }
+    | { field: "missing", value: CampoStanza };
+
+export type StanzaQuery = IQuery<StanzaFilter, "piano" | "id_spazio" | "cod_stanza" | "destinazione_uso" | "altezza" | "spessore_muro" | "superficie">;
+
+export type InfissoQuery = IQuery<
+    { field: "edificio" | "tipo" | "materiale" | "vetro", value: string },
+    "id" | "tipo" | "materiale" | "vetro" | "altezza" | "larghezza" | "mq">;
+
+export type UtenzaQuery = IQuery<
+    { field: "edificio", value: string } | { field: "tipo", value: "Acqua" | "Elettricità" | "Riscaldamento" },
+    "tipo" | "cod_contatore">;
+
+export type FotovoltaicoQuery = IQuery<
+    { field: "edificio" | "proprietario", value: string },
+    "potenza" | "proprietario">;
+
+export type ErrorCode =
+    | "NOT_FOUND" | "ALREADY_EXISTS" | "STILL_REFERENCED" | "INVALID_INPUT" | "INVALID_STATE"
+    | "EDIFICIO_NOT_SELECTED" | "DATABASE" | "DATABASE_UNAVAILABLE" | "SCHEMA_TOO_NEW" | "TIMEOUT" | "TASK" | "EXPORT"
+    | "IMPORT" | "BACKUP" | "DATA_QUALITY" | "IO" | "TAURI" | "UNEXPECTED";
+
+/** Errore restituito dai command */
+export interface IErrorResponse {
+    code: ErrorCode,
+    entity: string | null,
+    field: string | null,
+    /** Chiave del messaggio nel catalogo delle traduzioni */
+    key: string,
+    params: Record<string, string>,
+    message: string,
+}
+
+export type Locale = "it" | "en";
+
+export interface IAppSettings {
+    locale: Locale,
+    pesi_punteggio: IScoreWeights,
+}
+
+export type ScoreComponent = "illuminazione" | "climatizzazione" | "infissi" | "isolamento_tetto" | "cappotto" | "fotovoltaico";
+
+export type IScoreWeights = Record<ScoreComponent, number>;
+
+export interface IComponentScore {
+    componente: ScoreComponent,
+    peso: number,
+    punteggio: number | null,
+    contributo: number | null,
+}
+
+export interface IEdificioScore {
+    edificio: string,
+    fascicolo: number,
+    punteggio: number | null,
+    componenti: IComponentScore[],
+}
+
+export type ISubstitution =
+    | { tipo: "materiale" | "vetro" | "illuminazione" | "climatizzazione", da: string, a: string }
+    | { tipo: "cappotto" | "isolamento_tetto", presente: boolean }
+    | { tipo: "fotovoltaico", potenza: number };
+
+export interface IScenario {
+    nome: string,
+    edificio: string,
+    descrizione: string | null,
+    sostituzioni: ISubstitution[],
+}
+
+export interface IScenarioIndicators {
+    punteggio: number | null,
+    componenti: IComponentScore[],
+    h_infissi: number,
+    uw_medio: number | null,
+    infissi_senza_trasmittanza: number,
+}
+
+export interface IScenarioComparison {
+    scenario: IScenario,
+    prima: IScenarioIndicators,
+    dopo: IScenarioIndicators,
+    infissi_modificati: number,
+    stanze_modificate: number,
+}
+
+export interface IFascicoloScore {
+    fascicolo: number,
+    punteggio: number | null,
+    componenti: IComponentScore[],
+    edifici: IEdificioScore[],
+}
+
+export interface IConnectionProfile {
+    name: string,
+    host: string,
+    port: number,
+    database: string,
+    user: string,
+    password: string,
+    pool_size: number,
+}
+
+/** Profilo salvato, senza la password */
+export interface IConnectionProfileSummary extends Omit<IConnectionProfile, "password"> {
+    has_password: boolean,
+}
+
+export interface IDatabaseSettings {
+    profiles: IConnectionProfileSummary[],
+    active: string | null,
+}
+
+export interface IMigrationReport {
+    schema_version: string | null,
+    applied: string[],
+}
+
+export type SchemaState =
+    | { state: "unknown" }
+    | ({ state: "ready" } & IMigrationReport)
+    | { state: "failed", error: string };
+
+export interface IDatabaseStatus {
+    connected: boolean,
+    active: string | null,
+    schema: SchemaState,
+}
@@ -0,0 +1,76 @@
+import TitleCard from "@/components/title-card.tsx";
+import {Card, CardContent, CardHeader} from "@/components/ui/card.tsx";
+import {useSelectedEdificio} from "@/context/SelectedEdificioProvider.tsx";
+import {IQualityReport, IssueKind} from "@/models/models.tsx";
+import {invoke} from "@tauri-apps/api/core";
+import {listen} from "@tauri-apps/api/event";
+import {useEffect, useState} from "react";
+
+interface DataQualityUpdated {
+    type_event: string;
+    reports: IQualityReport[];
+}
+
+const ISSUE_LABEL: Record<IssueKind, string> = {
+    stanza_incompleta                : "Stanza incompleta",
+    stanza_senza_infissi             : "Stanza senza infissi",
+    infisso_non_assegnato            : "Infisso non assegnato",
+    edificio_senza_utenze            : "Edificio senza utenze",
+    altezza_non_plausibile           : "Altezza non plausibile",
+    spessore_muro_non_plausibile     : "Spessore muro non plausibile",
+    dimensioni_infisso_non_plausibili: "Dimensioni infisso non plausibili"
+};
+
+/** Elenco dei dati da completare o verificare per l'edificio selezionato */
+const CardDataQuality = () => {
+    const {edificio} = useSelectedEdificio();
+    const [report, setReport] = useState<IQualityReport | null>(null);
+
+    useEffect(() => {
+        const chiave = edificio?.chiave;
+        setReport(null);
+        if (!chiave) {
+            return;
+        }
+        invoke<IQualityReport[]>("get_data_quality", {edificio: chiave})
+            .then(reports => setReport(reports[0] ?? null))
+            .catch(console.error);
+
+        const qualityListener = listen<DataQualityUpdated>("data_quality", ({payload}) => {
+            setReport(payload.reports.find(r => r.edificio === chiave) ?? null);
+        });
+
+        return () => {
+            qualityListener
+                .then(callback => callback())
+                .catch(console.error);
+        };
+    }, [edificio?.chiave]);
+
+    return <Card className="col-span-2">
+        <CardHeader>
+            <div className="flex justify-between items-center">
+                <TitleCard title="Da completare"/>
+                {report && <span className="text-sm text-muted-foreground">
+                    {report.stanze_complete}/{report.stanze} stanze complete ({Math.round(report.completezza)}%)
+                </span>}
+            </div>
+        </CardHeader>
+        <CardContent>
+            {!report
+             ? <p className="text-sm text-muted-foreground">Analisi non ancora disponibile</p>
+             : report.issues.length === 0
+               ? <p className="text-sm text-muted-foreground">Nessun dato da completare</p>
+               : <ul className="flex flex-col gap-1 text-sm max-h-80 overflow-y-auto">
+                   {report.issues.map((issue, index) => {
+                       return <li key={`${issue.kind}-${issue.riferimento}-${index}`}>
+                           <span className="font-medium">{ISSUE_LABEL[issue.kind]}</span>
+                           {" "}{issue.riferimento}: <span className="text-muted-foreground">{issue.descrizione}</span>
+                       </li>;
+                   })}
+               </ul>}
+        </CardContent>
+    </Card>;
+};
+
+export default CardDataQuality;
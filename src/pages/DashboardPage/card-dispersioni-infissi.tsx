@@ -0,0 +1,79 @@
+import TitleCard from "@/components/title-card.tsx";
+import {Card, CardContent, CardHeader} from "@/components/ui/card.tsx";
+import {Table, TableBody, TableCell, TableHead, TableHeader, TableRow} from "@/components/ui/table.tsx";
+import {useSelectedEdificio} from "@/context/SelectedEdificioProvider.tsx";
+import {useInfissi, useStanze} from "@/context/UseProvider.tsx";
+import {IEdificioHeatLoss} from "@/models/models.tsx";
+import {invoke} from "@tauri-apps/api/core";
+import {useEffect, useState} from "react";
+
+/** Trasmittanza media degli infissi e dispersione per stanza dell'edificio selezionato */
+const CardDispersioniInfissi = () => {
+    const {edificio} = useSelectedEdificio();
+    const stanzeContext = useStanze();
+    const infissiContext = useInfissi();
+    const [heatLoss, setHeatLoss] = useState<IEdificioHeatLoss | null>(null);
+
+    useEffect(() => {
+        const chiave = edificio?.chiave;
+        setHeatLoss(null);
+        if (!chiave) {
+            return;
+        }
+        invoke<IEdificioHeatLoss>("get_dispersioni_edificio", {edificio: chiave})
+            .then(setHeatLoss)
+            .catch(console.error);
+    }, [edificio?.chiave, stanzeContext.data, infissiContext.data]);
+
+    const stanze = heatLoss?.stanze
+        .filter(stanza => stanza.mq_infissi > 0)
+        .sort((a, b) => b.h_infissi - a.h_infissi) ?? [];
+
+    return <Card className="col-span-2">
+        <CardHeader>
+            <TitleCard title="Dispersioni infissi"/>
+        </CardHeader>
+        <CardContent className="flex flex-col gap-4">
+            {!heatLoss
+             ? <p className="text-sm text-muted-foreground">Dati non disponibili</p>
+             : <>
+                 <div className="grid grid-cols-3 gap-4 text-sm">
+                     <div>
+                         <p className="text-muted-foreground">Uw medio</p>
+                         <p className="text-lg font-semibold">
+                             {heatLoss.uw_medio === null ? "-" : `${heatLoss.uw_medio.toFixed(2)} W/m²K`}
+                         </p>
+                     </div>
+                     <div>
+                         <p className="text-muted-foreground">Dispersione</p>
+                         <p className="text-lg font-semibold">{heatLoss.h_infissi.toFixed(1)} W/K</p>
+                     </div>
+                     <div>
+                         <p className="text-muted-foreground">Infissi senza trasmittanza</p>
+                         <p className="text-lg font-semibold">{heatLoss.infissi_senza_trasmittanza}</p>
+                     </div>
+                 </div>
+                 <Table>
+                     <TableHeader>
+                         <TableRow>
+                             <TableHead>Piano</TableHead>
+                             <TableHead>Stanza</TableHead>
+                             <TableHead className="text-right">Infissi (m²)</TableHead>
+                             <TableHead className="text-right">Dispersione (W/K)</TableHead>
+                         </TableRow>
+                     </TableHeader>
+                     <TableBody>
+                         {stanze.map(stanza => <TableRow key={stanza.id}>
+                             <TableCell>{stanza.piano}</TableCell>
+                             <TableCell>{stanza.cod_stanza}</TableCell>
+                             <TableCell className="text-right">{stanza.mq_infissi.toFixed(2)}</TableCell>
+                             <TableCell className="text-right">{stanza.h_infissi.toFixed(1)}</TableCell>
+                         </TableRow>)}
+                     </TableBody>
+                 </Table>
+             </>}
+        </CardContent>
+    </Card>;
+};
+
+export default CardDispersioniInfissi;
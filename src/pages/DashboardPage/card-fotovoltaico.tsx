@@ -1,3 +1,4 @@
+import ClearableSelect from "@/components/clearable-select.tsx";
 import InputWithMeasureUnit from "@/components/input-with-measure-unit.tsx";
 import TitleCard from "@/components/title-card.tsx";
 import {Button} from "@/components/ui/button.tsx";
@@ -16,11 +17,12 @@ import {
 } from "@/components/ui/sheet.tsx";
 import {Table, TableBody, TableCell, TableHead, TableHeader, TableRow} from "@/components/ui/table.tsx";
 import {useSelectedEdificio} from "@/context/SelectedEdificioProvider.tsx";
-import {useFotovoltaico} from "@/context/UseProvider.tsx";
-import {IFotovoltaico} from "@/models/models.tsx";
+import {useFotovoltaico, useUtenze} from "@/context/UseProvider.tsx";
+import {IFotovoltaico, IStimaProduzione, Orientamento} from "@/models/models.tsx";
 import {zodResolver} from "@hookform/resolvers/zod";
+import {invoke} from "@tauri-apps/api/core";
 import {Pencil, PlusIcon} from "lucide-react";
-import {ChangeEvent} from "react";
+import {ChangeEvent, useEffect, useState} from "react";
 import {ControllerRenderProps, useForm} from "react-hook-form";
 import {z} from "zod";
 
@@ -30,9 +32,76 @@ const FormSchema = z.object({
     }),
     proprietario: z.string().min(2, {
         message: "Il nome deve essere composto da almeno 2 caratteri"
-    })
+    }),
+    orientamento      : z.string().optional(),
+    inclinazione      : z.string().regex(/^(\d{1,2}|90)?$/, {
+        message: "L'inclinazione deve essere compresa tra 0 e 90 gradi"
+    }).optional(),
+    anno_installazione: z.string().refine(value => {
+        if (value === "") {
+            return true;
+        }
+        const anno = Number(value);
+        return /^\d{4}$/.test(value) && anno >= 1970 && anno <= new Date().getFullYear();
+    }, {
+        message: "L'anno deve essere compreso tra il 1970 e l'anno corrente"
+    }).optional(),
+    provincia         : z.string().regex(/^([A-Za-z]{2})?$/, {
+        message: "Inserire la sigla della provincia"
+    }).optional()
+});
+
+const ORIENTAMENTI: Orientamento[] = ["N", "NE", "E", "SE", "S", "SO", "O", "NO"];
+
+/** Valori del form per l'impianto da modificare, vuoti per un nuovo impianto */
+const formValues = (fotovoltaico?: IFotovoltaico): z.infer<typeof FormSchema> => ({
+    potenza           : fotovoltaico?.potenza ?? 0.0,
+    proprietario      : fotovoltaico?.proprietario ?? "",
+    orientamento      : fotovoltaico?.orientamento ?? "",
+    inclinazione      : fotovoltaico?.inclinazione?.toString() ?? "",
+    anno_installazione: fotovoltaico?.anno_installazione?.toString() ?? "",
+    provincia         : fotovoltaico?.provincia ?? ""
 });
 
+const formatKwh = (value: number | null) => value === null ? "-" : `${Math.round(value)} kWh`;
+
+/** Produzione annua stimata degli impianti confrontata con il consumo elettrico */
+const StimaProduzione = () => {
+    const {edificio} = useSelectedEdificio();
+    const fotovoltaicoContext = useFotovoltaico();
+    const utenzeContext = useUtenze();
+    const [stima, setStima] = useState<IStimaProduzione | null>(null);
+
+    const chiave = edificio?.chiave;
+
+    useEffect(() => {
+        setStima(null);
+        if (!chiave) {
+            return;
+        }
+        invoke<IStimaProduzione>("get_stima_fotovoltaico", {edificio: chiave})
+            .then(setStima)
+            .catch(console.error);
+    }, [chiave, fotovoltaicoContext.data, utenzeContext.data]);
+
+    if (!stima || stima.impianti.length === 0) {
+        return null;
+    }
+
+    return <div className="mt-4 text-sm flex flex-col gap-1">
+        <p>Produzione annua stimata: <span className="font-medium">{formatKwh(stima.produzione_annua)}</span></p>
+        <p>Consumo elettrico annuo: <span className="font-medium">{formatKwh(stima.consumo_elettrico)}</span></p>
+        {stima.copertura !== null &&
+            <p>Copertura dei consumi: <span className="font-medium">{Math.round(stima.copertura * 100)}%</span></p>}
+        {stima.impianti_senza_stima > 0 && <p className="text-muted-foreground">
+            {stima.impianti_senza_stima} impianti senza provincia esclusi dalla stima
+        </p>}
+        {stima.impianti.some(i => i.esposizione_ipotizzata) && <p className="text-muted-foreground">
+            Per gli impianti senza orientamento o inclinazione si ipotizza l'esposizione a sud a 30°
+        </p>}
+    </div>;
+};
+
 
 const CardFotovoltaico = () => {
     const fotovoltaicoContext = useFotovoltaico();
@@ -41,7 +110,7 @@ const CardFotovoltaico = () => {
         <CardHeader>
             <div className="flex justify-between items-center">
                 <TitleCard title="Fotovoltaico"/>
-                <InsertFotovoltaico/>
+                <FormFotovoltaico/>
             </div>
         </CardHeader>
         <CardContent>
@@ -52,6 +121,9 @@ const CardFotovoltaico = () => {
                             <TableHead className="text-center font-bold">Numero</TableHead>
                             <TableHead className="text-center font-bold">Potenza</TableHead>
                             <TableHead className="text-center font-bold">Proprietario</TableHead>
+                            <TableHead className="text-center font-bold">Esposizione</TableHead>
+                            <TableHead className="text-center font-bold">Anno</TableHead>
+                            <TableHead/>
                         </TableRow>
                     </TableHeader>
                     <TableBody>
@@ -61,29 +133,33 @@ const CardFotovoltaico = () => {
                                 <TableCell className="text-center">{value.id}</TableCell>
                                 <TableCell className="text-center">{value.potenza} KW</TableCell>
                                 <TableCell className="text-center">{value.proprietario}</TableCell>
+                                <TableCell className="text-center">
+                                    {value.orientamento ?? "-"}{value.inclinazione != null && ` ${value.inclinazione}°`}
+                                </TableCell>
+                                <TableCell className="text-center">{value.anno_installazione ?? "-"}</TableCell>
+                                <TableCell className="text-center"><FormFotovoltaico fotovoltaico={value}/></TableCell>
                             </TableRow>;
                             })) : (
                             <TableRow>
-                            <TableCell colSpan={5} className="h-24 text-center">No results</TableCell>
+                            <TableCell colSpan={6} className="h-24 text-center">No results</TableCell>
                         </TableRow>)}
                     </TableBody>
                 </Table>
             </div>
+            <StimaProduzione/>
         </CardContent>
     </Card>;
 };
 
-const InsertFotovoltaico = () => {
-    const {insertFotovoltaico} = useFotovoltaico();
+/** Form per aggiungere un impianto o, se indicato, modificare `fotovoltaico` */
+const FormFotovoltaico = ({fotovoltaico}: { fotovoltaico?: IFotovoltaico }) => {
+    const {insertFotovoltaico, updateFotovoltaico} = useFotovoltaico();
     const {edificio} = useSelectedEdificio();
 
 
     const form = useForm<z.infer<typeof FormSchema>>({
         resolver     : zodResolver(FormSchema),
-        defaultValues: {
-            potenza     : 0.0,
-            proprietario: ""
-        }
+        defaultValues: formValues(fotovoltaico)
     });
 
     function onSubmit(data: z.infer<typeof FormSchema>) {
@@ -92,11 +168,19 @@ const InsertFotovoltaico = () => {
         }
 
         const newFotovoltaico: IFotovoltaico = {
-            id          : 0,
-            id_edificio : edificio?.chiave,
-            potenza     : data.potenza,
-            proprietario: data.proprietario
+            id                : fotovoltaico?.id ?? 0,
+            id_edificio       : fotovoltaico?.id_edificio ?? edificio.chiave,
+            potenza           : data.potenza,
+            proprietario      : data.proprietario,
+            orientamento      : data.orientamento ? data.orientamento as Orientamento : undefined,
+            inclinazione      : data.inclinazione ? Number(data.inclinazione) : undefined,
+            anno_installazione: data.anno_installazione ? Number(data.anno_installazione) : undefined,
+            provincia         : data.provincia ? data.provincia.toUpperCase() : undefined
         };
+        if (fotovoltaico) {
+            updateFotovoltaico(newFotovoltaico).catch(console.error);
+            return;
+        }
         insertFotovoltaico(newFotovoltaico).catch(console.error);
         form.reset();
     }
@@ -122,15 +206,19 @@ const InsertFotovoltaico = () => {
     }
 
 
-    return <Sheet>
+    return <Sheet onOpenChange={open => open && form.reset(formValues(fotovoltaico))}>
         <SheetTrigger asChild>
-            <Button variant="ghost" size={"sm"} disabled={edificio === undefined}><PlusIcon/></Button>
+            <Button variant="ghost" size={"sm"} disabled={edificio === undefined}>
+                {fotovoltaico ? <Pencil/> : <PlusIcon/>}
+            </Button>
         </SheetTrigger>
         <SheetContent className="w-[400px]">
             <SheetHeader>
-                <SheetTitle>Aggiungi </SheetTitle>
+                <SheetTitle>{fotovoltaico ? "Modifica" : "Aggiungi"} </SheetTitle>
                 <SheetDescription>
-                    Compila il form per aggiungere un nuovo tipo a
+                    {fotovoltaico
+                        ? `Compila il form per modificare l'impianto ${fotovoltaico.id}`
+                        : "Compila il form per aggiungere un nuovo tipo a"}
                 </SheetDescription>
             </SheetHeader>
             <Form {...form}>
@@ -163,6 +251,64 @@ const InsertFotovoltaico = () => {
                                 </FormItem>;
                             }}
                         />
+                        <FormField
+                            control={form.control}
+                            name={"orientamento"}
+                            render={({field}) => {
+                                return <FormItem>
+                                    <FormLabel>Orientamento</FormLabel>
+                                    <ClearableSelect value={field.value ?? ""}
+                                                     onChange={field.onChange}
+                                                     onClear={() => field.onChange("")}
+                                                     options={ORIENTAMENTI}
+                                    />
+                                    <FormMessage/>
+                                </FormItem>;
+                            }}
+                        />
+                        <FormField
+                            control={form.control}
+                            name={"inclinazione"}
+                            render={({field}) => {
+                                return <FormItem>
+                                    <FormLabel>Inclinazione</FormLabel>
+                                    <InputWithMeasureUnit
+                                        unitLabel="°"
+                                        value={field.value}
+                                        onChange={field.onChange}/>
+                                    <FormMessage/>
+                                </FormItem>;
+                            }}
+                        />
+                        <FormField
+                            control={form.control}
+                            name={"anno_installazione"}
+                            render={({field}) => {
+                                return <FormItem>
+                                    <FormLabel>Anno di installazione</FormLabel>
+                                    <Input value={field.value}
+                                           inputMode="numeric"
+                                           onChange={field.onChange}
+                                    />
+                                    <FormMessage/>
+                                </FormItem>;
+                            }}
+                        />
+                        <FormField
+                            control={form.control}
+                            name={"provincia"}
+                            render={({field}) => {
+                                return <FormItem>
+                                    <FormLabel>Provincia</FormLabel>
+                                    <Input value={field.value}
+                                           maxLength={2}
+                                           placeholder="Sigla, es. RM"
+                                           onChange={field.onChange}
+                                    />
+                                    <FormMessage/>
+                                </FormItem>;
+                            }}
+                        />
                     </div>
                 </form>
             </Form>
@@ -171,7 +317,7 @@ const InsertFotovoltaico = () => {
                     <Button type="button" className="text-white" onClick={async () => {
                         await form.handleSubmit(onSubmit)();
                     }}>
-                        <Pencil/>Aggiungi
+                        <Pencil/>{fotovoltaico ? "Modifica" : "Aggiungi"}
                     </Button>
                 </SheetClose>
             </SheetFooter>
@@ -0,0 +1,69 @@
+import TitleCard from "@/components/title-card.tsx";
+import {Card, CardContent, CardHeader} from "@/components/ui/card.tsx";
+import {Table, TableBody, TableCell, TableHead, TableHeader, TableRow} from "@/components/ui/table.tsx";
+import {useSelectedEdificio} from "@/context/SelectedEdificioProvider.tsx";
+import {useStanze} from "@/context/UseProvider.tsx";
+import {IEdificioMetrics, IMetricsTotals} from "@/models/models.tsx";
+import {invoke} from "@tauri-apps/api/core";
+import {useEffect, useState} from "react";
+
+const formatRapporto = (rapporto: number | null) => !rapporto ? "-" : `1/${Math.round(1 / rapporto)}`;
+
+const RigaTotali = ({label, totali}: { label: string, totali: IMetricsTotals }) => {
+    return <TableRow>
+        <TableCell className="font-medium">{label}</TableCell>
+        <TableCell className="text-right">{totali.stanze_misurate}/{totali.stanze}</TableCell>
+        <TableCell className="text-right">{totali.superficie.toFixed(2)}</TableCell>
+        <TableCell className="text-right">{totali.volume.toFixed(2)}</TableCell>
+        <TableCell className="text-right">{totali.superficie_disperdente.toFixed(2)}</TableCell>
+        <TableCell className="text-right">{totali.mq_infissi.toFixed(2)}</TableCell>
+        <TableCell className="text-right">{formatRapporto(totali.rapporto_aero_illuminante)}</TableCell>
+    </TableRow>;
+};
+
+/** Superfici e volumi dell'edificio selezionato, per piano */
+const CardMetricheEdificio = () => {
+    const {edificio} = useSelectedEdificio();
+    const stanzeContext = useStanze();
+    const [metrics, setMetrics] = useState<IEdificioMetrics | null>(null);
+
+    useEffect(() => {
+        const chiave = edificio?.chiave;
+        setMetrics(null);
+        if (!chiave) {
+            return;
+        }
+        invoke<IEdificioMetrics>("get_metriche_edificio", {edificio: chiave})
+            .then(setMetrics)
+            .catch(console.error);
+    }, [edificio?.chiave, stanzeContext.data]);
+
+    return <Card className="col-span-2">
+        <CardHeader>
+            <TitleCard title="Dimensioni"/>
+        </CardHeader>
+        <CardContent>
+            {!metrics
+             ? <p className="text-sm text-muted-foreground">Dati non disponibili</p>
+             : <Table>
+                 <TableHeader>
+                     <TableRow>
+                         <TableHead>Piano</TableHead>
+                         <TableHead className="text-right">Stanze misurate</TableHead>
+                         <TableHead className="text-right">Superficie (m²)</TableHead>
+                         <TableHead className="text-right">Volume (m³)</TableHead>
+                         <TableHead className="text-right">Pareti esposte (m²)</TableHead>
+                         <TableHead className="text-right">Infissi (m²)</TableHead>
+                         <TableHead className="text-right">Rapporto aero-illuminante</TableHead>
+                     </TableRow>
+                 </TableHeader>
+                 <TableBody>
+                     {metrics.piani.map(piano => <RigaTotali key={piano.piano} label={piano.piano} totali={piano}/>)}
+                     <RigaTotali label="Totale" totali={metrics}/>
+                 </TableBody>
+             </Table>}
+        </CardContent>
+    </Card>;
+};
+
+export default CardMetricheEdificio;
@@ -0,0 +1,142 @@
+import TitleCard from "@/components/title-card.tsx";
+import {Button} from "@/components/ui/button.tsx";
+import {Card, CardContent, CardHeader} from "@/components/ui/card.tsx";
+import {
+    Dialog,
+    DialogContent,
+    DialogFooter,
+    DialogHeader,
+    DialogTitle,
+    DialogTrigger
+} from "@/components/ui/dialog.tsx";
+import {Input} from "@/components/ui/input.tsx";
+import {Label} from "@/components/ui/label.tsx";
+import {Progress} from "@/components/ui/progress.tsx";
+import {useNotification} from "@/context/NotificationProvider.tsx";
+import {useSelectedEdificio} from "@/context/SelectedEdificioProvider.tsx";
+import {useEdifici, useInfissi, useStanze} from "@/context/UseProvider.tsx";
+import {errorMessage} from "@/helpers/helpers.ts";
+import {IAppSettings, IEdificioScore, IScoreWeights, ScoreComponent} from "@/models/models.tsx";
+import {invoke} from "@tauri-apps/api/core";
+import {Settings2} from "lucide-react";
+import {useEffect, useState} from "react";
+
+const COMPONENT_LABEL: Record<ScoreComponent, string> = {
+    illuminazione   : "Illuminazione",
+    climatizzazione : "Climatizzazione",
+    infissi         : "Infissi",
+    isolamento_tetto: "Isolamento tetto",
+    cappotto        : "Cappotto",
+    fotovoltaico    : "Fotovoltaico"
+};
+
+const DialogPesi = ({onSaved}: { onSaved: () => void }) => {
+    const {addNotification} = useNotification();
+    const [open, setOpen] = useState(false);
+    const [settings, setSettings] = useState<IAppSettings | null>(null);
+
+    useEffect(() => {
+        if (!open) {
+            return;
+        }
+        invoke<IAppSettings>("get_settings")
+            .then(setSettings)
+            .catch((e) => addNotification(errorMessage(e), "error"));
+    }, [open, addNotification]);
+
+    const changePeso = (componente: ScoreComponent, value: string) => {
+        if (!settings) return;
+        const pesi: IScoreWeights = {...settings.pesi_punteggio, [componente]: Number(value)};
+        setSettings({...settings, pesi_punteggio: pesi});
+    };
+
+    const save = async () => {
+        if (!settings) return;
+        try {
+            await invoke("save_settings", {settings});
+            setOpen(false);
+            onSaved();
+        } catch (e) {
+            addNotification(errorMessage(e), "error");
+        }
+    };
+
+    return <Dialog open={open} onOpenChange={setOpen}>
+        <DialogTrigger asChild>
+            <Button variant="ghost" size="icon">
+                <Settings2/>
+                <span className="sr-only">Pesi del punteggio</span>
+            </Button>
+        </DialogTrigger>
+        <DialogContent>
+            <DialogHeader>
+                <DialogTitle>Pesi del punteggio</DialogTitle>
+            </DialogHeader>
+            {settings && <div className="grid grid-cols-2 gap-4">
+                {(Object.keys(COMPONENT_LABEL) as ScoreComponent[]).map(componente =>
+                    <div key={componente} className="flex flex-col gap-2">
+                        <Label htmlFor={`peso-${componente}`}>{COMPONENT_LABEL[componente]}</Label>
+                        <Input id={`peso-${componente}`} type="number" min={0}
+                               value={settings.pesi_punteggio[componente]}
+                               onChange={e => changePeso(componente, e.target.value)}/>
+                    </div>)}
+            </div>}
+            <DialogFooter>
+                <Button onClick={save} disabled={!settings}>Salva</Button>
+            </DialogFooter>
+        </DialogContent>
+    </Dialog>;
+};
+
+/** Punteggio energetico dell'edificio selezionato con il dettaglio dei componenti */
+const CardPunteggioEnergetico = () => {
+    const {edificio} = useSelectedEdificio();
+    const edificiContext = useEdifici();
+    const stanzeContext = useStanze();
+    const infissiContext = useInfissi();
+    const [score, setScore] = useState<IEdificioScore | null>(null);
+    const [reload, setReload] = useState(0);
+
+    useEffect(() => {
+        const chiave = edificio?.chiave;
+        setScore(null);
+        if (!chiave) {
+            return;
+        }
+        invoke<IEdificioScore>("get_punteggio_edificio", {edificio: chiave})
+            .then(setScore)
+            .catch(console.error);
+    }, [edificio?.chiave, edificiContext.data, stanzeContext.data, infissiContext.data, reload]);
+
+    return <Card className="col-span-2">
+        <CardHeader>
+            <div className="flex justify-between items-center">
+                <TitleCard title="Punteggio energetico"/>
+                <div className="flex items-center gap-2">
+                    {score?.punteggio != null && <span className="text-lg font-semibold">
+                        {Math.round(score.punteggio)}/100
+                    </span>}
+                    <DialogPesi onSaved={() => setReload(value => value + 1)}/>
+                </div>
+            </div>
+        </CardHeader>
+        <CardContent className="flex flex-col gap-3">
+            {!score
+             ? <p className="text-sm text-muted-foreground">Dati non disponibili</p>
+             : score.componenti.map(componente =>
+                    <div key={componente.componente} className="flex flex-col gap-1 text-sm">
+                        <div className="flex justify-between">
+                            <span>{COMPONENT_LABEL[componente.componente]}</span>
+                            <span className="text-muted-foreground">
+                                {componente.punteggio === null
+                                 ? "dati mancanti"
+                                 : `${Math.round(componente.punteggio)} (peso ${componente.peso})`}
+                            </span>
+                        </div>
+                        <Progress value={componente.punteggio ?? 0}/>
+                    </div>)}
+        </CardContent>
+    </Card>;
+};
+
+export default CardPunteggioEnergetico;
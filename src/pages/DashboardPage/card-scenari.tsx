@@ -0,0 +1,275 @@
+import TitleCard from "@/components/title-card.tsx";
+import {Button} from "@/components/ui/button.tsx";
+import {Card, CardContent, CardHeader} from "@/components/ui/card.tsx";
+import {
+    Dialog,
+    DialogContent,
+    DialogFooter,
+    DialogHeader,
+    DialogTitle,
+    DialogTrigger
+} from "@/components/ui/dialog.tsx";
+import {Input} from "@/components/ui/input.tsx";
+import {Label} from "@/components/ui/label.tsx";
+import {Select, SelectContent, SelectItem, SelectTrigger, SelectValue} from "@/components/ui/select.tsx";
+import {Switch} from "@/components/ui/switch.tsx";
+import {Table, TableBody, TableCell, TableHead, TableHeader, TableRow} from "@/components/ui/table.tsx";
+import {useNotification} from "@/context/NotificationProvider.tsx";
+import {useSelectedEdificio} from "@/context/SelectedEdificioProvider.tsx";
+import {useEdifici, useInfissi, useStanze, useTypes} from "@/context/UseProvider.tsx";
+import {errorMessage} from "@/helpers/helpers.ts";
+import {IScenario, IScenarioComparison, ISubstitution} from "@/models/models.tsx";
+import {invoke} from "@tauri-apps/api/core";
+import {save} from "@tauri-apps/plugin-dialog";
+import {FileDown, Plus, Trash2} from "lucide-react";
+import {useCallback, useEffect, useState} from "react";
+
+type TipoSostituzione = ISubstitution["tipo"];
+
+const TIPO_LABEL: Record<TipoSostituzione, string> = {
+    materiale       : "Materiale infissi",
+    vetro           : "Vetro infissi",
+    illuminazione   : "Illuminazione",
+    climatizzazione : "Climatizzazione",
+    cappotto        : "Cappotto",
+    isolamento_tetto: "Isolamento tetto",
+    fotovoltaico    : "Fotovoltaico"
+};
+
+const nuovaSostituzione = (tipo: TipoSostituzione): ISubstitution => {
+    switch (tipo) {
+        case "cappotto":
+        case "isolamento_tetto":
+            return {tipo, presente: true};
+        case "fotovoltaico":
+            return {tipo, potenza: 0};
+        default:
+            return {tipo, da: "", a: ""};
+    }
+};
+
+const formatPunteggio = (punteggio: number | null) => punteggio === null ? "-" : Math.round(punteggio).toString();
+
+const RigaSostituzione = ({sostituzione, onChange, onRemove}: {
+    sostituzione: ISubstitution,
+    onChange: (sostituzione: ISubstitution) => void,
+    onRemove: () => void
+}) => {
+    const types = useTypes();
+    const tipi: Record<string, string[]> = {
+        materiale      : types.materialiInfissiType,
+        vetro          : types.vetroInfissiType,
+        illuminazione  : types.illuminazioneType,
+        climatizzazione: types.climatizzazioneType
+    };
+
+    const selectTipo = (value: string, onValueChange: (value: string) => void, options: string[]) =>
+        <Select value={value} onValueChange={onValueChange}>
+            <SelectTrigger className="w-full"><SelectValue/></SelectTrigger>
+            <SelectContent>
+                {options.map(option => <SelectItem key={option} value={option}>{option}</SelectItem>)}
+            </SelectContent>
+        </Select>;
+
+    return <div className="grid grid-cols-[1fr_1fr_1fr_auto] gap-2 items-center">
+        {selectTipo(sostituzione.tipo,
+            value => onChange(nuovaSostituzione(value as TipoSostituzione)),
+            Object.keys(TIPO_LABEL))}
+        {"da" in sostituzione && <>
+            {selectTipo(sostituzione.da, da => onChange({...sostituzione, da}), tipi[sostituzione.tipo])}
+            {selectTipo(sostituzione.a, a => onChange({...sostituzione, a}), tipi[sostituzione.tipo])}
+        </>}
+        {"presente" in sostituzione && <div className="col-span-2 flex items-center gap-2">
+            <Switch checked={sostituzione.presente}
+                    onCheckedChange={presente => onChange({...sostituzione, presente})}/>
+            <span className="text-sm">{sostituzione.presente ? "Presente" : "Assente"}</span>
+        </div>}
+        {"potenza" in sostituzione && <Input className="col-span-2" type="number" min={0} step={0.1}
+                                              placeholder="Potenza (kW)"
+                                              value={sostituzione.potenza || ""}
+                                              onChange={e => onChange({
+                                                  ...sostituzione,
+                                                  potenza: Number(e.target.value)
+                                              })}/>}
+        <Button variant="ghost" size="icon" onClick={onRemove}>
+            <Trash2/>
+            <span className="sr-only">Rimuovi</span>
+        </Button>
+    </div>;
+};
+
+const DialogScenario = ({edificio, onSaved}: { edificio: string, onSaved: () => void }) => {
+    const {addNotification} = useNotification();
+    const [open, setOpen] = useState(false);
+    const [nome, setNome] = useState("");
+    const [sostituzioni, setSostituzioni] = useState<ISubstitution[]>([]);
+    const [anteprima, setAnteprima] = useState<IScenarioComparison | null>(null);
+
+    const scenario: IScenario = {nome, edificio, descrizione: null, sostituzioni};
+
+    const reset = (open: boolean) => {
+        setOpen(open);
+        setNome("");
+        setSostituzioni([]);
+        setAnteprima(null);
+    };
+
+    const simulate = async () => {
+        try {
+            setAnteprima(await invoke<IScenarioComparison>("simulate_scenario", {scenario}));
+        } catch (e) {
+            addNotification(errorMessage(e), "error");
+        }
+    };
+
+    const saveScenario = async () => {
+        try {
+            await invoke<IScenarioComparison>("save_scenario", {scenario});
+            reset(false);
+            onSaved();
+        } catch (e) {
+            addNotification(errorMessage(e), "error");
+        }
+    };
+
+    return <Dialog open={open} onOpenChange={reset}>
+        <DialogTrigger asChild>
+            <Button variant="ghost" size="icon">
+                <Plus/>
+                <span className="sr-only">Nuovo scenario</span>
+            </Button>
+        </DialogTrigger>
+        <DialogContent className="sm:max-w-2xl">
+            <DialogHeader>
+                <DialogTitle>Nuovo scenario</DialogTitle>
+            </DialogHeader>
+            <div className="flex flex-col gap-4">
+                <div className="flex flex-col gap-2">
+                    <Label htmlFor="nome-scenario">Nome</Label>
+                    <Input id="nome-scenario" value={nome} onChange={e => setNome(e.target.value)}/>
+                </div>
+                {sostituzioni.map((sostituzione, i) =>
+                    <RigaSostituzione key={i} sostituzione={sostituzione}
+                                      onChange={value => setSostituzioni(sostituzioni.map((s, j) => j === i ? value : s))}
+                                      onRemove={() => setSostituzioni(sostituzioni.filter((_, j) => j !== i))}/>)}
+                <Button variant="outline"
+                        onClick={() => setSostituzioni([...sostituzioni, nuovaSostituzione("materiale")])}>
+                    Aggiungi modifica
+                </Button>
+                {anteprima && <p className="text-sm">
+                    Punteggio {formatPunteggio(anteprima.prima.punteggio)} → {formatPunteggio(anteprima.dopo.punteggio)},
+                    dispersione infissi {anteprima.prima.h_infissi.toFixed(1)} → {anteprima.dopo.h_infissi.toFixed(1)} W/K
+                </p>}
+            </div>
+            <DialogFooter>
+                <Button variant="outline" onClick={simulate}>Simula</Button>
+                <Button onClick={saveScenario}>Salva</Button>
+            </DialogFooter>
+        </DialogContent>
+    </Dialog>;
+};
+
+/** Scenari di riqualificazione dell'edificio selezionato, confrontati con lo stato attuale */
+const CardScenari = () => {
+    const {edificio} = useSelectedEdificio();
+    const {addNotification} = useNotification();
+    const edificiContext = useEdifici();
+    const stanzeContext = useStanze();
+    const infissiContext = useInfissi();
+    const [confronti, setConfronti] = useState<IScenarioComparison[]>([]);
+
+    const chiave = edificio?.chiave;
+
+    const loadScenari = useCallback(async () => {
+        setConfronti([]);
+        if (!chiave) {
+            return;
+        }
+        const scenari = await invoke<IScenario[]>("get_scenari", {edificio: chiave});
+        setConfronti(await Promise.all(scenari.map(scenario =>
+            invoke<IScenarioComparison>("simulate_scenario", {scenario}))));
+    }, [chiave]);
+
+    useEffect(() => {
+        loadScenari().catch(console.error);
+    }, [loadScenari, edificiContext.data, stanzeContext.data, infissiContext.data]);
+
+    const deleteScenario = async (nome: string) => {
+        try {
+            await invoke("delete_scenario", {edificio: chiave, nome});
+            await loadScenari();
+        } catch (e) {
+            addNotification(errorMessage(e), "error");
+        }
+    };
+
+    const exportScenari = async () => {
+        const path = await save({
+            title      : "Esporta gli scenari",
+            defaultPath: `scenari_${chiave}.xlsx`,
+            filters    : [{name: "Excel", extensions: ["xlsx"]}]
+        });
+        if (!path) {
+            return;
+        }
+        try {
+            await invoke("export_scenari", {edificio: chiave, path});
+            addNotification("Scenari esportati", "success");
+        } catch (e) {
+            addNotification(errorMessage(e), "error");
+        }
+    };
+
+    return <Card className="col-span-2">
+        <CardHeader>
+            <div className="flex justify-between items-center">
+                <TitleCard title="Scenari di riqualificazione"/>
+                {chiave && <div className="flex items-center gap-1">
+                    <Button variant="ghost" size="icon" onClick={exportScenari} disabled={confronti.length === 0}>
+                        <FileDown/>
+                        <span className="sr-only">Esporta</span>
+                    </Button>
+                    <DialogScenario edificio={chiave} onSaved={() => loadScenari().catch(console.error)}/>
+                </div>}
+            </div>
+        </CardHeader>
+        <CardContent>
+            {confronti.length === 0
+             ? <p className="text-sm text-muted-foreground">Nessuno scenario salvato</p>
+             : <Table>
+                 <TableHeader>
+                     <TableRow>
+                         <TableHead>Scenario</TableHead>
+                         <TableHead>Modifiche</TableHead>
+                         <TableHead className="text-right">Punteggio</TableHead>
+                         <TableHead className="text-right">Dispersione infissi (W/K)</TableHead>
+                         <TableHead/>
+                     </TableRow>
+                 </TableHeader>
+                 <TableBody>
+                     {confronti.map(confronto => <TableRow key={confronto.scenario.nome}>
+                         <TableCell className="font-medium">{confronto.scenario.nome}</TableCell>
+                         <TableCell>
+                             {confronto.scenario.sostituzioni.map(s => TIPO_LABEL[s.tipo]).join(", ")}
+                         </TableCell>
+                         <TableCell className="text-right">
+                             {formatPunteggio(confronto.prima.punteggio)} → {formatPunteggio(confronto.dopo.punteggio)}
+                         </TableCell>
+                         <TableCell className="text-right">
+                             {confronto.prima.h_infissi.toFixed(1)} → {confronto.dopo.h_infissi.toFixed(1)}
+                         </TableCell>
+                         <TableCell className="text-right">
+                             <Button variant="ghost" size="icon"
+                                     onClick={() => deleteScenario(confronto.scenario.nome)}>
+                                 <Trash2/>
+                                 <span className="sr-only">Elimina</span>
+                             </Button>
+                         </TableCell>
+                     </TableRow>)}
+                 </TableBody>
+             </Table>}
+        </CardContent>
+    </Card>;
+};
+
+export default CardScenari;
@@ -26,9 +26,19 @@ import {z} from "zod";
 const FormSchema = z.object({
     tipo               : z.string(),
     cod_contatore      : z.string(),
-    indirizzo_contatore: z.string().optional()
+    indirizzo_contatore: z.string().optional(),
+    consumo_annuo      : z.string().regex(/^(\d+(\.\d+)?)?$/, {
+        message: "Il consumo deve essere un numero positivo"
+    }).optional()
 });
 
+/** Valori del form per l'utenza da modificare, vuoti per una nuova utenza */
+const formValues = (utenza?: IUtenza): z.infer<typeof FormSchema> => ({
+    tipo               : utenza?.tipo ?? "",
+    cod_contatore      : utenza?.cod_contatore ?? "",
+    indirizzo_contatore: utenza?.indirizzo_contatore ?? "",
+    consumo_annuo      : utenza?.consumo_annuo?.toString() ?? ""
+});
 
 const CardUtenzeEdificio = () => {
     const utenzeContext = useUtenze();
@@ -37,7 +47,7 @@ const CardUtenzeEdificio = () => {
         <CardHeader>
             <div className="flex justify-between items-center">
                 <TitleCard title="Utenze"/>
-                <InsertUtenza/>
+                <FormUtenza/>
             </div>
         </CardHeader>
         <CardContent>
@@ -48,6 +58,8 @@ const CardUtenzeEdificio = () => {
                             <TableHead className="text-center font-bold">Tipologia</TableHead>
                             <TableHead className="text-center font-bold">Codice</TableHead>
                             <TableHead className="text-center font-bold">Indirizzo</TableHead>
+                            <TableHead className="text-center font-bold">Consumo annuo</TableHead>
+                            <TableHead/>
                         </TableRow>
                     </TableHeader>
                     <TableBody>
@@ -56,6 +68,8 @@ const CardUtenzeEdificio = () => {
                                 <TableCell className="text-center">{value.tipo}</TableCell>
                                 <TableCell className="text-center">{value.cod_contatore}</TableCell>
                                 <TableCell className="text-center">{value.indirizzo_contatore}</TableCell>
+                                <TableCell className="text-center">{value.consumo_annuo ?? "-"}</TableCell>
+                                <TableCell className="text-center"><FormUtenza utenza={value}/></TableCell>
                             </TableRow>;
                         }) : (
                             <TableRow>
@@ -68,12 +82,14 @@ const CardUtenzeEdificio = () => {
     </Card>;
 };
 
-const InsertUtenza = () => {
-    const {insertUtenza} = useUtenze();
+/** Form per aggiungere un'utenza o, se indicata, modificare `utenza` */
+const FormUtenza = ({utenza}: { utenza?: IUtenza }) => {
+    const {insertUtenza, updateUtenza} = useUtenze();
     const {edificio} = useSelectedEdificio();
 
     const form = useForm<z.infer<typeof FormSchema>>({
-        resolver: zodResolver(FormSchema)
+        resolver     : zodResolver(FormSchema),
+        defaultValues: formValues(utenza)
     });
 
     function onSubmit(data: z.infer<typeof FormSchema>) {
@@ -96,30 +112,42 @@ const InsertUtenza = () => {
             }
         }
 
+        const consumoAnnuo = data.consumo_annuo ? Number(data.consumo_annuo) : undefined;
+        if (utenza) {
+            updateUtenza({
+                ...utenza,
+                cod_contatore      : data.cod_contatore,
+                indirizzo_contatore: data.indirizzo_contatore || undefined,
+                consumo_annuo      : consumoAnnuo
+            }).catch(console.error);
+            return;
+        }
+
         const newUtenza: IUtenza = {
             id                 : 0,
             id_edificio        : edificio.chiave,
             tipo               : data.tipo,
             cod_contatore      : data.cod_contatore,
-            indirizzo_contatore: data.indirizzo_contatore
+            indirizzo_contatore: data.indirizzo_contatore,
+            consumo_annuo      : consumoAnnuo
         };
         insertUtenza(newUtenza).catch(console.error);
-        form.reset({
-            tipo               : "",
-            cod_contatore      : "",
-            indirizzo_contatore: ""
-        });
+        form.reset(formValues());
     }
 
-    return <Sheet>
+    return <Sheet onOpenChange={open => open && form.reset(formValues(utenza))}>
         <SheetTrigger asChild>
-            <Button variant="ghost" size={"sm"} disabled={edificio === undefined}><PlusIcon/></Button>
+            <Button variant="ghost" size={"sm"} disabled={edificio === undefined}>
+                {utenza ? <Pencil/> : <PlusIcon/>}
+            </Button>
         </SheetTrigger>
         <SheetContent className="w-[400px]">
             <SheetHeader>
-                <SheetTitle>Aggiungi </SheetTitle>
+                <SheetTitle>{utenza ? "Modifica" : "Aggiungi"} </SheetTitle>
                 <SheetDescription>
-                    Compila il form per aggiungere un nuovo tipo a
+                    {utenza
+                        ? `Compila il form per modificare l'utenza ${utenza.cod_contatore}`
+                        : "Compila il form per aggiungere un nuovo tipo a"}
                 </SheetDescription>
             </SheetHeader>
             <Form {...form}>
@@ -131,10 +159,12 @@ const InsertUtenza = () => {
                             render={({field}) => {
                                 return <FormItem>
                                     <FormLabel>Tipo</FormLabel>
-                                    <ClearableSelect value={field.value}
-                                                     onChange={field.onChange}
-                                                     options={["Idrica", "Elettrica", "Termica"]}
-                                    />
+                                    {utenza
+                                        ? <Input value={field.value} disabled/>
+                                        : <ClearableSelect value={field.value}
+                                                           onChange={field.onChange}
+                                                           options={["Idrica", "Elettrica", "Termica"]}
+                                        />}
                                     <FormMessage/>
                                 </FormItem>;
                             }}
@@ -165,6 +195,21 @@ const InsertUtenza = () => {
                                 </FormItem>;
                             }}
                         />
+                        <FormField
+                            control={form.control}
+                            name={"consumo_annuo"}
+                            render={({field}) => {
+                                return <FormItem>
+                                    <FormLabel>Consumo annuo</FormLabel>
+                                    <Input value={field.value}
+                                           inputMode="decimal"
+                                           placeholder="kWh per le utenze elettriche"
+                                           onChange={field.onChange}
+                                    />
+                                    <FormMessage/>
+                                </FormItem>;
+                            }}
+                        />
                     </div>
                 </form>
             </Form>
@@ -173,7 +218,7 @@ const InsertUtenza = () => {
                     <Button type="button" className="text-white" onClick={async () => {
                         await form.handleSubmit(onSubmit)();
                     }}>
-                        <Pencil/>Aggiungi
+                        <Pencil/>{utenza ? "Modifica" : "Aggiungi"}
                     </Button>
                 </SheetClose>
             </SheetFooter>
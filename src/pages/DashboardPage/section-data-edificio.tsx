@@ -1,5 +1,10 @@
 import CardUtenzeEdificio from "@/pages/DashboardPage/card-utenze-edificio.tsx";
 import CardFotovoltaico from "@/pages/DashboardPage/card-fotovoltaico.tsx";
+import CardDataQuality from "@/pages/DashboardPage/card-data-quality.tsx";
+import CardMetricheEdificio from "@/pages/DashboardPage/card-metriche-edificio.tsx";
+import CardDispersioniInfissi from "@/pages/DashboardPage/card-dispersioni-infissi.tsx";
+import CardPunteggioEnergetico from "@/pages/DashboardPage/card-punteggio-energetico.tsx";
+import CardScenari from "@/pages/DashboardPage/card-scenari.tsx";
 
 const SectionDataEdificio = () => {
     return <div className="*:data-[slot=card]:shadow-xs grid grid-cols-2 gap-4
@@ -7,6 +12,11 @@ const SectionDataEdificio = () => {
             dark:*:data-[slot=card]:bg-card lg:px-6">
         <CardUtenzeEdificio/>
         <CardFotovoltaico/>
+        <CardDataQuality/>
+        <CardMetricheEdificio/>
+        <CardDispersioniInfissi/>
+        <CardPunteggioEnergetico/>
+        <CardScenari/>
     </div>;
 };
 
@@ -1,5 +1,6 @@
 import CommentsButton from "@/components/annotazione-button.tsx";
 import ClearableSelect from "@/components/clearable-select.tsx";
+import ImportInfissiButton from "@/components/import-infissi-button.tsx";
 import InputWithMeasureUnit from "@/components/input-with-measure-unit.tsx";
 import TitleCard from "@/components/title-card.tsx";
 import {Button} from "@/components/ui/button.tsx";
@@ -17,6 +18,7 @@ import {ChangeEvent, useState} from "react";
 import {useForm} from "react-hook-form";
 import {toast} from "sonner";
 import {z} from "zod";
+import {errorMessage} from "@/helpers/helpers.ts";
 
 const nextAlphabeticalID = (prevID: string | null) => {
     if (!prevID || prevID === "") return "A";
@@ -141,7 +143,7 @@ const CardFormInfisso = () => {
                     annotazione: annotazione
                 });
             } catch (e) {
-                addNotification(e as string, "error");
+                addNotification(errorMessage(e), "error");
             }
         }
         setAnnotazioni([]);
@@ -175,7 +177,8 @@ const CardFormInfisso = () => {
                 <div className="flex gap-5 items-center">
                     <TitleCard title="Inserisci Infisso"/>
                     <CommentsButton setAnnotazione={setAnnotazioni} disabled={edificio === undefined}/>
-                    <div className="flex flex-1 justify-end">
+                    <div className="flex flex-1 justify-end gap-2">
+                        <ImportInfissiButton disabled={edificio === undefined}/>
                         <Button type="button" className="dark:text-white" variant="secondary" onClick={clearForm}>
                             <Trash/> Pulisci Form
                         </Button>
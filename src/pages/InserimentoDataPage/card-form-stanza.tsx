@@ -21,18 +21,19 @@ import {
 import {useNotification} from "@/context/NotificationProvider.tsx";
 import {useSelectedEdificio} from "@/context/SelectedEdificioProvider.tsx";
 import {useDatabase, useStanze, useTypes} from "@/context/UseProvider.tsx";
-import {handleInputNumericChange} from "@/helpers/helpers";
+import { handleInputNumericChange, errorMessage } from "@/helpers/helpers.ts";
 import {getSavedFormData, useLocalStorageForm} from "@/hooks/useLocalStorageForm.ts";
-import {IAnnotazione, IStanza, NuovoTipo, TipoKey} from "@/models/models.tsx";
+import {IAnnotazione, IPareteEsposta, IStanza, NuovoTipo, Orientamento, TipoKey} from "@/models/models.tsx";
 import {zodResolver} from "@hookform/resolvers/zod";
 import {invoke} from "@tauri-apps/api/core";
 import {Pencil, PlusIcon, Trash} from "lucide-react";
-import {useMemo, useState} from "react";
+import {ChangeEvent, useMemo, useState} from "react";
 import {ControllerRenderProps, FieldPath, useForm, UseFormReturn} from "react-hook-form";
 import {toast} from "sonner";
 
 import {z} from "zod";
 
+const ORIENTAMENTI: Orientamento[] = ["N", "NE", "E", "SE", "S", "SO", "O", "NO"];
 
 const FormSchema = z.object({
     stanza          : z.string({
@@ -46,6 +47,15 @@ const FormSchema = z.object({
     spessore_muro   : z.number().max(256, {
         message: "Lo spessore del muro deve essere maggiore di 0 e minore di 256"
     }).optional(),
+    superficie      : z.number().min(0, {
+        message: "La superficie non può essere negativa"
+    }).optional(),
+    perimetro       : z.number().min(0, {
+        message: "Il perimetro non può essere negativo"
+    }).optional(),
+    pareti_esposte  : z.record(z.string(), z.number().min(0, {
+        message: "La lunghezza non può essere negativa"
+    })).optional(),
     riscaldamento   : z.string().optional(),
     raffrescamento  : z.string().optional(),
     illuminazione   : z.string().optional(),
@@ -71,9 +81,12 @@ const CardFormStanza = () => {
     const form = useForm<z.infer<typeof FormSchema>>({
         resolver     : zodResolver(FormSchema),
         defaultValues: savedValues || {
-            altezza      : 0,
-            spessore_muro: 0,
-            infissi      : []
+            altezza       : 0,
+            spessore_muro : 0,
+            superficie    : 0,
+            perimetro     : 0,
+            pareti_esposte: {},
+            infissi       : []
         }
     });
     useLocalStorageForm(form, "stanzaFormData");
@@ -131,11 +144,18 @@ const CardFormStanza = () => {
             toast.error("Stanza non trovata");
             return;
         }
+        // le pareti non compilate lasciano invariate quelle salvate
+        const pareti: IPareteEsposta[] = ORIENTAMENTI
+            .filter(orientamento => (data.pareti_esposte?.[orientamento] ?? 0) > 0)
+            .map(orientamento => ({orientamento, lunghezza: data.pareti_esposte![orientamento]}));
         for (const stanza of stanze) {
             const newStanza: IStanza = {
                 ...stanza,
                 altezza       : data.altezza == 0 ? undefined : data.altezza,
                 spessore_muro : data.spessore_muro == 0 ? undefined : data.spessore_muro,
+                superficie    : data.superficie == 0 ? undefined : data.superficie,
+                perimetro     : data.perimetro == 0 ? undefined : data.perimetro,
+                pareti_esposte: pareti.length === 0 ? undefined : pareti,
                 riscaldamento : data.riscaldamento,
                 raffrescamento: data.raffrescamento,
                 illuminazione : data.illuminazione,
@@ -162,7 +182,7 @@ const CardFormStanza = () => {
                     annotazione: annotazione
                 });
             } catch (e) {
-                addNotification(e as string, "error");
+                addNotification(errorMessage(e), "error");
             }
         }
         setAnnotazioni([]);
@@ -176,6 +196,9 @@ const CardFormStanza = () => {
             piano           : "",
             altezza         : 0,
             spessore_muro   : 0,
+            superficie      : 0,
+            perimetro       : 0,
+            pareti_esposte  : {},
             riscaldamento   : "",
             raffrescamento  : "",
             illuminazione   : "",
@@ -265,14 +288,14 @@ const CardFormStanza = () => {
                                     />
                                 </div>
                             </div>
-                            {/* Altezza e Spessore Muro */}
+                            {/* Dimensioni */}
                             <div className="row-start-2 col-span-12">
                                 <div className="grid grid-cols-12 gap-5">
                                     <FormField
                                         control={form.control}
                                         name="altezza"
                                         render={({field}) => (
-                                            <div className="col-span-6">
+                                            <div className="col-span-3">
                                             <FormItem>
                                                 <FormLabel className="flex items-center">
                                                     <p>Altezza</p>
@@ -291,7 +314,7 @@ const CardFormStanza = () => {
                                         control={form.control}
                                         name="spessore_muro"
                                         render={({field}) => (
-                                            <div className="col-span-6">
+                                            <div className="col-span-3">
                                             <FormItem>
                                                 <FormLabel className="flex items-center">
                                                     <p>Spessore Muro</p>
@@ -306,6 +329,46 @@ const CardFormStanza = () => {
                                             </FormItem>
                                         </div>)}
                                     />
+                                    <FormField
+                                        control={form.control}
+                                        name="superficie"
+                                        render={({field}) => (
+                                            <div className="col-span-3">
+                                            <FormItem>
+                                                <FormLabel className="flex items-center">
+                                                    <p>Superficie</p>
+                                                </FormLabel>
+                                                <InputWithMeasureUnit
+                                                    type="number" min={0} step={0.01}
+                                                    value={field.value}
+                                                    onChange={e => handleDecimalChange(e, field.onChange)}
+                                                    disabled={edificio === undefined}
+                                                    unitLabel="m²"
+                                                />
+                                                <FormMessage/>
+                                            </FormItem>
+                                        </div>)}
+                                    />
+                                    <FormField
+                                        control={form.control}
+                                        name="perimetro"
+                                        render={({field}) => (
+                                            <div className="col-span-3">
+                                            <FormItem>
+                                                <FormLabel className="flex items-center">
+                                                    <p>Perimetro</p>
+                                                </FormLabel>
+                                                <InputWithMeasureUnit
+                                                    type="number" min={0} step={0.01}
+                                                    value={field.value}
+                                                    onChange={e => handleDecimalChange(e, field.onChange)}
+                                                    disabled={edificio === undefined}
+                                                    unitLabel="m"
+                                                />
+                                                <FormMessage/>
+                                            </FormItem>
+                                        </div>)}
+                                    />
                                 </div>
                             </div>
                             {/* Riscaldamento e Raffrescamento */}
@@ -333,6 +396,35 @@ const CardFormStanza = () => {
                                                       tipo={"illuminazione"}
                                 />
                             </div>
+                            {/* Pareti esposte */}
+                            <div className="row-start-5 col-span-12">
+                                <FormField
+                                    control={form.control}
+                                    name="pareti_esposte"
+                                    render={({field}) => (
+                                        <FormItem>
+                                        <FormLabel>Pareti esposte</FormLabel>
+                                        <div className="grid grid-cols-8 gap-3">
+                                            {ORIENTAMENTI.map(orientamento => (
+                                                <div key={orientamento} className="grid gap-1">
+                                                    <Label className="text-muted-foreground">{orientamento}</Label>
+                                                    <InputWithMeasureUnit
+                                                        type="number" min={0} step={0.01}
+                                                        value={field.value?.[orientamento] ?? 0}
+                                                        onChange={e => handleDecimalChange(e, value => field.onChange({
+                                                            ...field.value,
+                                                            [orientamento]: value
+                                                        }))}
+                                                        disabled={edificio === undefined}
+                                                        unitLabel="m"
+                                                    />
+                                                </div>
+                                            ))}
+                                        </div>
+                                        <FormMessage/>
+                                    </FormItem>)}
+                                />
+                            </div>
                             {/* Infissi */}
                             <div className="row-start-6 col-span-12">
                                 <FormField
@@ -362,6 +454,11 @@ const CardFormStanza = () => {
     </div>;
 };
 
+const handleDecimalChange = (event: ChangeEvent<HTMLInputElement>, onChange: (value: number) => void) => {
+    const value = event.target.valueAsNumber;
+    onChange(isNaN(value) ? 0 : value);
+};
+
 interface SelectWithOtherFieldProps<TFormValues extends Record<string, unknown>> {
     form: UseFormReturn<TFormValues>;
     name: FieldPath<TFormValues>;
@@ -420,7 +517,7 @@ const SheetAddNewTipo = ({tipo}: { tipo: TipoKey }) => {
             await insertType(insertTipo);
             addNotification(`Tipo ${newNameTipo} inserito`, "success");
         } catch (e) {
-            addNotification(e as string, "error");
+            addNotification(errorMessage(e), "error");
         } finally {
             setNewNameTipo("");
             setEffEnergetica(0);
@@ -0,0 +1,144 @@
+import TitleCard from "@/components/title-card.tsx";
+import {Button} from "@/components/ui/button.tsx";
+import {Card, CardContent, CardFooter, CardHeader} from "@/components/ui/card.tsx";
+import {Input} from "@/components/ui/input.tsx";
+import {Label} from "@/components/ui/label.tsx";
+import {errorMessage} from "@/helpers/helpers.ts";
+import {IConnectionProfile, IConnectionProfileSummary, IDatabaseSettings} from "@/models/models.tsx";
+import {invoke} from "@tauri-apps/api/core";
+import {useEffect, useState} from "react";
+
+const EMPTY_PROFILE: IConnectionProfile = {
+    name     : "",
+    host     : "localhost",
+    port     : 5432,
+    database : "",
+    user     : "",
+    password : "",
+    pool_size: 10
+};
+
+type Esito = { type: "success" | "error", message: string };
+
+/** La password salvata non viene inviata: lasciata vuota resta invariata */
+const toProfile = (summary: IConnectionProfileSummary): IConnectionProfile => ({
+    name     : summary.name,
+    host     : summary.host,
+    port     : summary.port,
+    database : summary.database,
+    user     : summary.user,
+    password : "",
+    pool_size: summary.pool_size
+});
+
+/** Configurazione della connessione al database, mostrata quando l'applicazione non è connessa */
+const PageDatabaseSetup = ({onConnected, error}: Readonly<{ onConnected: () => void, error?: string }>) => {
+    const [settings, setSettings] = useState<IDatabaseSettings>({profiles: [], active: null});
+    const [profile, setProfile] = useState<IConnectionProfile>(EMPTY_PROFILE);
+    const [esito, setEsito] = useState<Esito | null>(error ? {type: "error", message: error} : null);
+    const [isLoading, setIsLoading] = useState(false);
+
+    const loadSettings = async () => {
+        const data = await invoke<IDatabaseSettings>("get_connection_profiles");
+        setSettings(data);
+        const active = data.profiles.find(p => p.name === data.active) ?? data.profiles[0];
+        if (active) {
+            setProfile(toProfile(active));
+        }
+    };
+
+    useEffect(() => {
+        loadSettings().catch(e => setEsito({type: "error", message: errorMessage(e)}));
+    }, []);
+
+    const run = async (action: () => Promise<void>) => {
+        setIsLoading(true);
+        setEsito(null);
+        try {
+            await action();
+        } catch (e) {
+            setEsito({type: "error", message: errorMessage(e)});
+        } finally {
+            setIsLoading(false);
+        }
+    };
+
+    const testConnection = () => run(async () => {
+        await invoke("test_connection", {profile});
+        setEsito({type: "success", message: "Connessione riuscita"});
+    });
+
+    const saveAndConnect = () => run(async () => {
+        await invoke("save_connection_profile", {profile});
+        await invoke("connect_database", {name: profile.name});
+        onConnected();
+    });
+
+    const deleteProfile = () => run(async () => {
+        await invoke("delete_connection_profile", {name: profile.name});
+        setProfile(EMPTY_PROFILE);
+        await loadSettings();
+    });
+
+    // la password salvata viene riusata solo per lo stesso server e utente
+    const hasSavedPassword = settings.profiles.some(p => p.name === profile.name && p.has_password
+        && p.host === profile.host && p.port === profile.port
+        && p.database === profile.database && p.user === profile.user);
+
+    const field = (key: keyof IConnectionProfile, label: string, type = "text", placeholder?: string) =>
+        <div className="grid gap-2">
+            <Label htmlFor={key}>{label}</Label>
+            <Input id={key} type={type} value={profile[key]} placeholder={placeholder}
+                   onChange={e => setProfile({
+                       ...profile,
+                       [key]: type === "number" ? Number(e.target.value) : e.target.value
+                   })}/>
+        </div>;
+
+    return <div className="flex min-h-svh items-center justify-center p-6">
+        <Card className="w-full max-w-lg">
+            <CardHeader>
+                <TitleCard title="Connessione al database"/>
+                <p className="text-sm text-muted-foreground">
+                    Il database non è raggiungibile. Inserisci i parametri di connessione.
+                </p>
+            </CardHeader>
+            <CardContent className="grid gap-4">
+                {settings.profiles.length > 0 && <div className="flex flex-wrap gap-2">
+                    {settings.profiles.map(p =>
+                        <Button key={p.name} size="sm" variant={p.name === profile.name ? "default" : "outline"}
+                                onClick={() => setProfile(toProfile(p))}>
+                            {p.name}
+                        </Button>)}
+                    <Button size="sm" variant="ghost" onClick={() => setProfile(EMPTY_PROFILE)}>Nuovo</Button>
+                </div>}
+                {field("name", "Nome profilo")}
+                <div className="grid grid-cols-3 gap-4">
+                    <div className="col-span-2">{field("host", "Host")}</div>
+                    {field("port", "Porta", "number")}
+                </div>
+                {field("database", "Database")}
+                <div className="grid grid-cols-2 gap-4">
+                    {field("user", "Utente")}
+                    {field("password", "Password", "password", hasSavedPassword ? "Invariata" : undefined)}
+                </div>
+                {field("pool_size", "Connessioni massime", "number")}
+                {esito && <p className={esito.type === "error" ? "text-sm text-destructive" : "text-sm text-green-600"}>
+                    {esito.message}
+                </p>}
+            </CardContent>
+            <CardFooter className="flex justify-between">
+                <Button variant="ghost" disabled={isLoading || !settings.profiles.some(p => p.name === profile.name)}
+                        onClick={deleteProfile}>
+                    Elimina
+                </Button>
+                <div className="flex gap-2">
+                    <Button variant="outline" disabled={isLoading} onClick={testConnection}>Test connessione</Button>
+                    <Button disabled={isLoading} onClick={saveAndConnect}>Salva e connetti</Button>
+                </div>
+            </CardFooter>
+        </Card>
+    </div>;
+};
+
+export default PageDatabaseSetup;